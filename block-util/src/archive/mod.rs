@@ -11,6 +11,7 @@ use std::sync::Arc;
 use anyhow::Result;
 use bytes::Bytes;
 use everscale_types::models::BlockId;
+use tl_proto::TlWrite;
 use tycho_util::FastHashMap;
 
 pub use self::proto::{
@@ -161,6 +162,58 @@ impl Archive {
     }
 }
 
+/// Serializes an archive entry header followed by the entry data.
+pub fn make_archive_entry(block_id: &BlockId, ty: ArchiveEntryType, data: &[u8]) -> Vec<u8> {
+    let mut entry = Vec::with_capacity(ARCHIVE_ENTRY_HEADER_LEN + data.len());
+    ArchiveEntryHeader {
+        block_id: *block_id,
+        ty,
+        data_len: data.len() as u32,
+    }
+    .write_to(&mut entry);
+    entry.extend_from_slice(data);
+    entry
+}
+
+/// Makes archive entries for the block, its proof and queue diff.
+///
+/// NOTE: Proof links of shard blocks are stored as [`ArchiveEntryType::Proof`] as well.
+pub fn make_block_entries(
+    block: &BlockStuffAug,
+    proof: &BlockProofStuffAug,
+    queue_diff: &QueueDiffStuffAug,
+) -> Result<Vec<Vec<u8>>> {
+    let block_id = block.id();
+    anyhow::ensure!(
+        proof.id() == block_id,
+        "proof block id mismatch (expected: {block_id}, found: {})",
+        proof.id(),
+    );
+    anyhow::ensure!(
+        queue_diff.block_id() == block_id,
+        "queue diff block id mismatch (expected: {block_id}, found: {})",
+        queue_diff.block_id(),
+    );
+
+    Ok(vec![
+        make_archive_entry(
+            block_id,
+            ArchiveEntryType::Block,
+            block.as_new_archive_data()?,
+        ),
+        make_archive_entry(
+            block_id,
+            ArchiveEntryType::Proof,
+            proof.as_new_archive_data()?,
+        ),
+        make_archive_entry(
+            block_id,
+            ArchiveEntryType::QueueDiff,
+            queue_diff.as_new_archive_data()?,
+        ),
+    ])
+}
+
 #[derive(Default)]
 pub struct ArchiveDataEntry {
     pub block: Option<Bytes>,
@@ -184,6 +237,17 @@ impl ArchiveData {
             ArchiveData::Existing => Err(WithArchiveDataError),
         }
     }
+
+    /// Same as [`as_new_archive_data`], but returns shared bytes
+    /// which can be used without copying.
+    ///
+    /// [`as_new_archive_data`]: Self::as_new_archive_data
+    pub fn as_new_archive_bytes(&self) -> Result<&Bytes, WithArchiveDataError> {
+        match self {
+            ArchiveData::New(data) => Ok(data),
+            ArchiveData::Existing => Err(WithArchiveDataError),
+        }
+    }
 }
 
 /// Parsed data wrapper, augmented with the optional raw data.
@@ -266,4 +330,73 @@ mod tests {
         );
         assert!(WithArchiveData::loaded(()).as_new_archive_data().is_err());
     }
+
+    #[test]
+    fn block_entries_round_trip() -> Result<()> {
+        use everscale_types::boc::{Boc, BocRepr};
+        use everscale_types::models::ShardIdent;
+
+        let mut archive_data = ARCHIVE_PREFIX.to_vec();
+        let mut block_ids = Vec::new();
+        for shard in [ShardIdent::MASTERCHAIN, ShardIdent::BASECHAIN] {
+            let block = BlockStuff::new_empty(shard, 1);
+            let block_id = *block.id();
+            let block = {
+                let data = Boc::encode(block.root_cell());
+                block.with_archive_data(data)
+            };
+
+            let proof = BlockProofStuff::new_empty(&block_id);
+            let proof = {
+                let data = BocRepr::encode(proof.as_ref())?;
+                proof.with_archive_data(data)
+            };
+
+            let queue_diff = empty_queue_diff(&block_id);
+
+            for entry in make_block_entries(&block, &proof, &queue_diff)? {
+                archive_data.extend_from_slice(&entry);
+            }
+            block_ids.push(block_id);
+        }
+
+        let archive = Archive::new(archive_data)?;
+        assert_eq!(archive.mc_block_ids.len(), 1);
+        assert_eq!(archive.blocks.len(), 2);
+        for block_id in &block_ids {
+            assert_eq!(archive.get_block_by_id(block_id)?.id(), block_id);
+            assert_eq!(archive.get_proof_by_id(block_id)?.id(), block_id);
+            assert_eq!(archive.get_queue_diff_by_id(block_id)?.block_id(), block_id);
+        }
+
+        // proof must be for the same block
+        let block = BlockStuff::new_empty(ShardIdent::BASECHAIN, 2);
+        let block = {
+            let data = Boc::encode(block.root_cell());
+            block.with_archive_data(data)
+        };
+        let proof = BlockProofStuff::new_empty(&block_ids[1]);
+        let proof = {
+            let data = BocRepr::encode(proof.as_ref())?;
+            proof.with_archive_data(data)
+        };
+        let queue_diff = empty_queue_diff(&block_ids[1]);
+        assert!(make_block_entries(&block, &proof, &queue_diff).is_err());
+
+        // queue diff must be for the same block
+        let proof = BlockProofStuff::new_empty(block.id());
+        let proof = {
+            let data = BocRepr::encode(proof.as_ref())?;
+            proof.with_archive_data(data)
+        };
+        assert!(make_block_entries(&block, &proof, &queue_diff).is_err());
+
+        Ok(())
+    }
+
+    fn empty_queue_diff(block_id: &BlockId) -> QueueDiffStuffAug {
+        let queue_diff = QueueDiffStuff::new_empty(block_id);
+        let data = tl_proto::serialize(queue_diff.as_ref());
+        WithArchiveData::new(queue_diff, data)
+    }
 }
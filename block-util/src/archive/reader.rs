@@ -7,13 +7,38 @@ use crate::archive::proto::{ArchiveEntryHeader, ARCHIVE_ENTRY_HEADER_LEN, ARCHIV
 /// Stateful archive package reader.
 pub struct ArchiveReader<'a> {
     data: &'a [u8],
+    total_len: usize,
+    entries_read: usize,
 }
 
 impl<'a> ArchiveReader<'a> {
     /// Starts reading archive package
     pub fn new(mut data: &'a [u8]) -> Result<Self, ArchiveReaderError> {
+        let total_len = data.len();
         read_archive_prefix(&mut data)?;
-        Ok(Self { data })
+        Ok(Self {
+            data,
+            total_len,
+            entries_read: 0,
+        })
+    }
+
+    /// Number of archive bytes processed so far (including the prefix).
+    #[inline]
+    pub fn bytes_consumed(&self) -> usize {
+        self.total_len - self.data.len()
+    }
+
+    /// Total size of the archive package in bytes.
+    #[inline]
+    pub fn total_len(&self) -> usize {
+        self.total_len
+    }
+
+    /// Number of successfully parsed archive entries.
+    #[inline]
+    pub fn entries_read(&self) -> usize {
+        self.entries_read
     }
 }
 
@@ -22,7 +47,11 @@ impl<'a> Iterator for ArchiveReader<'a> {
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        read_next_entry(&mut self.data)
+        let entry = read_next_entry(&mut self.data);
+        if let Some(Ok(_)) = &entry {
+            self.entries_read += 1;
+        }
+        entry
     }
 }
 
@@ -176,3 +205,40 @@ pub enum ArchiveReaderError {
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
+
+#[cfg(test)]
+mod tests {
+    use tycho_util::compression::zstd_decompress;
+
+    use super::*;
+
+    #[test]
+    fn reader_progress_is_monotonic() -> anyhow::Result<()> {
+        let compressed = include_bytes!("../../../core/tests/data/archive_1.bin");
+        let mut data = Vec::new();
+        zstd_decompress(compressed, &mut data)?;
+
+        let mut reader = ArchiveReader::new(&data)?;
+        assert_eq!(reader.bytes_consumed(), ARCHIVE_PREFIX.len());
+        assert_eq!(reader.entries_read(), 0);
+        assert_eq!(reader.total_len(), data.len());
+
+        let mut prev_consumed = reader.bytes_consumed();
+        let mut prev_entries = reader.entries_read();
+        while let Some(entry) = reader.next() {
+            let entry = entry?;
+            assert_eq!(reader.entries_read(), prev_entries + 1);
+            assert_eq!(
+                reader.bytes_consumed(),
+                prev_consumed + ARCHIVE_ENTRY_HEADER_LEN + entry.data.len()
+            );
+            prev_consumed = reader.bytes_consumed();
+            prev_entries = reader.entries_read();
+        }
+
+        assert!(prev_entries > 0);
+        assert_eq!(reader.bytes_consumed(), data.len());
+
+        Ok(())
+    }
+}
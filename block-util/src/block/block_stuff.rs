@@ -1,7 +1,7 @@
 use std::sync::{Arc, OnceLock};
 use std::time::Duration;
 
-use anyhow::Result;
+use anyhow::{ensure, Result};
 use bytes::Bytes;
 use everscale_types::models::*;
 use everscale_types::prelude::*;
@@ -245,6 +245,37 @@ impl BlockStuff {
             .collect()
     }
 
+    /// Ids of the top shard blocks referenced by this masterchain block.
+    ///
+    /// Fails if some shard description is inconsistent with the masterchain block.
+    pub fn shard_block_ids(&self) -> Result<Vec<BlockId>> {
+        let mc_seqno = self.id().seqno;
+
+        let mut result = Vec::new();
+        for item in self.load_custom()?.shards.iter() {
+            let (shard, descr) = item?;
+            ensure!(
+                !shard.is_masterchain(),
+                "masterchain in shard descriptions of block {}",
+                self.id()
+            );
+            ensure!(
+                descr.reg_mc_seqno <= mc_seqno,
+                "shard block {shard}:{} is registered in a future mc block {} (current {mc_seqno})",
+                descr.seqno,
+                descr.reg_mc_seqno,
+            );
+
+            result.push(BlockId {
+                shard,
+                seqno: descr.seqno,
+                root_hash: descr.root_hash,
+                file_hash: descr.file_hash,
+            });
+        }
+        Ok(result)
+    }
+
     pub fn shard_blocks_seqno(&self) -> Result<FastHashMap<ShardIdent, u32>> {
         self.load_custom()?
             .shards
@@ -289,3 +320,47 @@ pub struct Inner {
     block_mc_extra: OnceLock<Result<McBlockExtra, everscale_types::error::Error>>,
     data_size: usize,
 }
+
+#[cfg(test)]
+mod tests {
+    use tycho_util::compression::zstd_decompress;
+
+    use super::*;
+    use crate::archive::{ArchiveEntryType, ArchiveReader};
+
+    #[test]
+    fn shard_block_ids_match_descriptions() -> Result<()> {
+        let compressed = include_bytes!("../../../core/tests/data/archive_1.bin");
+        let mut data = Vec::new();
+        zstd_decompress(compressed, &mut data)?;
+
+        let mut mc_blocks = 0;
+        for entry in ArchiveReader::new(&data)? {
+            let entry = entry?;
+            if entry.ty != ArchiveEntryType::Block || !entry.block_id.is_masterchain() {
+                continue;
+            }
+            mc_blocks += 1;
+
+            let block = BlockStuff::deserialize_checked(&entry.block_id, entry.data)?;
+            let ids = block.shard_block_ids()?;
+
+            let shard_blocks = block.shard_blocks()?;
+            assert_eq!(ids.len(), shard_blocks.len());
+            for id in &ids {
+                assert_eq!(shard_blocks.get(&id.shard), Some(id));
+            }
+
+            for item in block.load_custom()?.shards.iter() {
+                let (shard, descr) = item?;
+                let id = ids.iter().find(|id| id.shard == shard).unwrap();
+                assert_eq!(id.seqno, descr.seqno);
+                assert_eq!(id.root_hash, descr.root_hash);
+                assert_eq!(id.file_hash, descr.file_hash);
+            }
+        }
+        assert!(mc_blocks > 0);
+
+        Ok(())
+    }
+}
@@ -1,5 +1,7 @@
 pub use self::min_ref_mc_state::{MinRefMcStateTracker, RefMcStateHandle};
 pub use self::shard_state_stuff::ShardStateStuff;
+pub use self::state_diff::StateDiff;
 
 mod min_ref_mc_state;
 mod shard_state_stuff;
+mod state_diff;
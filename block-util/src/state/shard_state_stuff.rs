@@ -5,7 +5,7 @@ use everscale_types::cell::Lazy;
 use everscale_types::models::*;
 use everscale_types::prelude::*;
 
-use crate::state::{MinRefMcStateTracker, RefMcStateHandle};
+use crate::state::{MinRefMcStateTracker, RefMcStateHandle, StateDiff};
 
 /// Parsed shard state.
 #[derive(Clone)]
@@ -28,6 +28,15 @@ impl ShardStateStuff {
         root: Cell,
         tracker: &MinRefMcStateTracker,
     ) -> Result<Self> {
+        // NOTE: Only zerostate ids refer to the state root,
+        // for other blocks `root_hash` is a hash of the block itself.
+        anyhow::ensure!(
+            block_id.seqno != 0 || &block_id.root_hash == root.repr_hash(),
+            "root_hash mismatch for {block_id}. Expected: {expected}, got: {got}",
+            expected = block_id.root_hash,
+            got = root.repr_hash(),
+        );
+
         let shard_state = root.parse::<Box<ShardStateUnsplit>>()?;
         Self::from_state_and_root(block_id, shard_state, root, tracker)
     }
@@ -147,6 +156,13 @@ impl ShardStateStuff {
 
         Ok(res)
     }
+
+    /// Summarizes differences with the `other` state.
+    ///
+    /// NOTE: Traverses both accounts dictionaries, use only for diagnostics.
+    pub fn diff(&self, other: &Self) -> Result<StateDiff> {
+        StateDiff::compute(self.state(), other.state())
+    }
 }
 
 impl AsRef<ShardStateUnsplit> for ShardStateStuff {
@@ -211,4 +227,80 @@ mod tests {
         }
         assert_eq!(state.seqno(), None);
     }
+
+    #[test]
+    fn zerostate_root_hash_mismatch() -> Result<()> {
+        let state = ShardStateUnsplit {
+            shard_ident: ShardIdent::MASTERCHAIN,
+            seqno: 0,
+            ..Default::default()
+        };
+        let root = CellBuilder::build_from(&state)?;
+        let tracker = MinRefMcStateTracker::new();
+
+        let mut block_id = BlockId {
+            shard: state.shard_ident,
+            seqno: state.seqno,
+            root_hash: HashBytes([0xaa; 32]),
+            file_hash: Default::default(),
+        };
+        let err = ShardStateStuff::from_root(&block_id, root.clone(), &tracker)
+            .err()
+            .expect("mismatched root must be rejected");
+        assert!(err.to_string().contains("root_hash mismatch"), "{err}");
+
+        block_id.root_hash = *root.repr_hash();
+        ShardStateStuff::from_root(&block_id, root, &tracker)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn diff_states_with_one_changed_account() -> Result<()> {
+        fn make_state(accounts: &[(u8, u64)]) -> Result<ShardStateStuff> {
+            let mut shard_accounts = ShardAccounts::new();
+            for &(id, last_trans_lt) in accounts {
+                shard_accounts.set(
+                    HashBytes([id; 32]),
+                    DepthBalanceInfo {
+                        split_depth: 0,
+                        balance: Default::default(),
+                    },
+                    ShardAccount {
+                        account: Lazy::new(&OptionalAccount::EMPTY)?,
+                        last_trans_hash: HashBytes([id; 32]),
+                        last_trans_lt,
+                    },
+                )?;
+            }
+
+            let block_id = BlockId {
+                shard: ShardIdent::BASECHAIN,
+                seqno: 1,
+                root_hash: Default::default(),
+                file_hash: Default::default(),
+            };
+            let state = ShardStateUnsplit {
+                shard_ident: block_id.shard,
+                seqno: block_id.seqno,
+                accounts: Lazy::new(&shard_accounts)?,
+                ..Default::default()
+            };
+
+            ShardStateStuff::from_state(&block_id, Box::new(state), &MinRefMcStateTracker::new())
+        }
+
+        let left = make_state(&[(1, 10), (2, 20), (3, 30)])?;
+        let right = make_state(&[(1, 10), (2, 21), (3, 30)])?;
+
+        assert!(left.diff(&left)?.is_empty());
+
+        let diff = left.diff(&right)?;
+        assert!(diff.fields.is_empty());
+        assert!(diff.added_accounts.is_empty());
+        assert!(diff.removed_accounts.is_empty());
+        assert_eq!(diff.changed_accounts, vec![HashBytes([2; 32])]);
+
+        Ok(())
+    }
 }
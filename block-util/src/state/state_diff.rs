@@ -0,0 +1,108 @@
+use anyhow::Result;
+use everscale_types::models::*;
+use everscale_types::prelude::*;
+
+/// High-level summary of differences between two shard states.
+///
+/// NOTE: Computing it requires a full traversal of both accounts
+/// dictionaries, so it is intended for diagnostics only.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct StateDiff {
+    /// Names of differing `ShardStateUnsplit` fields (excluding accounts).
+    pub fields: Vec<&'static str>,
+    /// Accounts present only in the other state.
+    pub added_accounts: Vec<HashBytes>,
+    /// Accounts present only in this state.
+    pub removed_accounts: Vec<HashBytes>,
+    /// Accounts present in both states but with a different content.
+    pub changed_accounts: Vec<HashBytes>,
+}
+
+impl StateDiff {
+    pub fn compute(this: &ShardStateUnsplit, other: &ShardStateUnsplit) -> Result<Self> {
+        let mut diff = Self::default();
+        diff.diff_fields(this, other);
+
+        if this.accounts.inner().repr_hash() != other.accounts.inner().repr_hash() {
+            diff.diff_accounts(&this.load_accounts()?, &other.load_accounts()?)?;
+        }
+
+        Ok(diff)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+            && self.added_accounts.is_empty()
+            && self.removed_accounts.is_empty()
+            && self.changed_accounts.is_empty()
+    }
+
+    fn diff_fields(&mut self, this: &ShardStateUnsplit, other: &ShardStateUnsplit) {
+        macro_rules! cmp_fields {
+            ($($field:ident),*$(,)?) => {$(
+                if this.$field != other.$field {
+                    self.fields.push(stringify!($field));
+                }
+            )*};
+        }
+
+        cmp_fields! {
+            global_id,
+            shard_ident,
+            seqno,
+            vert_seqno,
+            gen_utime,
+            gen_utime_ms,
+            gen_lt,
+            min_ref_mc_seqno,
+            before_split,
+            overload_history,
+            underload_history,
+            total_balance,
+            total_validator_fees,
+            master_ref,
+        }
+
+        // Cell-backed fields are compared by their hashes
+        if this.processed_upto.inner().repr_hash() != other.processed_upto.inner().repr_hash() {
+            self.fields.push("processed_upto");
+        }
+        if this.libraries.root().as_ref().map(Cell::repr_hash)
+            != other.libraries.root().as_ref().map(Cell::repr_hash)
+        {
+            self.fields.push("libraries");
+        }
+        let custom_hash = |s: &ShardStateUnsplit| s.custom.as_ref().map(|c| *c.inner().repr_hash());
+        if custom_hash(this) != custom_hash(other) {
+            self.fields.push("custom");
+        }
+    }
+
+    fn diff_accounts(&mut self, this: &ShardAccounts, other: &ShardAccounts) -> Result<()> {
+        for entry in this.iter() {
+            let (id, (_, account)) = entry?;
+            match other.get(id)? {
+                None => self.removed_accounts.push(id),
+                Some((_, other_account)) if !same_account(&account, &other_account) => {
+                    self.changed_accounts.push(id);
+                }
+                Some(_) => {}
+            }
+        }
+
+        for entry in other.iter() {
+            let (id, _) = entry?;
+            if this.get(id)?.is_none() {
+                self.added_accounts.push(id);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn same_account(a: &ShardAccount, b: &ShardAccount) -> bool {
+    a.last_trans_lt == b.last_trans_lt
+        && a.last_trans_hash == b.last_trans_hash
+        && a.account.inner().repr_hash() == b.account.inner().repr_hash()
+}
@@ -0,0 +1,46 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
+
+use crate::node::NodeConfig;
+use crate::BaseArgs;
+
+/// Inspect the node config.
+#[derive(Parser)]
+pub struct Cmd {
+    #[clap(subcommand)]
+    cmd: SubCmd,
+}
+
+impl Cmd {
+    pub fn run(self, args: BaseArgs) -> Result<()> {
+        match self.cmd {
+            SubCmd::Show(cmd) => cmd.run(args),
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum SubCmd {
+    Show(CmdShow),
+}
+
+/// Print the effective node config with all defaults resolved.
+#[derive(Parser)]
+struct CmdShow {
+    /// Path to the node config. Default: `$TYCHO_HOME/config.json`
+    #[clap(long)]
+    config: Option<PathBuf>,
+}
+
+impl CmdShow {
+    fn run(self, args: BaseArgs) -> Result<()> {
+        let node_config = NodeConfig::from_file(args.node_config_path(self.config.as_ref()))
+            .context("failed to load node config")?
+            .with_relative_paths(&args.home);
+
+        println!("{}", node_config.to_json()?);
+        Ok(())
+    }
+}
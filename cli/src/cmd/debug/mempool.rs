@@ -16,7 +16,7 @@ use tycho_consensus::prelude::{
 };
 use tycho_consensus::test_utils::{test_logger, AnchorConsumer, LastAnchorFile};
 use tycho_core::block_strider::{FileZerostateProvider, ZerostateProvider};
-use tycho_core::global_config::{GlobalConfig, ZerostateId};
+use tycho_core::global_config::{GlobalConfig, ZerostateIds};
 use tycho_network::PeerId;
 use tycho_storage::{FileDb, NewBlockMeta, Storage};
 use tycho_util::cli::logger::init_logger;
@@ -330,13 +330,16 @@ impl Mempool {
 async fn load_mc_zerostate(
     provider: FileZerostateProvider,
     storage: &Storage,
-    mc_zerostate_id: &ZerostateId,
+    mc_zerostate_ids: &ZerostateIds,
 ) -> Result<ShardStateStuff> {
     let zerostates = provider
         .load_zerostates(storage.shard_state_storage().min_ref_mc_state())
         .collect::<Result<Vec<_>, _>>()?;
 
-    let mc_block_id = mc_zerostate_id.as_block_id();
+    let mc_block_id = mc_zerostate_ids
+        .find_first(|id| zerostates.iter().any(|state| state.block_id() == id))
+        .context("no masterchain zerostate provided")?
+        .as_block_id();
 
     let mc_zerostate = zerostates
         .into_iter()
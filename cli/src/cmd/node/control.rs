@@ -33,6 +33,7 @@ pub enum CmdControl {
     GcBlocks(CmdGcBlocks),
     GcStates(CmdGcStates),
     Compact(CmdCompact),
+    CompactionStatus(CmdCompactionStatus),
     #[clap(subcommand)]
     MemProfiler(CmdMemProfiler),
 }
@@ -55,6 +56,7 @@ impl CmdControl {
             Self::GcBlocks(cmd) => cmd.run(args),
             Self::GcStates(cmd) => cmd.run(args),
             Self::Compact(cmd) => cmd.run(args),
+            Self::CompactionStatus(cmd) => cmd.run(args),
             Self::MemProfiler(cmd) => cmd.run(args),
         }
     }
@@ -303,6 +305,22 @@ impl CmdCompact {
     }
 }
 
+/// Get the progress of a manual compaction.
+#[derive(Parser)]
+pub struct CmdCompactionStatus {
+    #[clap(flatten)]
+    args: ControlArgs,
+}
+
+impl CmdCompactionStatus {
+    pub fn run(self, args: BaseArgs) -> Result<()> {
+        self.args.rt(args, |client| async move {
+            let status = client.get_compaction_status().await?;
+            print_json(status)
+        })
+    }
+}
+
 /// Manage memory profiler.
 #[derive(Subcommand)]
 pub enum CmdMemProfiler {
@@ -1,12 +1,16 @@
 use std::net::SocketAddr;
 use std::path::PathBuf;
+use std::time::Duration;
 
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
+use tokio::sync::watch;
+use tycho_consensus::prelude::MempoolNodeConfig;
 use tycho_core::global_config::GlobalConfig;
 use tycho_util::cli::logger::{init_logger, set_abort_with_tracing};
 use tycho_util::cli::metrics::init_metrics;
 use tycho_util::cli::{resolve_public_ip, signal};
+use tycho_util::futures::JoinTask;
 
 pub use self::control::CmdControl;
 use crate::node::{Node, NodeConfig, NodeKeys};
@@ -106,6 +110,9 @@ impl CmdRun {
             init_metrics(metrics_config)?;
         }
 
+        let node_config_path = args.node_config_path(self.config.as_ref());
+        let mempool_config = node_config.mempool.clone();
+
         let node = {
             let global_config =
                 GlobalConfig::from_file(args.global_config_path(self.global_config.as_ref()))
@@ -138,10 +145,14 @@ impl CmdRun {
                 node_config,
                 global_config,
                 control_socket,
+                self.import_zerostate.as_deref(),
             )
             .await?
         };
 
+        // NOTE: Mempool node config is initialized by the node.
+        let _mempool_config_watcher = watch_mempool_node_config(node_config_path, mempool_config);
+
         node.wait_for_neighbours().await;
 
         let init_block_id = node
@@ -156,3 +167,61 @@ impl CmdRun {
         Ok(())
     }
 }
+
+/// Applies node-local mempool config changes from the node config file without a restart.
+///
+/// Watching stops when the returned task is dropped.
+fn watch_mempool_node_config(
+    node_config_path: PathBuf,
+    initial: MempoolNodeConfig,
+) -> JoinTask<()> {
+    let (updates_tx, updates_rx) = watch::channel(initial);
+    let follow = tycho_consensus::prelude::NodeConfig::follow(updates_rx);
+
+    let watch_file = async move {
+        tracing::info!(
+            node_config = %node_config_path.display(),
+            "started watching for changes in mempool node config"
+        );
+
+        let get_metadata = || {
+            std::fs::metadata(&node_config_path)
+                .ok()
+                .and_then(|m| m.modified().ok())
+        };
+
+        let mut last_modified = get_metadata();
+
+        let mut interval = tokio::time::interval(Duration::from_secs(10));
+        loop {
+            interval.tick().await;
+
+            let modified = get_metadata();
+            if last_modified == modified {
+                continue;
+            }
+            last_modified = modified;
+
+            let mempool = match NodeConfig::from_file(&node_config_path) {
+                Ok(config) => config.mempool,
+                Err(e) => {
+                    tracing::error!(%e, "failed to reload node config");
+                    continue;
+                }
+            };
+
+            updates_tx.send_if_modified(|current| {
+                let changed = *current != mempool;
+                *current = mempool;
+                changed
+            });
+        }
+    };
+
+    JoinTask::new(async move {
+        tokio::select! {
+            _ = follow => {},
+            _ = watch_file => {},
+        }
+    })
+}
@@ -8,6 +8,7 @@ use anyhow::Result;
 use clap::{Args, Parser, Subcommand};
 
 mod cmd {
+    pub mod config;
     #[cfg(feature = "debug")]
     pub mod debug;
     pub mod elect;
@@ -68,6 +69,7 @@ enum Cmd {
     Node(cmd::node::Cmd),
     Tool(cmd::tools::Cmd),
     Elect(cmd::elect::Cmd),
+    Config(cmd::config::Cmd),
     #[cfg(feature = "debug")]
     Debug(cmd::debug::Cmd),
     Util(cmd::util::Cmd),
@@ -80,6 +82,7 @@ impl Cmd {
             Cmd::Node(cmd) => cmd.run(args),
             Cmd::Tool(cmd) => cmd.run(),
             Cmd::Elect(cmd) => cmd.run(args),
+            Cmd::Config(cmd) => cmd.run(args),
             #[cfg(feature = "debug")]
             Cmd::Debug(cmd) => cmd.run(),
             Cmd::Util(cmd) => cmd.run(),
@@ -1,4 +1,4 @@
-use std::net::{IpAddr, Ipv4Addr};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::path::{Path, PathBuf};
 
 use anyhow::Result;
@@ -341,11 +341,39 @@ impl NodeConfig {
     }
 
     pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
-        let data = serde_json::to_string_pretty(self)?;
-        std::fs::write(path, data)?;
+        std::fs::write(path, self.to_json()?)?;
         Ok(())
     }
 
+    /// Serializes the config with all fields, including the resolved defaults.
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(Into::into)
+    }
+
+    /// Checks that the public address, advertised to other nodes,
+    /// is consistent with the address the node listens on.
+    pub fn check_public_addr(&self, public_addr: &SocketAddr) -> Vec<PublicAddrIssue> {
+        let public_ip = public_addr.ip();
+        let mut issues = Vec::new();
+
+        if public_ip.is_unspecified() {
+            issues.push(PublicAddrIssue::Unspecified(public_ip));
+        } else if public_ip.is_loopback() {
+            issues.push(PublicAddrIssue::Loopback(public_ip));
+        } else if is_private_ip(&public_ip) {
+            issues.push(PublicAddrIssue::Private(public_ip));
+        }
+
+        if self.local_ip.is_loopback() && !public_ip.is_loopback() {
+            issues.push(PublicAddrIssue::LoopbackListener {
+                local_ip: self.local_ip,
+                public_ip,
+            });
+        }
+
+        issues
+    }
+
     pub fn with_relative_paths<P: AsRef<Path>>(mut self, base_dir: P) -> Self {
         let base_dir = base_dir.as_ref();
 
@@ -361,7 +389,125 @@ impl NodeConfig {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PublicAddrIssue {
+    /// Other nodes will not be able to connect to the node.
+    Unspecified(IpAddr),
+    /// Only nodes on the same host will be able to connect to the node.
+    Loopback(IpAddr),
+    /// Only nodes from the same private network will be able to connect to the node.
+    Private(IpAddr),
+    /// Node listens on loopback only, so the public address is unreachable.
+    LoopbackListener { local_ip: IpAddr, public_ip: IpAddr },
+}
+
+impl PublicAddrIssue {
+    /// Whether the node is certainly unreachable with such config.
+    ///
+    /// Loopback and private public addresses are fine for local networks.
+    pub fn is_fatal(&self) -> bool {
+        !matches!(self, Self::Loopback(_) | Self::Private(_))
+    }
+}
+
+impl std::fmt::Display for PublicAddrIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unspecified(ip) => write!(f, "public ip {ip} is unspecified"),
+            Self::Loopback(ip) => write!(
+                f,
+                "public ip {ip} is a loopback address, \
+                only local nodes will be able to connect"
+            ),
+            Self::Private(ip) => write!(
+                f,
+                "public ip {ip} is a private address, \
+                only nodes from the same network will be able to connect"
+            ),
+            Self::LoopbackListener {
+                local_ip,
+                public_ip,
+            } => write!(
+                f,
+                "local ip {local_ip} is a loopback address, \
+                public ip {public_ip} will not be reachable"
+            ),
+        }
+    }
+}
+
+fn is_private_ip(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => ip.is_private() || ip.is_link_local(),
+        // NOTE: `Ipv6Addr::is_unique_local` is not stable yet
+        IpAddr::V6(ip) => (ip.segments()[0] & 0xfe00) == 0xfc00,
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct MemoryProfilingConfig {
     pub profiling_dir: PathBuf,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loopback_public_addr() {
+        let config = NodeConfig::default();
+
+        // Fine for a local network
+        let issues = config.check_public_addr(&SocketAddr::from(([127, 0, 0, 1], config.port)));
+        assert_eq!(issues, [PublicAddrIssue::Loopback(
+            Ipv4Addr::LOCALHOST.into()
+        )]);
+        assert!(!issues[0].is_fatal());
+
+        // Public address is unreachable when listening on loopback
+        let config = NodeConfig {
+            local_ip: Ipv4Addr::LOCALHOST.into(),
+            ..Default::default()
+        };
+        let public_ip = IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4));
+        let issues = config.check_public_addr(&SocketAddr::new(public_ip, config.port));
+        assert_eq!(issues, [PublicAddrIssue::LoopbackListener {
+            local_ip: config.local_ip,
+            public_ip,
+        }]);
+        assert!(issues[0].is_fatal());
+    }
+
+    #[test]
+    fn effective_config_roundtrip() {
+        let config: NodeConfig = serde_json::from_str(r#"{ "port": 12345 }"#).unwrap();
+        assert_eq!(config.port, 12345);
+
+        let printed = config.to_json().unwrap();
+        let parsed: NodeConfig = serde_json::from_str(&printed).unwrap();
+
+        let printed: serde_json::Value = serde_json::from_str(&printed).unwrap();
+        let reprinted = serde_json::to_value(&parsed).unwrap();
+        assert_eq!(reprinted, printed);
+
+        // Memory-derived defaults are materialized in the printed config
+        let storage = &printed["storage"];
+        assert!(storage["rocksdb_lru_capacity"].is_string());
+        assert!(storage["cells_cache_size"].is_string());
+    }
+
+    #[test]
+    fn forwarded_public_port() {
+        let config = NodeConfig::default();
+        let public_ip = IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4));
+
+        assert!(config
+            .check_public_addr(&SocketAddr::new(public_ip, config.port))
+            .is_empty());
+
+        // NAT may forward a different public port to the local one
+        assert!(config
+            .check_public_addr(&SocketAddr::new(public_ip, config.port + 1))
+            .is_empty());
+    }
+}
@@ -80,7 +80,16 @@ impl Node {
         node_config: NodeConfig,
         global_config: GlobalConfig,
         control_socket: PathBuf,
+        import_zerostate: Option<&[PathBuf]>,
     ) -> Result<Self> {
+        for issue in node_config.check_public_addr(&public_addr) {
+            anyhow::ensure!(
+                !issue.is_fatal(),
+                "invalid public address {public_addr}: {issue}"
+            );
+            tracing::warn!(%public_addr, "{issue}");
+        }
+
         // Setup network
         let keypair = Arc::new(ed25519::KeyPair::from(&keys.as_secret()));
         let local_id = keypair.public_key.into();
@@ -149,7 +158,9 @@ impl Node {
         );
 
         // Setup blockchain rpc
-        let zerostate = global_config.zerostate;
+        let zerostate =
+            Starter::select_zerostate(&storage, &global_config.zerostate, import_zerostate)?;
+        tracing::info!(zerostate_id = %zerostate.as_block_id(), "selected zerostate");
 
         let rpc_mempool_adapter = RpcMempoolAdapter {
             inner: Arc::new(MempoolAdapterStdImpl::new(
@@ -225,21 +236,22 @@ impl Node {
 
     /// Initialize the node and return the init block id.
     pub async fn boot(&self, zerostates: Option<Vec<PathBuf>>) -> Result<BlockId> {
-        let node_state = self.storage.node_state();
-        let last_mc_block_id = match node_state.load_last_mc_block_id() {
+        let starter = Starter::new(
+            self.storage.clone(),
+            self.blockchain_rpc_client.clone(),
+            self.zerostate,
+            self.starter_config.clone(),
+        );
+
+        let last_mc_block_id = match starter.try_warm_boot().await? {
             Some(block_id) => block_id,
             None => {
-                Starter::new(
-                    self.storage.clone(),
-                    self.blockchain_rpc_client.clone(),
-                    self.zerostate,
-                    self.starter_config.clone(),
-                )
-                .cold_boot(
-                    ColdBootType::LatestPersistent,
-                    zerostates.map(FileZerostateProvider),
-                )
-                .await?
+                starter
+                    .cold_boot(
+                        ColdBootType::LatestPersistent,
+                        zerostates.map(FileZerostateProvider),
+                    )
+                    .await?
             }
         };
 
@@ -443,6 +455,7 @@ impl Node {
 
         let strider_state =
             PersistentBlockStriderState::new(self.zerostate.as_block_id(), self.storage.clone());
+        strider_state.resume_from()?;
 
         let block_strider = BlockStrider::builder()
             .with_provider(
@@ -576,10 +589,10 @@ impl BroadcastListener for RpcMempoolAdapter {
 
     fn handle_message(
         &self,
-        _: Arc<InboundRequestMeta>,
+        meta: Arc<InboundRequestMeta>,
         message: Bytes,
     ) -> Self::HandleMessageFut<'_> {
-        self.inner.send_external(message);
+        self.inner.send_external_from_peer(&meta.peer_id, message);
         futures_util::future::ready(())
     }
 }
@@ -21,7 +21,7 @@ use tycho_util::metrics::HistogramGuard;
 use tycho_util::FastHashMap;
 
 use super::phase::{Phase, PhaseState};
-use super::PrevData;
+use super::{sorted_shards, PrevData};
 use crate::collator::debug_info::BlockDebugInfo;
 use crate::collator::error::{CollationCancelReason, CollatorError};
 use crate::collator::execution_manager::MessagesExecutor;
@@ -918,11 +918,8 @@ impl Phase<FinalizeState> {
             Self::update_shard_config(collation_data, &workchains, update_shard_cc)?;
 
         // 3. save new shard_hashes
-        let shards_iter = collation_data
-            .get_shards()?
-            .iter()
-            .map(|(k, v)| (k, v.as_ref()));
-        let shards = ShardHashes::from_shards(shards_iter)?;
+        let shards = sorted_shards(collation_data.get_shards()?);
+        let shards = ShardHashes::from_shards(shards)?;
 
         // 4. check extension flags
         // prev_state_extra.flags is checked in the McStateExtra::load_from
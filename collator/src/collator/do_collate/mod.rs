@@ -1,4 +1,4 @@
-use std::collections::hash_map;
+use std::collections::{hash_map, BTreeMap};
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -12,6 +12,7 @@ use prepare::PrepareState;
 use tycho_block_util::config::{apply_price_factor, compute_gas_price_factor};
 use tycho_block_util::queue::QueueKey;
 use tycho_block_util::state::MinRefMcStateTracker;
+use tycho_core::global_config::MempoolGlobalConfig;
 use tycho_storage::{NewBlockMeta, StoreStateHint};
 use tycho_util::futures::JoinTask;
 use tycho_util::metrics::HistogramGuard;
@@ -652,11 +653,12 @@ impl CollatorStdImpl {
             "import_new_shard_top_blocks_for_masterchain",
         );
 
-        // convert to map for merging
+        // convert to map for merging,
+        // ordered by shard to build the same block regardless of input order
         let top_shard_blocks_info_map = top_shard_blocks_info
             .into_iter()
             .map(|info| (info.block_id.shard, info))
-            .collect::<FastHashMap<_, _>>();
+            .collect::<BTreeMap<_, _>>();
 
         // update existing shard descriptions for which top blocks were not changed
         for (shard_id, prev_shard_descr) in collation_data_builder.shards_mut()? {
@@ -817,6 +819,33 @@ impl CollatorStdImpl {
         mc_data: &Arc<McData>,
         prev_shard_data: &PrevData,
         top_shard_blocks_info: Option<Vec<TopBlockDescription>>,
+    ) -> Result<Box<BlockCollationData>> {
+        if !self.shard_id.is_masterchain() {
+            self.shard_blocks_count_from_last_anchor =
+                self.shard_blocks_count_from_last_anchor.saturating_add(1);
+        }
+
+        Self::build_collation_data(
+            next_block_id_short,
+            next_chain_time,
+            created_by,
+            mc_data,
+            prev_shard_data,
+            top_shard_blocks_info,
+            self.mempool_config_override.clone(),
+        )
+    }
+
+    /// Builds collation data only from the passed inputs
+    /// so that the same inputs produce the same block.
+    fn build_collation_data(
+        next_block_id_short: BlockIdShort,
+        next_chain_time: u64,
+        created_by: HashBytes,
+        mc_data: &Arc<McData>,
+        prev_shard_data: &PrevData,
+        top_shard_blocks_info: Option<Vec<TopBlockDescription>>,
+        mempool_config_override: Option<MempoolGlobalConfig>,
     ) -> Result<Box<BlockCollationData>> {
         // need to generate unique for each block
         // generate seed from the chain_time from the anchor
@@ -827,12 +856,7 @@ impl CollatorStdImpl {
         }));
         tracing::trace!(target: tracing_targets::COLLATOR, "rand_seed from chain time: {}", rand_seed);
 
-        let is_masterchain = self.shard_id.is_masterchain();
-
-        if !is_masterchain {
-            self.shard_blocks_count_from_last_anchor =
-                self.shard_blocks_count_from_last_anchor.saturating_add(1);
-        }
+        let is_masterchain = next_block_id_short.shard.is_masterchain();
 
         // prepare block collation data
         let block_limits = mc_data.config.get_block_limits(is_masterchain)?;
@@ -847,7 +871,7 @@ impl CollatorStdImpl {
             mc_data.block_id.seqno,
             next_chain_time,
             created_by,
-            self.mempool_config_override.clone(),
+            mempool_config_override,
         );
 
         // init ShardHashes descriptions for master
@@ -939,7 +963,9 @@ impl CollatorStdImpl {
             let new_queue_diff_hash = *finalized.block_candidate.queue_diff_aug.diff_hash();
 
             let collation_config = match &finalized.mc_data {
-                Some(mcd) => Arc::new(mcd.config.get_collation_config()?),
+                Some(mcd) => {
+                    Arc::new((self.config).collation_config_for(&self.shard_id, &mcd.config)?)
+                }
                 None => finalized.collation_config,
             };
 
@@ -1191,6 +1217,21 @@ impl CollatorStdImpl {
     }
 }
 
+/// Returns shard descriptions ordered by shard ident.
+///
+/// Hash map iteration order is not stable between nodes,
+/// so anything that ends up in a block must be built from a sorted view.
+fn sorted_shards(
+    shards: &FastHashMap<ShardIdent, Box<ShardDescription>>,
+) -> Vec<(&ShardIdent, &ShardDescription)> {
+    let mut shards = shards
+        .iter()
+        .map(|(k, v)| (k, v.as_ref()))
+        .collect::<Vec<_>>();
+    shards.sort_unstable_by_key(|(k, _)| *k);
+    shards
+}
+
 fn calculate_min_internals_processed_to_for_shard(
     shard_id: &ShardIdent,
     shard_min_processed_to: Option<QueueKey>,
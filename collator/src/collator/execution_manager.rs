@@ -128,6 +128,7 @@ impl MessagesExecutor {
             })
             .collect_vec_list();
 
+        // results are collected in the deterministic order of accounts in the group
         for result in result {
             for executed in result {
                 self.save_subgroup_result(
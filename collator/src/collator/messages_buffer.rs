@@ -746,23 +746,35 @@ impl MessageGroup {
     pub fn contains_account(&self, account_id: &HashBytes) -> bool {
         self.msgs.contains_key(account_id)
     }
+
+    /// Returns account messages ordered by account address.
+    /// Messages of each account keep the order in which they were read from buffers.
+    ///
+    /// The order of the underlying map depends on the hasher and the insertion history,
+    /// so it must not leak into the order in which executed messages are collected.
+    #[allow(clippy::vec_box)]
+    fn into_ordered(self) -> Vec<(HashBytes, Vec<Box<ParsedMessage>>)> {
+        let mut msgs: Vec<_> = self.msgs.into_iter().collect();
+        msgs.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+        msgs
+    }
 }
 
 impl IntoParallelIterator for MessageGroup {
     type Item = (HashBytes, Vec<Box<ParsedMessage>>);
-    type Iter = rayon::collections::hash_map::IntoIter<HashBytes, Vec<Box<ParsedMessage>>>;
+    type Iter = rayon::vec::IntoIter<Self::Item>;
 
     fn into_par_iter(self) -> Self::Iter {
-        self.msgs.into_par_iter()
+        self.into_ordered().into_par_iter()
     }
 }
 
 impl IntoIterator for MessageGroup {
     type Item = (HashBytes, Vec<Box<ParsedMessage>>);
-    type IntoIter = std::collections::hash_map::IntoIter<HashBytes, Vec<Box<ParsedMessage>>>;
+    type IntoIter = std::vec::IntoIter<Self::Item>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.msgs.into_iter()
+        self.into_ordered().into_iter()
     }
 }
 
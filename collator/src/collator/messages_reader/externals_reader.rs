@@ -978,25 +978,25 @@ impl ExternalsRangeReader {
                 self.msgs_exec_params.externals_expire_timeout as u64 * 1000;
 
             if next_chain_time.saturating_sub(anchor.chain_time) > externals_expire_timeout_ms {
-                let iter = anchor.iter_externals(msgs_read_offset_in_last_anchor as usize);
+                let iter = anchor.externals_for_shard(
+                    &self.for_shard_id,
+                    msgs_read_offset_in_last_anchor as usize,
+                );
                 let mut expired_msgs_count = 0;
                 for ext_msg in iter {
-                    if self.for_shard_id.contains_address(&ext_msg.info.dst) {
-                        tracing::trace!(target: tracing_targets::COLLATOR,
-                            anchor_id,
-                            anchor_chain_time = anchor.chain_time,
-                            next_chain_time,
-                            "ext_msg hash: {}, dst: {} is expired by timeout {} ms",
-                            ext_msg.hash(), ext_msg.info.dst, externals_expire_timeout_ms,
-                        );
-                        expired_msgs_count += 1;
+                    tracing::trace!(target: tracing_targets::COLLATOR,
+                        anchor_id,
+                        anchor_chain_time = anchor.chain_time,
+                        next_chain_time,
+                        "ext_msg hash: {}, dst: {} is expired by timeout {} ms",
+                        ext_msg.hash(), ext_msg.info.dst, externals_expire_timeout_ms,
+                    );
+                    expired_msgs_count += 1;
 
-                        // update expired messages count in reader metrics
-                        let target_partition =
-                            partition_router.get_partition(None, &ext_msg.info.dst);
-                        let par_metrics = metrics_by_partitions.get_mut(target_partition);
-                        par_metrics.expired_ext_msgs_count += 1;
-                    }
+                    // update expired messages count in reader metrics
+                    let target_partition = partition_router.get_partition(None, &ext_msg.info.dst);
+                    let par_metrics = metrics_by_partitions.get_mut(target_partition);
+                    par_metrics.expired_ext_msgs_count += 1;
                 }
 
                 metrics::counter!("tycho_do_collate_ext_msgs_expired_count", &labels)
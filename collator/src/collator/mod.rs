@@ -22,7 +22,10 @@ use tycho_util::futures::JoinTask;
 use tycho_util::metrics::{HistogramGuard, HistogramGuardWithLabels};
 use types::{AnchorInfo, AnchorsCache};
 
-use self::types::{BlockSerializerCache, CollatorStats, PrevData, WorkingState};
+use self::types::{
+    should_import_next_anchor, BlockSerializerCache, CollatorStats, EmptyAttemptsBackoff, PrevData,
+    WorkingState,
+};
 use crate::internal_queue::types::EnqueuedMessage;
 use crate::mempool::{GetAnchorResult, MempoolAdapter, MempoolAnchorId};
 use crate::queue_adapter::MessageQueueAdapter;
@@ -72,6 +75,8 @@ pub struct CollatorContext {
 
     /// For graceful collation cancellation
     pub cancel_collation: Arc<Notify>,
+    /// To end the empty attempts backoff when new master data is enqueued
+    pub mc_data_updated: Arc<Notify>,
 }
 
 #[async_trait]
@@ -166,6 +171,7 @@ impl CollatorFactory for CollatorStdImplFactory {
             cx.mc_data,
             cx.mempool_config_override,
             cx.cancel_collation,
+            cx.mc_data_updated,
         )
         .await
     }
@@ -237,12 +243,15 @@ pub struct CollatorStdImpl {
     timer: std::time::Instant,
     anchor_timer: std::time::Instant,
     shard_blocks_count_from_last_anchor: u16,
+    empty_attempts_backoff: EmptyAttemptsBackoff,
 
     /// Mempool config override for a new genesis
     mempool_config_override: Option<MempoolGlobalConfig>,
 
     /// For graceful collation cancellation
     cancel_collation: Arc<Notify>,
+    /// To end the empty attempts backoff when new master data is enqueued
+    mc_data_updated: Arc<Notify>,
 }
 
 impl CollatorStdImpl {
@@ -259,6 +268,7 @@ impl CollatorStdImpl {
         mc_data: Arc<McData>,
         mempool_config_override: Option<MempoolGlobalConfig>,
         cancel_collation: Arc<Notify>,
+        mc_data_updated: Arc<Notify>,
     ) -> Result<AsyncQueuedDispatcher<Self>> {
         const BLOCK_CELL_COUNT_BASELINE: usize = 100_000;
 
@@ -292,8 +302,10 @@ impl CollatorStdImpl {
             timer: std::time::Instant::now(),
             anchor_timer: std::time::Instant::now(),
             shard_blocks_count_from_last_anchor: 0,
+            empty_attempts_backoff: EmptyAttemptsBackoff::default(),
             mempool_config_override,
             cancel_collation,
+            mc_data_updated,
         };
 
         // create dispatcher for own async tasks queue
@@ -359,6 +371,7 @@ impl CollatorStdImpl {
         // init working state
         let mut working_state = Self::init_working_state(
             &self.next_block_info,
+            &self.config,
             self.state_node_adapter.clone(),
             mc_data,
             prev_blocks_ids,
@@ -607,7 +620,8 @@ impl CollatorStdImpl {
 
             // update mc_data if newer
             if working_state.mc_data.block_id.seqno < mc_data.block_id.seqno {
-                working_state.collation_config = Arc::new(mc_data.config.get_collation_config()?);
+                working_state.collation_config =
+                    Arc::new((self.config).collation_config_for(&self.shard_id, &mc_data.config)?);
                 working_state.mc_data = mc_data;
 
                 if working_state.has_unprocessed_messages == Some(false) {
@@ -648,6 +662,7 @@ impl CollatorStdImpl {
             );
             let mut working_state = Self::init_working_state(
                 &self.next_block_info,
+                &self.config,
                 self.state_node_adapter.clone(),
                 mc_data,
                 new_prev_blocks_ids,
@@ -757,6 +772,7 @@ impl CollatorStdImpl {
     #[tracing::instrument(skip_all, fields(next_block_id = %next_block_id_short))]
     async fn init_working_state(
         next_block_id_short: &BlockIdShort,
+        config: &CollatorConfig,
         state_node_adapter: Arc<dyn StateNodeAdapter>,
         mc_data: Arc<McData>,
         prev_blocks_ids: Vec<BlockId>,
@@ -772,7 +788,12 @@ impl CollatorStdImpl {
         // build and validate working state
         tracing::debug!(target: tracing_targets::COLLATOR, "building working state...");
 
-        Self::build_and_validate_init_working_state(mc_data, prev_states, prev_queue_diff_hashes)
+        Self::build_and_validate_init_working_state(
+            config,
+            mc_data,
+            prev_states,
+            prev_queue_diff_hashes,
+        )
     }
 
     async fn reload_prev_data(
@@ -960,6 +981,7 @@ impl CollatorStdImpl {
     ///
     /// Perform some validations on state
     fn build_and_validate_init_working_state(
+        config: &CollatorConfig,
         mc_data: Arc<McData>,
         prev_states: Vec<ShardStateStuff>,
         prev_queue_diff_hashes: Vec<HashBytes>,
@@ -970,7 +992,8 @@ impl CollatorStdImpl {
 
         let next_block_id_short = calc_next_block_id_short(prev_shard_data.blocks_ids());
 
-        let collation_config = Arc::new(mc_data.config.get_collation_config()?);
+        let collation_config =
+            Arc::new(config.collation_config_for(&next_block_id_short.shard, &mc_data.config)?);
 
         Ok(Box::new(WorkingState {
             next_block_id_short,
@@ -1767,6 +1790,9 @@ impl CollatorStdImpl {
                 let force_import_anchor_by_used_wu =
                     wu_used_from_last_anchor > wu_used_to_import_next_anchor;
 
+                // import anchors without waiting for used wu to catch up with mempool
+                let collator_lags = self.mpool_adapter.pressure().collator_lags();
+
                 // decide if should import anchors
                 let (last_imported_anchor_id, last_imported_chain_time) = self
                     .anchors_cache
@@ -1775,7 +1801,7 @@ impl CollatorStdImpl {
                 match (
                     has_uprocessed_messages,
                     has_externals,
-                    force_import_anchor_by_used_wu,
+                    force_import_anchor_by_used_wu || collator_lags,
                 ) {
                     (true, _, false) => break 'check TryCollateCheck::HasUnprocessedMessages,
                     (_, true, false) => break 'check TryCollateCheck::HasExternals,
@@ -1795,6 +1821,7 @@ impl CollatorStdImpl {
                             top_processed_to_anchor,
                             last_imported_anchor_id,
                             last_imported_chain_time,
+                            collator_lags,
                             "wu used from last anchor {} reached limit {} on length {}, will import next anchor",
                             wu_used_from_last_anchor, wu_used_to_import_next_anchor,  uncommitted_chain_length,
                         );
@@ -1898,9 +1925,11 @@ impl CollatorStdImpl {
                                     "used wu dropped to",
                                 );
 
-                                if working_state.wu_used_from_last_anchor
-                                    < wu_used_to_import_next_anchor
-                                {
+                                if !should_import_next_anchor(
+                                    working_state.wu_used_from_last_anchor,
+                                    wu_used_to_import_next_anchor,
+                                    self.mpool_adapter.pressure(),
+                                ) {
                                     break;
                                 }
                             }
@@ -1974,12 +2003,31 @@ impl CollatorStdImpl {
                     ForceMasterCollation::No
                 };
 
+                self.empty_attempts_backoff.reset();
+
                 drop(histogram);
                 self.do_collate(working_state, None, force_next_mc_block)
                     .await?;
             }
             TryCollateCheck::NoPendingMessages
             | TryCollateCheck::ForceMcBlockByUncommittedChainLength => {
+                // anchor import waits for mempool, so only skipped import may lead to
+                // immediate attempts without any progress
+                let is_empty_attempt = anchor_import_skipped
+                    && matches!(try_collate_check, TryCollateCheck::NoPendingMessages);
+                if is_empty_attempt {
+                    metrics::counter!("tycho_collator_empty_collation_attempts_count", &labels)
+                        .increment(1);
+
+                    // import is skipped until top processed to anchor advances with new master data,
+                    // manager will handle the cancellation on skip
+                    (self.empty_attempts_backoff)
+                        .back_off(&self.mc_data_updated, &self.cancel_collation)
+                        .await;
+                } else {
+                    self.empty_attempts_backoff.reset();
+                }
+
                 tracing::debug!(target: tracing_targets::COLLATOR,
                     reason = ?try_collate_check,
                     anchor_import_skipped,
@@ -7,10 +7,11 @@ use everscale_types::models::{
     BlockId, BlockchainConfig, CurrencyCollection, ShardIdent, ValidatorInfo,
 };
 use tycho_block_util::state::MinRefMcStateTracker;
+use tycho_network::PeerId;
 
-use crate::collator::types::AnchorsCache;
+use crate::collator::types::{should_import_next_anchor, AnchorsCache, EmptyAttemptsBackoff};
 use crate::collator::{CollatorStdImpl, ImportInitAnchorsResult, InitAnchorSource};
-use crate::mempool::{MempoolAdapterStubImpl, MempoolAnchor, MempoolEventListener};
+use crate::mempool::{MempoolAdapterStubImpl, MempoolAnchor, MempoolEventListener, PressureLevel};
 use crate::test_utils::try_init_test_tracing;
 use crate::types::processed_upto::{
     ExternalsProcessedUptoStuff, ExternalsRangeInfo, ProcessedUptoInfoExtension,
@@ -573,3 +574,170 @@ fn test_get_anchors_processing_info() {
         mc_data.block_id,
     );
 }
+
+#[test]
+fn test_externals_processed_upto_restored_after_restart() {
+    use everscale_types::models::ProcessedUptoInfo;
+
+    use crate::collator::messages_reader::ReaderState;
+
+    let mut processed_upto = ProcessedUptoInfoStuff::default();
+    processed_upto
+        .partitions
+        .insert(0, ProcessedUptoPartitionStuff {
+            externals: ExternalsProcessedUptoStuff {
+                processed_to: (1816, 23429),
+                ranges: [(1005, ExternalsRangeInfo {
+                    skip_offset: 0,
+                    processed_offset: 3,
+                    chain_time: 1738313270000,
+                    from: (1816, 23429),
+                    to: (1820, 100),
+                })]
+                .into_iter()
+                .collect(),
+            },
+            internals: Default::default(),
+        });
+
+    // collator stores processed upto info into the block on finalization
+    let stored: ProcessedUptoInfo = processed_upto.clone().try_into().unwrap();
+
+    // and restores it from the state on init after restart
+    let loaded: ProcessedUptoInfoStuff = stored.try_into().unwrap();
+    let reader_state = ReaderState::new(&loaded);
+    let restored = reader_state.get_updated_processed_upto();
+
+    assert_eq!(
+        restored.get_min_externals_processed_to().unwrap(),
+        processed_upto.get_min_externals_processed_to().unwrap(),
+    );
+
+    let restored_ext = &restored.partitions.get(&0).unwrap().externals;
+    assert_eq!(restored_ext.processed_to, (1816, 23429));
+
+    let range = restored_ext.ranges.get(&1005).unwrap();
+    assert_eq!(range.from, (1816, 23429));
+    assert_eq!(range.to, (1820, 100));
+    assert_eq!(range.processed_offset, 3);
+    assert_eq!(range.chain_time, 1738313270000);
+}
+
+#[test]
+fn test_anchor_chain_time_regression() {
+    let author = PeerId([0; 32]);
+    let make_anchor = |id, prev_id, chain_time| {
+        Arc::new(MempoolAnchor::new(id, prev_id, author, chain_time, vec![]))
+    };
+
+    let mut anchors_cache = AnchorsCache::default();
+    anchors_cache.insert(make_anchor(1, None, 1000), 0);
+    anchors_cache.insert(make_anchor(2, Some(1), 2000), 0);
+    let last = anchors_cache.get_last_imported_anchor_id_and_ct();
+    assert_eq!(last, Some((2, 2000)));
+
+    // regressing chain time is replaced with the previous one
+    anchors_cache.insert(make_anchor(3, Some(2), 1500), 0);
+    let last = anchors_cache.get_last_imported_anchor_id_and_ct();
+    assert_eq!(last, Some((3, 2000)));
+
+    // equal chain time is allowed
+    anchors_cache.insert(make_anchor(4, Some(3), 2000), 0);
+    let last = anchors_cache.get_last_imported_anchor_id_and_ct();
+    assert_eq!(last, Some((4, 2000)));
+
+    anchors_cache.insert(make_anchor(5, Some(4), 3000), 0);
+    let last = anchors_cache.get_last_imported_anchor_id_and_ct();
+    assert_eq!(last, Some((5, 3000)));
+}
+
+#[test]
+fn empty_collation_attempts_back_off() {
+    let mut backoff = EmptyAttemptsBackoff::default();
+
+    // first attempts are not delayed
+    assert_eq!(backoff.on_empty_attempt(), None);
+    assert_eq!(backoff.on_empty_attempt(), None);
+
+    // then delay grows until the limit
+    let mut prev = std::time::Duration::ZERO;
+    for _ in 0..32 {
+        let delay = backoff.on_empty_attempt().expect("must back off");
+        assert!(delay >= prev);
+        assert!(delay <= std::time::Duration::from_secs(1));
+        prev = delay;
+    }
+    assert_eq!(prev, std::time::Duration::from_secs(1));
+    assert_eq!(backoff.attempts(), 34);
+
+    // any progress resets the backoff
+    backoff.reset();
+    assert_eq!(backoff.attempts(), 0);
+    assert_eq!(backoff.on_empty_attempt(), None);
+}
+
+#[tokio::test]
+async fn idle_shard_backs_off_until_new_mc_data() {
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    use tokio::sync::Notify;
+
+    let mut backoff = EmptyAttemptsBackoff::default();
+    let mc_data_updated = Arc::new(Notify::new());
+    let cancel_collation = Notify::new();
+
+    // idle shard: no new master data and no cancellation
+    let mut waited = Vec::new();
+    for _ in 0..5 {
+        let started_at = Instant::now();
+        backoff.back_off(&mc_data_updated, &cancel_collation).await;
+        waited.push(started_at.elapsed());
+    }
+    assert!(waited[..2].iter().all(|d| *d < Duration::from_millis(10)));
+    assert!(waited[2] >= Duration::from_millis(10));
+    assert!(waited[4] >= Duration::from_millis(40));
+
+    // long backoff is cut short when manager enqueues new master data
+    for _ in 0..10 {
+        backoff.on_empty_attempt();
+    }
+    let started_at = Instant::now();
+    let manager = tokio::spawn({
+        let mc_data_updated = mc_data_updated.clone();
+        async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            mc_data_updated.notify_one();
+        }
+    });
+    backoff.back_off(&mc_data_updated, &cancel_collation).await;
+    assert!(started_at.elapsed() < Duration::from_millis(500));
+    manager.await.unwrap();
+
+    // master data enqueued right before the backoff is not missed
+    mc_data_updated.notify_one();
+    let started_at = Instant::now();
+    backoff.back_off(&mc_data_updated, &cancel_collation).await;
+    assert!(started_at.elapsed() < Duration::from_millis(500));
+}
+
+#[test]
+fn anchors_are_imported_without_wu_while_collator_lags() {
+    const WU_TO_IMPORT: u64 = 1000;
+
+    assert!(should_import_next_anchor(
+        WU_TO_IMPORT,
+        WU_TO_IMPORT,
+        PressureLevel::Low
+    ));
+    assert!(!should_import_next_anchor(
+        WU_TO_IMPORT - 1,
+        WU_TO_IMPORT,
+        PressureLevel::Low
+    ));
+
+    // large backlog of unconsumed anchors
+    for pressure in [PressureLevel::Medium, PressureLevel::High] {
+        assert!(should_import_next_anchor(0, WU_TO_IMPORT, pressure));
+    }
+}
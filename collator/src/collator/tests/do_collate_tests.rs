@@ -1,9 +1,30 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use everscale_types::cell::{CellBuilder, HashBytes};
 use everscale_types::models::*;
+use tycho_block_util::block::ValidatorSubsetInfo;
 use tycho_block_util::queue::QueueKey;
+use tycho_network::PeerId;
+use tycho_util::sync::CancellationFlag;
 use tycho_util::FastHashMap;
 
-use crate::collator::do_collate::calculate_min_internals_processed_to_for_shard;
-use crate::types::ProcessedToByPartitions;
+use crate::collator::do_collate::phase::ActualState;
+use crate::collator::do_collate::{
+    calculate_min_internals_processed_to_for_shard, is_first_block_after_prev_master, sorted_shards,
+};
+use crate::collator::types::{
+    AnchorsCache, BlockSerializerCache, ShardDescriptionExt, WorkingState,
+};
+use crate::collator::CollatorStdImpl;
+use crate::internal_queue::queue::{QueueFactory, QueueFactoryStdImpl};
+use crate::internal_queue::state::storage::QueueStateImplFactory;
+use crate::mempool::MempoolAnchor;
+use crate::queue_adapter::MessageQueueAdapterStdImpl;
+use crate::test_utils::prepare_test_storage;
+use crate::types::{
+    BlockCandidate, CollationSessionInfo, CollatorConfig, McData, ProcessedToByPartitions,
+};
 
 #[test]
 fn test_calculate_min_processed_to_masterchain() {
@@ -140,3 +161,197 @@ fn test_calculate_min_processed_to_shard() {
     // Minimum value from master should be returned
     assert_eq!(result, Some(QueueKey::max_for_lt(9)));
 }
+
+#[test]
+fn sorted_shards_do_not_depend_on_insertion_order() {
+    let mut shard_ids = vec![ShardIdent::new_full(0)];
+    for _ in 0..3 {
+        shard_ids = shard_ids
+            .into_iter()
+            .flat_map(|shard| {
+                let (left, right) = shard.split().unwrap();
+                [left, right]
+            })
+            .collect();
+    }
+
+    let make_descr = |i: usize, shard: ShardIdent| {
+        let block_id = BlockId {
+            shard,
+            seqno: 10 + i as u32,
+            root_hash: HashBytes([i as u8; 32]),
+            file_hash: HashBytes([i as u8; 32]),
+        };
+        let block_info = BlockInfo {
+            end_lt: 1000 + i as u64,
+            ..Default::default()
+        };
+        let descr =
+            ShardDescription::from_block_info(block_id, &block_info, 0, &ValueFlow::default());
+        (shard, Box::new(descr))
+    };
+
+    // same content, different insertion order and capacity
+    let mut first = FastHashMap::default();
+    for (i, shard) in shard_ids.iter().enumerate() {
+        let (shard, descr) = make_descr(i, *shard);
+        first.insert(shard, descr);
+    }
+    let mut second = FastHashMap::with_capacity_and_hasher(256, Default::default());
+    for (i, shard) in shard_ids.iter().enumerate().rev() {
+        let (shard, descr) = make_descr(i, *shard);
+        second.insert(shard, descr);
+    }
+
+    let first_sorted = sorted_shards(&first);
+    let second_sorted = sorted_shards(&second);
+
+    let keys = |shards: &[(&ShardIdent, &ShardDescription)]| {
+        shards
+            .iter()
+            .map(|(k, v)| (**k, v.end_lt))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(keys(&first_sorted), keys(&second_sorted));
+
+    let mut expected = shard_ids.clone();
+    expected.sort_unstable();
+    let sorted_ids = first_sorted.iter().map(|(k, _)| **k).collect::<Vec<_>>();
+    assert_eq!(sorted_ids, expected);
+
+    // built shard hashes must be byte-identical
+    let build = |shards| {
+        let shard_hashes = ShardHashes::from_shards(shards).unwrap();
+        CellBuilder::build_from(&shard_hashes).unwrap()
+    };
+    let first_cell = build(first_sorted);
+    let second_cell = build(second_sorted);
+    assert_eq!(first_cell.repr_hash(), second_cell.repr_hash());
+}
+
+/// Collates the next master block on top of the test storage.
+///
+/// Every call uses its own storage, queue and caches,
+/// so the candidates share nothing except for the inputs.
+async fn collate_next_master_block(reorder_shards: bool) -> Result<Box<BlockCandidate>> {
+    let (storage, _tmp_dir) = prepare_test_storage().await?;
+
+    let mc_block_id = storage.node_state().load_last_mc_block_id().unwrap();
+    let mc_state = storage
+        .shard_state_storage()
+        .load_state(&mc_block_id)
+        .await?;
+    let handle = storage
+        .block_handle_storage()
+        .load_handle(&mc_block_id)
+        .unwrap();
+    let queue_diff = storage.block_storage().load_queue_diff(&handle).await?;
+
+    let config = Arc::new(CollatorConfig::default());
+    let mc_data = McData::load_from_state(&mc_state, Default::default())?;
+    let working_state = CollatorStdImpl::build_and_validate_init_working_state(
+        &config,
+        mc_data,
+        vec![mc_state],
+        vec![*queue_diff.diff_hash()],
+    )?;
+    let WorkingState {
+        next_block_id_short,
+        mc_data,
+        collation_config,
+        wu_used_from_last_anchor,
+        prev_shard_data,
+        usage_tree,
+        reader_state,
+        ..
+    } = *working_state;
+    let prev_shard_data = prev_shard_data.unwrap();
+
+    let anchor = Arc::new(MempoolAnchor::new(
+        1,
+        Some(0),
+        PeerId([1; 32]),
+        mc_data.gen_chain_time + 1000,
+        Vec::new(),
+    ));
+    let mut anchors_cache = AnchorsCache::default();
+    anchors_cache.insert(anchor.clone(), 0);
+
+    let mut collation_data = CollatorStdImpl::build_collation_data(
+        next_block_id_short,
+        anchor.chain_time,
+        anchor.author.to_bytes().into(),
+        &mc_data,
+        &prev_shard_data,
+        None,
+        None,
+    )?;
+    if reorder_shards {
+        // same content, different hash map layout
+        let shards = collation_data.get_shards_mut()?;
+        let mut entries = shards.drain().collect::<Vec<_>>();
+        entries.reverse();
+        let mut reordered = FastHashMap::with_capacity_and_hasher(256, Default::default());
+        reordered.extend(entries);
+        *shards = reordered;
+    }
+
+    let is_first_block_after_prev_master =
+        is_first_block_after_prev_master(prev_shard_data.blocks_ids()[0], &mc_data.shards);
+    let state = Box::new(ActualState {
+        collation_config,
+        collation_data,
+        mc_data,
+        prev_shard_data,
+        shard_id: ShardIdent::MASTERCHAIN,
+        collation_is_cancelled: CancellationFlag::new(),
+        is_first_block_after_prev_master,
+    });
+
+    let queue = QueueFactoryStdImpl {
+        state: QueueStateImplFactory::new(storage.clone()),
+        config: Default::default(),
+    }
+    .create();
+    let mq_adapter = Arc::new(MessageQueueAdapterStdImpl::new(queue));
+
+    let collation_session = Arc::new(CollationSessionInfo::new(
+        ShardIdent::MASTERCHAIN,
+        0,
+        ValidatorSubsetInfo {
+            validators: Vec::new(),
+            short_hash: 0,
+        },
+        None,
+    ));
+
+    let res = CollatorStdImpl::run(
+        config,
+        mq_adapter,
+        reader_state,
+        anchors_cache,
+        BlockSerializerCache::with_capacity(0),
+        state,
+        collation_session,
+        wu_used_from_last_anchor,
+        usage_tree.unwrap(),
+    )?;
+
+    Ok(res.finalized.block_candidate)
+}
+
+#[tokio::test]
+async fn independently_built_candidates_are_identical() -> Result<()> {
+    let first = collate_next_master_block(false).await?;
+    let second = collate_next_master_block(true).await?;
+
+    assert_eq!(first.block.id(), second.block.id());
+    assert_eq!(
+        first.block.as_new_archive_data()?,
+        second.block.as_new_archive_data()?,
+    );
+    assert_eq!(first.collated_data, second.collated_data);
+    assert_eq!(first.collated_file_hash, second.collated_file_hash);
+
+    Ok(())
+}
@@ -747,3 +747,53 @@ fn test_message_group_filling_from_buffers() {
     );
     timer.print_elapsed_and_restart();
 }
+
+#[test]
+fn test_message_group_order_is_deterministic() {
+    let dst_shard = ShardIdent::new_full(0);
+    let dst_addrs: Vec<_> = [7u8, 3, 11, 1, 5]
+        .into_iter()
+        .map(|i| {
+            IntAddr::Std(StdAddr::new(
+                dst_shard.workchain() as i8,
+                HashBytes([i; 32]),
+            ))
+        })
+        .collect();
+
+    let run = |addrs: &mut dyn Iterator<Item = (usize, &IntAddr)>| {
+        let mut buffer = MessagesBuffer::default();
+        for (idx, dst) in addrs {
+            for msg_idx in 0..3u32 {
+                let msg_idx = idx as u32 * 10 + msg_idx;
+                buffer.add_message(make_stub_external_parsed_message(
+                    1,
+                    1000,
+                    msg_idx,
+                    dst.clone(),
+                ));
+            }
+        }
+
+        let mut msg_group = MessageGroup::default();
+        buffer.fill_message_group(&mut msg_group, 10, 3, |_| (false, 0), IncludeAllMessages);
+
+        msg_group
+            .into_iter()
+            .map(|(account_id, msgs)| {
+                let hashes: Vec<_> = msgs.iter().map(|msg| *msg.cell.repr_hash()).collect();
+                (account_id, hashes)
+            })
+            .collect::<Vec<_>>()
+    };
+
+    // same messages are read into buffers in different account order
+    let first = run(&mut dst_addrs.iter().enumerate());
+    let second = run(&mut dst_addrs.iter().enumerate().rev());
+
+    assert_eq!(first.len(), dst_addrs.len());
+    assert_eq!(first, second);
+
+    // accounts are applied in the order of addresses
+    assert!(first.windows(2).all(|w| w[0].0 < w[1].0));
+}
@@ -1522,13 +1522,13 @@ where
             externals.push(msg.msg);
         }
 
-        let anchor = Arc::new(MempoolAnchor {
-            id: anchor_id,
-            prev_id: (self.last_anchor_id > 0).then_some(self.last_anchor_id),
-            author: PeerId(Default::default()),
-            chain_time: anchor_ct,
+        let anchor = Arc::new(MempoolAnchor::new(
+            anchor_id,
+            (self.last_anchor_id > 0).then_some(self.last_anchor_id),
+            PeerId(Default::default()),
+            anchor_ct,
             externals,
-        });
+        ));
 
         self.mempool.insert(anchor_id, anchor.clone());
 
@@ -18,6 +18,7 @@ use everscale_types::models::{
 use everscale_types::num::Tokens;
 use parking_lot::Mutex;
 use tl_proto::TlWrite;
+use tokio::sync::Notify;
 use tycho_block_util::queue::{QueueKey, QueuePartitionIdx, SerializedQueueDiff};
 use tycho_block_util::state::{RefMcStateHandle, ShardStateStuff};
 use tycho_core::global_config::MempoolGlobalConfig;
@@ -31,7 +32,7 @@ use crate::internal_queue::types::{
     AccountStatistics, DiffStatistics, InternalMessageValue, QueueShardRange, QueueStatistics,
     SeparatedStatisticsByPartitions,
 };
-use crate::mempool::{MempoolAnchor, MempoolAnchorId};
+use crate::mempool::{MempoolAnchor, MempoolAnchorId, PressureLevel};
 use crate::queue_adapter::MessageQueueAdapter;
 use crate::tracing_targets;
 use crate::types::processed_upto::{
@@ -617,6 +618,71 @@ pub(super) struct CollatorStats {
     pub tps: u128,
 }
 
+/// Tracks consecutive shard collation attempts without any progress
+/// (no messages to collate and anchor import skipped)
+/// and calculates a delay before the next attempt is requested.
+#[derive(Debug, Default)]
+pub(super) struct EmptyAttemptsBackoff {
+    attempts: u32,
+}
+
+impl EmptyAttemptsBackoff {
+    /// Number of empty attempts that are allowed without a delay.
+    const FREE_ATTEMPTS: u32 = 2;
+    const MIN_DELAY: Duration = Duration::from_millis(10);
+    const MAX_DELAY: Duration = Duration::from_secs(1);
+
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    pub fn reset(&mut self) {
+        self.attempts = 0;
+    }
+
+    /// Registers an empty attempt and returns a delay
+    /// which grows exponentially up to [`Self::MAX_DELAY`].
+    pub fn on_empty_attempt(&mut self) -> Option<Duration> {
+        self.attempts = self.attempts.saturating_add(1);
+
+        let exp = self.attempts.checked_sub(Self::FREE_ATTEMPTS + 1)?;
+        let delay = Self::MIN_DELAY.saturating_mul(1 << exp.min(16));
+        Some(delay.min(Self::MAX_DELAY))
+    }
+
+    /// Registers an empty attempt and waits for the delay, if any.
+    /// New master data may advance top processed to anchor and unblock anchor import,
+    /// and collation cancel is handled by manager on skip, so both end the wait earlier.
+    pub async fn back_off(&mut self, mc_data_updated: &Notify, cancel_collation: &Notify) {
+        let Some(delay) = self.on_empty_attempt() else {
+            return;
+        };
+
+        tracing::debug!(target: tracing_targets::COLLATOR,
+            attempts = self.attempts,
+            delay = %humantime::format_duration(delay),
+            "no progress in collation attempts, backing off",
+        );
+
+        tokio::select! {
+            _ = tokio::time::sleep(delay) => {}
+            _ = mc_data_updated.notified() => {}
+            _ = cancel_collation.notified() => {}
+        }
+    }
+}
+
+/// Anchors are imported after each `wu_used_to_import_next_anchor` used by collation.
+/// While collator lags behind mempool, anchors are imported without waiting
+/// for used work units, so the backlog of unconsumed anchors shrinks.
+pub(super) fn should_import_next_anchor(
+    wu_used_from_last_anchor: u64,
+    wu_used_to_import_next_anchor: u64,
+    pressure: PressureLevel,
+) -> bool {
+    pressure.collator_lags() || wu_used_from_last_anchor >= wu_used_to_import_next_anchor
+}
+
 #[derive(Debug, Clone)]
 pub(super) struct AnchorInfo {
     pub id: MempoolAnchorId,
@@ -1134,7 +1200,26 @@ impl AnchorsCache {
             self.has_pending_externals = true;
             self.cache.push_back((anchor.id, anchor.clone()));
         }
-        self.last_imported_anchor = Some(AnchorInfo::from_anchor(anchor, our_exts_count));
+
+        let mut anchor_info = AnchorInfo::from_anchor(anchor, our_exts_count);
+
+        // NOTE: Chain time of the next block is taken from the last imported anchor,
+        // so a regressing chain time (a mempool bug) must not produce a backwards timestamp.
+        if let Some(prev) = &self.last_imported_anchor {
+            if anchor_info.ct < prev.ct {
+                metrics::counter!("tycho_collator_anchor_chain_time_regression_count").increment(1);
+                tracing::error!(target: tracing_targets::COLLATOR,
+                    prev_anchor_id = prev.id,
+                    prev_chain_time = prev.ct,
+                    anchor_id = anchor_info.id,
+                    chain_time = anchor_info.ct,
+                    "imported anchor chain time regressed, previous chain time will be used",
+                );
+                anchor_info.ct = prev.ct;
+            }
+        }
+
+        self.last_imported_anchor = Some(anchor_info);
     }
 
     pub fn remove(&mut self, index: usize) -> Option<(MempoolAnchorId, Arc<MempoolAnchor>)> {
@@ -90,6 +90,13 @@ where
         mc_top_blocks: &[(BlockId, bool)],
         partitions: &FastHashSet<QueuePartitionIdx>,
     ) -> Result<()>;
+    /// Commit diffs of several master blocks in a single write.
+    /// Master blocks that are already committed are skipped.
+    fn commit_diffs(
+        &self,
+        mc_blocks_top_blocks: &[Vec<(BlockId, bool)>],
+        partitions: &FastHashSet<QueuePartitionIdx>,
+    ) -> Result<()>;
     /// Remove all data in uncommitted zone storage
     fn clear_uncommitted_state(
         &self,
@@ -269,86 +276,18 @@ where
         // Take global lock
         let _global_write_guard = self.global_lock.write().unwrap_or_else(|e| e.into_inner());
 
-        let mc_block_id = mc_top_blocks
-            .iter()
-            .find(|(block_id, _)| block_id.is_masterchain())
-            .map(|(block_id, _)| block_id)
-            .ok_or_else(|| anyhow!("Masterchain block not found in commit_diff"))?;
-
-        // check current commit pointer. If it is greater than committing diff then skip
-        let committed_pointer = self.state.get_commit_pointers()?;
-        let mc_pointer = committed_pointer.get(&mc_block_id.shard);
-        if let Some(mc_pointer) = mc_pointer {
-            if mc_pointer.seqno >= mc_block_id.seqno {
-                tracing::debug!(
-                    target: tracing_targets::MQ,
-                    "Skip commit diff for block_id: {}. Committed by next mc_block_id: {}",
-                    mc_block_id,
-                    mc_pointer.seqno
-                );
-                // Skip commit because it was already committed
-                return Ok(());
-            }
-        }
-
-        let mut gc_ranges = FastHashMap::default();
-
-        let mut commit_pointer = FastHashMap::default();
-
-        for (block_id, top_shard_block_changed) in mc_top_blocks {
-            // Check if the diff is already applied
-            let diff = self
-                .state
-                .get_diff_info(&block_id.shard, block_id.seqno, DiffZone::Both)?;
-
-            let diff = match diff {
-                // If top shard block changed and diff not found, then bail
-                None if *top_shard_block_changed && mc_block_id.seqno != 0 => {
-                    bail!("Diff not found for block_id: {}", block_id)
-                }
-                // If top shard block not changed and diff not found, then continue
-                None => continue,
-                Some(diff) => diff,
-            };
-
-            // Check for duplicate shard in commit_diff
-            if commit_pointer
-                .insert(block_id.shard, (diff.max_message, diff.seqno))
-                .is_some()
-            {
-                bail!("Duplicate shard in commit_diff: {}", block_id.shard);
-            }
-
-            // Update gc ranges
-            if *top_shard_block_changed {
-                for (shard_ident, processed_to_key) in diff.processed_to.iter() {
-                    gc_ranges
-                        .entry(*shard_ident)
-                        .and_modify(|last: &mut GcEndKey| {
-                            if processed_to_key < &last.end_key {
-                                last.end_key = *processed_to_key;
-                                last.on_top_block_id = *block_id;
-                            }
-                        })
-                        .or_insert(GcEndKey {
-                            end_key: *processed_to_key,
-                            on_top_block_id: *block_id,
-                        });
-                }
-            }
-        }
-
-        // change pointer position
-        self.state.commit(&commit_pointer, mc_block_id)?;
+        self.commit_diffs_impl([mc_top_blocks], partitions)
+    }
 
-        // run GC for each found partition in routers
-        for (shard, gc_end_key) in gc_ranges {
-            for partition in partitions {
-                self.gc.update_delete_until(*partition, shard, gc_end_key);
-            }
-        }
+    fn commit_diffs(
+        &self,
+        mc_blocks_top_blocks: &[Vec<(BlockId, bool)>],
+        partitions: &FastHashSet<QueuePartitionIdx>,
+    ) -> Result<()> {
+        // Take global lock once for the whole batch
+        let _global_write_guard = self.global_lock.write().unwrap_or_else(|e| e.into_inner());
 
-        Ok(())
+        self.commit_diffs_impl(mc_blocks_top_blocks.iter().map(Vec::as_slice), partitions)
     }
 
     fn clear_uncommitted_state(
@@ -409,3 +348,132 @@ where
         self.state.get_last_committed_mc_block_id()
     }
 }
+
+impl<P, V> QueueImpl<P, V>
+where
+    P: QueueState<V> + Send + Sync + 'static,
+    V: InternalMessageValue + Send + Sync,
+{
+    /// Collects commit pointers of all master blocks and writes them at once,
+    /// so the batch is either fully committed or not committed at all.
+    ///
+    /// NOTE: Must be called under the global write lock.
+    fn commit_diffs_impl<'a, I>(
+        &self,
+        mc_blocks_top_blocks: I,
+        partitions: &FastHashSet<QueuePartitionIdx>,
+    ) -> Result<()>
+    where
+        I: IntoIterator<Item = &'a [(BlockId, bool)]>,
+    {
+        // check current commit pointer. If it is greater than committing diff then skip
+        let committed_pointer = self.state.get_commit_pointers()?;
+        let mut committed_mc_seqno = committed_pointer
+            .get(&ShardIdent::MASTERCHAIN)
+            .map(|mc_pointer| mc_pointer.seqno);
+
+        let mut last_mc_block_id = None;
+        let mut commit_pointer = FastHashMap::default();
+        let mut gc_ranges = Vec::new();
+
+        for mc_top_blocks in mc_blocks_top_blocks {
+            let mc_block_id = mc_top_blocks
+                .iter()
+                .find(|(block_id, _)| block_id.is_masterchain())
+                .map(|(block_id, _)| block_id)
+                .ok_or_else(|| anyhow!("Masterchain block not found in commit_diff"))?;
+
+            if let Some(committed_mc_seqno) = committed_mc_seqno {
+                if committed_mc_seqno >= mc_block_id.seqno {
+                    tracing::debug!(
+                        target: tracing_targets::MQ,
+                        "Skip commit diff for block_id: {}. Committed by next mc_block_id: {}",
+                        mc_block_id,
+                        committed_mc_seqno
+                    );
+                    // Skip commit because it was already committed
+                    continue;
+                }
+            }
+
+            let mut block_gc_ranges = FastHashMap::default();
+
+            let mut block_commit_pointer = FastHashMap::default();
+
+            for (block_id, top_shard_block_changed) in mc_top_blocks {
+                // Check if the diff is already applied
+                let diff =
+                    self.state
+                        .get_diff_info(&block_id.shard, block_id.seqno, DiffZone::Both)?;
+
+                let diff = match diff {
+                    // If top shard block changed and diff not found, then bail
+                    None if *top_shard_block_changed && mc_block_id.seqno != 0 => {
+                        bail!("Diff not found for block_id: {}", block_id)
+                    }
+                    // If top shard block not changed and diff not found, then continue
+                    None => continue,
+                    Some(diff) => diff,
+                };
+
+                // Check for duplicate shard in commit_diff
+                if block_commit_pointer
+                    .insert(block_id.shard, (diff.max_message, diff.seqno))
+                    .is_some()
+                {
+                    bail!("Duplicate shard in commit_diff: {}", block_id.shard);
+                }
+
+                // Update gc ranges
+                if *top_shard_block_changed {
+                    for (shard_ident, processed_to_key) in diff.processed_to.iter() {
+                        block_gc_ranges
+                            .entry(*shard_ident)
+                            .and_modify(|last: &mut GcEndKey| {
+                                if processed_to_key < &last.end_key {
+                                    last.end_key = *processed_to_key;
+                                    last.on_top_block_id = *block_id;
+                                }
+                            })
+                            .or_insert(GcEndKey {
+                                end_key: *processed_to_key,
+                                on_top_block_id: *block_id,
+                            });
+                    }
+                }
+            }
+
+            // next master blocks move pointers further
+            for (shard, (max_message, seqno)) in block_commit_pointer {
+                commit_pointer
+                    .entry(shard)
+                    .and_modify(|last: &mut (QueueKey, u32)| {
+                        if last.0 < max_message {
+                            *last = (max_message, seqno);
+                        }
+                    })
+                    .or_insert((max_message, seqno));
+            }
+            gc_ranges.push(block_gc_ranges);
+
+            committed_mc_seqno = Some(mc_block_id.seqno);
+            last_mc_block_id = Some(*mc_block_id);
+        }
+
+        let Some(mc_block_id) = last_mc_block_id else {
+            return Ok(());
+        };
+
+        // change pointer position for the whole batch in a single write
+        self.state.commit(&commit_pointer, &mc_block_id)?;
+
+        // run GC for each found partition in routers
+        for (shard, gc_end_key) in gc_ranges.into_iter().flatten() {
+            for partition in partitions {
+                self.gc.update_delete_until(*partition, shard, gc_end_key);
+            }
+        }
+
+        Ok(())
+    }
+}
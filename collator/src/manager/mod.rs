@@ -2,7 +2,7 @@ use std::collections::{hash_map, BTreeMap, VecDeque};
 use std::sync::Arc;
 
 use ahash::HashMapExt;
-use anyhow::{anyhow, bail, Context, Result};
+use anyhow::{bail, Context, Result};
 use async_trait::async_trait;
 use everscale_crypto::ed25519::KeyPair;
 use everscale_types::models::{
@@ -25,7 +25,7 @@ use types::{
 
 use self::blocks_cache::BlocksCache;
 use self::types::{BlockCacheKey, CandidateStatus, CollationSyncState, McBlockSubgraphExtract};
-use self::utils::find_us_in_collators_set;
+use self::utils::{compute_collators_subset, find_us_in_collators_set};
 use crate::collator::{
     CollationCancelReason, Collator, CollatorContext, CollatorEventListener, CollatorFactory,
     ForceMasterCollation,
@@ -487,11 +487,7 @@ where
 
         let _histogram = HistogramGuard::begin("tycho_collator_commit_queue_diffs_time");
 
-        let mut top_blocks: Vec<_> = top_shard_blocks_info
-            .iter()
-            .map(|(id, updated)| (*id, *updated))
-            .collect();
-        top_blocks.push((*block_id, true));
+        let top_blocks = Self::get_mc_block_top_blocks(block_id, top_shard_blocks_info);
 
         if let Err(err) = mq_adapter.commit_diff(top_blocks, partitions) {
             bail!(
@@ -508,6 +504,19 @@ where
         Ok(())
     }
 
+    /// Master block with its top shard blocks and flags if they were updated
+    fn get_mc_block_top_blocks(
+        block_id: &BlockId,
+        top_shard_blocks_info: &[(BlockId, bool)],
+    ) -> Vec<(BlockId, bool)> {
+        let mut top_blocks: Vec<_> = top_shard_blocks_info
+            .iter()
+            .map(|(id, updated)| (*id, *updated))
+            .collect();
+        top_blocks.push((*block_id, true));
+        top_blocks
+    }
+
     /// Returns `BlockId` if diff was applied.
     /// * `first_required_diffs` - contains ids of known first required diffs for queue for each shard
     fn apply_block_queue_diff_from_entry_stuff(
@@ -1927,6 +1936,10 @@ where
             }
         }
 
+        // diffs of all synced master blocks are committed at once on sync finish
+        let mut mc_blocks_top_blocks = Vec::new();
+        let mut partitions = FastHashSet::default();
+
         // extract all recevied blocks, apply required diffs
         // and return latest master state
         loop {
@@ -1986,15 +1999,35 @@ where
             let to_blocks_keys = mc_block_entry.get_top_blocks_keys()?;
             blocks_cache.set_gc_to_boundary(&to_blocks_keys);
 
+            // the last master block commits all previous diffs anyway,
+            // so intermediate ones are committed only when all their diffs were applied
+            let top_blocks = Self::get_mc_block_top_blocks(
+                &mc_block_entry.block_id,
+                &mc_block_entry.top_shard_blocks_info,
+            );
+            let all_diffs_applied = (top_blocks.iter())
+                .all(|(id, updated)| !updated || res.applied_diffs_ids.contains(id));
+            if is_last || (mc_block_entry.block_id.seqno != 0 && all_diffs_applied) {
+                mc_blocks_top_blocks.push(top_blocks);
+                partitions.extend(subgraph.get_partitions());
+            }
+
             // on sync finish we commit diffs
             if is_last {
-                let partitions = subgraph.get_partitions();
-                Self::commit_block_queue_diff(
-                    mq_adapter.clone(),
-                    &mc_block_entry.block_id,
-                    &mc_block_entry.top_shard_blocks_info,
-                    &partitions,
-                )?;
+                let _histogram = HistogramGuard::begin("tycho_collator_commit_queue_diffs_time");
+                let mc_blocks_count = mc_blocks_top_blocks.len();
+                mq_adapter
+                    .commit_diffs(mc_blocks_top_blocks, &partitions)
+                    .with_context(|| {
+                        format!(
+                            "Error committing message queue diffs on sync to block ({})",
+                            mc_block_entry.block_id,
+                        )
+                    })?;
+                tracing::info!(target: tracing_targets::COLLATION_MANAGER,
+                    mc_blocks_count,
+                    "message queue diffs were committed",
+                );
 
                 // when we run sync by any reason we should drop uncommitted queue updates
                 // after restoring the required state
@@ -2227,18 +2260,12 @@ where
                 Result::<_>::Ok((subset.clone(), *hash_short))
             }
             hash_map::Entry::Vacant(entry) => {
-                let (subset, hash_short) = full_validators_set
-                    .compute_mc_subset(current_session_seqno, collation_config.shuffle_mc_validators)
-                    .ok_or_else(|| anyhow!(
-                        "Error calculating subset of validators for session (shard_id = {}, seqno = {})",
-                        ShardIdent::MASTERCHAIN,
-                        current_session_seqno,
-                    ))?;
-
-                let subset: FastHashMap<_, _> = subset
-                    .into_iter()
-                    .map(|vldr| (vldr.public_key.into(), vldr))
-                    .collect();
+                let (subset, hash_short) = compute_collators_subset(
+                    &full_validators_set,
+                    shard_id,
+                    current_session_seqno,
+                    collation_config.shuffle_mc_validators,
+                )?;
                 let subset = Arc::new(subset);
 
                 entry.insert((subset.clone(), hash_short));
@@ -2362,6 +2389,7 @@ where
                     );
 
                     let cancel_collation_notify = Arc::new(Notify::new());
+                    let mc_data_updated_notify = Arc::new(Notify::new());
 
                     match self
                         .collator_factory
@@ -2377,6 +2405,7 @@ where
                             mc_data: mc_data.clone(),
                             mempool_config_override: self.mempool_config_override.clone(),
                             cancel_collation: cancel_collation_notify.clone(),
+                            mc_data_updated: mc_data_updated_notify.clone(),
                         })
                         .await
                     {
@@ -2392,6 +2421,7 @@ where
                                 collator: Arc::new(collator),
                                 state: CollatorState::Active,
                                 cancel_collation: cancel_collation_notify,
+                                mc_data_updated: mc_data_updated_notify,
                             });
                         }
                     }
@@ -2429,6 +2459,8 @@ where
                     )
                 };
                 active_collator.state = CollatorState::Active;
+                // resume task is queued after the current collation attempt, that may be backing off
+                active_collator.mc_data_updated.notify_one();
                 active_collator.collator.clone()
             };
 
@@ -2816,3 +2816,45 @@ fn build_out_msg_description<V: InternalMessageValue>(
 
     Ok(res)
 }
+
+#[test]
+fn test_compute_collators_subset_is_deterministic() {
+    use everscale_types::models::{ValidatorDescription, ValidatorSet};
+
+    use crate::manager::utils::compute_collators_subset;
+
+    let mut vset = ValidatorSet {
+        utime_since: 0,
+        utime_until: 0,
+        main: 3.try_into().unwrap(),
+        total_weight: 0,
+        list: Vec::new(),
+    };
+    for i in 0..5u8 {
+        vset.list.push(ValidatorDescription {
+            public_key: HashBytes([i + 1; 32]),
+            weight: 1,
+            adnl_addr: None,
+            mc_seqno_since: 0,
+            prev_total_weight: vset.total_weight,
+        });
+        vset.total_weight += 1;
+    }
+
+    let shard_id = ShardIdent::new_full(0);
+    for session_seqno in 0..10 {
+        let (subset_1, hash_short_1) =
+            compute_collators_subset(&vset, shard_id, session_seqno, true).unwrap();
+        let (subset_2, hash_short_2) =
+            compute_collators_subset(&vset, shard_id, session_seqno, true).unwrap();
+
+        assert_eq!(hash_short_1, hash_short_2);
+        assert_eq!(subset_1.len(), 3);
+
+        let mut keys_1 = subset_1.keys().collect::<Vec<_>>();
+        let mut keys_2 = subset_2.keys().collect::<Vec<_>>();
+        keys_1.sort();
+        keys_2.sort();
+        assert_eq!(keys_1, keys_2);
+    }
+}
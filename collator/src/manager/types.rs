@@ -38,6 +38,8 @@ pub(super) struct ActiveCollator<C> {
 
     /// For graceful collation cancellation
     pub cancel_collation: Arc<Notify>,
+    /// To end the empty attempts backoff when new master data is enqueued
+    pub mc_data_updated: Arc<Notify>,
 }
 
 #[derive(Default)]
@@ -1,5 +1,6 @@
+use anyhow::{anyhow, Result};
 use everscale_crypto::ed25519::{KeyPair, PublicKey};
-use everscale_types::models::ValidatorDescription;
+use everscale_types::models::{ShardIdent, ValidatorDescription, ValidatorSet};
 use tycho_util::FastHashMap;
 
 pub fn find_us_in_collators_set(
@@ -13,3 +14,32 @@ pub fn find_us_in_collators_set(
         None
     }
 }
+
+/// Computes the collators subset for the shard session from the full validator set.
+/// Returns the subset indexed by public key and its short hash.
+///
+/// NOTE: All shards are currently collated and validated by the masterchain subset,
+///     so the selection only depends on the validator set, session seqno and shuffle flag.
+pub fn compute_collators_subset(
+    full_validators_set: &ValidatorSet,
+    shard_id: ShardIdent,
+    session_seqno: u32,
+    shuffle_validators: bool,
+) -> Result<(FastHashMap<[u8; 32], ValidatorDescription>, u32)> {
+    let (subset, hash_short) = full_validators_set
+        .compute_mc_subset(session_seqno, shuffle_validators)
+        .ok_or_else(|| {
+            anyhow!(
+                "Error calculating subset of validators for session (shard_id = {}, seqno = {})",
+                shard_id,
+                session_seqno,
+            )
+        })?;
+
+    let subset = subset
+        .into_iter()
+        .map(|vldr| (vldr.public_key.into(), vldr))
+        .collect();
+
+    Ok((subset, hash_short))
+}
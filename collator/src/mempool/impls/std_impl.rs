@@ -5,6 +5,7 @@ mod deduplicator;
 mod parser;
 mod state_update_queue;
 
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
 use anyhow::{Context, Result};
@@ -12,10 +13,10 @@ use async_trait::async_trait;
 use bytes::Bytes;
 use everscale_crypto::ed25519::KeyPair;
 use futures_util::FutureExt;
-use tokio::sync::{mpsc, oneshot, Mutex};
+use tokio::sync::{broadcast, mpsc, oneshot, Mutex};
 use tracing::Instrument;
 use tycho_consensus::prelude::*;
-use tycho_network::{Network, OverlayService, PeerResolver};
+use tycho_network::{Network, OverlayService, PeerId, PeerResolver};
 use tycho_storage::MempoolStorage;
 
 use crate::mempool::impls::std_impl::anchor_handler::AnchorHandler;
@@ -23,7 +24,7 @@ use crate::mempool::impls::std_impl::cache::Cache;
 use crate::mempool::impls::std_impl::config::ConfigAdapter;
 use crate::mempool::{
     DebugStateUpdateContext, GetAnchorResult, MempoolAdapter, MempoolAdapterFactory,
-    MempoolAnchorId, MempoolEventListener, StateUpdateContext,
+    MempoolAnchorId, MempoolEventListener, PressureLevel, StateUpdateContext,
 };
 use crate::tracing_targets;
 use crate::types::processed_upto::BlockSeqno;
@@ -36,6 +37,7 @@ pub struct MempoolAdapterStdImpl {
 
     store: MempoolAdapterStore,
     input_buffer: InputBuffer,
+    externals_throttle: ExternalsThrottle,
     top_known_anchor: RoundWatch<TopKnownAnchor>,
 }
 
@@ -65,6 +67,7 @@ impl MempoolAdapterStdImpl {
             }),
             store: MempoolAdapterStore::new(mempool_storage.clone(), RoundWatch::default()),
             input_buffer: InputBuffer::default(),
+            externals_throttle: ExternalsThrottle::default(),
             top_known_anchor: RoundWatch::default(),
         }
     }
@@ -182,7 +185,12 @@ impl MempoolAdapterStdImpl {
         merged_conf: &MempoolMergedConfig,
         ctx: &StateUpdateContext,
     ) -> Result<EngineSession> {
-        tracing::info!(target: tracing_targets::MEMPOOL_ADAPTER, "Starting mempool engine...");
+        tracing::info!(
+            target: tracing_targets::MEMPOOL_ADAPTER,
+            start_round = merged_conf.genesis_info().start_round,
+            genesis_round = merged_conf.genesis_round(),
+            "Starting mempool engine..."
+        );
 
         let (anchor_tx, anchor_rx) = mpsc::unbounded_channel();
 
@@ -196,7 +204,7 @@ impl MempoolAdapterStdImpl {
             mempool_adapter_store: self.store.clone(),
             input_buffer: self.input_buffer.clone(),
             top_known_anchor: self.top_known_anchor.clone(),
-            output: anchor_tx,
+            output: Arc::new(anchor_tx),
         };
 
         // actual oldest sync round will be not less than this
@@ -225,8 +233,36 @@ impl MempoolAdapterStdImpl {
             engine_stop_tx,
         );
 
+        let mut ban_events = session.subscribe_ban_events();
+        let network = self.net_args.network.clone();
+        tokio::spawn(async move {
+            loop {
+                match ban_events.recv().await {
+                    Ok(BanEvent { peer_id, reason }) => {
+                        tracing::warn!(
+                            target: tracing_targets::MEMPOOL_ADAPTER,
+                            %peer_id,
+                            reason = reason.as_str(),
+                            "Disconnecting misbehaving mempool peer"
+                        );
+                        network.disconnect(&peer_id);
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => tracing::warn!(
+                        target: tracing_targets::MEMPOOL_ADAPTER,
+                        skipped,
+                        "Mempool ban events lagged"
+                    ),
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+
         let mut anchor_task = AnchorHandler::new(merged_conf.consensus(), anchor_rx)
-            .run(self.cache.clone(), self.store.clone())
+            .run(
+                self.cache.clone(),
+                self.store.clone(),
+                self.top_known_anchor.clone(),
+            )
             .boxed();
 
         tokio::spawn(async move {
@@ -250,8 +286,61 @@ impl MempoolAdapterStdImpl {
         Ok(session)
     }
 
+    /// Pressure of anchors produced by mempool but not yet consumed by collator,
+    /// so the sender of externals can throttle before they are dropped
+    pub fn pressure(&self) -> PressureLevel {
+        self.cache.pressure(&NodeConfig::get())
+    }
+
     pub fn send_external(&self, message: Bytes) {
-        self.input_buffer.push(message);
+        self.send_external_from(InputBuffer::DEFAULT_SOURCE, message);
+    }
+
+    /// Externals broadcast by other peers are tagged with the sender,
+    /// so a single noisy gateway cannot crowd out the others in a point payload
+    pub fn send_external_from_peer(&self, peer_id: &PeerId, message: Bytes) {
+        let source = InputSourceId::from_le_bytes(peer_id.0[..4].try_into().unwrap());
+        self.send_external_from(source, message);
+    }
+
+    fn send_external_from(&self, source: InputSourceId, message: Bytes) {
+        // do not let consensus take more payload while collator cannot keep up
+        if self.externals_throttle.should_drop(self.pressure()) {
+            return;
+        }
+        self.input_buffer.push_from(source, message);
+    }
+}
+
+/// Counts and logs externals dropped under high pressure
+#[derive(Default)]
+struct ExternalsThrottle {
+    /// dropped since the pressure became high
+    dropped: AtomicU64,
+}
+
+impl ExternalsThrottle {
+    fn should_drop(&self, pressure: PressureLevel) -> bool {
+        if pressure == PressureLevel::High {
+            metrics::counter!("tycho_mempool_externals_dropped_count").increment(1);
+            if self.dropped.fetch_add(1, Ordering::Relaxed) == 0 {
+                tracing::warn!(
+                    target: tracing_targets::MEMPOOL_ADAPTER,
+                    "collator lags behind mempool, dropping new externals"
+                );
+            }
+            true
+        } else {
+            let dropped = self.dropped.swap(0, Ordering::Relaxed);
+            if dropped > 0 {
+                tracing::info!(
+                    target: tracing_targets::MEMPOOL_ADAPTER,
+                    dropped,
+                    "collator caught up with mempool, accepting new externals"
+                );
+            }
+            false
+        }
     }
 }
 
@@ -306,7 +395,10 @@ impl MempoolAdapter for MempoolAdapterStdImpl {
         );
 
         let result = match self.cache.get_anchor_by_id(anchor_id).await {
-            Some(anchor) => GetAnchorResult::Exist(anchor),
+            Some(anchor) => {
+                self.cache.set_consumed(anchor.id);
+                GetAnchorResult::Exist(anchor)
+            }
             None => GetAnchorResult::NotExist,
         };
 
@@ -321,7 +413,10 @@ impl MempoolAdapter for MempoolAdapterStdImpl {
         );
 
         let result = match self.cache.get_next_anchor(prev_anchor_id).await? {
-            Some(anchor) => GetAnchorResult::Exist(anchor),
+            Some(anchor) => {
+                self.cache.set_consumed(anchor.id);
+                GetAnchorResult::Exist(anchor)
+            }
             None => GetAnchorResult::NotExist,
         };
 
@@ -332,4 +427,31 @@ impl MempoolAdapter for MempoolAdapterStdImpl {
         self.cache.clear(before_anchor_id);
         Ok(())
     }
+
+    fn pressure(&self) -> PressureLevel {
+        MempoolAdapterStdImpl::pressure(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn externals_are_dropped_under_high_pressure() {
+        let throttle = ExternalsThrottle::default();
+
+        assert!(!throttle.should_drop(PressureLevel::Low));
+        assert!(!throttle.should_drop(PressureLevel::Medium));
+        assert_eq!(throttle.dropped.load(Ordering::Relaxed), 0);
+
+        for _ in 0..3 {
+            assert!(throttle.should_drop(PressureLevel::High));
+        }
+        assert_eq!(throttle.dropped.load(Ordering::Relaxed), 3);
+
+        // counter is reset when collator catches up
+        assert!(!throttle.should_drop(PressureLevel::Medium));
+        assert_eq!(throttle.dropped.load(Ordering::Relaxed), 0);
+    }
 }
@@ -4,7 +4,10 @@ use std::time::Duration;
 use bumpalo::Bump;
 use everscale_types::models::ConsensusConfig;
 use tokio::sync::mpsc;
-use tycho_consensus::prelude::{AnchorData, MempoolAdapterStore, MempoolOutput};
+use tycho_consensus::prelude::{
+    AnchorData, AnchorOrderError, AnchorResync, MempoolAdapterStore, MempoolOutput, RoundWatch,
+    TopKnownAnchor,
+};
 use tycho_util::time::now_millis;
 
 use crate::mempool::impls::std_impl::cache::Cache;
@@ -20,8 +23,9 @@ pub struct AnchorHandler {
 struct Shuttle {
     cache: Arc<Cache>,
     store: MempoolAdapterStore,
+    top_known_anchor: RoundWatch<TopKnownAnchor>,
     parser: Parser,
-    first_after_gap: Option<MempoolAnchorId>,
+    resync: AnchorResync,
 }
 
 impl AnchorHandler {
@@ -35,7 +39,12 @@ impl AnchorHandler {
         }
     }
 
-    pub async fn run(mut self, cache: Arc<Cache>, store: MempoolAdapterStore) {
+    pub async fn run(
+        mut self,
+        cache: Arc<Cache>,
+        store: MempoolAdapterStore,
+        top_known_anchor: RoundWatch<TopKnownAnchor>,
+    ) {
         scopeguard::defer!(tracing::warn!(
             target: tracing_targets::MEMPOOL_ADAPTER,
             "handle anchors task stopped"
@@ -43,8 +52,9 @@ impl AnchorHandler {
         let mut shuttle = Shuttle {
             cache,
             store,
+            top_known_anchor,
             parser: Parser::new(self.deduplicate_rounds),
-            first_after_gap: None,
+            resync: AnchorResync::new(self.deduplicate_rounds),
         };
         while let Some(output) = self.anchor_rx.recv().await {
             shuttle = self.handle_mempool_output(shuttle, output).await;
@@ -61,7 +71,13 @@ impl AnchorHandler {
         match output {
             MempoolOutput::NextAnchor(committed) => return shuttle.handle(committed).await,
             MempoolOutput::NewStartAfterGap(anchors_full_bottom) => {
-                shuttle.reset(self.deduplicate_rounds, anchors_full_bottom.0);
+                let first_to_execute =
+                    (shuttle.resync).new_start(anchors_full_bottom, &shuttle.top_known_anchor);
+                shuttle.reset(
+                    self.deduplicate_rounds,
+                    anchors_full_bottom.0,
+                    first_to_execute.0,
+                );
             }
             MempoolOutput::Running => shuttle.cache.set_paused(false),
             MempoolOutput::Paused => shuttle.cache.set_paused(true),
@@ -71,12 +87,15 @@ impl AnchorHandler {
 }
 
 impl Shuttle {
-    fn reset(&mut self, deduplicate_rounds: u16, anchors_full_bottom: MempoolAnchorId) {
+    fn reset(
+        &mut self,
+        deduplicate_rounds: u16,
+        anchors_full_bottom: MempoolAnchorId,
+        first_to_execute: MempoolAnchorId,
+    ) {
         self.cache.reset();
         self.parser = Parser::new(deduplicate_rounds);
-        let first_to_execute = anchors_full_bottom.saturating_add(deduplicate_rounds as u32);
         self.store.report_new_start(first_to_execute);
-        self.first_after_gap = Some(first_to_execute);
         tracing::info!(
             target: tracing_targets::MEMPOOL_ADAPTER,
             new_bottom = anchors_full_bottom,
@@ -87,11 +106,33 @@ impl Shuttle {
 
     async fn handle(mut self, committed: AnchorData) -> Self {
         let anchor_id: MempoolAnchorId = committed.anchor.round().0;
+
+        match self.cache.check_order(&committed) {
+            Ok(()) => {}
+            Err(e @ AnchorOrderError::NotAfterLast { .. }) => {
+                // restarted engine repeats anchors that are already cached
+                metrics::counter!("tycho_mempool_adapter_repeated_anchors").increment(1);
+                tracing::warn!(
+                    target: tracing_targets::MEMPOOL_ADAPTER,
+                    id = anchor_id,
+                    "skipped anchor: {e}"
+                );
+                return self;
+            }
+            Err(e @ AnchorOrderError::Gap { .. }) => {
+                metrics::counter!("tycho_mempool_adapter_anchor_gaps").increment(1);
+                tracing::error!(
+                    target: tracing_targets::MEMPOOL_ADAPTER,
+                    id = anchor_id,
+                    "anchors are lost without a new start: {e}"
+                );
+            }
+        }
+
         metrics::gauge!("tycho_mempool_last_anchor_round").set(anchor_id);
 
         let chain_time = committed.anchor.time().millis();
-        let is_executable =
-            (self.first_after_gap.as_ref()).is_none_or(|first_id| anchor_id >= *first_id);
+        let is_executable = self.resync.is_executable(committed.anchor.round());
 
         let task = tokio::task::spawn_blocking(move || {
             let bump = Bump::with_capacity(
@@ -114,13 +155,13 @@ impl Shuttle {
             let unique_messages_len = unique_messages.len();
 
             if is_executable {
-                self.cache.push(Arc::new(MempoolAnchor {
-                    id: anchor_id,
-                    prev_id: committed.prev_anchor.map(|round| round.0),
+                self.cache.push(Arc::new(MempoolAnchor::new(
+                    anchor_id,
+                    committed.prev_anchor.map(|round| round.0),
+                    committed.anchor.author(),
                     chain_time,
-                    author: committed.anchor.author(),
-                    externals: unique_messages,
-                }));
+                    unique_messages,
+                )));
             }
 
             self.parser.clean(anchor_id);
@@ -1,11 +1,13 @@
 use std::cmp;
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
 
 use indexmap::IndexMap;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use tokio::sync::Notify;
+use tycho_consensus::prelude::{AnchorData, AnchorOrderCheck, AnchorOrderError, MempoolNodeConfig};
 
-use crate::mempool::{MempoolAnchor, MempoolAnchorId};
+use crate::mempool::{MempoolAnchor, MempoolAnchorId, PressureLevel};
 use crate::tracing_targets;
 
 #[derive(thiserror::Error, Debug)]
@@ -48,15 +50,26 @@ struct CacheData {
 pub struct Cache {
     data: RwLock<CacheData>,
     anchor_added: Notify,
+    /// the latest anchor returned to collator, zero until the first one is returned
+    last_consumed: AtomicU32,
+    /// outlives engine sessions, so a restarted engine cannot repeat or skip anchors unnoticed
+    order: Mutex<AnchorOrderCheck>,
 }
 
 impl Cache {
     pub fn reset(&self) {
         let mut data = self.data.write();
         data.anchors = Default::default();
+        self.last_consumed.store(0, Ordering::Relaxed);
+        self.order.lock().reset();
         // let waiters wait for new data to be pushed
     }
 
+    /// Must be called for every committed anchor in order of arrival, before it is pushed
+    pub fn check_order(&self, committed: &AnchorData) -> Result<(), AnchorOrderError> {
+        self.order.lock().check(committed)
+    }
+
     pub fn push(&self, anchor: Arc<MempoolAnchor>) {
         let mut data = self.data.write();
         let old = data.anchors.insert(anchor.id, anchor);
@@ -76,6 +89,30 @@ impl Cache {
         self.anchor_added.notify_waiters();
     }
 
+    pub fn set_consumed(&self, anchor_id: MempoolAnchorId) {
+        self.last_consumed.fetch_max(anchor_id, Ordering::Relaxed);
+    }
+
+    /// Amount of cached anchors that were not returned to collator yet.
+    ///
+    /// Zero until collator takes its first anchor: after a restart the cache is filled
+    /// before collator starts, and it is not a lag.
+    pub fn backlog(&self) -> usize {
+        let last_consumed = self.last_consumed.load(Ordering::Relaxed);
+        if last_consumed == 0 {
+            return 0;
+        }
+        let data = self.data.read();
+        // anchors are inserted in order of their ids
+        (data.anchors.keys().rev())
+            .take_while(|id| **id > last_consumed)
+            .count()
+    }
+
+    pub fn pressure(&self, conf: &MempoolNodeConfig) -> PressureLevel {
+        PressureLevel::from_backlog(self.backlog(), conf)
+    }
+
     pub async fn get_anchor_by_id(&self, anchor_id: MempoolAnchorId) -> Option<Arc<MempoolAnchor>> {
         loop {
             // NOTE: Subscribe to notification before checking
@@ -265,3 +302,64 @@ impl CacheData {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU16;
+
+    use super::*;
+    use crate::mempool::make_stub_anchor;
+
+    #[test]
+    fn large_backlog_reports_high_pressure() {
+        let conf = MempoolNodeConfig {
+            backlog_medium_anchors: 5,
+            backlog_high_anchors: NonZeroU16::new(32),
+            ..Default::default()
+        };
+
+        let cache = Cache::default();
+        assert_eq!(cache.pressure(&conf), PressureLevel::Low);
+
+        for id in 1..=64 {
+            cache.push(Arc::new(make_stub_anchor(id, id - 1)));
+        }
+        // collator did not start yet, the cache is filled after restart
+        assert_eq!(cache.backlog(), 0);
+        assert_eq!(cache.pressure(&conf), PressureLevel::Low);
+
+        cache.set_consumed(1);
+        assert_eq!(cache.backlog(), 63);
+        assert_eq!(cache.pressure(&conf), PressureLevel::High);
+        // collator catches up by default, but externals are not dropped
+        let default_conf = MempoolNodeConfig::default();
+        assert_eq!(cache.pressure(&default_conf), PressureLevel::Medium);
+
+        // collator catches up
+        cache.set_consumed(59);
+        assert_eq!(cache.pressure(&conf), PressureLevel::Medium);
+
+        cache.set_consumed(64);
+        assert_eq!(cache.backlog(), 0);
+        assert_eq!(cache.pressure(&conf), PressureLevel::Low);
+
+        // consumed mark never goes back
+        cache.set_consumed(1);
+        assert_eq!(cache.pressure(&conf), PressureLevel::Low);
+
+        // consumed mark is reset with mempool restart
+        cache.reset();
+        for id in 1..=64 {
+            cache.push(Arc::new(make_stub_anchor(id, id - 1)));
+        }
+        assert_eq!(cache.backlog(), 0);
+
+        // drops are disabled
+        cache.set_consumed(1);
+        let conf = MempoolNodeConfig {
+            backlog_high_anchors: None,
+            ..conf
+        };
+        assert_eq!(cache.pressure(&conf), PressureLevel::Medium);
+    }
+}
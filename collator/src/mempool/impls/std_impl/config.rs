@@ -21,6 +21,8 @@ impl ConfigAdapter {
             curr_v_set: new_cx.curr_v_set(),
             curr_v_subset: new_cx.curr_v_subset(),
             next_v_set: new_cx.next_v_set(),
+            prev_v_weights: new_cx.prev_v_weights(),
+            curr_v_weights: new_cx.curr_v_weights(),
         };
 
         anyhow::ensure!(
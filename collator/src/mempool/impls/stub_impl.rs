@@ -357,13 +357,13 @@ pub(crate) fn make_empty_anchor(
     prev_id: MempoolAnchorId,
     chain_time: u64,
 ) -> Arc<MempoolAnchor> {
-    Arc::new(MempoolAnchor {
+    Arc::new(MempoolAnchor::new(
         id,
-        prev_id: Some(prev_id),
-        author: PeerId(Default::default()),
+        Some(prev_id),
+        PeerId(Default::default()),
         chain_time,
-        externals: vec![],
-    })
+        vec![],
+    ))
 }
 
 pub(crate) fn make_stub_anchor(id: MempoolAnchorId, prev_id: MempoolAnchorId) -> MempoolAnchor {
@@ -381,13 +381,13 @@ pub(crate) fn make_stub_anchor(id: MempoolAnchorId, prev_id: MempoolAnchorId) ->
         externals.push(Arc::new(make_stub_external(id, chain_time, i, dst)));
     }
 
-    MempoolAnchor {
+    MempoolAnchor::new(
         id,
-        prev_id: Some(prev_id),
-        author: PeerId(Default::default()),
+        Some(prev_id),
+        PeerId(Default::default()),
         chain_time,
         externals,
-    }
+    )
 }
 
 pub(crate) fn make_stub_external(
@@ -444,13 +444,13 @@ pub(crate) fn make_anchor_from_file(
         externals.push(Arc::new(ExternalMessage { cell, info }));
     }
 
-    Ok(Arc::new(MempoolAnchor {
+    Ok(Arc::new(MempoolAnchor::new(
         id,
-        prev_id: Some(prev_id),
-        author: PeerId(Default::default()),
+        Some(prev_id),
+        PeerId(Default::default()),
         chain_time,
         externals,
-    }))
+    )))
 }
 
 fn make_round_interval() -> Duration {
@@ -6,6 +6,7 @@ use anyhow::Result;
 use async_trait::async_trait;
 use everscale_types::models::*;
 use everscale_types::prelude::*;
+use tycho_consensus::prelude::MempoolNodeConfig;
 use tycho_network::PeerId;
 
 pub use self::impls::*;
@@ -80,12 +81,48 @@ pub trait MempoolAdapter: Send + Sync + 'static {
     /// We can do this for anchors that processed in blocks
     /// which included in signed master - we do not need them anymore
     fn clear_anchors_cache(&self, before_anchor_id: MempoolAnchorId) -> Result<()>;
+
+    /// Pressure of anchors produced by mempool but not yet consumed by collator.
+    /// Collator imports anchors faster under pressure, and mempool adapter
+    /// may throttle intake of new externals when collation cannot keep up.
+    fn pressure(&self) -> PressureLevel {
+        PressureLevel::Low
+    }
 }
 
 // === Types ===
 
 pub type MempoolAnchorId = u32;
 
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PressureLevel {
+    #[default]
+    Low,
+    Medium,
+    High,
+}
+
+impl PressureLevel {
+    /// Collator should import anchors without waiting for used work units.
+    pub fn collator_lags(self) -> bool {
+        self != Self::Low
+    }
+
+    pub fn from_backlog(unconsumed_anchors: usize, conf: &MempoolNodeConfig) -> Self {
+        let reaches = |threshold: usize| unconsumed_anchors >= threshold;
+        if conf
+            .backlog_high_anchors
+            .is_some_and(|high| reaches(high.get() as usize))
+        {
+            Self::High
+        } else if reaches(conf.backlog_medium_anchors as usize) {
+            Self::Medium
+        } else {
+            Self::Low
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct ExternalMessage {
     pub cell: Cell,
@@ -106,22 +143,84 @@ pub struct MempoolAnchor {
     pub author: PeerId,
     pub chain_time: u64,
     pub externals: Vec<Arc<ExternalMessage>>,
+    /// `(workchain, dst prefix, external idx)` sorted to find shard externals by a range
+    dst_index: Box<[(i32, u64, u32)]>,
 }
 
 impl MempoolAnchor {
+    pub fn new(
+        id: MempoolAnchorId,
+        prev_id: Option<MempoolAnchorId>,
+        author: PeerId,
+        chain_time: u64,
+        externals: Vec<Arc<ExternalMessage>>,
+    ) -> Self {
+        let mut dst_index = externals
+            .iter()
+            .enumerate()
+            .map(|(idx, ext)| (ext.info.dst.workchain(), ext.info.dst.prefix(), idx as u32))
+            .collect::<Box<[_]>>();
+        dst_index.sort_unstable();
+
+        Self {
+            id,
+            prev_id,
+            author,
+            chain_time,
+            externals,
+            dst_index,
+        }
+    }
+
+    /// Returns externals starting from `offset` with destination in the specified shard,
+    /// in the anchor order.
+    pub fn externals_for_shard(
+        &self,
+        shard_id: &ShardIdent,
+        offset: usize,
+    ) -> impl Iterator<Item = &Arc<ExternalMessage>> + '_ {
+        self.shard_externals_indices(shard_id, offset)
+            .into_iter()
+            .map(|idx| &self.externals[idx as usize])
+    }
+
     pub fn count_externals_for(&self, shard_id: &ShardIdent, offset: usize) -> usize {
-        self.externals
+        self.shard_dst_index(shard_id)
             .iter()
-            .skip(offset)
-            .filter(|ext| shard_id.contains_address(&ext.info.dst))
+            .filter(|(_, _, idx)| *idx as usize >= offset)
             .count()
     }
 
     pub fn has_externals_for(&self, shard_id: &ShardIdent, offset: usize) -> bool {
-        self.externals
+        self.shard_dst_index(shard_id)
+            .iter()
+            .any(|(_, _, idx)| *idx as usize >= offset)
+    }
+
+    fn shard_externals_indices(&self, shard_id: &ShardIdent, offset: usize) -> Vec<u32> {
+        let mut indices = self
+            .shard_dst_index(shard_id)
             .iter()
-            .skip(offset)
-            .any(|ext| shard_id.contains_address(&ext.info.dst))
+            .map(|(_, _, idx)| *idx)
+            .filter(|idx| *idx as usize >= offset)
+            .collect::<Vec<_>>();
+        indices.sort_unstable();
+        indices
+    }
+
+    fn shard_dst_index(&self, shard_id: &ShardIdent) -> &[(i32, u64, u32)] {
+        let workchain = shard_id.workchain();
+        let prefix = shard_id.prefix();
+        let tag = prefix & prefix.wrapping_neg();
+        let (lo, hi) = (prefix - tag, prefix + (tag - 1));
+
+        let start = self
+            .dst_index
+            .partition_point(|&(wc, p, _)| (wc, p) < (workchain, lo));
+        let end = self
+            .dst_index
+            .partition_point(|&(wc, p, _)| (wc, p) <= (workchain, hi));
+        &self.dst_index[start..end]
     }
 
     pub fn iter_externals(
@@ -145,3 +244,80 @@ impl GetAnchorResult {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn externals_for_shard() {
+        let make_dst = |workchain: i8, first_byte: u8| {
+            let mut address = HashBytes::ZERO;
+            address.0[0] = first_byte;
+            IntAddr::Std(StdAddr::new(workchain, address))
+        };
+
+        let dsts = [
+            make_dst(0, 0x10),
+            make_dst(-1, 0x10),
+            make_dst(0, 0xc0),
+            make_dst(0, 0x40),
+            make_dst(0, 0x80),
+            make_dst(-1, 0xf0),
+            make_dst(0, 0x00),
+        ];
+        let externals = dsts
+            .iter()
+            .enumerate()
+            .map(|(i, dst)| Arc::new(make_stub_external(1, 1000, i as u32, dst.clone())))
+            .collect::<Vec<_>>();
+        let anchor = MempoolAnchor::new(1, None, PeerId(Default::default()), 1000, externals);
+
+        let indices_for = |shard_id: &ShardIdent| {
+            let indices = anchor
+                .externals_for_shard(shard_id, 0)
+                .map(|ext| {
+                    anchor
+                        .externals
+                        .iter()
+                        .position(|e| Arc::ptr_eq(e, ext))
+                        .unwrap()
+                })
+                .collect::<Vec<_>>();
+
+            // Must be consistent with a full scan
+            let expected = (anchor.externals.iter().enumerate())
+                .filter(|(_, ext)| shard_id.contains_address(&ext.info.dst))
+                .map(|(i, _)| i)
+                .collect::<Vec<_>>();
+            assert_eq!(indices, expected);
+            assert_eq!(anchor.count_externals_for(shard_id, 0), expected.len());
+
+            indices
+        };
+
+        assert_eq!(indices_for(&ShardIdent::MASTERCHAIN), [1, 5]);
+        assert_eq!(indices_for(&ShardIdent::BASECHAIN), [0, 2, 3, 4, 6]);
+
+        // Skip already read externals
+        let after_offset = anchor
+            .externals_for_shard(&ShardIdent::BASECHAIN, 3)
+            .map(|ext| &ext.info.dst)
+            .collect::<Vec<_>>();
+        assert_eq!(after_offset, [&dsts[3], &dsts[4], &dsts[6]]);
+
+        // Split basechain shard
+        let (left, right) = ShardIdent::BASECHAIN.split().unwrap();
+        assert_eq!(indices_for(&left), [0, 3, 6]);
+        assert_eq!(indices_for(&right), [2, 4]);
+
+        let (left_left, left_right) = left.split().unwrap();
+        assert_eq!(indices_for(&left_left), [0, 6]);
+        assert_eq!(indices_for(&left_right), [3]);
+
+        // Offset is applied by the anchor order
+        assert_eq!(anchor.count_externals_for(&left, 1), 2);
+        assert!(anchor.has_externals_for(&right, 4));
+        assert!(!anchor.has_externals_for(&right, 5));
+    }
+}
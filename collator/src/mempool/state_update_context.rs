@@ -4,6 +4,7 @@ use everscale_types::cell::HashBytes;
 use everscale_types::models::{
     BlockId, ConsensusConfig, ConsensusInfo, ValidatorDescription, ValidatorSet,
 };
+use tycho_consensus::prelude::PeerWeights;
 use tycho_network::PeerId;
 
 use super::MempoolAnchorId;
@@ -53,6 +54,16 @@ impl StateUpdateContext {
         )
     }
 
+    pub fn prev_v_weights(&self) -> PeerWeights {
+        (self.prev_validator_set.as_ref())
+            .map(|(_, v_set)| Self::peer_weights(v_set))
+            .unwrap_or_default()
+    }
+
+    pub fn curr_v_weights(&self) -> PeerWeights {
+        Self::peer_weights(&self.current_validator_set.1)
+    }
+
     // NOTE: do not try to calculate subset from next set
     //  because it is impossible without known future session_update_round
 
@@ -69,6 +80,14 @@ impl StateUpdateContext {
         )
     }
 
+    fn peer_weights(validator_set: &ValidatorSet) -> PeerWeights {
+        PeerWeights::new(
+            (validator_set.list.iter())
+                .map(|descr| (PeerId(descr.public_key.0), descr.weight))
+                .collect(),
+        )
+    }
+
     fn peer_ids<'a>(iter: impl Iterator<Item = &'a ValidatorDescription>) -> Vec<PeerId> {
         iter.map(|descr| PeerId(descr.public_key.0)).collect()
     }
@@ -61,6 +61,17 @@ where
         partitions: &FastHashSet<QueuePartitionIdx>,
     ) -> Result<()>;
 
+    /// Commit previously applied diffs of several master blocks in a single write,
+    /// so the batch is either fully committed or not committed at all.
+    /// Each item contains master block and its top shard blocks.
+    /// Items should be ordered by master block seqno. Master blocks that were
+    /// already committed are skipped, so committing the same batch twice is a no-op.
+    fn commit_diffs(
+        &self,
+        mc_blocks_top_blocks: Vec<Vec<(BlockId, bool)>>,
+        partitions: &FastHashSet<QueuePartitionIdx>,
+    ) -> Result<()>;
+
     fn clear_uncommitted_state(&self, top_shards: &[ShardIdent]) -> Result<()>;
 
     /// Get diff for the given block from committed and uncommitted state
@@ -218,6 +229,27 @@ impl<V: InternalMessageValue> MessageQueueAdapter<V> for MessageQueueAdapterStdI
         Ok(())
     }
 
+    #[instrument(skip_all, fields(?partitions))]
+    fn commit_diffs(
+        &self,
+        mc_blocks_top_blocks: Vec<Vec<(BlockId, bool)>>,
+        // TODO: get partitions from queue state
+        partitions: &FastHashSet<QueuePartitionIdx>,
+    ) -> Result<()> {
+        let start_time = std::time::Instant::now();
+
+        self.queue.commit_diffs(&mc_blocks_top_blocks, partitions)?;
+
+        let elapsed = start_time.elapsed();
+        tracing::info!(target: tracing_targets::MQ_ADAPTER,
+            mc_blocks_count = mc_blocks_top_blocks.len(),
+            elapsed = %humantime::format_duration(elapsed),
+            "commit_diffs completed"
+        );
+
+        Ok(())
+    }
+
     #[instrument(skip_all)]
     fn clear_uncommitted_state(&self, top_shards: &[ShardIdent]) -> Result<()> {
         let start_time = std::time::Instant::now();
@@ -4,16 +4,18 @@ use std::fmt;
 use std::sync::Arc;
 
 use anyhow::Result;
+use bytes::Bytes;
 use everscale_crypto::ed25519::KeyPair;
 use everscale_types::models::*;
 use everscale_types::prelude::*;
 use processed_upto::{ProcessedUptoInfoExtension, ProcessedUptoInfoStuff};
 use serde::{Deserialize, Serialize};
-use tycho_block_util::block::{BlockStuffAug, ValidatorSubsetInfo};
+use tl_proto::{TlRead, TlWrite};
+use tycho_block_util::block::{BlockStuff, BlockStuffAug, ValidatorSubsetInfo};
 use tycho_block_util::queue::{QueueDiffStuffAug, QueueKey, QueuePartitionIdx};
 use tycho_block_util::state::{RefMcStateHandle, ShardStateStuff};
 use tycho_network::PeerId;
-use tycho_util::FastHashMap;
+use tycho_util::{serde_helpers, FastHashMap};
 
 use crate::collator::ForceMasterCollation;
 use crate::mempool::MempoolAnchorId;
@@ -30,6 +32,8 @@ pub struct CollatorConfig {
     pub check_value_flow: bool,
     pub validate_config: bool,
     pub fast_sync: bool,
+    /// Local overrides of the collation config params for specific shards.
+    pub shard_overrides: Vec<ShardCollationOverride>,
 }
 
 impl Default for CollatorConfig {
@@ -41,6 +45,57 @@ impl Default for CollatorConfig {
             check_value_flow: false,
             validate_config: true,
             fast_sync: true,
+            shard_overrides: Vec::new(),
+        }
+    }
+}
+
+impl CollatorConfig {
+    /// Collation config from the blockchain config with applied shard overrides.
+    pub fn collation_config_for(
+        &self,
+        shard: &ShardIdent,
+        config: &BlockchainConfig,
+    ) -> Result<CollationConfig> {
+        let mut collation_config = config.get_collation_config()?;
+        let shard_override = self
+            .shard_overrides
+            .iter()
+            .find(|item| item.shard == *shard);
+        if let Some(item) = shard_override {
+            item.params.apply(&mut collation_config);
+        }
+        Ok(collation_config)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShardCollationOverride {
+    #[serde(with = "serde_helpers::string")]
+    pub shard: ShardIdent,
+    #[serde(flatten)]
+    pub params: CollationConfigOverride,
+}
+
+/// Params that replace the ones from the blockchain config when set.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CollationConfigOverride {
+    pub empty_sc_block_interval_ms: Option<u32>,
+    pub max_uncommitted_chain_length: Option<u8>,
+    pub wu_used_to_import_next_anchor: Option<u64>,
+}
+
+impl CollationConfigOverride {
+    pub fn apply(&self, collation_config: &mut CollationConfig) {
+        if let Some(value) = self.empty_sc_block_interval_ms {
+            collation_config.empty_sc_block_interval_ms = value;
+        }
+        if let Some(value) = self.max_uncommitted_chain_length {
+            collation_config.max_uncommitted_chain_length = value;
+        }
+        if let Some(value) = self.wu_used_to_import_next_anchor {
+            collation_config.wu_used_to_import_next_anchor = value;
         }
     }
 }
@@ -56,6 +111,8 @@ struct PartialCollatorConfig {
     validate_config: bool,
     #[serde(default = "default_true")]
     fast_sync: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    shard_overrides: Vec<ShardCollationOverride>,
 }
 
 impl<'de> serde::Deserialize<'de> for CollatorConfig {
@@ -70,6 +127,7 @@ impl<'de> serde::Deserialize<'de> for CollatorConfig {
             check_value_flow: partial.check_value_flow,
             validate_config: partial.validate_config,
             fast_sync: partial.fast_sync,
+            shard_overrides: partial.shard_overrides,
             ..Default::default()
         })
     }
@@ -85,6 +143,7 @@ impl serde::Serialize for CollatorConfig {
             check_value_flow: self.check_value_flow,
             validate_config: self.validate_config,
             fast_sync: self.fast_sync,
+            shard_overrides: self.shard_overrides.clone(),
         }
         .serialize(serializer)
     }
@@ -246,6 +305,72 @@ pub struct BlockCandidate {
     pub processed_upto: ProcessedUptoInfoStuff,
 }
 
+impl BlockCandidate {
+    /// Builds a serializable representation of the candidate without signatures.
+    ///
+    /// Returns an error if the candidate was built without raw block data.
+    pub fn to_repr(&self) -> Result<BlockCandidateRepr> {
+        Ok(BlockCandidateRepr {
+            block_id: *self.block.id(),
+            chain_time: self.chain_time,
+            data: Bytes::copy_from_slice(self.block.as_new_archive_data()?),
+            signatures: Vec::new(),
+        })
+    }
+}
+
+/// Serializable block candidate data for passing it between
+/// the collator and validator or for storing it while awaiting signatures.
+#[derive(Debug, Clone, PartialEq, Eq, TlRead, TlWrite)]
+#[tl(boxed, id = "collator.blockCandidate", scheme = "proto.tl")]
+pub struct BlockCandidateRepr {
+    #[tl(with = "tycho_block_util::tl::block_id")]
+    pub block_id: BlockId,
+    pub chain_time: u64,
+    pub data: Bytes,
+    pub signatures: Vec<BlockCandidateSignature>,
+}
+
+impl BlockCandidateRepr {
+    /// Attaches signatures collected for the candidate.
+    pub fn attach_signatures(&mut self, signatures: &BlockSignatures) {
+        self.signatures = signatures
+            .signatures
+            .iter()
+            .map(|(node_id, signature)| BlockCandidateSignature {
+                node_id: *node_id,
+                signature: signature.clone(),
+            })
+            .collect();
+        self.signatures.sort_unstable_by(|a, b| a.node_id.cmp(&b.node_id));
+    }
+
+    /// Returns attached signatures.
+    pub fn signatures(&self) -> BlockSignatures {
+        BlockSignatures {
+            signatures: self
+                .signatures
+                .iter()
+                .map(|item| (item.node_id, item.signature.clone()))
+                .collect(),
+        }
+    }
+
+    /// Deserializes and checks the candidate block.
+    pub fn load_block(&self) -> Result<BlockStuffAug> {
+        let block = BlockStuff::deserialize_checked(&self.block_id, &self.data)?;
+        Ok(block.with_archive_data(self.data.clone()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, TlRead, TlWrite)]
+pub struct BlockCandidateSignature {
+    #[tl(with = "tycho_block_util::tl::hash_bytes")]
+    pub node_id: HashBytes,
+    #[tl(with = "tycho_util::tl::signature_arc")]
+    pub signature: ArcSignature,
+}
+
 #[derive(Default, Clone)]
 pub struct BlockSignatures {
     pub signatures: FastHashMap<HashBytes, ArcSignature>,
@@ -637,3 +762,145 @@ impl ShardIdentExt for ShardIdent {
         false
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn block_candidate_repr_round_trip() {
+        let block = BlockStuff::new_empty(ShardIdent::BASECHAIN, 1);
+        let data = Boc::encode(block.root_cell());
+        let block_id = *block.id();
+
+        let mut repr = BlockCandidateRepr {
+            block_id,
+            chain_time: 1738313270000,
+            data: Bytes::from(data),
+            signatures: Vec::new(),
+        };
+
+        let serialized = tl_proto::serialize(&repr);
+        let deserialized = tl_proto::deserialize::<BlockCandidateRepr>(&serialized).unwrap();
+        assert_eq!(deserialized, repr);
+        assert!(deserialized.signatures().signatures.is_empty());
+
+        // signatures can be attached after deserialization
+        let mut signatures = BlockSignatures::default();
+        signatures
+            .signatures
+            .insert(HashBytes([1; 32]), Arc::new([2; 64]));
+        signatures
+            .signatures
+            .insert(HashBytes([3; 32]), Arc::new([4; 64]));
+        repr.attach_signatures(&signatures);
+
+        let serialized = tl_proto::serialize(&repr);
+        let deserialized = tl_proto::deserialize::<BlockCandidateRepr>(&serialized).unwrap();
+        assert_eq!(deserialized, repr);
+        assert_eq!(deserialized.signatures().signatures, signatures.signatures);
+
+        let block_aug = deserialized.load_block().unwrap();
+        assert_eq!(block_aug.id(), &block_id);
+        assert_eq!(block_aug.as_new_archive_data().unwrap(), repr.data.as_ref());
+    }
+
+    fn base_collation_config() -> CollationConfig {
+        let mut group_slots_fractions = Dict::<u16, u8>::new();
+        group_slots_fractions.set(0, 80).unwrap();
+        group_slots_fractions.set(1, 10).unwrap();
+
+        CollationConfig {
+            shuffle_mc_validators: true,
+
+            mc_block_min_interval_ms: 2500,
+            empty_sc_block_interval_ms: 60_000,
+
+            max_uncommitted_chain_length: 31,
+
+            msgs_exec_params: MsgsExecutionParams {
+                buffer_limit: 10_000,
+                group_limit: 100,
+                group_vert_size: 10,
+                externals_expire_timeout: 60,
+                open_ranges_limit: 100,
+                par_0_int_msgs_count_limit: 50_000,
+                par_0_ext_msgs_count_limit: 5_000,
+                group_slots_fractions,
+                range_messages_limit: 10_000,
+            },
+
+            wu_used_to_import_next_anchor: 1_200_000_000,
+
+            work_units_params: WorkUnitsParams {
+                prepare: WorkUnitsParamsPrepare {
+                    fixed_part: 500_000, // 500 ns
+                    msgs_stats: 0,
+                    remaning_msgs_stats: 0,
+                    read_ext_msgs: 200,     // 200 ns
+                    read_int_msgs: 5_000,   // 5 mcs
+                    read_new_msgs: 500,     // 500 ns
+                    add_to_msg_groups: 150, // 150 ns
+                },
+                execute: WorkUnitsParamsExecute {
+                    prepare: 114_000,          // 114 mcs
+                    execute_err: 6_000,        // 6 mcs
+                    execute: 25_000,           // 25 mcs
+                    execute_delimiter: 10_000, //
+                    serialize_enqueue: 3_000,  // 3 mcs
+                    serialize_dequeue: 3_000,  // 3 mcs
+                    insert_new_msgs: 3_000,    // 3 mcs
+                    subgroup_size: 16,
+                },
+                finalize: WorkUnitsParamsFinalize {
+                    build_transactions: 1_000,    // 1 mcs
+                    build_accounts: 500,          // 0.5 mcs
+                    build_in_msg: 500,            // 0.5 mcs
+                    build_out_msg: 500,           // 0.5 mcs
+                    serialize_min: 15_000_000,    // 15 ms
+                    serialize_accounts: 1_000,    // 1 mcs
+                    serialize_msg: 2_000,         // 2 mcs
+                    state_update_min: 15_000_000, // 15 ms
+                    state_update_accounts: 500,   // 0.5 mcs
+                    state_update_msg: 2_000,      // 2 mcs
+                    create_diff: 0,
+                    serialize_diff: 0,
+                    apply_diff: 0,
+                    diff_tail_len: 0,
+                },
+            },
+        }
+    }
+
+    #[test]
+    fn shard_collation_overrides() -> Result<()> {
+        let base = base_collation_config();
+        let mut blockchain_config = BlockchainConfig::new_empty(HashBytes([0x55; 32]));
+        blockchain_config.params.set_collation_config(&base)?;
+
+        let config = CollatorConfig {
+            shard_overrides: vec![ShardCollationOverride {
+                shard: ShardIdent::BASECHAIN,
+                params: CollationConfigOverride {
+                    empty_sc_block_interval_ms: Some(1_000),
+                    max_uncommitted_chain_length: Some(5),
+                    ..Default::default()
+                },
+            }],
+            ..Default::default()
+        };
+
+        let overridden = config.collation_config_for(&ShardIdent::BASECHAIN, &blockchain_config)?;
+        assert_eq!(overridden.empty_sc_block_interval_ms, 1_000);
+        assert_eq!(overridden.max_uncommitted_chain_length, 5);
+        assert_eq!(
+            overridden.wu_used_to_import_next_anchor,
+            base.wu_used_to_import_next_anchor
+        );
+
+        let other = config.collation_config_for(&ShardIdent::MASTERCHAIN, &blockchain_config)?;
+        assert_eq!(other, base);
+
+        Ok(())
+    }
+}
@@ -89,6 +89,7 @@ async fn test_collation_process_on_stubs() {
         check_value_flow: false,
         validate_config: true,
         fast_sync: false,
+        shard_overrides: Vec::new(),
     };
 
     tracing::info!("Trying to start CollationManager");
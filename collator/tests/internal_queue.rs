@@ -1440,6 +1440,94 @@ async fn test_queue_tail_and_diff_info() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_commit_diffs_batch_twice() -> anyhow::Result<()> {
+    let (storage, _tmp_dir) = Storage::new_temp().await?;
+
+    let queue_factory = QueueFactoryStdImpl {
+        state: QueueStateImplFactory { storage },
+        config: QueueConfig {
+            gc_interval: Duration::from_secs(1),
+        },
+    };
+
+    let queue: QueueImpl<QueueStateStdImpl, StoredObject> = queue_factory.create();
+
+    let block_mc1 = BlockId {
+        shard: ShardIdent::MASTERCHAIN,
+        seqno: 1,
+        root_hash: Default::default(),
+        file_hash: Default::default(),
+    };
+    let block_mc2 = BlockId {
+        shard: ShardIdent::MASTERCHAIN,
+        seqno: 2,
+        root_hash: Default::default(),
+        file_hash: Default::default(),
+    };
+
+    for (block_id, key) in [(block_mc1, 1), (block_mc2, 2)] {
+        let mut diff = QueueDiffWithMessages::new();
+        let stored_object = create_stored_object(key, RouterAddr {
+            workchain: -1,
+            account: HashBytes::from([key as u8; 32]),
+        })?;
+        diff.messages.insert(stored_object.key(), stored_object);
+
+        let statistics = DiffStatistics::from_diff(
+            &diff,
+            block_id.shard,
+            diff.min_message().cloned().unwrap_or_default(),
+            diff.max_message().cloned().unwrap_or_default(),
+        );
+
+        queue.apply_diff(
+            diff,
+            block_id.as_short_id(),
+            &HashBytes::from([key as u8; 32]),
+            statistics,
+            Some(DiffZone::Both),
+        )?;
+    }
+
+    let partitions: FastHashSet<_> = [QueuePartitionIdx::default(), 1].into_iter().collect();
+    let batch = vec![vec![(block_mc1, true)], vec![(block_mc2, true)]];
+
+    // batch with a missing diff is not committed partially
+    let block_mc3 = BlockId {
+        seqno: 3,
+        ..block_mc2
+    };
+    let broken_batch = vec![vec![(block_mc1, true)], vec![(block_mc3, true)]];
+    assert!(queue.commit_diffs(&broken_batch, &partitions).is_err());
+
+    assert_eq!(queue.get_last_committed_mc_block_id()?, None);
+    let res = queue.get_diff_info(&block_mc1.shard, block_mc1.seqno, DiffZone::Uncommitted)?;
+    assert!(res.is_some());
+
+    // commit the whole batch
+    queue.commit_diffs(&batch, &partitions)?;
+
+    assert_eq!(queue.get_last_committed_mc_block_id()?, Some(block_mc2));
+    for block_id in [block_mc1, block_mc2] {
+        let res = queue.get_diff_info(&block_id.shard, block_id.seqno, DiffZone::Uncommitted)?;
+        assert!(res.is_none());
+        let res = queue.get_diff_info(&block_id.shard, block_id.seqno, DiffZone::Committed)?;
+        assert!(res.is_some());
+    }
+    let diff_len_mc = queue.get_diffs_tail_len(&ShardIdent::MASTERCHAIN, &QueueKey::MIN);
+    assert_eq!(diff_len_mc, 2);
+
+    // committing the same batch again is a no-op
+    queue.commit_diffs(&batch, &partitions)?;
+
+    assert_eq!(queue.get_last_committed_mc_block_id()?, Some(block_mc2));
+    let diff_len_mc = queue.get_diffs_tail_len(&ShardIdent::MASTERCHAIN, &QueueKey::MIN);
+    assert_eq!(diff_len_mc, 2);
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
 async fn test_version() -> anyhow::Result<()> {
     let (storage, _tmp_dir) = Storage::new_temp().await?;
@@ -0,0 +1,147 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use criterion::measurement::{Measurement, ValueFormatter};
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use tycho_consensus::test_utils::bench::{Digest, PointByIdRequests, PointId, QueryRequest, Round};
+use tycho_network::PeerId;
+
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+/// same ids requested by a number of concurrent download tasks
+const POINTS: u8 = 100;
+const TASKS_PER_POINT: usize = 30;
+
+fn point_ids() -> Vec<PointId> {
+    (0..POINTS)
+        .map(|i| PointId {
+            author: PeerId([i; 32]),
+            round: Round(i as u32),
+            digest: Digest::wrap([i; 32]),
+        })
+        .collect()
+}
+
+/// Counts heap allocations instead of time, so the harness reports them along with timings
+struct Allocations;
+
+impl Measurement for Allocations {
+    type Intermediate = usize;
+    type Value = usize;
+
+    fn start(&self) -> Self::Intermediate {
+        ALLOCATIONS.load(Ordering::Relaxed)
+    }
+
+    fn end(&self, start: Self::Intermediate) -> Self::Value {
+        ALLOCATIONS.load(Ordering::Relaxed) - start
+    }
+
+    fn add(&self, v1: &Self::Value, v2: &Self::Value) -> Self::Value {
+        v1 + v2
+    }
+
+    fn zero(&self) -> Self::Value {
+        0
+    }
+
+    fn to_f64(&self, value: &Self::Value) -> f64 {
+        *value as f64
+    }
+
+    fn formatter(&self) -> &dyn ValueFormatter {
+        &AllocationsFormatter
+    }
+}
+
+struct AllocationsFormatter;
+
+impl ValueFormatter for AllocationsFormatter {
+    fn scale_values(&self, _typical_value: f64, _values: &mut [f64]) -> &'static str {
+        "allocs"
+    }
+
+    fn scale_throughputs(
+        &self,
+        _typical_value: f64,
+        throughput: &Throughput,
+        values: &mut [f64],
+    ) -> &'static str {
+        let (amount, unit) = match *throughput {
+            Throughput::Elements(elems) => (elems, "allocs/elem"),
+            Throughput::Bytes(bytes) | Throughput::BytesDecimal(bytes) => (bytes, "allocs/byte"),
+        };
+        for value in values {
+            *value /= amount as f64;
+        }
+        unit
+    }
+
+    fn scale_for_machines(&self, _values: &mut [f64]) -> &'static str {
+        "allocs"
+    }
+}
+
+fn encode_all(ids: &[PointId]) {
+    for id in ids {
+        for _ in 0..TASKS_PER_POINT {
+            criterion::black_box(QueryRequest::point_by_id(id));
+        }
+    }
+}
+
+fn cached_all(cache: &PointByIdRequests, ids: &[PointId]) {
+    for id in ids {
+        for _ in 0..TASKS_PER_POINT {
+            criterion::black_box(cache.get(id));
+        }
+    }
+}
+
+fn point_by_id_request_benchmark<M: Measurement>(c: &mut Criterion<M>, group_name: &str) {
+    let ids = point_ids();
+    let mut group = c.benchmark_group(group_name);
+
+    group.bench_function("encode", |b| {
+        b.iter(|| encode_all(&ids));
+    });
+
+    let cache = PointByIdRequests::new(POINTS as usize);
+    group.bench_function("cached", |b| {
+        b.iter(|| cached_all(&cache, &ids));
+    });
+
+    group.finish();
+}
+
+fn time_benchmark(c: &mut Criterion) {
+    point_by_id_request_benchmark(c, "point-by-id-request");
+}
+
+fn allocations_benchmark(c: &mut Criterion<Allocations>) {
+    point_by_id_request_benchmark(c, "point-by-id-request-allocations");
+}
+
+criterion_group!(benches, time_benchmark);
+criterion_group! {
+    name = allocations;
+    config = Criterion::default().with_measurement(Allocations);
+    targets = allocations_benchmark
+}
+criterion_main!(benches, allocations);
@@ -194,7 +194,7 @@ fn make_network(
                                 cli.steps_until_full,
                                 merged_conf.consensus(),
                             ),
-                            output: committed_tx,
+                            output: Arc::new(committed_tx),
                             top_known_anchor,
                         };
 
@@ -65,10 +65,21 @@ impl AnchorStage {
     }
 }
 
-pub fn align_genesis(start_round: u32) -> Round {
-    Round(((start_round + 1) / WAVE_ROUNDS) * WAVE_ROUNDS + 2)
+/// Genesis is placed at the first round with `Proof` anchor stage role (`4k+2`)
+/// that is not less than `start_round`, so it is shifted forward by at most 3 rounds.
+/// Note that `start_round` usually comes from the last processed anchor and is not aligned.
+pub fn align_genesis(start_round: u32) -> Result<Round, GenesisAlignError> {
+    (start_round.checked_add(1))
+        .map(|round| (round / WAVE_ROUNDS) * WAVE_ROUNDS)
+        .and_then(|round| round.checked_add(2))
+        .map(Round)
+        .ok_or(GenesisAlignError(start_round))
 }
 
+#[derive(thiserror::Error, Debug)]
+#[error("genesis start round {0} is too large to be aligned to a {WAVE_ROUNDS} rounds wave")]
+pub struct GenesisAlignError(u32);
+
 #[cfg(test)]
 mod tests {
     use anyhow::{ensure, Result};
@@ -77,8 +88,8 @@ mod tests {
 
     #[test]
     pub fn test_genesis_aligned() -> Result<()> {
-        for start_round in 0..10 {
-            let genesis_round = align_genesis(start_round).0;
+        for start_round in (0..10).chain(u32::MAX - 10..u32::MAX - 4) {
+            let genesis_round = align_genesis(start_round)?.0;
             ensure!(
                 genesis_round >= start_round,
                 "genesis round must not be less than start round after alignment, \
@@ -107,4 +118,29 @@ mod tests {
         }
         Ok(())
     }
+
+    #[test]
+    pub fn test_genesis_edge_rounds() -> Result<()> {
+        ensure!(align_genesis(0)?.0 == 2, "zero start round");
+        ensure!(
+            align_genesis(2)?.0 == 2,
+            "already aligned start round must not shift"
+        );
+        ensure!(align_genesis(3)?.0 == 6, "must shift to the next wave");
+        ensure!(
+            align_genesis(6)?.0 == 6,
+            "already aligned start round must not shift"
+        );
+        // the last representable proof round is `u32::MAX - 1`
+        ensure!(
+            align_genesis(u32::MAX - 4)?.0 == u32::MAX - 1,
+            "last aligned"
+        );
+        ensure!(
+            align_genesis(u32::MAX - 1)?.0 == u32::MAX - 1,
+            "last aligned"
+        );
+        ensure!(align_genesis(u32::MAX).is_err(), "must not overflow");
+        Ok(())
+    }
 }
@@ -21,6 +21,10 @@ impl AnchorChain {
         self.queue.back()
     }
 
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
     pub fn top_proof_round(&self) -> Option<Round> {
         Some(self.queue.back()?.proof.round())
     }
@@ -23,6 +23,19 @@ enum SyncError {
     HistoryConflict(Round),
 }
 
+/// Read-only copy of [`Committer`] state to inspect a stalled commit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommitterSnapshot {
+    pub bottom_round: Round,
+    pub top_round: Round,
+    /// number of retained rounds
+    pub dag_len: usize,
+    pub full_history_bottom: Round,
+    /// number of anchors that are determined but not committed yet
+    pub anchor_chain_len: usize,
+    pub anchor_chain_top_proof: Option<Round>,
+}
+
 pub struct Committer {
     dag: DagBack,
     // from the oldest to the current round; newer ones are in the future;
@@ -62,6 +75,17 @@ impl Committer {
         self.dag.len()
     }
 
+    pub fn dump(&self) -> CommitterSnapshot {
+        CommitterSnapshot {
+            bottom_round: self.dag.bottom_round(),
+            top_round: self.dag.top().round(),
+            dag_len: self.dag.len(),
+            full_history_bottom: self.full_history_bottom,
+            anchor_chain_len: self.anchor_chain.len(),
+            anchor_chain_top_proof: self.anchor_chain.top_proof_round(),
+        }
+    }
+
     /// returns new bottom after gap if it was moved, and `None` if no gap occurred
     pub fn extend_from_ahead(&mut self, rounds: &[DagRound]) {
         self.dag.extend_from_front(rounds);
@@ -398,6 +422,55 @@ mod test {
         std::io::stdout().flush().ok();
     }
 
+    #[tokio::test]
+    async fn dump_reflects_state() {
+        let peers: [(PeerId, Arc<KeyPair>); PEER_COUNT] = array::from_fn(|i| {
+            let keys = KeyPair::from(&SecretKey::from_bytes([i as u8; 32]));
+            (PeerId::from(keys.public_key), Arc::new(keys))
+        });
+
+        let (peer_schedule, _, _, engine_ctx) =
+            test_utils::make_engine_parts(&peers, peers[0].1.clone());
+        let conf = engine_ctx.conf();
+        let commit_history_rounds = conf.consensus.commit_history_rounds;
+
+        let genesis_round = DagRound::new_bottom(conf.genesis_round, &peer_schedule, conf);
+        let mut dag = DagFront::default();
+        let mut committer = dag.init(genesis_round, conf);
+
+        let snapshot = committer.dump();
+        assert_eq!(snapshot, CommitterSnapshot {
+            bottom_round: conf.genesis_round,
+            top_round: conf.genesis_round,
+            dag_len: 1,
+            full_history_bottom: conf.genesis_round + commit_history_rounds,
+            anchor_chain_len: 0,
+            anchor_chain_top_proof: None,
+        });
+
+        let top_round = conf.genesis_round + 10_u32;
+        let round_ctx = RoundCtx::new(&engine_ctx, top_round);
+        _ = dag.fill_to_top(top_round, Some(&mut committer), &peer_schedule, &round_ctx);
+
+        let snapshot = committer.dump();
+        assert_eq!(snapshot.bottom_round, conf.genesis_round);
+        assert_eq!(snapshot.top_round, top_round);
+        assert_eq!(snapshot.dag_len, 11);
+
+        let new_bottom = conf.genesis_round + 4_u32;
+        assert!(committer.drop_upto(new_bottom, conf).is_ok());
+
+        let snapshot = committer.dump();
+        assert_eq!(snapshot, CommitterSnapshot {
+            bottom_round: new_bottom,
+            top_round,
+            dag_len: 7,
+            full_history_bottom: new_bottom + commit_history_rounds,
+            anchor_chain_len: 0,
+            anchor_chain_top_proof: None,
+        });
+    }
+
     fn remove_round(dag: &mut DagBack, round: Round) -> (Round, FastDashMap<PeerId, DagLocation>) {
         let in_question = dag.get(round).expect("in dag").clone();
         let removed = in_question.locations().clone();
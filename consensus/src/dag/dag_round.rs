@@ -313,32 +313,66 @@ impl DagRound {
     }
 
     pub fn scan(&self, round: Round) -> Option<Self> {
-        assert!(
-            round <= self.round(),
-            "Coding error: cannot scan DAG rounds chain for a future round"
-        );
+        match self.try_scan(round) {
+            Ok(dag_round) => Some(dag_round),
+            Err(DagRoundScanError::NotFound { .. }) => None,
+            Err(e) => panic!("Coding error: {e}"),
+        }
+    }
+
+    /// Same as [`Self::scan`] but leaves it to the caller to decide
+    /// whether a missing round is fatal or may be handled gracefully
+    pub fn try_scan(&self, round: Round) -> Result<Self, DagRoundScanError> {
+        if round > self.round() {
+            return Err(DagRoundScanError::FutureRound {
+                round,
+                top: self.round(),
+            });
+        }
         let mut visited = self.clone();
         if visited.round() == round {
-            return Some(visited);
+            return Ok(visited);
         }
         while let Some(dag_round) = visited.prev().upgrade() {
             match dag_round.round().cmp(&round) {
-                cmp::Ordering::Less => panic!(
-                    "Coding error: linked list of dag rounds cannot contain gaps, \
-                    found {} to be prev for {}, scanned for {} from {}",
-                    dag_round.round().0,
-                    visited.round().0,
-                    round.0,
-                    self.round().0
-                ),
-                cmp::Ordering::Equal => return Some(dag_round),
+                cmp::Ordering::Less => {
+                    return Err(DagRoundScanError::Gap {
+                        prev: dag_round.round(),
+                        next: visited.round(),
+                        round,
+                        top: self.round(),
+                    });
+                }
+                cmp::Ordering::Equal => return Ok(dag_round),
                 cmp::Ordering::Greater => visited = dag_round,
             }
         }
-        None
+        Err(DagRoundScanError::NotFound {
+            round,
+            bottom: visited.round(),
+        })
     }
 }
 
+#[derive(thiserror::Error, Debug)]
+pub enum DagRoundScanError {
+    #[error("cannot scan DAG rounds chain for a future round {} from {}", round.0, top.0)]
+    FutureRound { round: Round, top: Round },
+    #[error(
+        "linked list of dag rounds cannot contain gaps, \
+        found {} to be prev for {}, scanned for {} from {}",
+        prev.0, next.0, round.0, top.0
+    )]
+    Gap {
+        prev: Round,
+        next: Round,
+        round: Round,
+        top: Round,
+    },
+    #[error("DAG round {} not found, bottom is {}", round.0, bottom.0)]
+    NotFound { round: Round, bottom: Round },
+}
+
 impl AltFormat for DagRound {}
 impl std::fmt::Debug for AltFmt<'_, DagRound> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -351,3 +385,52 @@ impl std::fmt::Debug for AltFmt<'_, DagRound> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::array;
+    use std::sync::Arc;
+
+    use everscale_crypto::ed25519::{KeyPair, SecretKey};
+    use tycho_network::PeerId;
+
+    use super::*;
+    use crate::effects::Ctx;
+    use crate::test_utils;
+
+    const PEER_COUNT: usize = 3;
+
+    #[tokio::test]
+    async fn scan_present_and_absent_rounds() {
+        let peers: [(PeerId, Arc<KeyPair>); PEER_COUNT] = array::from_fn(|i| {
+            let keys = KeyPair::from(&SecretKey::from_bytes([i as u8; 32]));
+            (PeerId::from(keys.public_key), Arc::new(keys))
+        });
+
+        let (peer_schedule, _, _, engine_ctx) =
+            test_utils::make_engine_parts(&peers, peers[0].1.clone());
+        let conf = engine_ctx.conf();
+
+        let bottom = DagRound::new_bottom(conf.genesis_round, &peer_schedule, conf);
+        let next = bottom.new_next(&peer_schedule, conf);
+        let top = next.new_next(&peer_schedule, conf);
+
+        let found = top.try_scan(conf.genesis_round).expect("must be found");
+        assert_eq!(found.round(), conf.genesis_round);
+        let found = top.try_scan(next.round()).expect("must be found");
+        assert_eq!(found.round(), next.round());
+        assert!(top.scan(top.round()).is_some());
+
+        let absent = conf.genesis_round.prev();
+        assert!(matches!(
+            top.try_scan(absent),
+            Err(DagRoundScanError::NotFound { .. })
+        ));
+        assert!(top.scan(absent).is_none());
+
+        assert!(matches!(
+            next.try_scan(top.round()),
+            Err(DagRoundScanError::FutureRound { .. })
+        ));
+    }
+}
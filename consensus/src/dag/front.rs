@@ -1,3 +1,4 @@
+use crate::dag::rounds_buffer::RoundsBuffer;
 use crate::dag::{Committer, DagHead, DagRound};
 use crate::effects::{AltFmt, AltFormat, Ctx, EngineCtx, RoundCtx};
 use crate::engine::{ConsensusConfigExt, MempoolConfig};
@@ -6,7 +7,7 @@ use crate::models::Round;
 
 pub struct DagFront {
     // from the oldest in front to the current round and the next one in back
-    rounds: Vec<DagRound>,
+    rounds: RoundsBuffer,
     // back bottom may be moved by commit
     last_back_bottom: Round,
     // keep until committer is resolved
@@ -16,7 +17,7 @@ pub struct DagFront {
 impl Default for DagFront {
     fn default() -> Self {
         Self {
-            rounds: Vec::new(),
+            rounds: RoundsBuffer::default(),
             last_back_bottom: Round::BOTTOM,
             has_pending_back_reset: false,
         }
@@ -113,9 +114,12 @@ impl DagFront {
             }
             committer
                 .extend_from_ahead(&self.drain_upto(new_top - conf.consensus.min_front_rounds()));
-            committer.extend_from_ahead(&self.rounds);
+            for chunk in self.rounds.chunks() {
+                committer.extend_from_ahead(chunk);
+            }
             EngineCtx::meter_dag_len(committer.dag_len());
         }
+        EngineCtx::meter_dag_front_len(self.rounds.len());
 
         new_full_history_bottom
     }
@@ -123,10 +127,8 @@ impl DagFront {
     fn drain_upto(&mut self, new_bottom_round: Round) -> Vec<DagRound> {
         let bottom = self.bottom_round();
 
-        let amount = (new_bottom_round - bottom.0).0 as usize;
-
         // leaves bottom round in place
-        let result = self.rounds.drain(..amount).collect::<Vec<_>>();
+        let result = self.rounds.drain_below(new_bottom_round);
 
         assert_eq!(
             self.bottom_round(),
@@ -140,11 +142,115 @@ impl DagFront {
     }
 }
 
+#[cfg(feature = "dag-export")]
+impl DagFront {
+    /// Renders the in-memory DAG as a Graphviz DOT graph.
+    ///
+    /// Every point version is a node clustered by round and coloured by its status;
+    /// solid edges are includes and dashed edges are witness.
+    /// Not resolved versions are drawn without edges.
+    /// Walks the whole front, so use only for debugging.
+    pub fn export_dot(&self) -> String {
+        let mut dot = String::new();
+        self.write_dot(&mut dot)
+            .expect("writing to string must not fail");
+        dot
+    }
+
+    fn write_dot(&self, f: &mut impl std::fmt::Write) -> std::fmt::Result {
+        use std::sync::atomic;
+
+        use futures_util::FutureExt;
+
+        use crate::models::DagPoint;
+
+        writeln!(f, "digraph dag {{")?;
+        writeln!(f, "  rankdir=BT;")?;
+        writeln!(f, "  node [shape=box, style=filled];")?;
+
+        for dag_round in self.rounds.iter() {
+            let round = dag_round.round();
+
+            // dash map order is random, keep output stable
+            let mut versions = dag_round
+                .select(|(author, loc)| {
+                    let versions = loc
+                        .versions
+                        .iter()
+                        .map(|(digest, future)| (*author, *digest, future.clone()))
+                        .collect::<Vec<_>>();
+                    Some(versions)
+                })
+                .flatten()
+                .collect::<Vec<_>>();
+            versions.sort_unstable_by_key(|(author, digest, _)| (*author, *digest));
+
+            writeln!(f, "  subgraph cluster_{} {{", round.0)?;
+            writeln!(f, "    label=\"round {}\";", round.0)?;
+
+            let mut edges = Vec::new();
+            for (author, digest, future) in &versions {
+                let node = DotNode(round, author, digest).to_string();
+                let resolved = future.clone().now_or_never();
+
+                let (status, color) = match &resolved {
+                    None => ("pending", "white"),
+                    Some(Err(_)) => ("cancelled", "gray"),
+                    Some(Ok(DagPoint::Valid(valid))) => {
+                        if valid.is_committed().load(atomic::Ordering::Relaxed) {
+                            ("committed", "palegreen")
+                        } else {
+                            ("valid", "lightblue")
+                        }
+                    }
+                    Some(Ok(DagPoint::Invalid(_))) => ("invalid", "orange"),
+                    Some(Ok(DagPoint::IllFormed(_))) => ("ill-formed", "red"),
+                    Some(Ok(DagPoint::NotFound(_))) => ("not found", "lightgray"),
+                };
+
+                let label = format!("{} @ {}\\n#{digest:.4}\\n{status}", author.alt(), round.0);
+                writeln!(f, "    \"{node}\" [label=\"{label}\", fillcolor={color}];")?;
+
+                let Some(info) = resolved.as_ref().and_then(|r| r.as_ref().ok()?.trusted()) else {
+                    continue;
+                };
+                for (peer, dep) in info.includes() {
+                    let to = DotNode(round.prev(), peer, dep).to_string();
+                    edges.push((node.clone(), to, "solid"));
+                }
+                for (peer, dep) in info.witness() {
+                    let to = DotNode(round.prev().prev(), peer, dep).to_string();
+                    edges.push((node.clone(), to, "dashed"));
+                }
+            }
+
+            writeln!(f, "  }}")?;
+
+            for (from, to, style) in edges {
+                writeln!(f, "  \"{from}\" -> \"{to}\" [style={style}];")?;
+            }
+        }
+
+        writeln!(f, "}}")
+    }
+}
+
+/// Unique node id, not truncated unlike log output
+#[cfg(feature = "dag-export")]
+struct DotNode<'a>(Round, &'a tycho_network::PeerId, &'a crate::models::Digest);
+
+#[cfg(feature = "dag-export")]
+impl std::fmt::Display for DotNode<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}:{}", self.0 .0, self.1, self.2)
+    }
+}
+
 impl AltFormat for DagFront {}
 impl std::fmt::Debug for AltFmt<'_, DagFront> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let inner = AltFormat::unpack(self);
-        for dag_round in &inner.rounds {
+        for dag_round in inner.rounds.iter() {
             write!(f, "{:?}; ", dag_round.alt())?;
         }
         write!(
@@ -178,3 +284,98 @@ impl std::fmt::Display for AltFmt<'_, DagFront> {
         )
     }
 }
+
+#[cfg(all(test, feature = "dag-export"))]
+mod tests {
+    use std::array;
+    use std::sync::atomic::Ordering;
+    use std::sync::Arc;
+
+    use everscale_crypto::ed25519::{KeyPair, SecretKey};
+    use futures_util::FutureExt;
+    use tycho_network::PeerId;
+
+    use super::*;
+    use crate::effects::MempoolStore;
+    use crate::models::DagPoint;
+    use crate::test_utils;
+
+    const PEER_COUNT: usize = 3;
+
+    #[tokio::test]
+    async fn export_dot_contains_points_and_links() {
+        let stub_store = MempoolStore::no_read_stub();
+
+        let peers: [(PeerId, Arc<KeyPair>); PEER_COUNT] = array::from_fn(|i| {
+            let keys = KeyPair::from(&SecretKey::from_bytes([i as u8; 32]));
+            (PeerId::from(keys.public_key), Arc::new(keys))
+        });
+        let local_keys = &peers[0].1;
+
+        let (peer_schedule, stub_downloader, genesis, engine_ctx) =
+            test_utils::make_engine_parts(&peers, local_keys.clone());
+        let conf = engine_ctx.conf();
+
+        let mut round_ctx = RoundCtx::new(&engine_ctx, conf.genesis_round);
+
+        let genesis_round = DagRound::new_bottom(conf.genesis_round, &peer_schedule, conf);
+        genesis_round
+            .add_local(&genesis, Some(local_keys), &stub_store, &round_ctx)
+            .await
+            .expect("cannot be closed");
+
+        let mut dag = DagFront::default();
+        let _committer = dag.init(genesis_round, conf);
+
+        let top_round = conf.genesis_round.next().next();
+        for round in conf.genesis_round.next().0..=top_round.0 {
+            round_ctx = RoundCtx::new(&engine_ctx, Round(round));
+            dag.fill_to_top(Round(round), None, &peer_schedule, &round_ctx);
+            test_utils::populate_points(
+                dag.top(),
+                &peers,
+                local_keys,
+                &peer_schedule,
+                &stub_downloader,
+                &stub_store,
+                &round_ctx,
+                0,
+                0,
+            )
+            .await;
+        }
+
+        let top_points = dag
+            .top()
+            .select(|(_, loc)| {
+                let dag_point = loc.versions.values().next()?.clone().now_or_never()?;
+                dag_point.ok()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(top_points.len(), PEER_COUNT);
+
+        let Some(DagPoint::Valid(committed)) = top_points.first() else {
+            panic!("local points must be valid");
+        };
+        committed.is_committed().store(true, Ordering::Relaxed);
+
+        let dot = dag.export_dot();
+
+        assert!(dot.starts_with("digraph dag {"));
+        for round in conf.genesis_round.0..=top_round.0 {
+            assert!(dot.contains(&format!("subgraph cluster_{round} {{")));
+        }
+        for dag_point in &top_points {
+            let info = dag_point.trusted().expect("must be valid");
+            let from = DotNode(top_round, &info.author(), info.digest()).to_string();
+            assert!(dot.contains(&format!("\"{from}\" [label=")));
+            assert_eq!(info.includes().len(), PEER_COUNT);
+            for (peer, digest) in info.includes() {
+                let to = DotNode(top_round.prev(), peer, digest);
+                assert!(dot.contains(&format!("\"{from}\" -> \"{to}\" [style=solid];")));
+            }
+        }
+        assert_eq!(dot.matches("committed").count(), 1);
+        assert!(dot.trim_end().ends_with('}'));
+    }
+}
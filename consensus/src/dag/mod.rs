@@ -1,6 +1,6 @@
-pub use anchor_stage::align_genesis;
 #[cfg(feature = "test")]
 pub use anchor_stage::AnchorStage;
+pub use anchor_stage::{align_genesis, GenesisAlignError};
 pub use commit::*;
 pub use dag_round::*;
 pub use front::*;
@@ -17,5 +17,6 @@ mod dag_round;
 mod front;
 mod head;
 mod producer;
+mod rounds_buffer;
 mod threshold;
 mod verifier;
@@ -1,4 +1,5 @@
 use std::collections::BTreeMap;
+use std::time::Instant;
 
 use tycho_network::PeerId;
 
@@ -28,6 +29,8 @@ pub enum ProduceError {
     NextRoundThreshold,
     #[error("node is not scheduled at this round")]
     NotScheduled,
+    #[error("node lags behind consensus")]
+    Lagging,
     #[error("included prev point # {} != broadcasted # {}", included.alt(), broadcasted.alt())]
     PrevPointMismatch {
         included: Digest,
@@ -41,6 +44,7 @@ impl Producer {
     pub fn new_point(
         last_own_point: Option<&LastOwnPoint>,
         input_buffer: &InputBuffer,
+        payload_deadline: Option<Instant>,
         head: &DagHead,
         conf: &MempoolConfig,
     ) -> Result<Point, ProduceError> {
@@ -92,12 +96,13 @@ impl Producer {
             AnchorStageRole::Proof,
         );
 
-        let payload = input_buffer.fetch(last_own_point.as_ref().is_none_or(|last| {
+        let only_fresh = last_own_point.as_ref().is_none_or(|last| {
             // it's not necessary to resend external messages from previous round
             // if at least 1F+1 peers (one reliable) signed previous point;
             // also notice that payload elems are deduplicated in mempool adapter
             last.evidence.len() >= last.signers.reliable_minority()
-        }));
+        });
+        let payload = input_buffer.fetch(only_fresh, payload_deadline);
 
         let prev_info = (includes.iter()).find(|point| point.author() == local_id);
 
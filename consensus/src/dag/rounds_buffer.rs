@@ -0,0 +1,150 @@
+use std::collections::VecDeque;
+
+use crate::dag::DagRound;
+use crate::models::Round;
+
+/// Contiguous chain of [`DagRound`]s, from the oldest to the newest.
+///
+/// Rounds are kept in fixed-size chunks, so a long range is appended without reallocation
+/// of already stored rounds, and drained rounds free whole chunks
+/// instead of shifting the rest of the buffer.
+pub struct RoundsBuffer {
+    chunks: VecDeque<Vec<DagRound>>,
+    len: usize,
+}
+
+impl Default for RoundsBuffer {
+    fn default() -> Self {
+        Self {
+            chunks: VecDeque::new(),
+            len: 0,
+        }
+    }
+}
+
+impl RoundsBuffer {
+    const CHUNK_LEN: usize = 16;
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn first(&self) -> Option<&DagRound> {
+        self.chunks.front().and_then(|chunk| chunk.first())
+    }
+
+    pub fn last(&self) -> Option<&DagRound> {
+        self.chunks.back().and_then(|chunk| chunk.last())
+    }
+
+    pub fn push(&mut self, dag_round: DagRound) {
+        if let Some(last) = self.last() {
+            assert_eq!(
+                last.round().next(),
+                dag_round.round(),
+                "rounds buffer must be contiguous"
+            );
+        }
+        match self.chunks.back_mut() {
+            Some(chunk) if chunk.len() < Self::CHUNK_LEN => chunk.push(dag_round),
+            _ => {
+                let mut chunk = Vec::with_capacity(Self::CHUNK_LEN);
+                chunk.push(dag_round);
+                self.chunks.push_back(chunk);
+            }
+        }
+        self.len += 1;
+    }
+
+    pub fn clear(&mut self) {
+        self.chunks.clear();
+        self.len = 0;
+    }
+
+    /// Removes rounds below the given one and returns them from the oldest to the newest
+    pub fn drain_below(&mut self, round: Round) -> Vec<DagRound> {
+        let mut result = Vec::new();
+        while let Some(chunk) = self.chunks.front_mut() {
+            let amount = chunk.partition_point(|dag_round| dag_round.round() < round);
+            if amount == 0 {
+                break;
+            }
+            self.len -= amount;
+            result.extend(chunk.drain(..amount));
+            if !chunk.is_empty() {
+                break;
+            }
+            self.chunks.pop_front();
+        }
+        result
+    }
+
+    /// Contiguous slices of rounds, from the oldest to the newest
+    pub fn chunks(&self) -> impl Iterator<Item = &[DagRound]> {
+        self.chunks.iter().map(|chunk| chunk.as_slice())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &DagRound> {
+        self.chunks.iter().flatten()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::array;
+    use std::sync::Arc;
+
+    use everscale_crypto::ed25519::{KeyPair, SecretKey};
+    use tycho_network::PeerId;
+
+    use super::*;
+    use crate::effects::Ctx;
+    use crate::test_utils;
+
+    const PEER_COUNT: usize = 3;
+
+    #[tokio::test]
+    async fn drains_rounds_below_bottom() {
+        let peers: [(PeerId, Arc<KeyPair>); PEER_COUNT] = array::from_fn(|i| {
+            let keys = KeyPair::from(&SecretKey::from_bytes([i as u8; 32]));
+            (PeerId::from(keys.public_key), Arc::new(keys))
+        });
+
+        let (peer_schedule, _, _, engine_ctx) =
+            test_utils::make_engine_parts(&peers, peers[0].1.clone());
+        let conf = engine_ctx.conf();
+
+        let mut buffer = RoundsBuffer::default();
+        let genesis_round = DagRound::new_bottom(conf.genesis_round, &peer_schedule, conf);
+        buffer.push(genesis_round);
+
+        // jump over several chunks
+        for _ in 0..(RoundsBuffer::CHUNK_LEN * 3) {
+            let top = buffer.last().expect("not empty");
+            buffer.push(top.new_next(&peer_schedule, conf));
+        }
+        let top_round = buffer.last().expect("not empty").round();
+
+        let bottom = top_round - (RoundsBuffer::CHUNK_LEN as u32 + 2);
+        let drained = buffer.drain_below(bottom);
+        assert_eq!(drained.first().map(|r| r.round()), Some(conf.genesis_round));
+        assert_eq!(drained.last().map(|r| r.round()), Some(bottom.prev()));
+
+        assert_eq!(buffer.first().map(|r| r.round()), Some(bottom));
+        assert_eq!(buffer.last().map(|r| r.round()), Some(top_round));
+        assert_eq!(buffer.len(), buffer.iter().count());
+        assert_eq!(buffer.len(), (top_round.0 - bottom.0) as usize + 1);
+        assert!(buffer.iter().all(|r| r.round() >= bottom));
+
+        // draining below top keeps the top, emptied chunks are freed
+        buffer.drain_below(top_round);
+        assert_eq!(buffer.len(), 1);
+        assert_eq!(buffer.first().map(|r| r.round()), Some(top_round));
+        let chunks = buffer.chunks().map(|chunk| chunk.len()).collect::<Vec<_>>();
+        assert_eq!(chunks, [1]);
+    }
+}
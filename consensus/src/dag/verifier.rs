@@ -32,6 +32,16 @@ use crate::models::{
 
 pub struct Verifier;
 
+/// How much a point should be trusted by [`Verifier::verify`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum VerificationMode {
+    /// for points received from network: signatures are checked along with structure
+    Full,
+    /// for points loaded from local storage, whose integrity was checked before they were stored;
+    /// must never be used for points received from other peers
+    TrustedReplay,
+}
+
 #[derive(Debug, Copy, Clone)]
 pub enum PointMap {
     Evidence, // r+0
@@ -45,6 +55,12 @@ pub enum VerifyError {
     Fail(VerifyFailReason),
     #[error("ill-formed: {0}")]
     IllFormed(IllFormedReason),
+    /// not a proof of misbehaviour: local peer schedule may be outdated
+    #[error("author is not scheduled: outdated peer schedule or author out of nowhere")]
+    UnknownAuthor,
+    /// not a proof of author's misbehaviour: point may be forged by its sender
+    #[error("bad signature")]
+    BadSignature,
 }
 #[derive(thiserror::Error, Debug)]
 pub enum VerifyFailReason {
@@ -52,8 +68,6 @@ pub enum VerifyFailReason {
     BeforeGenesis,
     #[error("uninit {:?} peer set of {} len at round {}", .0.2, .0.0, .0.1.0)]
     Uninit((usize, Round, PointMap)),
-    #[error("author is not scheduled: outdated peer schedule or author out of nowhere")]
-    UnknownAuthor,
 }
 #[derive(thiserror::Error, Debug, Clone)]
 pub enum IllFormedReason {
@@ -95,12 +109,23 @@ impl Verifier {
         info: &PointInfo,
         peer_schedule: &PeerSchedule,
         conf: &MempoolConfig,
+        mode: VerificationMode,
     ) -> Result<(), VerifyError> {
         let _task_duration = HistogramGuard::begin("tycho_mempool_verifier_verify_time");
 
-        let result = Self::verify_impl(info, peer_schedule, conf).map_or(Ok(()), Err);
+        let result = match mode {
+            VerificationMode::Full if !Self::is_signature_ok(info) => {
+                Err(VerifyError::BadSignature)
+            }
+            VerificationMode::Full | VerificationMode::TrustedReplay => {
+                Self::verify_impl(info, peer_schedule, conf).map_or(Ok(()), Err)
+            }
+        };
 
         ValidateCtx::verified(&result);
+        if result.is_ok() {
+            ValidateCtx::point_links(info, "received");
+        }
         result
     }
 
@@ -126,14 +151,14 @@ impl Verifier {
                 // for genesis point it's sufficient to be well-formed and pass integrity check,
                 // it cannot be validated against AnchorStage (as it knows nothing about genesis)
                 // and cannot contain dependencies
-                return ctx.validated(&cert, ValidateResult::Valid);
+                return ctx.validated(&cert, &downloader, ValidateResult::Valid);
             }
             cmp::Ordering::Greater => {} // peer usage is already verified
         }
 
         let Some(r_0_pre) = r_0.upgrade() else {
             tracing::info!("cannot validate point, no round in local DAG");
-            return ctx.validated(&cert, ValidateResult::Invalid);
+            return ctx.validated(&cert, &downloader, ValidateResult::Invalid);
         };
         assert_eq!(
             r_0_pre.round(),
@@ -142,7 +167,11 @@ impl Verifier {
         );
 
         if !Self::is_self_links_ok(&info, &r_0_pre) {
-            return ctx.validated(&cert, ValidateResult::IllFormed(IllFormedReason::SelfLink));
+            return ctx.validated(
+                &cert,
+                &downloader,
+                ValidateResult::IllFormed(IllFormedReason::SelfLink),
+            );
         }
 
         if ![AnchorStageRole::Proof, AnchorStageRole::Trigger]
@@ -150,7 +179,7 @@ impl Verifier {
             .all(|role| Self::is_anchor_link_ok(role, &info, &r_0_pre, ctx.conf()))
         {
             let reason = IllFormedReason::AnchorLink;
-            return ctx.validated(&cert, ValidateResult::IllFormed(reason));
+            return ctx.validated(&cert, &downloader, ValidateResult::IllFormed(reason));
         };
 
         drop(r_0_pre);
@@ -160,18 +189,18 @@ impl Verifier {
 
         let Some(r_0) = r_0.upgrade() else {
             tracing::info!("cannot validate point, no round in local DAG after proof check");
-            return ctx.validated(&cert, ValidateResult::Invalid);
+            return ctx.validated(&cert, &downloader, ValidateResult::Invalid);
         };
 
         let Some(r_1) = r_0.prev().upgrade() else {
             tracing::info!("cannot validate point's 'includes', no round in local DAG");
-            return ctx.validated(&cert, ValidateResult::Invalid);
+            return ctx.validated(&cert, &downloader, ValidateResult::Invalid);
         };
 
         let r_2_opt = r_1.prev().upgrade();
         if r_2_opt.is_none() && !info.witness().is_empty() {
             tracing::debug!("cannot validate point's 'witness', no round in local DAG");
-            return ctx.validated(&cert, ValidateResult::Invalid);
+            return ctx.validated(&cert, &downloader, ValidateResult::Invalid);
         }
 
         let (direct_deps, cert_deps) =
@@ -216,7 +245,7 @@ impl Verifier {
             ValidateResult::Invalid
         };
 
-        ctx.validated(&cert, status)
+        ctx.validated(&cert, &downloader, status)
     }
 
     fn is_self_links_ok(
@@ -485,6 +514,11 @@ impl Verifier {
             }
         };
 
+        // check author first, so a point out of known epoch is not mislabeled as ill-formed
+        if same_round_peers.0.is_ok() && !same_round_peers.1.contains(&info.author()) {
+            return Some(VerifyError::UnknownAuthor);
+        }
+
         // point belongs to current genesis
         if let Some(reason) = Self::links_across_genesis(info, conf) {
             return Some(VerifyError::IllFormed(reason));
@@ -509,10 +543,6 @@ impl Verifier {
                 return Some(VerifyError::Fail(reason));
             }
             (Ok(total), scheduled) => {
-                if !scheduled.contains(&info.author()) {
-                    let reason = VerifyFailReason::UnknownAuthor;
-                    return Some(VerifyError::Fail(reason));
-                }
                 let evidence = &info.evidence();
                 if !evidence.is_empty() {
                     if total == PeerCount::GENESIS {
@@ -598,6 +628,15 @@ impl Verifier {
         None
     }
 
+    /// signature work is the most expensive part of verification
+    fn is_signature_ok(info: &PointInfo) -> bool {
+        let _task_duration = HistogramGuard::begin("tycho_mempool_verifier_signature_time");
+        info.signature().verifies(&info.author(), info.digest())
+            && info.prev_digest().is_none_or(|prev_digest| {
+                (info.evidence().iter()).all(|(peer_id, sig)| sig.verifies(peer_id, prev_digest))
+            })
+    }
+
     fn links_across_genesis(info: &PointInfo, conf: &MempoolConfig) -> Option<IllFormedReason> {
         let proof_round = info.anchor_round(AnchorStageRole::Proof);
         let trigger_round = info.anchor_round(AnchorStageRole::Trigger);
@@ -658,6 +697,8 @@ impl ValidateCtx {
     fn verified(result: &Result<(), VerifyError>) {
         let label = match result {
             Err(VerifyError::Fail(_)) => "failed",
+            Err(VerifyError::UnknownAuthor) => "unknown_author",
+            Err(VerifyError::BadSignature) => "bad_signature",
             Err(VerifyError::IllFormed(IllFormedReason::UnknownPeers(_))) => "bad_peer",
             Err(VerifyError::IllFormed(_)) => "ill_formed",
             Ok(_) => {
@@ -668,6 +709,15 @@ impl ValidateCtx {
         metrics::counter!("tycho_mempool_points_verify_err", Self::KIND => label).increment(1);
     }
 
+    /// `origin` is either `produced` for own points or `received` for verified broadcasts
+    pub fn point_links(info: &PointInfo, origin: &'static str) {
+        const ORIGIN: &str = "origin";
+        metrics::histogram!("tycho_mempool_point_includes_peers", ORIGIN => origin)
+            .record(info.includes().len() as f64);
+        metrics::histogram!("tycho_mempool_point_witness_peers", ORIGIN => origin)
+            .record(info.witness().len() as f64);
+    }
+
     pub fn resolved(dag_point: &DagPoint) {
         const ORD: &str = "ord";
         let ord = if dag_point.is_first_resolved() {
@@ -688,24 +738,33 @@ impl ValidateCtx {
             .increment(1);
     }
 
-    fn validated(&self, cert: &Cert, result: ValidateResult) -> TaskResult<ValidateResult> {
+    fn validated(
+        &self,
+        cert: &Cert,
+        downloader: &Downloader,
+        result: ValidateResult,
+    ) -> TaskResult<ValidateResult> {
         match &result {
             ValidateResult::IllFormed(reason) => {
-                tracing::error!(
-                    parent: self.span(),
-                    result = "ill-formed",
-                    is_certified = cert.is_certified(),
-                    reason = display(reason),
-                    "validated",
-                );
+                if downloader.should_log_rejection(self.author()) {
+                    tracing::error!(
+                        parent: self.span(),
+                        result = "ill-formed",
+                        is_certified = cert.is_certified(),
+                        reason = display(reason),
+                        "validated",
+                    );
+                }
             }
             ValidateResult::Invalid => {
-                tracing::warn!(
-                    parent: self.span(),
-                    is_certified = cert.is_certified(),
-                    result = "invalid",
-                    "validated",
-                );
+                if downloader.should_log_rejection(self.author()) {
+                    tracing::warn!(
+                        parent: self.span(),
+                        is_certified = cert.is_certified(),
+                        result = "invalid",
+                        "validated",
+                    );
+                }
             }
             ValidateResult::Valid => {
                 tracing::debug!(
@@ -719,3 +778,73 @@ impl ValidateCtx {
         Ok(result)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::array;
+    use std::sync::Arc;
+
+    use everscale_crypto::ed25519::{KeyPair, SecretKey};
+    use tycho_network::PeerId;
+
+    use super::*;
+    use crate::effects::Ctx;
+    use crate::models::test_point::{new_key_pair, point};
+    use crate::test_utils;
+
+    const PEER_COUNT: usize = 3;
+
+    #[tokio::test]
+    async fn out_of_epoch_author_is_not_ill_formed() {
+        let peers: [(PeerId, Arc<KeyPair>); PEER_COUNT] = array::from_fn(|i| {
+            let keys = KeyPair::from(&SecretKey::from_bytes([i as u8; 32]));
+            (PeerId::from(keys.public_key), Arc::new(keys))
+        });
+
+        let (peer_schedule, _, _, engine_ctx) =
+            test_utils::make_engine_parts(&peers, peers[0].1.clone());
+        let conf = engine_ctx.conf();
+
+        // author is not in any epoch of peer schedule
+        let point = point(&new_key_pair(), &[], conf);
+
+        let result = Verifier::verify(point.info(), &peer_schedule, conf, VerificationMode::Full);
+        assert!(
+            matches!(result, Err(VerifyError::UnknownAuthor)),
+            "unexpected result {result:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn trusted_replay_skips_signature_check() {
+        let peers: [(PeerId, Arc<KeyPair>); PEER_COUNT] = array::from_fn(|i| {
+            let keys = KeyPair::from(&SecretKey::from_bytes([i as u8; 32]));
+            (PeerId::from(keys.public_key), Arc::new(keys))
+        });
+
+        let (peer_schedule, _, _, engine_ctx) =
+            test_utils::make_engine_parts(&peers, peers[0].1.clone());
+        let conf = engine_ctx.conf();
+
+        let point = point(&new_key_pair(), &[], conf);
+
+        // corrupt author's signature: it follows tl tag and digest
+        let mut info_bytes = tl_proto::serialize(point.info());
+        info_bytes[4 + Digest::MAX_TL_BYTES] ^= 0xFF;
+        let forged = tl_proto::deserialize::<PointInfo>(&info_bytes).expect("point info");
+
+        let result = Verifier::verify(&forged, &peer_schedule, conf, VerificationMode::Full);
+        assert!(
+            matches!(result, Err(VerifyError::BadSignature)),
+            "unexpected result {result:?}"
+        );
+
+        // signature is not checked, so the same structural result as for a valid point
+        let mode = VerificationMode::TrustedReplay;
+        let result = Verifier::verify(&forged, &peer_schedule, conf, mode);
+        assert!(
+            matches!(result, Err(VerifyError::UnknownAuthor)),
+            "unexpected result {result:?}"
+        );
+    }
+}
@@ -22,7 +22,7 @@ pub trait AltFormat {
 impl AltFormat for PeerId {}
 impl Display for AltFmt<'_, PeerId> {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        match NodeConfig::get().log_truncate_long_values {
+        match NodeConfig::load().log_truncate_long_values {
             false => write!(f, "{}", self.0),
             true => write!(f, "{:.4}", self.0),
         }
@@ -46,7 +46,7 @@ impl Display for AltFmt<'_, [PeerId]> {
 impl AltFormat for Digest {}
 impl Display for AltFmt<'_, Digest> {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        match NodeConfig::get().log_truncate_long_values {
+        match NodeConfig::load().log_truncate_long_values {
             false => write!(f, "{}", self.0),
             true => write!(f, "{:.4}", self.0),
         }
@@ -56,7 +56,7 @@ impl Display for AltFmt<'_, Digest> {
 impl AltFormat for Signature {}
 impl Display for AltFmt<'_, Signature> {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        match NodeConfig::get().log_truncate_long_values {
+        match NodeConfig::load().log_truncate_long_values {
             false => write!(f, "{}", self.0),
             true => write!(f, "{:.4}", self.0),
         }
@@ -66,7 +66,7 @@ impl Display for AltFmt<'_, Signature> {
 impl AltFormat for PointId {}
 impl Debug for AltFmt<'_, PointId> {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        match NodeConfig::get().log_truncate_long_values {
+        match NodeConfig::load().log_truncate_long_values {
             false => write!(f, "{:?}", self.0),
             true => write!(
                 f,
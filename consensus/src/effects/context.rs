@@ -2,6 +2,7 @@ use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
 
 use tracing::Span;
+use tycho_network::PeerId;
 
 use crate::effects::task::TaskTracker;
 use crate::effects::{AltFormat, TaskCtx};
@@ -48,6 +49,9 @@ impl EngineCtx {
     pub fn meter_dag_len(len: usize) {
         metrics::gauge!("tycho_mempool_rounds_dag_length").set(len as u32);
     }
+    pub fn meter_dag_front_len(len: usize) {
+        metrics::gauge!("tycho_mempool_rounds_dag_front_length").set(len as u32);
+    }
 }
 
 #[derive(Clone)]
@@ -201,6 +205,7 @@ pub struct ValidateCtx(Arc<ValidateCtxInner>);
 struct ValidateCtxInner {
     span: Span,
     parent: RoundCtx,
+    author: PeerId,
 }
 impl Ctx for ValidateCtx {
     fn span(&self) -> &Span {
@@ -231,6 +236,11 @@ impl ValidateCtx {
         Self(Arc::new(ValidateCtxInner {
             parent: round_ctx,
             span,
+            author: info.author(),
         }))
     }
+
+    pub fn author(&self) -> &PeerId {
+        &self.0.author
+    }
 }
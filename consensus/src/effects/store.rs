@@ -1,4 +1,5 @@
 use std::cmp;
+use std::num::NonZeroU16;
 use std::ops::RangeInclusive;
 use std::sync::Arc;
 
@@ -16,7 +17,7 @@ use weedb::rocksdb::{DBRawIterator, IteratorMode, ReadOptions, WriteBatch};
 
 use crate::effects::{AltFormat, Cancelled, Ctx, RoundCtx, Task};
 use crate::engine::round_watch::{Commit, Consensus, RoundWatch, RoundWatcher, TopKnownAnchor};
-use crate::engine::{ConsensusConfigExt, MempoolConfig, NodeConfig};
+use crate::engine::{CollatorLagAlert, ConsensusConfigExt, MempoolConfig, NodeConfig};
 use crate::models::{
     Digest, Point, PointInfo, PointRestore, PointRestoreSelect, PointStatus, PointStatusStored,
     PointStatusStoredRef, PointStatusValidated, Round,
@@ -45,6 +46,8 @@ trait MempoolStoreImpl: Send + Sync {
 
     fn get_point(&self, round: Round, digest: &Digest) -> Result<Option<Point>>;
 
+    fn has_point(&self, round: Round, digest: &Digest) -> Result<bool>;
+
     fn multi_get_info(&self, keys: &[(Round, Digest)]) -> Result<Vec<PointInfo>>;
 
     fn get_point_raw(&self, round: Round, digest: &Digest) -> Result<Option<Bytes>>;
@@ -161,6 +164,14 @@ impl MempoolStore {
             .expect("DB get point")
     }
 
+    /// Checks that the point is stored without reading and deserializing it.
+    pub fn has_point(&self, round: Round, digest: &Digest) -> bool {
+        self.0
+            .has_point(round, digest)
+            .with_context(|| format!("round {} digest {}", round.0, digest.alt()))
+            .expect("DB has point")
+    }
+
     pub fn get_point_raw(&self, round: Round, digest: &Digest) -> Option<Bytes> {
         self.0
             .get_point_raw(round, digest)
@@ -226,10 +237,28 @@ impl DbCleaner {
         }
     }
 
+    /// node config is read on every call, so its updates apply to the next cut
+    fn current_least_to_keep(
+        consensus: Round,
+        committed: Round,
+        top_known_anchor: Round,
+        conf: &MempoolConfig,
+    ) -> Round {
+        let clean_db_period_rounds = NodeConfig::load().clean_db_period_rounds;
+        Self::least_to_keep(
+            consensus,
+            committed,
+            top_known_anchor,
+            clean_db_period_rounds,
+            conf,
+        )
+    }
+
     fn least_to_keep(
         consensus: Round,
         committed: Round,
         top_known_anchor: Round,
+        clean_db_period_rounds: NonZeroU16,
         conf: &MempoolConfig,
     ) -> Round {
         // If the node is not scheduled, then it's paused and does not receive broadcasts:
@@ -245,7 +274,7 @@ impl DbCleaner {
             // TKA for deep sync: consensus round is stalled, history not needed for others
             consensus.max(top_known_anchor) - conf.consensus.max_total_rounds(),
         );
-        let remainder = least_to_keep.0 % NodeConfig::get().clean_db_period_rounds.get() as u32;
+        let remainder = least_to_keep.0 % clean_db_period_rounds.get() as u32;
         (conf.genesis_round).max(least_to_keep - remainder)
     }
 
@@ -264,8 +293,10 @@ impl DbCleaner {
             let mut consensus = consensus_round.get();
             let mut committed = committed_round.get();
             let mut top_known = top_known_anchor.get();
+            let mut collator_lag =
+                CollatorLagAlert::new(NodeConfig::get().warn_collator_lag_rounds);
             let mut prev_least_to_keep =
-                Self::least_to_keep(consensus, committed, top_known, round_ctx.conf());
+                Self::current_least_to_keep(consensus, committed, top_known, round_ctx.conf());
             loop {
                 tokio::select! {
                     biased;
@@ -275,15 +306,14 @@ impl DbCleaner {
                 }
 
                 metrics::gauge!("tycho_mempool_consensus_current_round").set(consensus.0);
-                metrics::gauge!("tycho_mempool_rounds_consensus_ahead_top_known")
-                    .set(consensus.diff_f64(top_known));
+                collator_lag.update(consensus, top_known, &round_ctx);
                 metrics::gauge!("tycho_mempool_rounds_consensus_ahead_committed")
                     .set(consensus.diff_f64(committed));
                 metrics::gauge!("tycho_mempool_rounds_committed_ahead_top_known")
                     .set(committed.diff_f64(top_known));
 
                 let new_least_to_keep =
-                    Self::least_to_keep(consensus, committed, top_known, round_ctx.conf());
+                    Self::current_least_to_keep(consensus, committed, top_known, round_ctx.conf());
                 metrics::gauge!("tycho_mempool_rounds_consensus_ahead_storage_round")
                     .set(consensus.diff_f64(new_least_to_keep));
 
@@ -418,6 +448,25 @@ impl MempoolStoreImpl for MempoolStorage {
             .transpose()
     }
 
+    fn has_point(&self, round: Round, digest: &Digest) -> Result<bool> {
+        metrics::counter!("tycho_mempool_store_has_point_count").increment(1);
+        let mut key = [0_u8; MempoolStorage::KEY_LEN];
+        MempoolStorage::fill_key(round.0, digest.inner(), &mut key);
+
+        let db = self.db.rocksdb();
+        // point info is written in the same batch with the point and is much smaller
+        let info_cf = self.db.points_info.cf();
+
+        // bloom filter gives no false negatives, so absent points are not read at all
+        if !db.key_may_exist_cf(&info_cf, key.as_slice()) {
+            return Ok(false);
+        }
+        let value = db
+            .get_pinned_cf(&info_cf, key.as_slice())
+            .context("db get")?;
+        Ok(value.is_some())
+    }
+
     fn multi_get_info(&self, keys: &[(Round, Digest)]) -> Result<Vec<PointInfo>> {
         let key_bytes = {
             let mut b_keys = Vec::with_capacity(keys.len());
@@ -749,6 +798,10 @@ impl MempoolStoreImpl for () {
         anyhow::bail!("should not be used in tests")
     }
 
+    fn has_point(&self, _: Round, _: &Digest) -> Result<bool> {
+        anyhow::bail!("should not be used in tests")
+    }
+
     fn multi_get_info(&self, _: &[(Round, Digest)]) -> Result<Vec<PointInfo>> {
         anyhow::bail!("should not be used in tests")
     }
@@ -789,3 +842,57 @@ impl MempoolStoreImpl for () {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use tycho_storage::Storage;
+
+    use super::*;
+    use crate::test_utils;
+
+    #[tokio::test]
+    async fn has_point_checks_presence() -> Result<()> {
+        let (storage, _tmp_dir) = Storage::new_temp().await?;
+        let adapter_store =
+            MempoolAdapterStore::new(storage.mempool_storage().clone(), RoundWatch::default());
+        let store = MempoolStore::new(&adapter_store);
+
+        let genesis = test_utils::default_test_config().genesis();
+        let info = genesis.info();
+        assert!(!store.has_point(info.round(), info.digest()));
+
+        store.insert_point(&genesis, PointStatusStoredRef::Exists);
+        assert!(store.has_point(info.round(), info.digest()));
+        assert!(!store.has_point(info.round().next(), info.digest()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn db_cleaner_follows_clean_db_period_update() {
+        // initializes global node config
+        let merged_conf = test_utils::default_test_config();
+        let conf = &merged_conf.conf;
+        let round = Round(10_500);
+        let least_to_keep = || DbCleaner::current_least_to_keep(round, round, round, conf);
+
+        // global config is shared with parallel tests: they only read the period
+        let initial = NodeConfig::get();
+        let initial_cut = least_to_keep();
+        assert_eq!(
+            initial_cut.0 % initial.clean_db_period_rounds.get() as u32,
+            0
+        );
+        assert_ne!(initial_cut, Round(10_000));
+
+        let mut changed = (*initial).clone();
+        changed.clean_db_period_rounds = NonZeroU16::new(1000).unwrap();
+        NodeConfig::update(&changed).expect("node-local value must be changed");
+        let changed_cut = least_to_keep();
+
+        NodeConfig::update(&initial).expect("restore initial config");
+
+        assert_eq!(changed_cut, Round(10_000));
+        assert_eq!(least_to_keep(), initial_cut);
+    }
+}
@@ -0,0 +1,26 @@
+use std::sync::Arc;
+
+use tokio::sync::mpsc;
+
+use crate::models::MempoolOutput;
+
+/// Receiver of committed anchors and engine status, in order of commit.
+///
+/// Called from blocking committer threads, so implementations must not block for long:
+/// hand the data over (i.e. to an indexer or a mempool adapter) and return.
+pub trait AnchorSink: Send + Sync + 'static {
+    /// Error stops the engine as if it was cancelled.
+    fn on_anchor(&self, output: MempoolOutput) -> Result<(), AnchorSinkClosed>;
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("anchor sink is closed")]
+pub struct AnchorSinkClosed;
+
+pub type AnchorSinkRef = Arc<dyn AnchorSink>;
+
+impl AnchorSink for mpsc::UnboundedSender<MempoolOutput> {
+    fn on_anchor(&self, output: MempoolOutput) -> Result<(), AnchorSinkClosed> {
+        self.send(output).map_err(|_closed| AnchorSinkClosed)
+    }
+}
@@ -2,14 +2,13 @@ use std::mem;
 use std::time::Duration;
 
 use itertools::Itertools;
-use tokio::sync::mpsc;
 use tokio::time::Interval;
 
 use crate::dag::{Committer, HistoryConflict};
 use crate::effects::{AltFormat, Cancelled, Ctx, EngineCtx, RoundCtx, Task};
 use crate::engine::lifecycle::EngineError;
-use crate::engine::{ConsensusConfigExt, EngineResult, MempoolConfig};
-use crate::models::{AnchorData, MempoolOutput, PointInfo, Round};
+use crate::engine::{AnchorSinkRef, ConsensusConfigExt, EngineResult, MempoolConfig};
+use crate::models::{AnchorData, AnchorOrderCheck, MempoolOutput, PointInfo, Round};
 
 pub struct CommitterTask {
     inner: Inner,
@@ -49,7 +48,7 @@ impl CommitterTask {
     pub async fn update_task(
         &mut self,
         full_history_bottom: Option<Round>,
-        committed_info_tx: mpsc::UnboundedSender<MempoolOutput>,
+        committed_info_tx: AnchorSinkRef,
         round_ctx: &RoundCtx,
     ) -> EngineResult<()> {
         let Some(committer) = self.inner.take_ready().await? else {
@@ -93,7 +92,7 @@ impl Inner {
     fn dropping(
         mut committer: Committer,
         full_history_bottom: Option<Round>,
-        committed_info_tx: mpsc::UnboundedSender<MempoolOutput>,
+        committed_info_tx: AnchorSinkRef,
         round_ctx: &RoundCtx,
     ) -> Self {
         let task_ctx = round_ctx.task();
@@ -120,8 +119,7 @@ impl Inner {
                         tracing::info!(
                             start_bottom,
                             start_dag_len,
-                            current_bottom = ?committer.bottom_round(),
-                            current_dag_len = committer.dag_len(),
+                            current = ?committer.dump(),
                             attempt,
                             "comitter rounds were dropped as impossible to sync"
                         );
@@ -141,16 +139,17 @@ impl Inner {
 
             if let Some(new_bottom) = new_full_history_bottom.or(full_history_bottom) {
                 committed_info_tx
-                    .send(MempoolOutput::NewStartAfterGap(new_bottom))
+                    .on_anchor(MempoolOutput::NewStartAfterGap(new_bottom))
                     .map_err(|_closed| Cancelled())?;
             }
 
             if let Some(committed) = committed {
                 round_ctx.log_committed(&committed);
+                assert_ordered(&committed);
                 for data in committed {
                     round_ctx.commit_metrics(&data.anchor);
                     committed_info_tx
-                        .send(MempoolOutput::NextAnchor(data))
+                        .on_anchor(MempoolOutput::NextAnchor(data))
                         .map_err(|_closed| Cancelled())?;
                 }
             }
@@ -166,7 +165,7 @@ impl Inner {
     fn fallible(
         mut committer: Committer,
         full_history_bottom: Option<Round>,
-        committed_info_tx: mpsc::UnboundedSender<MempoolOutput>,
+        committed_info_tx: AnchorSinkRef,
         round_ctx: &RoundCtx,
     ) -> Self {
         let task_ctx = round_ctx.task();
@@ -185,8 +184,7 @@ impl Inner {
                         err = %history_conflict,
                         start_bottom,
                         start_dag_len,
-                        current_bottom = ?committer.bottom_round(),
-                        current_dag_len = committer.dag_len(),
+                        current = ?committer.dump(),
                         "will try to fix local history"
                     );
                     return Err(history_conflict.into());
@@ -195,15 +193,16 @@ impl Inner {
 
             if let Some(new_bottom) = full_history_bottom {
                 committed_info_tx
-                    .send(MempoolOutput::NewStartAfterGap(new_bottom))
+                    .on_anchor(MempoolOutput::NewStartAfterGap(new_bottom))
                     .map_err(|_closed| EngineError::Cancelled)?;
             }
 
             round_ctx.log_committed(&committed);
+            assert_ordered(&committed);
             for data in committed {
                 round_ctx.commit_metrics(&data.anchor);
                 committed_info_tx
-                    .send(MempoolOutput::NextAnchor(data))
+                    .on_anchor(MempoolOutput::NextAnchor(data))
                     .map_err(|_closed| EngineError::Cancelled)?;
             }
 
@@ -215,6 +214,15 @@ impl Inner {
     }
 }
 
+fn assert_ordered(committed: &[AnchorData]) {
+    let mut check = AnchorOrderCheck::default();
+    for data in committed {
+        if let Err(e) = check.check(data) {
+            panic!("committed anchors are out of order: {e}");
+        }
+    }
+}
+
 impl RoundCtx {
     fn commit_metrics(&self, anchor: &PointInfo) {
         metrics::counter!("tycho_mempool_commit_anchors").increment(1);
@@ -245,3 +253,157 @@ impl RoundCtx {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::array;
+    use std::sync::Arc;
+
+    use everscale_crypto::ed25519::{KeyPair, SecretKey};
+    use parking_lot::Mutex;
+    use tycho_network::PeerId;
+
+    use super::*;
+    use crate::dag::{DagFront, DagRound};
+    use crate::effects::MempoolStore;
+    use crate::engine::{AnchorSink, AnchorSinkClosed};
+    use crate::models::AnchorOrderError;
+    use crate::test_utils;
+
+    const PEER_COUNT: usize = 3;
+
+    #[derive(Default)]
+    struct CountingSink(Mutex<Vec<(Round, Option<Round>)>>);
+
+    impl AnchorSink for CountingSink {
+        fn on_anchor(&self, output: MempoolOutput) -> Result<(), AnchorSinkClosed> {
+            if let MempoolOutput::NextAnchor(data) = output {
+                let mut anchors = self.0.lock();
+                anchors.push((data.anchor.round(), data.prev_anchor));
+            }
+            Ok(())
+        }
+    }
+
+    async fn commit_up_to(top_round: u32, sink: AnchorSinkRef) {
+        let stub_store = MempoolStore::no_read_stub();
+
+        let peers: [(PeerId, Arc<KeyPair>); PEER_COUNT] = array::from_fn(|i| {
+            let keys = KeyPair::from(&SecretKey::from_bytes([i as u8; 32]));
+            (PeerId::from(keys.public_key), Arc::new(keys))
+        });
+        let local_keys = &peers[0].1;
+
+        let (peer_schedule, stub_downloader, genesis, engine_ctx) =
+            test_utils::make_engine_parts(&peers, local_keys.clone());
+        let conf = engine_ctx.conf();
+
+        let mut round_ctx = RoundCtx::new(&engine_ctx, conf.genesis_round);
+
+        let genesis_round = DagRound::new_bottom(conf.genesis_round, &peer_schedule, conf);
+        genesis_round
+            .add_local(&genesis, Some(local_keys), &stub_store, &round_ctx)
+            .await
+            .expect("cannot be closed");
+
+        let mut dag = DagFront::default();
+        let mut committer = dag.init(genesis_round, conf);
+
+        for round in (conf.genesis_round.next().0..top_round).map(Round) {
+            round_ctx = RoundCtx::new(&engine_ctx, round);
+            _ = dag.fill_to_top(round, Some(&mut committer), &peer_schedule, &round_ctx);
+            test_utils::populate_points(
+                dag.top(),
+                &peers,
+                local_keys,
+                &peer_schedule,
+                &stub_downloader,
+                &stub_store,
+                &round_ctx,
+                0,
+                0,
+            )
+            .await;
+        }
+
+        let mut committer_task = CommitterTask::new(committer, conf);
+        committer_task
+            .update_task(None, sink, &round_ctx)
+            .await
+            .expect("committer is ready");
+        while (committer_task.ready_mut().await)
+            .expect("commit must succeed")
+            .is_none()
+        {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn sink_receives_all_anchors_in_order() {
+        let sink = Arc::new(CountingSink::default());
+        commit_up_to(30, sink.clone()).await;
+
+        let anchors = sink.0.lock();
+        assert!(!anchors.is_empty(), "no anchors committed");
+        for pair in anchors.windows(2) {
+            let [(prev, _), (next, prev_anchor)] = pair else {
+                unreachable!()
+            };
+            assert!(prev < next, "anchors out of order: {prev:?} then {next:?}");
+            assert_eq!(*prev_anchor, Some(*prev), "skipped anchor before {next:?}");
+        }
+    }
+
+    /// Keeps its state across committer restarts, as the mempool adapter does.
+    #[derive(Default)]
+    struct CheckingSink(Mutex<CheckingState>);
+
+    #[derive(Default)]
+    struct CheckingState {
+        check: AnchorOrderCheck,
+        accepted: Vec<Round>,
+        not_after_last: usize,
+    }
+
+    impl AnchorSink for CheckingSink {
+        fn on_anchor(&self, output: MempoolOutput) -> Result<(), AnchorSinkClosed> {
+            let mut state = self.0.lock();
+            match output {
+                MempoolOutput::NextAnchor(data) => match state.check.check(&data) {
+                    Ok(()) => state.accepted.push(data.anchor.round()),
+                    Err(AnchorOrderError::NotAfterLast { .. }) => state.not_after_last += 1,
+                    Err(e) => panic!("{e}"),
+                },
+                MempoolOutput::NewStartAfterGap(_) => state.check.reset(),
+                MempoolOutput::Running | MempoolOutput::Paused => {}
+            }
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn restarted_committer_repeats_are_detected() {
+        let sink = Arc::new(CheckingSink::default());
+
+        commit_up_to(30, sink.clone()).await;
+        let before_restart = sink.0.lock().accepted.len();
+        assert!(before_restart > 0, "no anchors committed");
+
+        // new committer starts from the same genesis and commits the same rounds again
+        commit_up_to(40, sink.clone()).await;
+
+        let state = sink.0.lock();
+        assert_eq!(
+            state.not_after_last, before_restart,
+            "repeats are not detected"
+        );
+        assert!(
+            state.accepted.len() > before_restart,
+            "no anchors after restart"
+        );
+        for pair in state.accepted.windows(2) {
+            assert!(pair[0] < pair[1], "anchors out of order: {pair:?}");
+        }
+    }
+}
@@ -7,11 +7,10 @@ use futures_util::future::BoxFuture;
 use futures_util::stream::FuturesUnordered;
 use futures_util::{FutureExt, TryStreamExt};
 use itertools::{Either, Itertools};
-use tokio::sync::mpsc;
 use tycho_network::PeerId;
 use tycho_util::metrics::HistogramGuard;
 
-use crate::dag::{DagFront, DagRound, KeyGroup, Verifier};
+use crate::dag::{DagFront, DagRound, KeyGroup, VerificationMode, Verifier};
 use crate::effects::{
     AltFormat, Ctx, DbCleaner, EngineCtx, MempoolStore, RoundCtx, Task, TaskResult, TaskTracker,
 };
@@ -19,7 +18,7 @@ use crate::engine::committer_task::CommitterTask;
 use crate::engine::lifecycle::{EngineError, EngineNetwork, FixHistoryFlag};
 use crate::engine::round_task::RoundTaskReady;
 use crate::engine::round_watch::{RoundWatch, RoundWatcher, TopKnownAnchor};
-use crate::engine::{ConsensusConfigExt, MempoolMergedConfig};
+use crate::engine::{AnchorSinkRef, ConsensusConfigExt, MempoolMergedConfig};
 use crate::models::{
     DagPoint, MempoolOutput, Point, PointRestore, PointRestoreSelect, PointStatusStoredRef, Round,
 };
@@ -30,7 +29,7 @@ pub type EngineResult<T> = std::result::Result<T, EngineError>;
 pub struct Engine {
     dag: DagFront,
     committer_run: CommitterTask,
-    output: mpsc::UnboundedSender<MempoolOutput>,
+    output: AnchorSinkRef,
     round_task: RoundTaskReady,
     db_cleaner: DbCleaner,
     _peer_schedule_updater: Task<()>,
@@ -52,8 +51,13 @@ impl Engine {
         Point::parse(genesis.serialized().to_vec())
             .expect("parse genesis: point tl serde is broken")
             .expect("parse genesis: integrity check is broken");
-        Verifier::verify(genesis.info(), &net.peer_schedule, conf)
-            .expect("failed to verify genesis");
+        Verifier::verify(
+            genesis.info(),
+            &net.peer_schedule,
+            conf,
+            VerificationMode::Full,
+        )
+        .expect("failed to verify genesis");
 
         let consensus_round = RoundWatch::default();
         consensus_round.set_max(conf.genesis_round);
@@ -100,6 +104,7 @@ impl Engine {
             bind.top_known_anchor.clone(),
             net.responder.clone(),
             bind.input_buffer.clone(),
+            &net.ban_events,
         );
 
         let peer_schedule_updater = engine_ctx.task().spawn({
@@ -209,7 +214,11 @@ impl Engine {
         // also last 3 rounds is enough to create point at last round with all witness deps
         let head_min_round = dag_top_round.prev().prev(); // 3 DagHead rounds inclusive
         for round in (head_min_round.0..=dag_top_round.0).map(Round) {
-            let dag_round = self.dag.top().scan(round).expect("must exist");
+            let dag_round = self
+                .dag
+                .top()
+                .try_scan(round)
+                .unwrap_or_else(|e| panic!("dag was incorrectly extended: {e}"));
             let keys = KeyGroup::new(round, &self.round_task.state.peer_schedule);
             let first_valid = keys
                 .to_produce
@@ -301,7 +310,10 @@ impl Engine {
                             .multi_get_info(keys) // assume load result is sorted
                             .into_par_iter()
                             .map(|info| {
-                                if Verifier::verify(&info, &peer_schedule, round_ctx.conf()).is_ok()
+                                // integrity was checked before the point was stored
+                                let mode = VerificationMode::TrustedReplay;
+                                if Verifier::verify(&info, &peer_schedule, round_ctx.conf(), mode)
+                                    .is_ok()
                                 {
                                     // return back as they were, now with prev_proof filled
                                     PointRestore::Exists(info)
@@ -323,7 +335,11 @@ impl Engine {
 
         let mut reversed_futures = BTreeMap::new();
         for ((round, order), point_restores) in task.await? {
-            let dag_round = self.dag.top().scan(round).expect("must exist");
+            let dag_round = self
+                .dag
+                .top()
+                .try_scan(round)
+                .unwrap_or_else(|e| panic!("dag was incorrectly extended: {e}"));
             let dag_restore = FuturesUnordered::new();
             for point_restore in point_restores {
                 dag_restore.push(dag_round.restore(
@@ -463,7 +479,7 @@ fn collator_feedback(
     mut top_known_anchor_recv: RoundWatcher<TopKnownAnchor>,
     old_dag_top_round: Round,
     is_paused: &mut bool,
-    committed_info_tx: &mpsc::UnboundedSender<MempoolOutput>,
+    committed_info_tx: &AnchorSinkRef,
     round_ctx: &RoundCtx,
 ) -> Result<Round, BoxFuture<'static, ()>> {
     let top_known_anchor = top_known_anchor_recv.get();
@@ -480,7 +496,7 @@ fn collator_feedback(
                 "enter pause by collator feedback",
             );
             *is_paused = true;
-            committed_info_tx.send(MempoolOutput::Paused).ok();
+            committed_info_tx.on_anchor(MempoolOutput::Paused).ok();
         }
 
         let timeout = Duration::from_millis(round_ctx.conf().consensus.broadcast_retry_millis as _);
@@ -514,7 +530,7 @@ fn collator_feedback(
             "exit from pause by collator feedback",
         );
         *is_paused = false;
-        committed_info_tx.send(MempoolOutput::Running).ok();
+        committed_info_tx.on_anchor(MempoolOutput::Running).ok();
         Ok(pause_at)
     } else {
         Ok(pause_at)
@@ -6,9 +6,12 @@ use bytes::Bytes;
 use everscale_types::models::ConsensusConfig;
 use parking_lot::{Mutex, MutexGuard};
 
+/// Tag of the external messages origin (e.g. a gateway) to share the batch fairly
+pub type InputSourceId = u32;
+
 trait InputBufferInner: Send {
-    fn push(&mut self, ext_in_msg: Bytes);
-    fn fetch_inner(&mut self, only_fresh: bool) -> Vec<Bytes>;
+    fn push(&mut self, source: InputSourceId, ext_in_msg: Bytes);
+    fn fetch_inner(&mut self, only_fresh: bool, deadline: Option<Instant>) -> Vec<Bytes>;
     fn apply_config(&mut self, config: &ConsensusConfig);
 }
 
@@ -22,18 +25,38 @@ impl Default for InputBuffer {
 }
 
 impl InputBuffer {
+    pub const DEFAULT_SOURCE: InputSourceId = 0;
+
     pub fn push(&self, ext_in_msg: Bytes) {
+        self.push_from(Self::DEFAULT_SOURCE, ext_in_msg);
+    }
+
+    /// Messages from different sources are taken into a batch in round-robin order,
+    /// so a noisy source cannot crowd out the others
+    pub fn push_from(&self, source: InputSourceId, ext_in_msg: Bytes) {
         let mut data = self.0.lock();
-        data.push(ext_in_msg);
+        data.push(source, ext_in_msg);
         // `fetch()` is topmost priority
         MutexGuard::unlock_fair(data);
     }
 
     /// `only_fresh = false` to repeat the same elements if they are still buffered,
-    /// use in case last round failed
-    pub fn fetch(&self, only_fresh: bool) -> Vec<Bytes> {
-        let mut inner = self.0.lock();
-        inner.fetch_inner(only_fresh)
+    /// use in case last round failed.
+    ///
+    /// `deadline` bounds both waiting for the buffer and taking messages from it,
+    /// so a slow input cannot delay the round: the point gets a smaller payload instead.
+    /// Blocks the thread while the buffer is locked, so must not be called from async code.
+    pub fn fetch(&self, only_fresh: bool, deadline: Option<Instant>) -> Vec<Bytes> {
+        let inner = match deadline {
+            None => Some(self.0.lock()),
+            Some(deadline) => self.0.try_lock_until(deadline),
+        };
+        let Some(mut inner) = inner else {
+            metrics::counter!("tycho_mempool_input_buffer_fetch_timeouts").increment(1);
+            tracing::warn!("input buffer is busy until deadline, point payload is empty");
+            return Vec::new();
+        };
+        inner.fetch_inner(only_fresh, deadline)
     }
 
     pub fn apply_config(&self, consensus_config: &ConsensusConfig) {
@@ -43,7 +66,7 @@ impl InputBuffer {
 }
 
 impl InputBufferInner for InputBufferData {
-    fn push(&mut self, ext_in_msg: Bytes) {
+    fn push(&mut self, source: InputSourceId, ext_in_msg: Bytes) {
         if self.payload_buffer_bytes == 0 || self.payload_batch_bytes == 0 {
             // TODO log debounce https://github.com/broxus/tycho/issues/406
             tracing::trace!("cannot enqueue msg until config is init");
@@ -52,14 +75,14 @@ impl InputBufferInner for InputBufferData {
                 .increment(ext_in_msg.len() as _);
             return; // ignore until config applied
         }
-        self.add(ext_in_msg);
+        self.add(source, ext_in_msg);
     }
 
-    fn fetch_inner(&mut self, only_fresh: bool) -> Vec<Bytes> {
+    fn fetch_inner(&mut self, only_fresh: bool, deadline: Option<Instant>) -> Vec<Bytes> {
         if only_fresh {
-            self.commit_offset();
+            self.commit_fetched();
         }
-        self.fetch()
+        self.fetch(deadline)
     }
 
     fn apply_config(&mut self, consensus_config: &ConsensusConfig) {
@@ -73,38 +96,68 @@ impl InputBufferInner for InputBufferData {
     }
 }
 
+struct BufferedMsg {
+    payload: Bytes,
+    ingested: Instant,
+    source: InputSourceId,
+    is_fetched: bool,
+}
+
 #[derive(Default)]
 struct InputBufferData {
-    data: VecDeque<(Bytes, Instant)>,
+    data: VecDeque<BufferedMsg>,
     data_bytes: usize,
-    offset_elements: usize,
     payload_buffer_bytes: usize,
     payload_batch_bytes: usize,
 }
 
 impl InputBufferData {
-    fn fetch(&mut self) -> Vec<Bytes> {
+    fn fetch(&mut self, deadline: Option<Instant>) -> Vec<Bytes> {
+        // not fetched message indices grouped by source in order of the first message
+        let mut queues = Vec::<(InputSourceId, VecDeque<usize>)>::new();
+        for (idx, msg) in self.data.iter_mut().enumerate() {
+            msg.is_fetched = false;
+            match queues.iter_mut().find(|(source, _)| *source == msg.source) {
+                Some((_, queue)) => queue.push_back(idx),
+                None => queues.push((msg.source, VecDeque::from([idx]))),
+            }
+        }
+
         let mut taken_bytes = 0;
         let now = Instant::now();
-        let result = self
-            .data
-            .iter()
-            .take_while(|(elem, _)| {
-                taken_bytes += elem.len();
-                taken_bytes <= self.payload_batch_bytes
-            })
-            .map(|(elem, ingested)| {
+        let mut is_late = false;
+        let mut result = Vec::new();
+        // source is skipped as soon as its next message does not fit into the batch,
+        // so a single source is taken as a prefix of its queue
+        while !queues.is_empty() {
+            queues.retain_mut(|(_, queue)| {
+                is_late = is_late || deadline.is_some_and(|deadline| Instant::now() >= deadline);
+                if is_late {
+                    return false;
+                }
+                let Some(&idx) = queue.front() else {
+                    return false;
+                };
+                let msg = &mut self.data[idx];
+                if taken_bytes + msg.payload.len() > self.payload_batch_bytes {
+                    return false;
+                }
+                taken_bytes += msg.payload.len();
                 metrics::histogram!("tycho_mempool_input_buffer_spent_time")
-                    .record(now.duration_since(*ingested));
-                elem
-            })
-            .cloned()
-            .collect::<Vec<_>>();
-        self.offset_elements = result.len(); // overwrite
+                    .record(now.duration_since(msg.ingested));
+                msg.is_fetched = true;
+                result.push(msg.payload.clone());
+                queue.pop_front();
+                !queue.is_empty()
+            });
+        }
+        if is_late {
+            metrics::counter!("tycho_mempool_input_buffer_fetch_timeouts").increment(1);
+        }
         result
     }
 
-    fn add(&mut self, payload: Bytes) {
+    fn add(&mut self, source: InputSourceId, payload: Bytes) {
         let payload_bytes = payload.len();
         assert!(
             payload_bytes <= self.payload_buffer_bytes,
@@ -120,20 +173,19 @@ impl InputBufferData {
             let to_drop = self
                 .data
                 .iter()
-                .take_while(|(front, _)| {
+                .take_while(|front| {
                     // last call must not change `self`
                     let take_more = self.data_bytes > max_data_bytes;
                     if take_more {
                         self.data_bytes = self
                             .data_bytes
-                            .checked_sub(front.len())
+                            .checked_sub(front.payload.len())
                             .expect("decrease buffered data size on eviction");
                     }
                     take_more
                 })
                 .count();
 
-            self.offset_elements = self.offset_elements.saturating_sub(to_drop);
             _ = self.data.drain(..to_drop);
 
             metrics::counter!("tycho_mempool_evicted_externals_count").increment(to_drop as _);
@@ -148,24 +200,29 @@ impl InputBufferData {
         }
 
         self.data_bytes += payload_bytes;
-        self.data.push_back((payload, Instant::now()));
+        self.data.push_back(BufferedMsg {
+            payload,
+            ingested: Instant::now(),
+            source,
+            is_fetched: false,
+        });
     }
 
-    fn commit_offset(&mut self) {
-        let committed_bytes: usize = self
-            .data
-            .drain(..self.offset_elements)
-            .map(|(comitted_bytes, _)| comitted_bytes.len())
-            .sum();
+    fn commit_fetched(&mut self) {
+        let mut committed_bytes = 0;
+        self.data.retain(|msg| {
+            if msg.is_fetched {
+                committed_bytes += msg.payload.len();
+            }
+            !msg.is_fetched
+        });
 
         self.update_capacity();
 
         self.data_bytes = self
             .data_bytes
             .checked_sub(committed_bytes)
-            .expect("decrease buffered data size on commit offset");
-
-        self.offset_elements = 0;
+            .expect("decrease buffered data size on commit");
     }
 
     /// Ensures that the capacity is not too large.
@@ -212,11 +269,11 @@ mod stub {
     }
 
     impl InputBufferInner for InputBufferStub {
-        fn push(&mut self, _: Bytes) {
+        fn push(&mut self, _: InputSourceId, _: Bytes) {
             panic!("not available for tests");
         }
 
-        fn fetch_inner(&mut self, _: bool) -> Vec<Bytes> {
+        fn fetch_inner(&mut self, _: bool, _: Option<Instant>) -> Vec<Bytes> {
             if self.payload_step == 0 {
                 return Vec::new();
             }
@@ -239,3 +296,109 @@ mod stub {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    const MSG_BYTES: usize = 100;
+
+    fn buffer(batch_msgs: usize) -> InputBufferData {
+        InputBufferData {
+            payload_buffer_bytes: 100 * MSG_BYTES,
+            payload_batch_bytes: batch_msgs * MSG_BYTES,
+            ..Default::default()
+        }
+    }
+
+    fn msg(source: InputSourceId, idx: usize) -> Bytes {
+        let mut data = vec![0; MSG_BYTES];
+        data[..4].copy_from_slice(&source.to_le_bytes());
+        data[4..12].copy_from_slice(&(idx as u64).to_le_bytes());
+        Bytes::from(data)
+    }
+
+    #[test]
+    fn sources_share_batch_under_contention() {
+        let mut buffer = buffer(10);
+        for idx in 0..20 {
+            buffer.push(1, msg(1, idx));
+        }
+        for idx in 0..5 {
+            buffer.push(2, msg(2, idx));
+        }
+
+        let batch = buffer.fetch_inner(true, None);
+        assert_eq!(batch.len(), 10);
+        let from_quiet = batch
+            .iter()
+            .filter(|m| m[..4] == 2_u32.to_le_bytes())
+            .count();
+        assert_eq!(from_quiet, 5, "quiet source must not be crowded out");
+
+        // the same batch is repeated until committed
+        assert_eq!(buffer.fetch_inner(false, None), batch);
+
+        // next batch contains only the rest of the noisy source, in order
+        let batch = buffer.fetch_inner(true, None);
+        assert_eq!(batch, (5..15).map(|idx| msg(1, idx)).collect::<Vec<_>>());
+        assert_eq!(buffer.data_bytes, 15 * MSG_BYTES);
+    }
+
+    #[test]
+    fn single_source_batch_is_a_prefix() {
+        let mut buffer = buffer(3);
+        for idx in 0..5 {
+            buffer.push(InputBuffer::DEFAULT_SOURCE, msg(0, idx));
+        }
+
+        let expected = (0..3).map(|idx| msg(0, idx)).collect::<Vec<_>>();
+        assert_eq!(buffer.fetch_inner(true, None), expected);
+        let expected = (3..5).map(|idx| msg(0, idx)).collect::<Vec<_>>();
+        assert_eq!(buffer.fetch_inner(true, None), expected);
+        assert!(buffer.fetch_inner(true, None).is_empty());
+        assert_eq!(buffer.data_bytes, 0);
+    }
+
+    #[test]
+    fn late_fetch_takes_nothing_and_keeps_messages() {
+        let mut buffer = buffer(10);
+        for idx in 0..5 {
+            buffer.push(InputBuffer::DEFAULT_SOURCE, msg(0, idx));
+        }
+
+        assert!(buffer.fetch_inner(true, Some(Instant::now())).is_empty());
+        let expected = (0..5).map(|idx| msg(0, idx)).collect::<Vec<_>>();
+        assert_eq!(buffer.fetch_inner(true, None), expected);
+    }
+
+    #[test]
+    fn slow_input_does_not_delay_fetch_past_deadline() {
+        let input_buffer = InputBuffer(Arc::new(Mutex::new(buffer(10))));
+        for idx in 0..5 {
+            input_buffer.push(msg(0, idx));
+        }
+
+        // slow input holds the buffer
+        let (locked_tx, locked_rx) = std::sync::mpsc::channel();
+        let slow_input = std::thread::spawn({
+            let input_buffer = input_buffer.clone();
+            move || {
+                let _guard = input_buffer.0.lock();
+                locked_tx.send(()).unwrap();
+                std::thread::sleep(Duration::from_millis(500));
+            }
+        });
+        locked_rx.recv().unwrap();
+
+        let start = Instant::now();
+        let payload = input_buffer.fetch(true, Some(start + Duration::from_millis(50)));
+        assert!(payload.is_empty(), "busy buffer must not be awaited");
+        assert!(start.elapsed() < Duration::from_millis(400));
+
+        slow_input.join().unwrap();
+        assert_eq!(input_buffer.fetch(true, None).len(), 5);
+    }
+}
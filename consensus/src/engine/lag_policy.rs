@@ -0,0 +1,196 @@
+use std::num::NonZeroU16;
+
+use crate::effects::{Ctx, RoundCtx};
+use crate::models::Round;
+
+/// Suppresses own point production while the node lags too far behind consensus,
+/// so a struggling node spends its resources on catching up instead of broadcasting.
+///
+/// Lag is measured as a gap between consensus round and the round of the local DAG head,
+/// so it is local to the node: a commit stall is the same for every peer and must not stop
+/// point production network-wide, otherwise no new anchor could ever be committed.
+/// Production is paused when the gap exceeds the threshold and resumes
+/// only after the gap is halved, to not toggle on every round near the bound.
+pub struct OwnPointLagPolicy {
+    max_lag_rounds: Option<NonZeroU16>,
+    is_lagging: bool,
+}
+
+impl OwnPointLagPolicy {
+    pub fn new(max_lag_rounds: Option<NonZeroU16>) -> Self {
+        Self {
+            max_lag_rounds,
+            is_lagging: false,
+        }
+    }
+
+    /// returns `true` if own point may be produced
+    pub fn update(&mut self, consensus: Round, current: Round, round_ctx: &RoundCtx) -> bool {
+        let Some(max_lag_rounds) = self.max_lag_rounds else {
+            return true;
+        };
+        let max_lag_rounds = max_lag_rounds.get();
+
+        if self.is_lagging {
+            if consensus <= current + max_lag_rounds / 2 {
+                self.is_lagging = false;
+                tracing::info!(
+                    parent: round_ctx.span(),
+                    consensus = consensus.0,
+                    current = current.0,
+                    "resume own point production: caught up with consensus",
+                );
+            }
+        } else if consensus > current + max_lag_rounds {
+            self.is_lagging = true;
+            tracing::warn!(
+                parent: round_ctx.span(),
+                consensus = consensus.0,
+                current = current.0,
+                max_lag_rounds,
+                "pause own point production: lagging behind consensus",
+            );
+        }
+
+        metrics::gauge!("tycho_mempool_engine_own_points_paused").set(self.is_lagging as u8);
+
+        !self.is_lagging
+    }
+}
+
+/// Warns when collator progress (top known anchor) falls too far behind consensus round,
+/// i.e. collator does not keep up with mempool and is likely to make it pause.
+pub struct CollatorLagAlert {
+    warn_lag_rounds: Option<NonZeroU16>,
+    is_lagging: bool,
+}
+
+impl CollatorLagAlert {
+    pub fn new(warn_lag_rounds: Option<NonZeroU16>) -> Self {
+        Self {
+            warn_lag_rounds,
+            is_lagging: false,
+        }
+    }
+
+    pub fn update(&mut self, consensus: Round, top_known_anchor: Round, round_ctx: &RoundCtx) {
+        metrics::gauge!("tycho_mempool_rounds_consensus_ahead_top_known")
+            .set(consensus.diff_f64(top_known_anchor));
+
+        let Some(warn_lag_rounds) = self.warn_lag_rounds else {
+            return;
+        };
+        let is_lagging = consensus > top_known_anchor + warn_lag_rounds.get();
+        if is_lagging == self.is_lagging {
+            return;
+        }
+        self.is_lagging = is_lagging;
+
+        if is_lagging {
+            tracing::warn!(
+                parent: round_ctx.span(),
+                consensus = consensus.0,
+                top_known_anchor = top_known_anchor.0,
+                warn_lag_rounds = warn_lag_rounds.get(),
+                "collator does not keep up with mempool",
+            );
+        } else {
+            tracing::info!(
+                parent: round_ctx.span(),
+                consensus = consensus.0,
+                top_known_anchor = top_known_anchor.0,
+                "collator caught up with mempool",
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::effects::{EngineCtx, TaskTracker};
+    use crate::test_utils::default_test_config;
+
+    #[test]
+    fn production_suppressed_while_lagging() {
+        let merged_conf = default_test_config();
+        let conf = &merged_conf.conf;
+        let engine_ctx = EngineCtx::new(conf.genesis_round, conf, &TaskTracker::default());
+        let round_ctx = RoundCtx::new(&engine_ctx, conf.genesis_round);
+
+        let current = Round(100);
+        let mut policy = OwnPointLagPolicy::new(NonZeroU16::new(10));
+
+        assert!(policy.update(current + 5_u32, current, &round_ctx));
+        assert!(policy.update(current + 10_u32, current, &round_ctx));
+
+        // lagging: suppressed until the gap is halved
+        assert!(!policy.update(current + 11_u32, current, &round_ctx));
+        assert!(!policy.update(current + 8_u32, current, &round_ctx));
+        assert!(!policy.update(current + 6_u32, current, &round_ctx));
+
+        // caught up
+        assert!(policy.update(current + 5_u32, current, &round_ctx));
+        assert!(policy.update(current + 10_u32, current, &round_ctx));
+
+        // disabled policy never suppresses
+        let mut policy = OwnPointLagPolicy::new(None);
+        assert!(policy.update(current + 1000_u32, current, &round_ctx));
+    }
+
+    #[test]
+    fn production_continues_when_every_peer_lags() {
+        let merged_conf = default_test_config();
+        let conf = &merged_conf.conf;
+        let engine_ctx = EngineCtx::new(conf.genesis_round, conf, &TaskTracker::default());
+
+        // commit stalls network-wide: every peer is far ahead of the last committed round,
+        // but each one is at consensus round, so all of them keep producing points
+        let committed = Round(100);
+        let mut policies = (0..4)
+            .map(|_| OwnPointLagPolicy::new(NonZeroU16::new(10)))
+            .collect::<Vec<_>>();
+        for consensus in (committed.0..committed.0 + 100).map(Round) {
+            let round_ctx = RoundCtx::new(&engine_ctx, consensus);
+            for policy in &mut policies {
+                assert!(policy.update(consensus, consensus, &round_ctx));
+                // dag head may be a round behind during round switch
+                assert!(policy.update(consensus, consensus.prev(), &round_ctx));
+            }
+        }
+    }
+
+    #[test]
+    fn collator_lag_gauge_reflects_gap() {
+        use tycho_util::test::TestRecorder;
+
+        const LAG_GAUGE: &str = "tycho_mempool_rounds_consensus_ahead_top_known";
+
+        let merged_conf = default_test_config();
+        let conf = &merged_conf.conf;
+        let engine_ctx = EngineCtx::new(conf.genesis_round, conf, &TaskTracker::default());
+        let round_ctx = RoundCtx::new(&engine_ctx, conf.genesis_round);
+
+        let recorder = TestRecorder::default();
+        let top_known_anchor = Round(100);
+        let mut alert = CollatorLagAlert::new(NonZeroU16::new(10));
+
+        metrics::with_local_recorder(&recorder, || {
+            alert.update(top_known_anchor + 5_u32, top_known_anchor, &round_ctx);
+            assert_eq!(recorder.gauge(LAG_GAUGE), Some(5.0));
+            assert!(!alert.is_lagging);
+
+            alert.update(top_known_anchor + 42_u32, top_known_anchor, &round_ctx);
+            assert_eq!(recorder.gauge(LAG_GAUGE), Some(42.0));
+            assert!(alert.is_lagging);
+
+            alert.update(
+                top_known_anchor + 42_u32,
+                top_known_anchor + 40_u32,
+                &round_ctx,
+            );
+            assert_eq!(recorder.gauge(LAG_GAUGE), Some(2.0));
+            assert!(!alert.is_lagging);
+        });
+    }
+}
@@ -1,21 +1,20 @@
 use std::sync::Arc;
 
 use everscale_crypto::ed25519::KeyPair;
-use tokio::sync::mpsc;
 use tycho_network::{Network, OverlayService, PeerResolver, PrivateOverlay};
 
 use crate::effects::{AltFormat, MempoolAdapterStore, TaskTracker};
 use crate::engine::round_watch::{RoundWatch, TopKnownAnchor};
-use crate::engine::{InputBuffer, MempoolMergedConfig};
-use crate::intercom::{Dispatcher, InitPeers, PeerSchedule, Responder};
-use crate::models::MempoolOutput;
+use crate::engine::{AnchorSinkRef, InputBuffer, MempoolMergedConfig};
+use crate::intercom::{BanEvents, Dispatcher, InitPeers, PeerSchedule, Responder};
 
 #[derive(Clone)]
 pub struct EngineBinding {
     pub mempool_adapter_store: MempoolAdapterStore,
     pub input_buffer: InputBuffer,
     pub top_known_anchor: RoundWatch<TopKnownAnchor>,
-    pub output: mpsc::UnboundedSender<MempoolOutput>,
+    /// use `Arc::new(mpsc::UnboundedSender<MempoolOutput>)` for a channel consumer
+    pub output: AnchorSinkRef,
 }
 
 #[derive(Clone)]
@@ -32,6 +31,8 @@ pub struct EngineNetwork {
     pub dispatcher: Dispatcher,
     /// dropped at full restart
     pub responder: Responder,
+    /// kept between restarts
+    pub ban_events: BanEvents,
 }
 
 impl EngineNetwork {
@@ -40,6 +41,7 @@ impl EngineNetwork {
         task_tracker: &TaskTracker,
         merged_conf: &MempoolMergedConfig,
         init_peers: &InitPeers,
+        ban_events: &BanEvents,
     ) -> Self {
         let responder = Responder::default();
 
@@ -66,6 +68,7 @@ impl EngineNetwork {
             peer_schedule,
             dispatcher,
             responder,
+            ban_events: ban_events.clone(),
         }
     }
 }
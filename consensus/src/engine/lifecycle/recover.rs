@@ -6,7 +6,7 @@ use parking_lot::Mutex;
 use crate::effects::{AltFormat, Cancelled, Task, TaskTracker};
 use crate::engine::lifecycle::{EngineError, EngineNetwork, FixHistoryFlag};
 use crate::engine::{Engine, MempoolMergedConfig};
-use crate::intercom::{InitPeers, PeerSchedule};
+use crate::intercom::{BanEvents, InitPeers, PeerSchedule};
 use crate::prelude::{EngineBinding, EngineNetworkArgs};
 
 pub struct EngineRecoverLoop {
@@ -31,6 +31,7 @@ pub struct RunAttributes {
     pub is_stopping: bool,
     pub peer_schedule: PeerSchedule,
     pub last_peers: InitPeers,
+    pub ban_events: BanEvents,
 }
 
 impl EngineRecoverLoop {
@@ -75,6 +76,7 @@ impl EngineRecoverLoop {
                     &guard.tracker,
                     &self.merged_conf,
                     &guard.last_peers,
+                    &guard.ban_events,
                 );
                 guard.peer_schedule = net.peer_schedule.clone();
                 (guard.tracker.clone(), net)
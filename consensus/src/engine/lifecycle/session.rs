@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use everscale_types::models::GenesisInfo;
 use parking_lot::Mutex;
-use tokio::sync::oneshot;
+use tokio::sync::{broadcast, oneshot};
 use tokio_util::task::AbortOnDropHandle;
 
 use crate::effects::TaskTracker;
@@ -10,7 +10,7 @@ use crate::engine::lifecycle::recover::{EngineRecoverLoop, RunAttributes};
 use crate::engine::lifecycle::session::isolated::SpanFields;
 use crate::engine::lifecycle::{EngineNetwork, FixHistoryFlag};
 use crate::engine::{Engine, MempoolMergedConfig};
-use crate::intercom::InitPeers;
+use crate::intercom::{BanEvent, BanEvents, InitPeers};
 use crate::prelude::{EngineBinding, EngineNetworkArgs};
 
 pub struct EngineSession {
@@ -32,7 +32,14 @@ impl EngineSession {
         let span_fields = SpanFields::new(net_args, merged_conf);
 
         let task_tracker = TaskTracker::default();
-        let net = EngineNetwork::new(net_args, &task_tracker, merged_conf, &init_peers);
+        let ban_events = BanEvents::default();
+        let net = EngineNetwork::new(
+            net_args,
+            &task_tracker,
+            merged_conf,
+            &init_peers,
+            &ban_events,
+        );
         let engine = Engine::new(
             &task_tracker,
             &bind,
@@ -46,6 +53,7 @@ impl EngineSession {
             is_stopping: false,
             peer_schedule: net.peer_schedule,
             last_peers: init_peers,
+            ban_events,
         }));
 
         let recover_loop = AbortOnDropHandle::new(tokio::spawn(
@@ -71,6 +79,12 @@ impl EngineSession {
         self.genesis_info
     }
 
+    /// Peers that sent points no reliable peer would send, e.g. to disconnect them.
+    /// Subscription outlives engine restarts.
+    pub fn subscribe_ban_events(&self) -> broadcast::Receiver<BanEvent> {
+        self.run_attrs.lock().ban_events.subscribe()
+    }
+
     pub fn set_peers(&self, peers: InitPeers) {
         let mut run_attrs = self.run_attrs.lock();
         run_attrs.peer_schedule.set_peers(&peers);
@@ -1,24 +1,83 @@
-use std::num::{NonZeroU16, NonZeroU8};
-use std::sync::OnceLock;
+use std::num::{NonZeroU16, NonZeroU32, NonZeroU8};
+use std::ops::Deref;
+use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::{ensure, Context, Result};
+use arc_swap::{ArcSwapOption, Guard};
 use everscale_crypto::ed25519::{KeyPair, SecretKey};
 use everscale_types::models::{ConsensusConfig, GenesisInfo};
 use serde::{Deserialize, Serialize};
+use tokio::sync::watch;
 use tycho_network::{OverlayId, PeerId};
 
 use crate::dag::align_genesis;
 use crate::models::{Link, Point, PointData, Round, UnixTime};
 
-// replace with `ArcSwapOption` + copy on get() if need to change in runtime
-static NODE_CONFIG: OnceLock<MempoolNodeConfig> = OnceLock::new();
+static NODE_CONFIG: ArcSwapOption<MempoolNodeConfig> = ArcSwapOption::const_empty();
 pub struct NodeConfig;
 impl NodeConfig {
-    pub fn get() -> &'static MempoolNodeConfig {
-        (NODE_CONFIG.get()).expect("mempool node config not initialized")
+    /// Changes made with [`Self::update`] are visible to every next call
+    pub fn get() -> Arc<MempoolNodeConfig> {
+        (NODE_CONFIG.load_full()).expect("mempool node config not initialized")
+    }
+
+    /// Same as [`Self::get`] without a clone of `Arc`, for hot paths;
+    /// the guard must not be held for long
+    pub fn load() -> NodeConfigGuard {
+        let guard = NODE_CONFIG.load();
+        assert!(guard.is_some(), "mempool node config not initialized");
+        NodeConfigGuard(guard)
+    }
+
+    /// Replaces node-local values in runtime, they take effect at the next use.
+    /// Values that are read once or must stay the same during the whole node run
+    /// are not allowed to change.
+    pub fn update(new: &MempoolNodeConfig) -> Result<(), NodeConfigUpdateError> {
+        Self::update_in(&NODE_CONFIG, new)
+    }
+
+    /// Applies every next value from the channel until the sender is dropped;
+    /// rejected values are logged and the previous config is kept.
+    pub async fn follow(mut updates: watch::Receiver<MempoolNodeConfig>) {
+        while updates.changed().await.is_ok() {
+            let new = updates.borrow_and_update().clone();
+            match Self::update(&new) {
+                Ok(()) => tracing::info!("mempool node config updated: {new:?}"),
+                Err(e) => tracing::error!("mempool node config update rejected: {e}"),
+            }
+        }
+    }
+
+    pub(crate) fn update_in(
+        slot: &ArcSwapOption<MempoolNodeConfig>,
+        new: &MempoolNodeConfig,
+    ) -> Result<(), NodeConfigUpdateError> {
+        let current = (slot.load_full()).ok_or(NodeConfigUpdateError::NotInitialized)?;
+        if let Some(field) = current.changed_fixed_field(new) {
+            return Err(NodeConfigUpdateError::FixedField(field));
+        }
+        slot.store(Some(Arc::new(new.clone())));
+        Ok(())
     }
 }
 
+pub struct NodeConfigGuard(Guard<Option<Arc<MempoolNodeConfig>>>);
+impl Deref for NodeConfigGuard {
+    type Target = MempoolNodeConfig;
+    fn deref(&self) -> &Self::Target {
+        (self.0.as_deref()).expect("checked on load")
+    }
+}
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum NodeConfigUpdateError {
+    #[error("mempool node config is not initialized")]
+    NotInitialized,
+    #[error("mempool node config field `{0}` cannot be changed in runtime")]
+    FixedField(&'static str),
+}
+
 /// values that can be changed in runtime via key block, private to crate
 #[derive(Clone, Debug, PartialEq)]
 pub struct MempoolConfig {
@@ -43,6 +102,10 @@ impl MempoolMergedConfig {
     pub fn consensus(&self) -> &ConsensusConfig {
         &self.conf.consensus
     }
+    /// effective genesis round after alignment of [`GenesisInfo::start_round`]
+    pub fn genesis_round(&self) -> u32 {
+        self.conf.genesis_round.0
+    }
 
     pub(crate) fn genesis_author(&self) -> PeerId {
         let key_pair = KeyPair::from(&SecretKey::from_bytes(self.overlay_id.0));
@@ -79,7 +142,11 @@ pub struct MempoolConfigBuilder {
 
 impl MempoolConfigBuilder {
     pub fn new(node_config: &MempoolNodeConfig) -> Self {
-        if NODE_CONFIG.get_or_init(|| node_config.clone()) != node_config {
+        let prev = NODE_CONFIG.compare_and_swap(
+            &None::<Arc<MempoolNodeConfig>>,
+            Some(Arc::new(node_config.clone())),
+        );
+        if prev.as_deref().is_some_and(|prev| prev != node_config) {
             tracing::error!(
                 "mempool node config was not changed; using prev {:?} ignored new {:?}",
                 NodeConfig::get(),
@@ -103,10 +170,31 @@ impl MempoolConfigBuilder {
             "no need to evict cached externals if can send them in one message"
         );
 
+        let clock_skew_millis = consensus_config.clock_skew_millis as u64;
+        ensure!(
+            clock_skew_millis > 0,
+            "zero clock skew makes every point from a peer with a slightly faster clock ill-formed"
+        );
+        ensure!(
+            clock_skew_millis >= consensus_config.broadcast_retry_millis as u64,
+            "clock skew {clock_skew_millis} ms must not be less than broadcast retry interval {} ms, \
+             otherwise points are rejected as from the future before a round can finish",
+            consensus_config.broadcast_retry_millis,
+        );
+        ensure!(
+            clock_skew_millis <= Self::MAX_CLOCK_SKEW_MILLIS,
+            "clock skew {clock_skew_millis} ms exceeds {} ms: \
+             anchor time will be too easy to shift into the future",
+            Self::MAX_CLOCK_SKEW_MILLIS,
+        );
+
         self.consensus_config = Some(consensus_config.clone());
         Ok(())
     }
 
+    /// Upper bound for [`ConsensusConfig::clock_skew_millis`]
+    pub const MAX_CLOCK_SKEW_MILLIS: u64 = 60 * 1000;
+
     pub fn set_genesis(&mut self, info: GenesisInfo) {
         self.genesis_info = Some(info);
     }
@@ -130,7 +218,15 @@ impl MempoolConfigBuilder {
             .as_ref()
             .context("mempool consensus config is not known")?;
 
-        let genesis_round = align_genesis(genesis_info.start_round);
+        let genesis_round = align_genesis(genesis_info.start_round)
+            .context("mempool genesis info for config is invalid")?;
+        if genesis_round.0 != genesis_info.start_round {
+            tracing::info!(
+                start_round = genesis_info.start_round,
+                genesis_round = genesis_round.0,
+                "mempool genesis round is aligned to anchor wave"
+            );
+        }
 
         let mempool_config = MempoolConfig {
             consensus: consensus.clone(),
@@ -185,6 +281,89 @@ pub struct MempoolNodeConfig {
 
     /// Max simultaneous point search tasks fulfilling download request
     pub max_upload_tasks: NonZeroU8,
+
+    /// Pause own point production while consensus round is ahead of the local DAG head round
+    /// by more than this amount of rounds; production resumes once the gap is halved.
+    /// `None` to produce points regardless of lag.
+    pub pause_own_points_lag_rounds: Option<NonZeroU16>,
+
+    /// Send own points to this amount of peers only, that re-gossip them further
+    /// along a tree instead of a direct broadcast to every peer.
+    /// `None` for full broadcast; it is also used for validator sets not larger than `fanout + 1`.
+    /// Peers may use different values: relays are accepted from a parent under any fanout.
+    pub broadcast_fanout: Option<NonZeroU8>,
+
+    /// Warn when consensus round is ahead of the top known anchor (collator progress)
+    /// by more than this amount of rounds. `None` to not warn.
+    pub warn_collator_lag_rounds: Option<NonZeroU16>,
+
+    /// Log at most this amount of rejected download responses and points per peer per second,
+    /// the rest is counted in metrics only. `None` to log every rejection.
+    pub max_rejection_logs_per_peer: Option<NonZeroU16>,
+
+    /// Limit time to fetch own point payload from input buffer, in percents of
+    /// the previous round duration, so a slow input cannot delay the round
+    /// and produces a point with a smaller payload.
+    /// The first round after start and `None` wait for the full payload.
+    pub payload_fetch_deadline_percent: Option<NonZeroU8>,
+
+    /// Limit time to wait for a point from its author, including connection establishment,
+    /// so must be well above round trip and handshake time between validators.
+    /// `None` to wait until the query fails by network timeout.
+    pub download_author_timeout_millis: Option<NonZeroU32>,
+
+    /// Limit time to wait for a point from any other peer, including connection establishment,
+    /// so a slow peer does not hold its slot of a download attempt.
+    /// `None` to wait until the query fails by network timeout.
+    pub download_peer_timeout_millis: Option<NonZeroU32>,
+
+    /// Report medium pressure when collator did not consume at least this amount
+    /// of anchors produced by mempool: collator imports anchors without waiting
+    /// for used work units until it catches up.
+    pub backlog_medium_anchors: u16,
+
+    /// Drop new externals while collator did not consume at least this amount
+    /// of anchors produced by mempool. `None` to never drop externals.
+    ///
+    /// Disabled by default: dropping externals is lossy, so it must be enabled explicitly.
+    pub backlog_high_anchors: Option<NonZeroU16>,
+}
+
+impl MempoolNodeConfig {
+    /// Time to wait for a single point download query, depends on whether the peer is its author
+    pub fn download_query_timeout(&self, is_author: bool) -> Option<Duration> {
+        let millis = if is_author {
+            self.download_author_timeout_millis?
+        } else {
+            self.download_peer_timeout_millis?
+        };
+        Some(Duration::from_millis(millis.get() as u64))
+    }
+
+    /// Time to fetch own point payload, a part of the time the previous round took
+    pub fn payload_fetch_timeout(&self, prev_round_time: Duration) -> Option<Duration> {
+        let percent = self.payload_fetch_deadline_percent?;
+        Some(prev_round_time * percent.get() as u32 / 100)
+    }
+
+    /// Name of the first field that cannot be changed in runtime but differs in `other`:
+    /// * task limits are applied once at startup
+    /// * lag thresholds and log limits are read once when the engine is created
+    fn changed_fixed_field(&self, other: &Self) -> Option<&'static str> {
+        if self.max_blocking_tasks != other.max_blocking_tasks {
+            Some("max_blocking_tasks")
+        } else if self.max_upload_tasks != other.max_upload_tasks {
+            Some("max_upload_tasks")
+        } else if self.pause_own_points_lag_rounds != other.pause_own_points_lag_rounds {
+            Some("pause_own_points_lag_rounds")
+        } else if self.warn_collator_lag_rounds != other.warn_collator_lag_rounds {
+            Some("warn_collator_lag_rounds")
+        } else if self.max_rejection_logs_per_peer != other.max_rejection_logs_per_peer {
+            Some("max_rejection_logs_per_peer")
+        } else {
+            None
+        }
+    }
 }
 
 impl Default for MempoolNodeConfig {
@@ -195,6 +374,114 @@ impl Default for MempoolNodeConfig {
             cache_future_broadcasts_rounds: 105,
             max_blocking_tasks: NonZeroU16::new(250).unwrap(),
             max_upload_tasks: NonZeroU8::new(50).unwrap(),
+            pause_own_points_lag_rounds: None,
+            broadcast_fanout: None,
+            warn_collator_lag_rounds: None,
+            max_rejection_logs_per_peer: NonZeroU16::new(10),
+            payload_fetch_deadline_percent: NonZeroU8::new(50),
+            download_author_timeout_millis: None,
+            download_peer_timeout_millis: None,
+            backlog_medium_anchors: 5,
+            backlog_high_anchors: None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::default_test_config;
+
+    fn builder() -> MempoolConfigBuilder {
+        MempoolConfigBuilder {
+            genesis_info: None,
+            consensus_config: None,
+        }
+    }
+
+    #[test]
+    fn clock_skew_bounds() {
+        let valid = default_test_config().conf.consensus;
+        builder()
+            .set_consensus_config(&valid)
+            .expect("test config must be valid");
+
+        let mut config = valid.clone();
+        config.clock_skew_millis = 0;
+        assert!(builder().set_consensus_config(&config).is_err());
+
+        config.clock_skew_millis = config.broadcast_retry_millis as _;
+        builder()
+            .set_consensus_config(&config)
+            .expect("skew equal to broadcast retry is allowed");
+
+        config.clock_skew_millis = (config.broadcast_retry_millis - 1) as _;
+        assert!(builder().set_consensus_config(&config).is_err());
+
+        let mut config = valid;
+        config.clock_skew_millis = MempoolConfigBuilder::MAX_CLOCK_SKEW_MILLIS as _;
+        builder()
+            .set_consensus_config(&config)
+            .expect("max skew is allowed");
+
+        config.clock_skew_millis = (MempoolConfigBuilder::MAX_CLOCK_SKEW_MILLIS + 1) as _;
+        assert!(builder().set_consensus_config(&config).is_err());
+    }
+
+    #[test]
+    fn download_query_timeout_is_opt_in() {
+        let mut config = MempoolNodeConfig::default();
+        assert_eq!(config.download_query_timeout(true), None);
+        assert_eq!(config.download_query_timeout(false), None);
+
+        config.download_author_timeout_millis = NonZeroU32::new(3000);
+        config.download_peer_timeout_millis = NonZeroU32::new(1500);
+        assert_eq!(
+            config.download_query_timeout(true),
+            Some(Duration::from_millis(3000))
+        );
+        assert_eq!(
+            config.download_query_timeout(false),
+            Some(Duration::from_millis(1500))
+        );
+    }
+
+    #[test]
+    fn payload_fetch_timeout_follows_round_time() {
+        let mut config = MempoolNodeConfig::default();
+        config.payload_fetch_deadline_percent = NonZeroU8::new(40);
+        let round_time = Duration::from_millis(500);
+        assert_eq!(
+            config.payload_fetch_timeout(round_time),
+            Some(Duration::from_millis(200))
+        );
+
+        config.payload_fetch_deadline_percent = None;
+        assert_eq!(config.payload_fetch_timeout(round_time), None);
+    }
+
+    #[test]
+    fn node_config_runtime_update() {
+        let slot = ArcSwapOption::const_empty();
+        let config = MempoolNodeConfig::default();
+        assert_eq!(
+            NodeConfig::update_in(&slot, &config),
+            Err(NodeConfigUpdateError::NotInitialized)
+        );
+        slot.store(Some(Arc::new(config.clone())));
+
+        let mut changed = config.clone();
+        changed.clean_db_period_rounds = NonZeroU16::new(7).unwrap();
+        NodeConfig::update_in(&slot, &changed).expect("node-local value must be changed");
+        let current = slot.load_full().unwrap();
+        assert_eq!(current.clean_db_period_rounds.get(), 7);
+
+        let mut fixed = changed.clone();
+        fixed.max_upload_tasks = NonZeroU8::new(3).unwrap();
+        assert_eq!(
+            NodeConfig::update_in(&slot, &fixed),
+            Err(NodeConfigUpdateError::FixedField("max_upload_tasks"))
+        );
+        assert_eq!(*slot.load_full().unwrap(), changed, "must keep prev value");
+    }
+}
@@ -1,13 +1,17 @@
+pub use anchor_sink::*;
 pub use consensus_config_ext::*;
 pub use impl_::*;
 pub use input_buffer::*;
+pub use lag_policy::CollatorLagAlert;
 pub use mempool_config::*;
 
 // parts must not know about private details of the whole
+mod anchor_sink;
 mod committer_task;
 mod consensus_config_ext;
 mod impl_;
 mod input_buffer;
+mod lag_policy;
 pub mod lifecycle;
 mod mempool_config;
 mod round_task;
@@ -1,21 +1,24 @@
 use std::cmp;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use futures_util::{future, FutureExt};
 use tokio::sync::{oneshot, watch};
 
 use crate::dag::{
-    DagHead, LastOwnPoint, ProduceError, Producer, ValidateResult, Verifier, WeakDagRound,
+    DagHead, LastOwnPoint, ProduceError, Producer, ValidateResult, VerificationMode, Verifier,
+    WeakDagRound,
 };
 use crate::effects::{
     AltFormat, CollectCtx, Ctx, MempoolStore, RoundCtx, Task, TaskResult, ValidateCtx,
 };
 use crate::engine::input_buffer::InputBuffer;
+use crate::engine::lag_policy::OwnPointLagPolicy;
 use crate::engine::round_watch::{Consensus, RoundWatch, TopKnownAnchor};
+use crate::engine::NodeConfig;
 use crate::intercom::{
-    BroadcastFilter, Broadcaster, BroadcasterSignal, Collector, CollectorSignal, Dispatcher,
-    Downloader, PeerSchedule, Responder,
+    BanEvents, BroadcastFilter, Broadcaster, BroadcasterSignal, Collector, CollectorSignal,
+    Dispatcher, Downloader, PeerSchedule, Responder,
 };
 use crate::models::{Cert, Link, Point, PointInfo};
 
@@ -25,10 +28,13 @@ pub struct RoundTaskState {
     pub responder: Responder,
     pub top_known_anchor: RoundWatch<TopKnownAnchor>,
     pub consensus_round: RoundWatch<Consensus>,
+    lag_policy: OwnPointLagPolicy,
     input_buffer: InputBuffer,
     dispatcher: Dispatcher,
     pub broadcast_filter: BroadcastFilter,
     pub downloader: Downloader,
+    /// previous round duration is the time budget for the next one
+    round_started: Option<Instant>,
 }
 
 pub struct RoundTaskReady {
@@ -41,6 +47,7 @@ pub struct RoundTaskReady {
 }
 
 impl RoundTaskReady {
+    #[allow(clippy::too_many_arguments)] // TODO arch: make args less granular
     pub fn new(
         dispatcher: &Dispatcher,
         peer_schedule: PeerSchedule,
@@ -49,9 +56,15 @@ impl RoundTaskReady {
         top_known_anchor: RoundWatch<TopKnownAnchor>,
         responder: Responder,
         input_buffer: InputBuffer,
+        ban_events: &BanEvents,
     ) -> Self {
-        let broadcast_filter = BroadcastFilter::new(&peer_schedule, consensus_round);
-        let downloader = Downloader::new(dispatcher, &peer_schedule, consensus_round.receiver());
+        let broadcast_filter = BroadcastFilter::new(dispatcher, &peer_schedule, consensus_round);
+        let downloader = Downloader::new(
+            dispatcher,
+            &peer_schedule,
+            consensus_round.receiver(),
+            ban_events,
+        );
         Self {
             state: RoundTaskState {
                 peer_schedule,
@@ -59,10 +72,12 @@ impl RoundTaskReady {
                 responder,
                 top_known_anchor,
                 consensus_round: consensus_round.clone(),
+                lag_policy: OwnPointLagPolicy::new(NodeConfig::get().pause_own_points_lag_rounds),
                 input_buffer,
                 dispatcher: dispatcher.clone(),
                 broadcast_filter,
                 downloader,
+                round_started: None,
             },
             collector: Collector::new(consensus_round.receiver()),
             last_own_point: None,
@@ -98,6 +113,7 @@ impl RoundTaskReady {
     async fn own_point_task(
         last_own_point: Option<Arc<LastOwnPoint>>,
         input_buffer: InputBuffer,
+        prev_round_time: Option<Duration>,
         store: MempoolStore,
         head: DagHead,
         mut collector_signal_rx: watch::Receiver<CollectorSignal>,
@@ -152,15 +168,27 @@ impl RoundTaskReady {
 
         let task_start_time = Instant::now();
 
-        let produced = (round_ctx.span()).in_scope(|| {
-            Producer::new_point(
-                last_own_point.as_deref(),
-                &input_buffer,
-                &head,
-                round_ctx.conf(),
-            )
+        let payload_deadline = prev_round_time
+            .and_then(|round_time| NodeConfig::load().payload_fetch_timeout(round_time))
+            .map(|timeout| task_start_time + timeout);
+
+        // input buffer may be locked by a slow input until the deadline
+        let produced = round_ctx.task().spawn_blocking({
+            let head = head.clone();
+            let round_ctx = round_ctx.clone();
+            move || {
+                (round_ctx.span()).in_scope(|| {
+                    Producer::new_point(
+                        last_own_point.as_deref(),
+                        &input_buffer,
+                        payload_deadline,
+                        &head,
+                        round_ctx.conf(),
+                    )
+                })
+            }
         });
-        let own_point = match produced {
+        let own_point = match produced.await? {
             Ok(own_point) => own_point,
             Err(err) => return Ok(Err(err)),
         };
@@ -196,6 +224,10 @@ impl RoundTaskReady {
 
         let collector_signal_tx = watch::Sender::new(CollectorSignal::Retry { ready: false });
 
+        let round_started = Instant::now();
+        let prev_round_time = (self.state.round_started.replace(round_started))
+            .map(|prev_started| round_started.duration_since(prev_started));
+
         let broadcaster_run = round_ctx.task().spawn({
             let produce_point_fut = match start_replay_bcasts {
                 Some((point, prev_bcast)) => {
@@ -204,9 +236,18 @@ impl RoundTaskReady {
                     }
                     future::ready(Ok(Ok(point))).boxed()
                 }
+                None if !self.state.lag_policy.update(
+                    self.state.consensus_round.get(),
+                    head.current().round(),
+                    round_ctx,
+                ) =>
+                {
+                    future::ready(Ok(Err(ProduceError::Lagging))).boxed()
+                }
                 None => Self::own_point_task(
                     self.last_own_point.clone(),
                     self.state.input_buffer.clone(),
+                    prev_round_time,
                     self.state.store.clone(),
                     head.clone(),
                     collector_signal_tx.subscribe(),
@@ -296,7 +337,9 @@ impl RoundTaskReady {
         let task_ctx = round_ctx.task();
         let round_ctx = round_ctx.clone();
         task_ctx.spawn(async move {
-            if let Err(error) = Verifier::verify(point.info(), &peer_schedule, round_ctx.conf()) {
+            let conf = round_ctx.conf();
+            let mode = VerificationMode::Full;
+            if let Err(error) = Verifier::verify(point.info(), &peer_schedule, conf, mode) {
                 let _guard = round_ctx.span().enter();
                 panic!("Failed to verify own point: {error}, {:?}", point)
             }
@@ -376,6 +419,7 @@ impl RoundCtx {
 
         match own_point {
             Ok(own_info) => {
+                ValidateCtx::point_links(own_info, "produced");
                 tracing::info!(
                     parent: self.span(),
                     digest = display(own_info.digest().alt()),
@@ -396,23 +440,93 @@ impl RoundCtx {
                     ProduceError::NotAllowed | ProduceError::NotEnoughEvidence => {
                         tracing::warn!(parent: self.span(), %reason, "produce point skipped");
                     }
-                    ProduceError::NextRoundThreshold | ProduceError::NotScheduled => {
+                    ProduceError::NextRoundThreshold
+                    | ProduceError::NotScheduled
+                    | ProduceError::Lagging => {
                         tracing::info!(parent: self.span(), %reason, "produce point skipped");
                     }
                     ProduceError::PrevPointMismatch { .. } => {
                         tracing::error!(parent: self.span(), %reason, "produce point skipped");
                     }
                 };
-                let label = match reason {
+                // skipped rounds are expected, while a failure means broken invariants
+                let (label, is_failed) = match reason {
                     ProduceError::NotAllowed
                     | ProduceError::NotEnoughEvidence
-                    | ProduceError::NextRoundThreshold => "late",
-                    ProduceError::NotScheduled => "not in v_set",
-                    ProduceError::PrevPointMismatch { .. } => "prev point",
+                    | ProduceError::NextRoundThreshold => ("late", false),
+                    ProduceError::NotScheduled => ("not in v_set", false),
+                    ProduceError::Lagging => ("lagging", false),
+                    ProduceError::PrevPointMismatch { .. } => ("prev point", true),
                 };
-                metrics::counter!("tycho_mempool_engine_produce_skipped", "kind" => label)
-                    .increment(1);
+                if is_failed {
+                    metrics::counter!("tycho_mempool_engine_produce_failed", "kind" => label)
+                        .increment(1);
+                } else {
+                    metrics::counter!("tycho_mempool_engine_produce_skipped", "kind" => label)
+                        .increment(1);
+                }
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::array;
+
+    use everscale_crypto::ed25519::{KeyPair, SecretKey};
+    use tycho_network::PeerId;
+    use tycho_util::test::TestRecorder;
+
+    use super::*;
+    use crate::models::test_point::{new_key_pair, point};
+    use crate::test_utils;
+
+    #[tokio::test]
+    async fn own_point_outcomes_are_counted() {
+        let peers: [(PeerId, Arc<KeyPair>); 3] = array::from_fn(|i| {
+            let keys = KeyPair::from(&SecretKey::from_bytes([i as u8; 32]));
+            (PeerId::from(keys.public_key), Arc::new(keys))
+        });
+        let (_, _, _, engine_ctx) = test_utils::make_engine_parts(&peers, peers[0].1.clone());
+        let conf = engine_ctx.conf();
+        let round_ctx = RoundCtx::new(&engine_ctx, conf.genesis_round);
+
+        let own_point = point(&new_key_pair(), &[], conf);
+
+        let recorder = TestRecorder::default();
+        metrics::with_local_recorder(&recorder, || {
+            round_ctx.own_point(Ok(own_point.info()));
+            round_ctx.own_point(Err(&ProduceError::NotScheduled));
+        });
+
+        assert_eq!(recorder.counter("tycho_mempool_points_produced"), 1);
+        assert_eq!(recorder.counter("tycho_mempool_engine_produce_skipped"), 1);
+        assert_eq!(recorder.counter("tycho_mempool_engine_produce_failed"), 0);
+    }
+    #[tokio::test]
+    async fn own_point_links_are_recorded() {
+        let peers: [(PeerId, Arc<KeyPair>); 3] = array::from_fn(|i| {
+            let keys = KeyPair::from(&SecretKey::from_bytes([i as u8; 32]));
+            (PeerId::from(keys.public_key), Arc::new(keys))
+        });
+        let (_, _, _, engine_ctx) = test_utils::make_engine_parts(&peers, peers[0].1.clone());
+        let conf = engine_ctx.conf();
+        let round_ctx = RoundCtx::new(&engine_ctx, conf.genesis_round);
+
+        let own_point = point(&new_key_pair(), &[], conf);
+        let includes = own_point.info().includes().len() as f64;
+        let witness = own_point.info().witness().len() as f64;
+
+        let recorder = TestRecorder::default();
+        metrics::with_local_recorder(&recorder, || {
+            round_ctx.own_point(Ok(own_point.info()));
+            round_ctx.own_point(Err(&ProduceError::NotScheduled));
+        });
+
+        let recorded = recorder.histogram("tycho_mempool_point_includes_peers");
+        assert_eq!(recorded, vec![includes]);
+        let recorded = recorder.histogram("tycho_mempool_point_witness_peers");
+        assert_eq!(recorded, vec![witness]);
+    }
+}
@@ -4,15 +4,22 @@ use std::sync::Arc;
 use std::{cmp, mem};
 
 use dashmap::mapref::entry::Entry as DashMapEntry;
+use futures_util::stream::FuturesUnordered;
+use futures_util::{future, StreamExt};
+use parking_lot::Mutex;
 use tycho_network::PeerId;
 use tycho_util::{FastDashMap, FastHashMap};
 
-use crate::dag::{DagHead, DagRound, IllFormedReason, Verifier, VerifyError, VerifyFailReason};
+use crate::dag::{
+    DagHead, DagRound, IllFormedReason, VerificationMode, Verifier, VerifyError, VerifyFailReason,
+};
 use crate::dyn_event;
-use crate::effects::{AltFormat, Ctx, MempoolStore, RoundCtx};
+use crate::effects::{AltFormat, Ctx, MempoolStore, RoundCtx, Task};
 use crate::engine::round_watch::{Consensus, RoundWatch};
 use crate::engine::{ConsensusConfigExt, NodeConfig};
-use crate::intercom::{Downloader, PeerSchedule};
+use crate::intercom::broadcast::GossipTree;
+use crate::intercom::core::QueryRequest;
+use crate::intercom::{Dispatcher, Downloader, PeerSchedule};
 use crate::models::{Digest, PeerCount, Point, PointId, Round};
 
 #[derive(Clone)]
@@ -21,12 +28,18 @@ pub struct BroadcastFilter {
 }
 
 impl BroadcastFilter {
-    pub fn new(peer_schedule: &PeerSchedule, consensus_round: &RoundWatch<Consensus>) -> Self {
+    pub fn new(
+        dispatcher: &Dispatcher,
+        peer_schedule: &PeerSchedule,
+        consensus_round: &RoundWatch<Consensus>,
+    ) -> Self {
         Self {
             inner: Arc::new(BroadcastFilterInner {
+                dispatcher: dispatcher.clone(),
                 peer_schedule: peer_schedule.clone(),
                 consensus_round: consensus_round.clone(),
                 by_round: Default::default(),
+                relays: Default::default(),
             }),
         }
     }
@@ -68,11 +81,14 @@ impl BroadcastFilter {
 //   I.e. access path will be RWLock<Round>->DashMapLock<PeerId>->ByRound->items.
 //   Signer's search for point @ DagHead.next() will stay the same: first check in BF, then in DAG.
 struct BroadcastFilterInner {
+    dispatcher: Dispatcher,
     peer_schedule: PeerSchedule,
     consensus_round: RoundWatch<Consensus>,
     // very much like DAG structure, but without dependency check;
     // just to determine reliably that consensus advanced without current node
     by_round: FastDashMap<Round, ByRoundItem>,
+    // aborted on drop together with round task state or when their round is flushed to DAG
+    relays: Mutex<Vec<(Round, Task<()>)>>,
 }
 struct ByRoundItem {
     peer_count: PeerCount,
@@ -114,10 +130,14 @@ impl ByAuthorItem {
 }
 #[derive(thiserror::Error, Debug)]
 enum CheckError {
-    #[error("sender {} is not author", .0.alt())]
+    #[error("sender {} is neither author nor gossip parent", .0.alt())]
     SenderNotAuthor(PeerId),
     #[error("failed to verify: {0}")]
     Fail(VerifyFailReason),
+    #[error("author is not scheduled")]
+    UnknownAuthor,
+    #[error("bad signature")]
+    BadSignature,
 }
 
 impl BroadcastFilterInner {
@@ -150,12 +170,30 @@ impl BroadcastFilterInner {
         // head may be outdated during Engine round switch
         let top_round = head.next().round();
 
-        let checked = if sender != author {
+        // relay is accepted from a parent under any fanout, as fanout is node-local;
+        // point must be re-gossiped only if it was received from a parent in the tree
+        let (is_relay, gossip) = {
+            let atomic = self.peer_schedule.atomic();
+            let local_id = atomic.local_id();
+            let peers = atomic.peers_for(round);
+            let is_relay = GossipTree::is_relay(&author, &digest, peers, sender, &local_id);
+            let gossip = GossipTree::for_point(&author, &digest, peers)
+                .filter(|tree| is_relay || tree.parent(&local_id) == Some(sender))
+                .map(|tree| tree.children(&local_id).to_vec());
+            (is_relay, gossip)
+        };
+
+        let checked = if sender != author && !is_relay {
             Err(CheckError::SenderNotAuthor(*sender))
         } else {
             // have to cache every point when the node lags behind consensus
             let prune_after = top_round + NodeConfig::get().cache_future_broadcasts_rounds;
-            match Verifier::verify(point.info(), &self.peer_schedule, round_ctx.conf()) {
+            match Verifier::verify(
+                point.info(),
+                &self.peer_schedule,
+                round_ctx.conf(),
+                VerificationMode::Full,
+            ) {
                 Ok(()) => Ok(if round > prune_after {
                     ByAuthorItem::OkPruned(digest)
                 } else {
@@ -167,10 +205,12 @@ impl BroadcastFilterInner {
                     ByAuthorItem::IllFormed(point.clone(), reason)
                 }),
                 Err(VerifyError::Fail(reason)) => Err(CheckError::Fail(reason)),
+                Err(VerifyError::UnknownAuthor) => Err(CheckError::UnknownAuthor),
+                Err(VerifyError::BadSignature) => Err(CheckError::BadSignature),
             }
         };
 
-        let (is_future_threshold_reached, duplicates, equivocation) = match &checked {
+        let (is_new, (is_future_threshold_reached, duplicates, equivocation)) = match &checked {
             Ok(verified) => {
                 // just don't want to mess with exact type, thus generic
                 enum MapSearch<T> {
@@ -213,6 +253,7 @@ impl BroadcastFilterInner {
                         let round_item = entry_ref.value_mut();
                         // ban the author, if we detect equivocation now; we won't be able to prove it
                         // if some signatures are invalid (it's another reason for a local ban)
+                        let is_new = !round_item.by_author.contains_key(&author);
                         let (duplicates, equivocation) = match round_item.by_author.entry(author) {
                             hash_map::Entry::Occupied(mut existing) => {
                                 let old_digest = *existing.get().item.digest();
@@ -238,21 +279,29 @@ impl BroadcastFilterInner {
                         };
                         let is_future_threshold_reached =
                             round_item.by_author.len() == round_item.peer_count.reliable_minority();
-                        (is_future_threshold_reached, duplicates, equivocation)
+                        let result = (is_future_threshold_reached, duplicates, equivocation);
+                        (is_new, result)
                     }
                     MapSearch::AddToDag => {
                         if let Some(dag_round) = head.next().scan(round) {
                             let iter = std::iter::once((&author, verified));
                             Self::add_all_to_dag(iter, &dag_round, downloader, store, round_ctx);
                         }
-                        (false, None, None)
+                        // dag deduplicates by itself
+                        (true, (false, None, None))
                     }
-                    MapSearch::Ignore => (false, None, None),
+                    MapSearch::Ignore => (false, (false, None, None)),
                 }
             }
-            Err(_) => (false, None, None),
+            Err(_) => (false, (false, None, None)),
         };
 
+        if is_new {
+            if let Some(children) = &gossip {
+                self.relay(round, point, children, round_ctx);
+            }
+        }
+
         let ill_formed_reason = (checked.as_ref().ok()).and_then(|item| item.ill_formed_reason());
         let level = if checked.is_err()
             || ill_formed_reason.is_some()
@@ -275,6 +324,7 @@ impl BroadcastFilterInner {
             duplicates = duplicates,
             equivocation = equivocation.as_ref().map(|digest| display(digest.alt())),
             threshold_reached = Some(is_future_threshold_reached).filter(|x| *x),
+            relayed = gossip.as_ref().map(|children| children.len()),
             "received broadcast"
         );
 
@@ -288,6 +338,25 @@ impl BroadcastFilterInner {
         }
     }
 
+    /// re-gossip the point down the tree; point author will send it directly to peers
+    /// that are not reached because of network errors, so responses are not awaited
+    fn relay(&self, round: Round, point: &Point, children: &[PeerId], round_ctx: &RoundCtx) {
+        if children.is_empty() {
+            return;
+        }
+        metrics::counter!("tycho_mempool_broadcast_relayed_count").increment(children.len() as _);
+        let request = QueryRequest::broadcast(point);
+        let queries = (children.iter())
+            .map(|peer_id| self.dispatcher.query_broadcast(peer_id, &request))
+            .collect::<FuturesUnordered<_>>();
+        let task = round_ctx
+            .task()
+            .spawn(queries.for_each(|_| future::ready(())));
+        let mut relays = self.relays.lock();
+        relays.retain(|(_, task)| !task.is_finished());
+        relays.push((round, task));
+    }
+
     /// just drop unneeded data when Engine is paused and round task is not running
     /// while collator is syncing blocks
     fn clean(&self, round: Round, head: &DagHead, round_ctx: &RoundCtx) {
@@ -343,6 +412,10 @@ impl BroadcastFilterInner {
         let head_prev_round = head.prev().round();
         let head_next_round = head.next().round();
 
+        // relayed points cannot be signed anymore, other peers will download them if needed
+        (self.relays.lock())
+            .retain(|(round, task)| *round >= head_prev_round && !task.is_finished());
+
         // Drain points in historical order that cannot be neither included nor signed,
         // thus out of Signer's interest and needed for validation and commit only.
         // We are unlikely to receive such old broadcasts while node is in sync with others.
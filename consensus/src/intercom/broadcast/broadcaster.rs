@@ -16,6 +16,7 @@ use crate::dag::LastOwnPoint;
 use crate::dyn_event;
 use crate::effects::{AltFormat, BroadcastCtx, Ctx, RoundCtx};
 use crate::intercom::broadcast::collector::CollectorSignal;
+use crate::intercom::broadcast::GossipTree;
 use crate::intercom::core::{BroadcastResponse, QueryRequest, SignatureResponse};
 use crate::intercom::peer_schedule::PeerState;
 use crate::intercom::{Dispatcher, PeerSchedule};
@@ -64,16 +65,32 @@ impl Broadcaster {
         collector_signal: watch::Receiver<CollectorSignal>,
         round_ctx: &RoundCtx,
     ) -> Self {
-        let (signers, bcast_peers, peer_updates) = {
+        let (signers, mut bcast_peers, peer_updates, gossip) = {
             let guard = peer_schedule.read();
             // `atomic` can be updated only under write lock, so view under read lock is consistent
-            let signers = peer_schedule
-                .atomic()
-                .peers_for(point.info().round().next())
-                .clone();
+            let atomic = peer_schedule.atomic();
+            let signers = atomic.peers_for(point.info().round().next()).clone();
+            let gossip = GossipTree::for_point(
+                &point.info().author(),
+                point.info().digest(),
+                atomic.peers_for(point.info().round()),
+            );
             let bcast_peers = guard.data.broadcast_receivers().clone();
-            (signers, bcast_peers, guard.updates())
+            (signers, bcast_peers, guard.updates(), gossip)
         };
+        let mut sig_peers = FastHashSet::default();
+        if let Some(tree) = &gossip {
+            // peers down the tree receive the point from relays, so just ask them for signatures;
+            // the point is sent directly only to those who respond without it
+            let children = tree.children(&point.info().author());
+            bcast_peers.retain(|peer_id| {
+                let is_relayed = tree.contains(peer_id) && !children.contains(peer_id);
+                if is_relayed {
+                    sig_peers.insert(*peer_id);
+                }
+                !is_relayed
+            });
+        }
         let signers_count =
             PeerCount::try_from(signers.len()).expect("validator set for current round is unknown");
 
@@ -96,7 +113,7 @@ impl Broadcaster {
             bcast_futures: FuturesUnordered::default(),
 
             sig_request: QueryRequest::signature(point.info().round()),
-            sig_peers: FastHashSet::default(),
+            sig_peers,
             sig_futures: FuturesUnordered::default(),
 
             point,
@@ -124,6 +141,7 @@ impl Broadcaster {
         tracing::debug!(
             parent: self.ctx.span(),
             current_peers = self.bcast_peers.len(),
+            relayed_peers = self.sig_peers.len(),
             "start",
         );
         for peer in mem::take(&mut self.bcast_peers) {
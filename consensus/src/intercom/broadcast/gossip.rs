@@ -0,0 +1,206 @@
+use tycho_network::PeerId;
+use tycho_util::FastHashSet;
+
+use crate::engine::NodeConfig;
+use crate::models::Digest;
+
+/// Alternative to full broadcast: point author pushes the point to a few peers,
+/// that re-gossip it to a few more peers, and so on.
+///
+/// Peers are arranged into a `fanout`-ary tree unique for every point, so every peer
+/// receives the point exactly once from its parent if all peers are honest.
+/// The order of peers is computed independently by every peer from the point's validator set,
+/// so a relayed broadcast can be checked to come from a parent in the tree.
+/// Fanout is node-local, thus peers may arrange the same order into trees of different arity.
+///
+/// Peers that were not reached because of a faulty relay are covered by author's
+/// signature requests: `NoPoint` response leads to a direct broadcast.
+pub struct GossipTree {
+    // author is the root; a peer at `i` is a parent of `[i * fanout + 1 ..= i * fanout + fanout]`
+    order: Vec<PeerId>,
+    fanout: usize,
+}
+
+impl GossipTree {
+    /// `None` if gossip is disabled by node config or the validator set is small enough
+    /// for the author to broadcast directly to every peer
+    pub fn for_point(
+        author: &PeerId,
+        digest: &Digest,
+        peers: &FastHashSet<PeerId>,
+    ) -> Option<Self> {
+        let fanout = NodeConfig::get().broadcast_fanout?.get() as usize;
+        // tree has a single level: no need to re-gossip
+        if peers.len() <= fanout + 1 {
+            return None;
+        }
+        Some(Self::new(author, digest, peers, fanout))
+    }
+
+    /// `true` if the sender is a parent of the receiver under any allowed fanout, because
+    /// the sender builds its tree with its own fanout; author is not a relay for own point
+    pub fn is_relay(
+        author: &PeerId,
+        digest: &Digest,
+        peers: &FastHashSet<PeerId>,
+        sender: &PeerId,
+        receiver: &PeerId,
+    ) -> bool {
+        if sender == author || peers.len() < 3 {
+            return false;
+        }
+        let tree = Self::new(author, digest, peers, 1);
+        let (Some(sender), Some(receiver)) = (tree.position(sender), tree.position(receiver))
+        else {
+            return false;
+        };
+        if sender == 0 || receiver <= sender {
+            return false;
+        }
+        // parent of `receiver` is at `(receiver - 1) / fanout`, and tree has at least two levels
+        let max_fanout = (peers.len() - 2).min(u8::MAX as usize);
+        let min_fanout = (receiver - 1) / (sender + 1) + 1;
+        min_fanout <= ((receiver - 1) / sender).min(max_fanout)
+    }
+
+    fn new(author: &PeerId, digest: &Digest, peers: &FastHashSet<PeerId>, fanout: usize) -> Self {
+        // order must not be predictable before point is created, so it depends on the digest
+        let mut others = (peers.iter())
+            .filter(|peer_id| *peer_id != author)
+            .map(|peer_id| {
+                let mut hasher = blake3::Hasher::new();
+                hasher.update(digest.inner());
+                hasher.update(peer_id.as_bytes());
+                (<[u8; 32]>::from(hasher.finalize()), *peer_id)
+            })
+            .collect::<Vec<_>>();
+        others.sort_unstable();
+
+        let mut order = Vec::with_capacity(others.len() + 1);
+        order.push(*author);
+        order.extend(others.into_iter().map(|(_, peer_id)| peer_id));
+
+        Self { order, fanout }
+    }
+
+    pub fn contains(&self, peer_id: &PeerId) -> bool {
+        self.position(peer_id).is_some()
+    }
+
+    /// the only peer (besides author) allowed to send the point to the given peer
+    pub fn parent(&self, peer_id: &PeerId) -> Option<&PeerId> {
+        match self.position(peer_id)? {
+            0 => None,
+            pos => Some(&self.order[(pos - 1) / self.fanout]),
+        }
+    }
+
+    /// peers to send the point to after the given one received it
+    pub fn children(&self, peer_id: &PeerId) -> &[PeerId] {
+        let Some(pos) = self.position(peer_id) else {
+            return &[];
+        };
+        let first = (pos * self.fanout + 1).min(self.order.len());
+        let last = (first + self.fanout).min(self.order.len());
+        &self.order[first..last]
+    }
+
+    fn position(&self, peer_id: &PeerId) -> Option<usize> {
+        self.order.iter().position(|p| p == peer_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use super::*;
+
+    fn peers(count: u8) -> FastHashSet<PeerId> {
+        (0..count).map(|i| PeerId([i; 32])).collect()
+    }
+
+    /// peers reached by tree relays only, skipping the ones that do not re-gossip
+    fn simulate(tree: &GossipTree, author: &PeerId, faulty: &FastHashSet<PeerId>) -> Vec<PeerId> {
+        let mut received = vec![*author];
+        let mut queue = VecDeque::from([*author]);
+        while let Some(sender) = queue.pop_front() {
+            if faulty.contains(&sender) {
+                continue;
+            }
+            for child in tree.children(&sender) {
+                assert_eq!(tree.parent(child), Some(&sender), "tree is inconsistent");
+                assert!(!received.contains(child), "received point twice");
+                received.push(*child);
+                queue.push_back(*child);
+            }
+        }
+        received
+    }
+
+    #[test]
+    fn all_honest_peers_receive_gossip() {
+        let peers = peers(100);
+        let author = PeerId([7; 32]);
+        let digest = Digest::wrap([42; 32]);
+        let tree = GossipTree::new(&author, &digest, &peers, 3);
+
+        // every peer receives the point and sends it to at most `fanout` peers
+        let received = simulate(&tree, &author, &Default::default());
+        assert_eq!(received.len(), peers.len());
+        assert!(peers.iter().all(|p| tree.children(p).len() <= 3));
+        assert_eq!(tree.parent(&author), None);
+
+        // peers behind faulty relays are reached by author directly after `NoPoint`,
+        // which still costs less than a full broadcast
+        let faulty = tree.children(&author)[..2].iter().copied().collect();
+        let received = simulate(&tree, &author, &faulty);
+        let missed = peers.iter().filter(|p| !received.contains(p)).count();
+        assert!(missed > 0);
+        assert!(tree.children(&author).len() + missed < peers.len() - 1);
+
+        // every point has its own tree
+        let other = GossipTree::new(&author, &Digest::wrap([43; 32]), &peers, 3);
+        assert_ne!(tree.children(&author), other.children(&author));
+    }
+
+    #[test]
+    fn relays_accepted_under_any_fanout() {
+        let peers = peers(50);
+        let author = PeerId([7; 32]);
+        let digest = Digest::wrap([42; 32]);
+
+        // peers with different fanouts build different trees from the same order
+        for fanout in [1, 2, 3, 5, 48] {
+            let tree = GossipTree::new(&author, &digest, &peers, fanout);
+            for receiver in &peers {
+                let Some(parent) = tree.parent(receiver) else {
+                    continue;
+                };
+                let is_relay = GossipTree::is_relay(&author, &digest, &peers, parent, receiver);
+                assert_eq!(is_relay, parent != &author, "fanout {fanout}");
+                // relay never goes up the tree
+                assert!(!GossipTree::is_relay(
+                    &author, &digest, &peers, receiver, parent
+                ));
+            }
+        }
+
+        // the last peer in order cannot be a parent under any fanout
+        let tree = GossipTree::new(&author, &digest, &peers, 1);
+        let last = tree.order.last().unwrap();
+        assert!(peers
+            .iter()
+            .all(|receiver| !GossipTree::is_relay(&author, &digest, &peers, last, receiver)));
+
+        // peers not in validator set are never relays
+        let stranger = PeerId([200; 32]);
+        assert!(!GossipTree::is_relay(
+            &author,
+            &digest,
+            &peers,
+            &stranger,
+            &tree.order[1]
+        ));
+    }
+}
@@ -1,9 +1,11 @@
 pub use broadcast_filter::*;
 pub use broadcaster::*;
 pub use collector::*;
+pub use gossip::*;
 pub(super) use signer::*;
 
 mod broadcast_filter;
 mod broadcaster;
 mod collector;
+mod gossip;
 mod signer;
@@ -1,34 +1,56 @@
+use std::sync::Arc;
+use std::time::Duration;
+
 use futures_util::future::BoxFuture;
-use tycho_network::{Network, PeerId, PrivateOverlay, Request};
+use tycho_network::{Network, PeerId, Priority, PrivateOverlay, Request};
 use tycho_util::metrics::HistogramGuard;
 
 use crate::intercom::core::{
-    BroadcastResponse, PointByIdResponse, QueryResponse, SignatureResponse,
+    BroadcastResponse, PointByIdRequests, PointByIdResponse, QueryRequestTag, QueryResponse,
+    SignatureResponse,
 };
-use crate::models::{Point, PointIntegrityError};
+use crate::models::{Point, PointId, PointIntegrityError};
 
 #[derive(Clone)]
 pub struct Dispatcher {
     overlay: PrivateOverlay,
     network: Network,
+    point_by_id_requests: Arc<PointByIdRequests>,
 }
 
 pub type PointQueryResult = anyhow::Result<PointByIdResponse<Result<Point, PointIntegrityError>>>;
 
+/// Peer did not respond in time, distinct from other network errors in [`PointQueryResult`]
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("query timed out after {} ms", .0.as_millis())]
+pub struct QueryTimeoutError(pub Duration);
+
 impl Dispatcher {
     pub fn new(network: &Network, private_overlay: &PrivateOverlay) -> Self {
         Self {
             overlay: private_overlay.clone(),
             network: network.clone(),
+            point_by_id_requests: Arc::new(PointByIdRequests::new(
+                Self::POINT_BY_ID_REQUESTS_CAPACITY,
+            )),
         }
     }
 
+    /// enough to cover a few rounds of points downloaded concurrently by every peer
+    const POINT_BY_ID_REQUESTS_CAPACITY: usize = 4096;
+
+    pub fn point_by_id_request(&self, id: &PointId) -> Request {
+        self.point_by_id_requests.get(id)
+    }
+
     pub fn query_broadcast(
         &self,
         peer_id: &PeerId,
         request: &Request,
     ) -> BoxFuture<'static, (PeerId, anyhow::Result<BroadcastResponse>)> {
         let peer_id = *peer_id;
+        let tag = QueryRequestTag::Broadcast;
+        Self::meter_query(tag);
         let metric = HistogramGuard::begin("tycho_mempool_broadcast_query_dispatcher_time");
         let overlay = self.overlay.clone();
         let network = self.network.clone();
@@ -37,11 +59,20 @@ impl Dispatcher {
 
         let future = async move {
             let _task_duration = metric;
-            let response = match overlay.query(&network, &peer_id, request).await {
+            let response = match overlay
+                .query_with_priority(&network, &peer_id, request, Priority::High)
+                .await
+            {
                 Ok(response) => response,
-                Err(e) => return (peer_id, Err(e)),
+                Err(e) => {
+                    Self::meter_error(tag);
+                    return (peer_id, Err(e));
+                }
             };
             let result = QueryResponse::parse_broadcast(&response);
+            if result.is_err() {
+                Self::meter_error(tag);
+            }
             (peer_id, result.map_err(Into::into))
         };
         Box::pin(future)
@@ -54,6 +85,8 @@ impl Dispatcher {
         request: &Request,
     ) -> BoxFuture<'static, (PeerId, bool, anyhow::Result<SignatureResponse>)> {
         let peer_id = *peer_id;
+        let tag = QueryRequestTag::Signature;
+        Self::meter_query(tag);
         let metric = HistogramGuard::begin("tycho_mempool_signature_query_dispatcher_time");
         let overlay = self.overlay.clone();
         let network = self.network.clone();
@@ -62,11 +95,20 @@ impl Dispatcher {
 
         let future = async move {
             let _task_duration = metric;
-            let response = match overlay.query(&network, &peer_id, request).await {
+            let response = match overlay
+                .query_with_priority(&network, &peer_id, request, Priority::High)
+                .await
+            {
                 Ok(response) => response,
-                Err(e) => return (peer_id, after_bcast, Err(e)),
+                Err(e) => {
+                    Self::meter_error(tag);
+                    return (peer_id, after_bcast, Err(e));
+                }
             };
             let result = QueryResponse::parse_signature(&response);
+            if result.is_err() {
+                Self::meter_error(tag);
+            }
             (peer_id, after_bcast, result.map_err(Into::into))
         };
         Box::pin(future)
@@ -76,8 +118,29 @@ impl Dispatcher {
         &self,
         peer_id: &PeerId,
         request: &Request,
+    ) -> BoxFuture<'static, (PeerId, PointQueryResult)> {
+        self.query_point_impl(peer_id, request, None)
+    }
+
+    /// Overlay query is dropped after the `timeout` and results in [`QueryTimeoutError`]
+    pub fn query_point_with_timeout(
+        &self,
+        peer_id: &PeerId,
+        request: &Request,
+        timeout: Duration,
+    ) -> BoxFuture<'static, (PeerId, PointQueryResult)> {
+        self.query_point_impl(peer_id, request, Some(timeout))
+    }
+
+    fn query_point_impl(
+        &self,
+        peer_id: &PeerId,
+        request: &Request,
+        timeout: Option<Duration>,
     ) -> BoxFuture<'static, (PeerId, PointQueryResult)> {
         let peer_id = *peer_id;
+        let tag = QueryRequestTag::PointById;
+        Self::meter_query(tag);
         let metric = HistogramGuard::begin("tycho_mempool_download_query_dispatcher_time");
         let overlay = self.overlay.clone();
         let network = self.network.clone();
@@ -86,13 +149,119 @@ impl Dispatcher {
 
         let future = async move {
             let _task_duration = metric;
-            let response = match overlay.query(&network, &peer_id, request).await {
+            let query = overlay.query(&network, &peer_id, request);
+            let response = match timeout {
+                None => query.await,
+                Some(timeout) => match tokio::time::timeout(timeout, query).await {
+                    Ok(result) => result,
+                    Err(_elapsed) => Err(QueryTimeoutError(timeout).into()),
+                },
+            };
+            let response = match response {
                 Ok(response) => response,
-                Err(e) => return (peer_id, Err(e)),
+                Err(e) => {
+                    Self::meter_error(tag);
+                    return (peer_id, Err(e));
+                }
             };
             let result = QueryResponse::parse_point_by_id(response).await;
+            if result.is_err() {
+                Self::meter_error(tag);
+            }
             (peer_id, result.map_err(Into::into))
         };
         Box::pin(future)
     }
+
+    const KIND: &'static str = "kind";
+
+    fn meter_query(tag: QueryRequestTag) {
+        metrics::counter!("tycho_mempool_dispatcher_queries_count", Self::KIND => tag.label())
+            .increment(1);
+    }
+
+    fn meter_error(tag: QueryRequestTag) {
+        metrics::counter!("tycho_mempool_dispatcher_query_errors_count", Self::KIND => tag.label())
+            .increment(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tycho_network::{OverlayId, PeerInfo, Router};
+    use tycho_util::test::TestRecorder;
+
+    use super::*;
+    use crate::intercom::Responder;
+    use crate::models::{Digest, Round};
+
+    #[tokio::test]
+    async fn point_by_id_query_is_labeled() {
+        let network = Network::builder()
+            .with_random_private_key()
+            .build("127.0.0.1:0", Router::builder().build())
+            .expect("network with unused stub socket");
+        let private_overlay =
+            PrivateOverlay::builder(*OverlayId::wrap(&[0; 32])).build(Responder::default());
+        let dispatcher = Dispatcher::new(&network, &private_overlay);
+
+        let point_id = PointId {
+            author: PeerId([1; 32]),
+            round: Round(1),
+            digest: Digest::wrap([2; 32]),
+        };
+        let request = dispatcher.point_by_id_request(&point_id);
+
+        let recorder = TestRecorder::default();
+        let _query = metrics::with_local_recorder(&recorder, || {
+            dispatcher.query_point(&point_id.author, &request)
+        });
+
+        let kinds =
+            recorder.label_values("tycho_mempool_dispatcher_queries_count", Dispatcher::KIND);
+        assert_eq!(kinds, ["point_by_id"]);
+    }
+
+    #[tokio::test]
+    async fn point_by_id_query_times_out() -> anyhow::Result<()> {
+        let network = Network::builder()
+            .with_random_private_key()
+            .build("127.0.0.1:0", Router::builder().build())?;
+        let private_overlay =
+            PrivateOverlay::builder(*OverlayId::wrap(&[0; 32])).build(Responder::default());
+        let dispatcher = Dispatcher::new(&network, &private_overlay);
+
+        // Packets sent to this address are never answered, so connection cannot be established
+        let black_hole = std::net::UdpSocket::bind("127.0.0.1:0")?;
+        let peer_id = PeerId([1; 32]);
+        let _handle = network.known_peers().insert(
+            Arc::new(PeerInfo {
+                id: peer_id,
+                address_list: vec![black_hole.local_addr()?.into()].into_boxed_slice(),
+                created_at: 0,
+                expires_at: u32::MAX,
+                signature: Box::new([0; 64]),
+            }),
+            false,
+        )?;
+
+        let point_id = PointId {
+            author: peer_id,
+            round: Round(1),
+            digest: Digest::wrap([2; 32]),
+        };
+        let request = dispatcher.point_by_id_request(&point_id);
+
+        let timeout = Duration::from_millis(100);
+        let (_, result) = dispatcher
+            .query_point_with_timeout(&peer_id, &request, timeout)
+            .await;
+        let error = result.expect_err("black hole must not respond");
+        assert_eq!(
+            error.downcast_ref::<QueryTimeoutError>(),
+            Some(&QueryTimeoutError(timeout))
+        );
+
+        Ok(())
+    }
 }
@@ -1,4 +1,5 @@
 use bytes::{Buf, Bytes};
+use quick_cache::sync::Cache;
 use tl_proto::{RawBytes, TlError, TlRead, TlWrite};
 use tycho_network::Request;
 use tycho_util::sync::rayon_run_fifo;
@@ -16,6 +17,17 @@ pub enum QueryRequestTag {
     Signature,
 }
 
+impl QueryRequestTag {
+    /// metric label value
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Broadcast => "broadcast",
+            Self::PointById => "point_by_id",
+            Self::Signature => "signature",
+        }
+    }
+}
+
 pub enum QueryRequest {
     Broadcast(Point),
     PointById(PointId),
@@ -45,6 +57,25 @@ impl QueryRequest {
     }
 }
 
+/// Bounded cache of encoded requests: the same point is often downloaded by many tasks
+/// (i.e. during sync), so the request is encoded once and then its body is shared
+pub struct PointByIdRequests(Cache<PointId, Request>);
+
+impl PointByIdRequests {
+    pub fn new(capacity: usize) -> Self {
+        Self(Cache::new(capacity))
+    }
+
+    pub fn get(&self, id: &PointId) -> Request {
+        if let Some(request) = self.0.get(id) {
+            return request;
+        }
+        let request = QueryRequest::point_by_id(id);
+        self.0.insert(*id, request.clone());
+        request
+    }
+}
+
 #[derive(TlWrite, Debug)]
 #[tl(boxed, id = "intercom.queryRequest", scheme = "proto.tl")]
 struct QueryRequestWrite<'a, T> {
@@ -56,6 +56,9 @@ pub enum PointByIdResponse<T> {
     DefinedNone,
     #[tl(id = "intercom.pointByIdResponse.tryLater")]
     TryLater,
+    /// same as [`Self::TryLater`] but with a hint when the peer expects to be ready, in millis
+    #[tl(id = "intercom.pointByIdResponse.tryLaterAfter")]
+    TryLaterAfter(u32),
 }
 
 pub struct QueryResponse;
@@ -97,7 +100,9 @@ impl QueryResponse {
             PointByIdResponse::Defined(_) => {
                 metrics::histogram!("tycho_mempool_download_query_responder_some_time")
             }
-            PointByIdResponse::DefinedNone | PointByIdResponse::TryLater => {
+            PointByIdResponse::DefinedNone
+            | PointByIdResponse::TryLater
+            | PointByIdResponse::TryLaterAfter(_) => {
                 metrics::histogram!("tycho_mempool_download_query_responder_none_time")
             }
         };
@@ -120,6 +125,7 @@ impl QueryResponse {
             }
             PointByIdResponse::DefinedNone => PointByIdResponse::DefinedNone,
             PointByIdResponse::TryLater => PointByIdResponse::TryLater,
+            PointByIdResponse::TryLaterAfter(millis) => PointByIdResponse::TryLaterAfter(millis),
         })
     }
 }
@@ -143,6 +149,7 @@ impl<T: AsRef<[u8]>> Display for AltFmt<'_, PointByIdResponse<T>> {
             PointByIdResponse::Defined(_) => f.write_str("Some"),
             PointByIdResponse::DefinedNone => f.write_str("None"),
             PointByIdResponse::TryLater => f.write_str("TryLater"),
+            PointByIdResponse::TryLaterAfter(millis) => write!(f, "TryLaterAfter({millis}ms)"),
         }
     }
 }
@@ -4,7 +4,7 @@ use std::time::Instant;
 use arc_swap::ArcSwapOption;
 use futures_util::future::BoxFuture;
 use futures_util::{future, FutureExt};
-use tycho_network::{Response, Service, ServiceRequest};
+use tycho_network::{Priority, Response, Service, ServiceRequest};
 
 use crate::dag::DagHead;
 use crate::effects::{AltFormat, MempoolStore, RoundCtx};
@@ -74,6 +74,9 @@ impl Responder {
     async fn handle_query(self, req: ServiceRequest) -> Option<Response> {
         let task_start = Instant::now();
 
+        // Queries are sent with a high priority by the dispatcher, so are the responses
+        req.response_priority.set(Priority::High);
+
         let raw_query = match QueryRequestRaw::new(req.body) {
             Ok(wrapper) => wrapper,
             Err(error) => {
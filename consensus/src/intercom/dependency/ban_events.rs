@@ -0,0 +1,58 @@
+use tokio::sync::broadcast;
+use tycho_network::PeerId;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BanReason {
+    /// responded with a point that cannot be parsed or fails integrity check
+    Unparsable,
+    /// responded with a point which id differs from the requested one
+    WrongPoint,
+    /// responded with a point that has invalid author's signature
+    BadSignature,
+}
+
+impl BanReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Unparsable => "unparsable",
+            Self::WrongPoint => "wrong_point",
+            Self::BadSignature => "bad_signature",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BanEvent {
+    pub peer_id: PeerId,
+    pub reason: BanReason,
+}
+
+/// Reports misbehaving peers to subscribers, that may act on network layer (e.g. disconnect).
+/// Shared between engine restarts, so subscriptions stay valid.
+#[derive(Clone)]
+pub struct BanEvents(broadcast::Sender<BanEvent>);
+
+impl Default for BanEvents {
+    fn default() -> Self {
+        Self(broadcast::Sender::new(Self::CAPACITY))
+    }
+}
+
+impl BanEvents {
+    const CAPACITY: usize = 1024;
+
+    pub fn subscribe(&self) -> broadcast::Receiver<BanEvent> {
+        self.0.subscribe()
+    }
+
+    pub fn send(&self, peer_id: &PeerId, reason: BanReason) {
+        metrics::counter!("tycho_mempool_ban_events", "reason" => reason.as_str()).increment(1);
+        // no subscribers is not an error
+        self.0
+            .send(BanEvent {
+                peer_id: *peer_id,
+                reason,
+            })
+            .ok();
+    }
+}
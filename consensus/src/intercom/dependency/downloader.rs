@@ -1,7 +1,7 @@
 use std::iter;
 use std::marker::PhantomData;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use futures_util::future::BoxFuture;
 use futures_util::stream::FuturesUnordered;
@@ -15,15 +15,17 @@ use tycho_network::{PeerId, Request};
 use tycho_util::metrics::HistogramGuard;
 use tycho_util::FastHashMap;
 
-use crate::dag::{IllFormedReason, Verifier, VerifyError};
+use crate::dag::{IllFormedReason, VerificationMode, Verifier, VerifyError};
 use crate::effects::{AltFormat, Ctx, DownloadCtx};
 use crate::engine::round_watch::{Consensus, RoundWatcher};
-use crate::engine::{ConsensusConfigExt, MempoolConfig};
-use crate::intercom::core::{PointByIdResponse, PointQueryResult, QueryRequest};
+use crate::engine::{ConsensusConfigExt, MempoolConfig, NodeConfig};
+use crate::intercom::core::{PointByIdResponse, PointQueryResult, QueryTimeoutError};
 use crate::intercom::dependency::limiter::Limiter;
+use crate::intercom::dependency::rejection_log::RejectionLogLimiter;
+use crate::intercom::dependency::{BanEvents, BanReason};
 use crate::intercom::peer_schedule::PeerState;
 use crate::intercom::{Dispatcher, PeerSchedule};
-use crate::models::{PeerCount, Point, PointId};
+use crate::models::{NotFoundQuorum, PeerCount, PeerWeights, Point, PointId};
 
 #[derive(Clone)]
 pub struct Downloader {
@@ -40,6 +42,8 @@ struct DownloaderInner {
     peer_schedule: PeerSchedule,
     limiter: Limiter,
     consensus_round: RoundWatcher<Consensus>,
+    ban_events: BanEvents,
+    rejection_logs: RejectionLogLimiter,
 }
 
 trait DownloadType: Send + 'static {
@@ -79,6 +83,24 @@ struct PeerStatus {
     is_depender: bool,
     /// has uncompleted request just now
     is_in_flight: bool,
+    /// peer asked not to query it until this moment
+    retry_after: Option<Instant>,
+}
+
+impl PeerStatus {
+    /// peer hint is capped, so a misbehaving peer cannot exclude itself for long
+    const MAX_TRY_LATER_DELAY: Duration = Duration::from_secs(10);
+
+    fn try_later(&mut self, delay: Option<Duration>) {
+        self.is_in_flight = false;
+        // apply the same retry strategy as for network errors
+        self.failed_queries = self.failed_queries.saturating_add(1);
+        self.retry_after = delay.map(|delay| Instant::now() + delay.min(Self::MAX_TRY_LATER_DELAY));
+    }
+
+    fn is_ready(&self, now: Instant) -> bool {
+        !self.is_in_flight && self.retry_after.is_none_or(|after| after <= now)
+    }
 }
 
 impl Downloader {
@@ -86,6 +108,7 @@ impl Downloader {
         dispatcher: &Dispatcher,
         peer_schedule: &PeerSchedule,
         consensus_round: RoundWatcher<Consensus>,
+        ban_events: &BanEvents,
     ) -> Self {
         Self {
             inner: Arc::new(DownloaderInner {
@@ -93,11 +116,21 @@ impl Downloader {
                 peer_schedule: peer_schedule.clone(),
                 limiter: Default::default(),
                 consensus_round,
+                ban_events: ban_events.clone(),
+                rejection_logs: RejectionLogLimiter::new(
+                    NodeConfig::get().max_rejection_logs_per_peer,
+                ),
             }),
         }
     }
 
-    /// 2F "point not found" responses lead to invalidation of all referencing points;
+    /// Rejections caused by the same peer are logged within a configured limit per second
+    pub fn should_log_rejection(&self, peer_id: &PeerId) -> bool {
+        self.inner.rejection_logs.should_log(peer_id)
+    }
+
+    /// 2F "point not found" responses, also backed by 2/3 of their stake when peers are weighted,
+    /// lead to invalidation of all referencing points;
     /// failed network queries are retried after all peers were queried the same amount of times,
     /// and only successful responses that point is not found are taken into account.
     ///
@@ -132,6 +165,7 @@ impl Downloader {
         ctx: DownloadCtx,
     ) -> Option<DownloadResult> {
         let _task_duration = HistogramGuard::begin("tycho_mempool_download_task_time");
+        let task_start = Instant::now();
         ctx.meter_start(point_id);
         let span_guard = ctx.span().enter();
         // request point from its signers (any depender is among them as point is already verified)
@@ -143,6 +177,15 @@ impl Downloader {
         };
         let peer_count = PeerCount::try_from(undone_peers.len())
             .expect("validator set is unknown, must keep prev epoch's set for sync");
+        let (weights, weight_threshold) = {
+            let atomic = self.inner.peer_schedule.atomic();
+            let weights = atomic.weights_for(point_id.round.next());
+            match weights.majority_of_others(peer_count, undone_peers.keys(), &atomic.local_id()) {
+                Some(threshold) => (weights.clone(), threshold),
+                // zero threshold would end the download at once: count peers equally
+                None => (Default::default(), peer_count.majority_of_others() as u64),
+            }
+        };
         let undone_peers = undone_peers
             .iter()
             // query author no matter if it is scheduled for the next round or not;
@@ -155,6 +198,7 @@ impl Downloader {
                     failed_queries: 0,
                     is_depender: false, // `true` comes from channel to start immediate download
                     is_in_flight: false,
+                    retry_after: None,
                 };
                 (*peer_id, status)
             })
@@ -166,14 +210,16 @@ impl Downloader {
             parent: self.clone(),
             _phantom: PhantomData,
             ctx,
-            request: QueryRequest::point_by_id(point_id),
+            request: self.inner.dispatcher.point_by_id_request(point_id),
             point_id: *point_id,
-            peer_count,
-            not_found: 0, // this node is +1 to 2F
+            weights,
+            // this node is +1 to 2F
+            not_found: NotFoundQuorum::new(peer_count, weight_threshold),
             updates,
             undone_peers,
             downloading: FuturesUnordered::new(),
             attempt: 0,
+            verified_from_depender: None,
         };
         let span = task.ctx.span().clone();
         let downloaded = task
@@ -182,6 +228,9 @@ impl Downloader {
             .await;
 
         DownloadCtx::meter_task::<T>(&task);
+        if let Some(is_depender) = task.verified_from_depender {
+            DownloadCtx::meter_downloaded(task_start, is_depender);
+        }
 
         if downloaded.is_none() {
             tracing::warn!(
@@ -202,8 +251,8 @@ struct DownloadTask<T> {
     request: Request,
     point_id: PointId,
 
-    peer_count: PeerCount,
-    not_found: u8, // count only responses considered reliable
+    weights: Arc<PeerWeights>,
+    not_found: NotFoundQuorum, // only responses considered reliable
 
     updates: broadcast::Receiver<(PeerId, PeerState)>,
 
@@ -211,6 +260,8 @@ struct DownloadTask<T> {
     downloading: FuturesUnordered<BoxFuture<'static, (PeerId, PointQueryResult)>>,
 
     attempt: u8,
+    /// set only if point is downloaded (not received by broadcast) and verified
+    verified_from_depender: Option<bool>,
 }
 
 impl<T: DownloadType> DownloadTask<T> {
@@ -239,7 +290,7 @@ impl<T: DownloadType> DownloadTask<T> {
                 Some((peer_id, result)) = self.downloading.next() =>
                     match self.verify(&peer_id, result) {
                         Some(found) => break Some(found),
-                        None => if self.not_found as usize >= self.peer_count.majority_of_others() {
+                        None => if self.not_found.is_reached() {
                             break None;
                         } else if self.downloading.is_empty() {
                             interval.reset_immediately(); // restart interval and tick immediately
@@ -263,10 +314,11 @@ impl<T: DownloadType> DownloadTask<T> {
     }
 
     fn download_random(&mut self) {
+        let now = Instant::now();
         let mut filtered = self
             .undone_peers
             .iter()
-            .filter(|(_, p)| p.state == PeerState::Resolved && !p.is_in_flight)
+            .filter(|(_, p)| p.state == PeerState::Resolved && p.is_ready(now))
             .map(|(peer_id, status)| {
                 (
                     *peer_id,
@@ -305,12 +357,13 @@ impl<T: DownloadType> DownloadTask<T> {
         );
         status.is_in_flight = true;
 
-        self.downloading.push(
-            self.parent
-                .inner
-                .dispatcher
-                .query_point(peer_id, &self.request),
-        );
+        let dispatcher = &self.parent.inner.dispatcher;
+        let is_author = *peer_id == self.point_id.author;
+        let query = match NodeConfig::get().download_query_timeout(is_author) {
+            Some(timeout) => dispatcher.query_point_with_timeout(peer_id, &self.request, timeout),
+            None => dispatcher.query_point(peer_id, &self.request),
+        };
+        self.downloading.push(query);
     }
 
     fn verify(&mut self, peer_id: &PeerId, result: PointQueryResult) -> Option<DownloadResult> {
@@ -322,24 +375,42 @@ impl<T: DownloadType> DownloadTask<T> {
                     let status = self.undone_peers.get_mut(peer_id).unwrap_or_else(|| {
                         panic!("Coding error: peer not in map {}", peer_id.alt())
                     });
-                    status.is_in_flight = false;
-                    // apply the same retry strategy as for network errors
-                    status.failed_queries = status.failed_queries.saturating_add(1);
+                    status.try_later(None);
                     tracing::trace!(peer = display(peer_id.alt()), "try later");
                     return None;
                 }
+                Ok(PointByIdResponse::TryLaterAfter(millis)) => {
+                    let status = self.undone_peers.get_mut(peer_id).unwrap_or_else(|| {
+                        panic!("Coding error: peer not in map {}", peer_id.alt())
+                    });
+                    status.try_later(Some(Duration::from_millis(millis as _)));
+                    tracing::trace!(peer = display(peer_id.alt()), millis, "try later");
+                    return None;
+                }
                 Err(network_err) => {
                     let status = self.undone_peers.get_mut(peer_id).unwrap_or_else(|| {
                         panic!("Coding error: peer not in map {}", peer_id.alt())
                     });
                     status.is_in_flight = false;
                     status.failed_queries = status.failed_queries.saturating_add(1);
-                    metrics::counter!("tycho_mempool_download_query_failed_count").increment(1);
-                    tracing::warn!(
-                        peer = display(peer_id.alt()),
-                        error = display(network_err),
-                        "network error",
-                    );
+                    if network_err.is::<QueryTimeoutError>() {
+                        metrics::counter!("tycho_mempool_download_query_timeout_count")
+                            .increment(1);
+                        tracing::debug!(
+                            peer = display(peer_id.alt()),
+                            error = display(network_err),
+                            "timeout",
+                        );
+                    } else {
+                        metrics::counter!("tycho_mempool_download_query_failed_count").increment(1);
+                        if self.parent.inner.rejection_logs.should_log(peer_id) {
+                            tracing::warn!(
+                                peer = display(peer_id.alt()),
+                                error = display(network_err),
+                                "network error",
+                            );
+                        }
+                    }
                     return None;
                 }
             };
@@ -355,7 +426,7 @@ impl<T: DownloadType> DownloadTask<T> {
 
         match defined_response {
             None => {
-                self.not_found = self.not_found.saturating_add(1);
+                self.not_found.add(self.weights.weight(peer_id));
                 DownloadCtx::meter_not_found();
                 tracing::debug!(
                     peer = display(peer_id.alt()),
@@ -366,26 +437,32 @@ impl<T: DownloadType> DownloadTask<T> {
             }
             Some(Err(parse_error)) => {
                 // reliable peer won't return unverifiable point
-                self.not_found = self.not_found.saturating_add(1);
+                self.not_found.add(self.weights.weight(peer_id));
                 DownloadCtx::meter_unreliable();
-                tracing::error!(
-                    result = display(parse_error),
-                    peer = display(peer_id.alt()),
-                    "downloaded",
-                );
+                (self.parent.inner.ban_events).send(peer_id, BanReason::Unparsable);
+                if self.parent.inner.rejection_logs.should_log(peer_id) {
+                    tracing::error!(
+                        result = display(parse_error),
+                        peer = display(peer_id.alt()),
+                        "downloaded",
+                    );
+                }
                 None
             }
             Some(Ok(point)) if point.info().id() != self.point_id => {
                 // it's a ban
-                self.not_found = self.not_found.saturating_add(1);
+                self.not_found.add(self.weights.weight(peer_id));
                 DownloadCtx::meter_unreliable();
-                tracing::error!(
-                    peer_id = display(peer_id.alt()),
-                    author = display(point.info().author().alt()),
-                    round = point.info().round().0,
-                    digest = display(point.info().digest().alt()),
-                    "returned wrong point",
-                );
+                (self.parent.inner.ban_events).send(peer_id, BanReason::WrongPoint);
+                if self.parent.inner.rejection_logs.should_log(peer_id) {
+                    tracing::error!(
+                        peer_id = display(peer_id.alt()),
+                        author = display(point.info().author().alt()),
+                        round = point.info().round().0,
+                        digest = display(point.info().digest().alt()),
+                        "returned wrong point",
+                    );
+                }
                 None
             }
             Some(Ok(point)) => {
@@ -393,16 +470,50 @@ impl<T: DownloadType> DownloadTask<T> {
                     point.info(),
                     &self.parent.inner.peer_schedule,
                     self.ctx.conf(),
+                    VerificationMode::Full,
                 ) {
-                    Ok(()) => Some(DownloadResult::Verified(point)), // `Some` breaks outer loop
+                    Ok(()) => {
+                        self.verified_from_depender = Some(status.is_depender);
+                        Some(DownloadResult::Verified(point)) // `Some` breaks outer loop
+                    }
                     Err(VerifyError::IllFormed(reason)) => {
-                        tracing::error!(
-                            error = display(&reason),
-                            point = debug(&point),
-                            "downloaded ill-formed"
-                        );
+                        if self.parent.inner.rejection_logs.should_log(peer_id) {
+                            tracing::error!(
+                                error = display(&reason),
+                                point = debug(&point),
+                                "downloaded ill-formed"
+                            );
+                        }
                         Some(DownloadResult::IllFormed(point, reason))
                     }
+                    Err(VerifyError::UnknownAuthor) => {
+                        // not a ban: local peer schedule may be outdated, so it's just not found
+                        self.not_found.add(self.weights.weight(peer_id));
+                        DownloadCtx::meter_not_found();
+                        if self.parent.inner.rejection_logs.should_log(peer_id) {
+                            tracing::warn!(
+                                peer = display(peer_id.alt()),
+                                point = debug(&point),
+                                "downloaded point of unknown author"
+                            );
+                        }
+                        None
+                    }
+                    Err(VerifyError::BadSignature) => {
+                        // ban the sender, not the author: reliable peer verifies signatures
+                        // before it stores a point, so the point is forged or corrupted
+                        self.not_found.add(self.weights.weight(peer_id));
+                        DownloadCtx::meter_unreliable();
+                        (self.parent.inner.ban_events).send(peer_id, BanReason::BadSignature);
+                        if self.parent.inner.rejection_logs.should_log(peer_id) {
+                            tracing::error!(
+                                peer = display(peer_id.alt()),
+                                point = debug(&point),
+                                "downloaded point with bad signature"
+                            );
+                        }
+                        None
+                    }
                     Err(VerifyError::Fail(error)) => {
                         panic!(
                             "should not receive {error} for downloaded {:?}",
@@ -451,6 +562,12 @@ impl DownloadCtx {
             .increment(task.downloading.len() as _);
     }
 
+    fn meter_downloaded(task_start: Instant, is_depender: bool) {
+        let from = if is_depender { "depender" } else { "random" };
+        metrics::histogram!("tycho_mempool_download_latency_time", "from" => from)
+            .record(task_start.elapsed());
+    }
+
     fn meter_start(&self, point_id: &PointId) {
         metrics::counter!("tycho_mempool_download_task_count").increment(1);
 
@@ -458,3 +575,168 @@ impl DownloadCtx {
             .set(self.download_max_depth(point_id.round));
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::array;
+    use std::sync::atomic::{self, AtomicUsize};
+    use std::sync::Arc;
+
+    use everscale_crypto::ed25519::{KeyPair, SecretKey};
+    use tracing_subscriber::layer::{Context, SubscriberExt};
+    use tycho_util::test::TestRecorder;
+
+    use super::*;
+    use crate::effects::RoundCtx;
+    use crate::intercom::dependency::BanEvent;
+    use crate::test_utils;
+
+    #[test]
+    fn try_later_hint_defers_retry() {
+        let mut status = PeerStatus {
+            state: PeerState::Resolved,
+            failed_queries: 0,
+            is_depender: false,
+            is_in_flight: true,
+            retry_after: None,
+        };
+
+        status.try_later(None);
+        assert!(
+            status.is_ready(Instant::now()),
+            "no hint keeps uniform interval"
+        );
+        assert_eq!(status.failed_queries, 1);
+
+        status.is_in_flight = true;
+        status.try_later(Some(Duration::from_millis(500)));
+        let now = Instant::now();
+        assert!(!status.is_ready(now), "hinted peer must not be queried");
+        assert!(status.is_ready(now + Duration::from_millis(500)));
+        assert_eq!(status.failed_queries, 2);
+
+        status.is_in_flight = true;
+        status.try_later(Some(Duration::from_secs(3600)));
+        let capped = Instant::now() + PeerStatus::MAX_TRY_LATER_DELAY;
+        assert!(status.is_ready(capped), "hint must be capped");
+    }
+
+    #[test]
+    fn download_latency_is_recorded() {
+        let recorder = TestRecorder::default();
+        metrics::with_local_recorder(&recorder, || {
+            DownloadCtx::meter_downloaded(Instant::now(), true);
+            DownloadCtx::meter_downloaded(Instant::now(), false);
+        });
+
+        let froms = recorder.label_values("tycho_mempool_download_latency_time", "from");
+        assert_eq!(froms, ["depender", "random"]);
+    }
+
+    fn in_flight_status() -> PeerStatus {
+        PeerStatus {
+            state: PeerState::Resolved,
+            failed_queries: 0,
+            is_depender: false,
+            is_in_flight: true,
+            retry_after: None,
+        }
+    }
+
+    /// task to download the point after genesis, that will receive genesis from `peer_id`
+    fn wrong_point_task(
+        peer_id: PeerId,
+    ) -> (
+        DownloadTask<ExponentialQuery>,
+        Point,
+        broadcast::Receiver<BanEvent>,
+    ) {
+        let peers: [(PeerId, Arc<KeyPair>); 3] = array::from_fn(|i| {
+            let keys = KeyPair::from(&SecretKey::from_bytes([i as u8; 32]));
+            (PeerId::from(keys.public_key), Arc::new(keys))
+        });
+        let (peer_schedule, downloader, genesis, engine_ctx) =
+            test_utils::make_engine_parts(&peers, peers[0].1.clone());
+        let ban_events = downloader.inner.ban_events.subscribe();
+
+        let point_id = PointId {
+            round: genesis.info().round().next(),
+            ..genesis.info().id()
+        };
+        let round_ctx = RoundCtx::new(&engine_ctx, point_id.round);
+
+        let task = DownloadTask::<ExponentialQuery> {
+            parent: downloader.clone(),
+            _phantom: PhantomData,
+            ctx: DownloadCtx::new(&round_ctx, &point_id),
+            request: downloader.inner.dispatcher.point_by_id_request(&point_id),
+            point_id,
+            weights: peer_schedule.atomic().weights_for(point_id.round).clone(),
+            not_found: NotFoundQuorum::new(PeerCount::GENESIS, u64::MAX),
+            updates: peer_schedule.read().updates(),
+            undone_peers: FastHashMap::from_iter([(peer_id, in_flight_status())]),
+            downloading: FuturesUnordered::new(),
+            attempt: 0,
+            verified_from_depender: None,
+        };
+        (task, genesis, ban_events)
+    }
+
+    #[tokio::test]
+    async fn wrong_point_response_emits_ban_event() {
+        let peer_id = PeerId::from(KeyPair::from(&SecretKey::from_bytes([1; 32])).public_key);
+        let (mut task, genesis, mut ban_events) = wrong_point_task(peer_id);
+
+        let result = task.verify(&peer_id, Ok(PointByIdResponse::Defined(Ok(genesis))));
+        assert!(result.is_none(), "wrong point must not be accepted");
+
+        let event = ban_events.try_recv().expect("ban event must be sent");
+        assert_eq!(event.peer_id, peer_id);
+        assert_eq!(event.reason, BanReason::WrongPoint);
+        assert!(ban_events.try_recv().is_err(), "single ban per response");
+    }
+
+    #[derive(Default, Clone)]
+    struct CountLogs(Arc<AtomicUsize>);
+
+    impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for CountLogs {
+        fn on_event(&self, event: &tracing::Event<'_>, _: Context<'_, S>) {
+            if *event.metadata().level() <= tracing::Level::WARN {
+                self.0.fetch_add(1, atomic::Ordering::Relaxed);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn rapid_rejections_are_logged_within_limit() {
+        const REJECTIONS: usize = 100;
+        let peer_id = PeerId::from(KeyPair::from(&SecretKey::from_bytes([1; 32])).public_key);
+        let (mut task, genesis, _ban_events) = wrong_point_task(peer_id);
+        let limit = (NodeConfig::get().max_rejection_logs_per_peer)
+            .expect("test config must limit rejection logs")
+            .get() as usize;
+
+        let logs = CountLogs::default();
+        let recorder = TestRecorder::default();
+        let subscriber = tracing_subscriber::registry().with(logs.clone());
+        tracing::subscriber::with_default(subscriber, || {
+            metrics::with_local_recorder(&recorder, || {
+                for _ in 0..REJECTIONS {
+                    task.undone_peers.insert(peer_id, in_flight_status());
+                    let response = PointByIdResponse::Defined(Ok(genesis.clone()));
+                    assert!(task.verify(&peer_id, Ok(response)).is_none());
+                }
+            });
+        });
+
+        assert_eq!(logs.0.load(atomic::Ordering::Relaxed), limit);
+        assert_eq!(
+            recorder.counter("tycho_mempool_download_unreliable_responses"),
+            REJECTIONS as u64
+        );
+        assert_eq!(
+            recorder.counter("tycho_mempool_download_rejection_logs_suppressed"),
+            (REJECTIONS - limit) as u64
+        );
+    }
+}
@@ -1,6 +1,9 @@
+pub use ban_events::*;
 pub use downloader::*;
 pub(super) use uploader::*;
 
+mod ban_events;
 mod downloader;
 mod limiter;
+mod rejection_log;
 mod uploader;
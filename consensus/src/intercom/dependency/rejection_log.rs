@@ -0,0 +1,172 @@
+use std::num::NonZeroU16;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use tycho_network::PeerId;
+use tycho_util::futures::JoinTask;
+use tycho_util::FastHashMap;
+
+use crate::effects::AltFormat;
+
+/// Limits log lines about rejected responses and points from the same peer,
+/// so a flood of bad responses cannot flood the log too.
+/// Metrics are not affected and count every rejection.
+pub struct RejectionLogLimiter {
+    max_per_window: Option<NonZeroU16>,
+    peers: Arc<Mutex<FastHashMap<PeerId, PeerWindow>>>,
+    _flush_task: Option<JoinTask<()>>,
+}
+
+struct PeerWindow {
+    started: Instant,
+    logged: u16,
+    suppressed: u32,
+}
+
+impl PeerWindow {
+    fn log_suppressed(&self, peer_id: &PeerId) {
+        if self.suppressed > 0 {
+            tracing::warn!(
+                peer = display(peer_id.alt()),
+                suppressed = self.suppressed,
+                "rejections were not logged",
+            );
+        }
+    }
+}
+
+impl RejectionLogLimiter {
+    const WINDOW: Duration = Duration::from_secs(1);
+
+    /// `None` to log every rejection
+    pub fn new(max_per_sec: Option<NonZeroU16>) -> Self {
+        let peers = Arc::<Mutex<FastHashMap<PeerId, PeerWindow>>>::default();
+
+        // summary must not wait for the next rejection, as the peer may go quiet
+        let flush_task = max_per_sec.map(|_| {
+            let peers = Arc::downgrade(&peers);
+            JoinTask::new(async move {
+                let mut interval = tokio::time::interval(Self::WINDOW);
+                interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+                loop {
+                    interval.tick().await;
+                    let Some(peers) = peers.upgrade() else {
+                        return;
+                    };
+                    Self::flush_expired(&mut peers.lock(), Instant::now());
+                }
+            })
+        });
+
+        Self {
+            max_per_window: max_per_sec,
+            peers,
+            _flush_task: flush_task,
+        }
+    }
+
+    /// logs summaries of finished windows and forgets their peers
+    fn flush_expired(peers: &mut FastHashMap<PeerId, PeerWindow>, now: Instant) {
+        peers.retain(|peer_id, window| {
+            let is_expired = now.saturating_duration_since(window.started) >= Self::WINDOW;
+            if is_expired {
+                window.log_suppressed(peer_id);
+            }
+            !is_expired
+        });
+    }
+
+    /// returns `true` if the rejection should be logged
+    pub fn should_log(&self, peer_id: &PeerId) -> bool {
+        self.should_log_at(peer_id, Instant::now())
+    }
+
+    fn should_log_at(&self, peer_id: &PeerId, now: Instant) -> bool {
+        let Some(max_per_window) = self.max_per_window else {
+            return true;
+        };
+
+        let mut peers = self.peers.lock();
+        let window = peers.entry(*peer_id).or_insert_with(|| PeerWindow {
+            started: now,
+            logged: 0,
+            suppressed: 0,
+        });
+
+        if now.saturating_duration_since(window.started) >= Self::WINDOW {
+            window.log_suppressed(peer_id);
+            window.started = now;
+            window.logged = 0;
+            window.suppressed = 0;
+        }
+
+        if window.logged < max_per_window.get() {
+            window.logged += 1;
+            true
+        } else {
+            window.suppressed = window.suppressed.saturating_add(1);
+            metrics::counter!("tycho_mempool_download_rejection_logs_suppressed").increment(1);
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn rapid_rejections_are_logged_within_limit() {
+        const REJECTIONS: u32 = 100;
+        let limiter = RejectionLogLimiter::new(NonZeroU16::new(10));
+        let peer_id = PeerId([1; 32]);
+        let other_peer_id = PeerId([2; 32]);
+        let now = Instant::now();
+
+        let logged = (0..REJECTIONS)
+            .filter(|_| limiter.should_log_at(&peer_id, now))
+            .count();
+        assert_eq!(logged, 10);
+        {
+            let peers = limiter.peers.lock();
+            let window = &peers[&peer_id];
+            assert_eq!(window.logged as u32 + window.suppressed, REJECTIONS);
+        }
+
+        // other peers are limited independently
+        assert!(limiter.should_log_at(&other_peer_id, now));
+
+        // the next window starts from scratch
+        let next = now + RejectionLogLimiter::WINDOW;
+        assert!(limiter.should_log_at(&peer_id, next));
+        assert_eq!(limiter.peers.lock()[&peer_id].suppressed, 0);
+    }
+
+    #[tokio::test]
+    async fn finished_windows_are_flushed_without_new_rejections() {
+        let limiter = RejectionLogLimiter::new(NonZeroU16::new(1));
+        let peer_id = PeerId([1; 32]);
+        let other_peer_id = PeerId([2; 32]);
+        let now = Instant::now();
+
+        assert!(limiter.should_log_at(&peer_id, now));
+        assert!(!limiter.should_log_at(&peer_id, now));
+        let later = now + RejectionLogLimiter::WINDOW / 2;
+        assert!(limiter.should_log_at(&other_peer_id, later));
+
+        // only the finished window is flushed
+        let next = now + RejectionLogLimiter::WINDOW;
+        RejectionLogLimiter::flush_expired(&mut limiter.peers.lock(), next);
+        let peers = limiter.peers.lock();
+        assert!(!peers.contains_key(&peer_id));
+        assert!(peers.contains_key(&other_peer_id));
+    }
+
+    #[test]
+    fn unlimited_logs_everything() {
+        let limiter = RejectionLogLimiter::new(None);
+        let peer_id = PeerId([1; 32]);
+        assert!((0..100).all(|_| limiter.should_log(&peer_id)));
+    }
+}
@@ -21,11 +21,22 @@ impl Uploader {
         head: &DagHead,
         store: &MempoolStore,
         round_ctx: &RoundCtx,
+    ) -> PointByIdResponse<Bytes> {
+        Self::find_limited(&LIMIT, peer_id, point_id, head, store, round_ctx).await
+    }
+
+    async fn find_limited(
+        limit: &SpawnLimit,
+        peer_id: &PeerId,
+        point_id: PointId,
+        head: &DagHead,
+        store: &MempoolStore,
+        round_ctx: &RoundCtx,
     ) -> PointByIdResponse<Bytes> {
         let (status_opt, result) = if point_id.round > head.current().round() {
             (None, PointByIdResponse::TryLater)
         } else {
-            let task_opt = LIMIT.try_spawn_blocking(round_ctx.task(), {
+            let task_opt = limit.try_spawn_blocking(round_ctx.task(), {
                 let store = store.clone();
                 let last_back_bottom = head.last_back_bottom();
                 move || {
@@ -67,7 +78,13 @@ impl Uploader {
                 Some(task) => task
                     .await
                     .unwrap_or_else(|Cancelled()| (None, PointByIdResponse::TryLater)),
-                None => (None, PointByIdResponse::TryLater),
+                // all upload tasks are busy: ask to come back after the usual retry interval
+                None => (
+                    None,
+                    PointByIdResponse::TryLaterAfter(
+                        round_ctx.conf().consensus.download_retry_millis as _,
+                    ),
+                ),
             }
         };
         tracing::debug!(
@@ -84,3 +101,63 @@ impl Uploader {
         result
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::array;
+    use std::sync::Arc;
+
+    use everscale_crypto::ed25519::{KeyPair, SecretKey};
+
+    use super::*;
+    use crate::dag::{DagFront, DagRound};
+    use crate::models::Round;
+    use crate::test_utils;
+
+    #[tokio::test]
+    async fn busy_uploader_hints_retry_delay() {
+        let stub_store = MempoolStore::no_read_stub();
+
+        let peers: [(PeerId, Arc<KeyPair>); 3] = array::from_fn(|i| {
+            let keys = KeyPair::from(&SecretKey::from_bytes([i as u8; 32]));
+            (PeerId::from(keys.public_key), Arc::new(keys))
+        });
+        let (peer_schedule, _, genesis, engine_ctx) =
+            test_utils::make_engine_parts(&peers, peers[0].1.clone());
+        let conf = engine_ctx.conf();
+
+        let mut dag = DagFront::default();
+        let _committer = dag.init(
+            DagRound::new_bottom(conf.genesis_round, &peer_schedule, conf),
+            conf,
+        );
+        let top_round = conf.genesis_round.next().next();
+        for round in conf.genesis_round.next().0..=top_round.0 {
+            let round_ctx = RoundCtx::new(&engine_ctx, Round(round));
+            dag.fill_to_top(Round(round), None, &peer_schedule, &round_ctx);
+        }
+        let head = dag.head(&peer_schedule);
+        let round_ctx = RoundCtx::new(&engine_ctx, head.current().round());
+
+        let point_id = genesis.info().id();
+        assert!(point_id.round <= head.current().round());
+
+        // no permits left: every upload task is busy
+        let limit = SpawnLimit::new(0);
+        let result = Uploader::find_limited(
+            &limit,
+            &peers[1].0,
+            point_id,
+            &head,
+            &stub_store,
+            &round_ctx,
+        )
+        .await;
+        match result {
+            PointByIdResponse::TryLaterAfter(millis) => {
+                assert_eq!(millis, conf.consensus.download_retry_millis as u32);
+            }
+            other => panic!("unexpected response: {}", other.alt()),
+        }
+    }
+}
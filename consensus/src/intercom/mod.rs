@@ -1,4 +1,6 @@
 pub use core::{Dispatcher, Responder};
+#[cfg(feature = "test")]
+pub use core::{PointByIdRequests, QueryRequest};
 
 pub use broadcast::*;
 pub use dependency::*;
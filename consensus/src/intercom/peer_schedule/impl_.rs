@@ -19,7 +19,7 @@ use crate::effects::{AltFmt, AltFormat, Task, TaskTracker};
 use crate::engine::MempoolMergedConfig;
 use crate::intercom::peer_schedule::locked::PeerScheduleLocked;
 use crate::intercom::peer_schedule::stateless::PeerScheduleStateless;
-use crate::models::Round;
+use crate::models::{PeerWeights, Round};
 // As validators are elected for wall-clock time range,
 // the round of validator set switch is not known beforehand
 // and will be determined by the time in anchor vertices:
@@ -56,6 +56,9 @@ pub struct InitPeers {
     pub curr_v_set: Vec<PeerId>,
     pub curr_v_subset: Vec<PeerId>,
     pub next_v_set: Vec<PeerId>,
+    /// stake of validators, peers are weighted equally if empty
+    pub prev_v_weights: PeerWeights,
+    pub curr_v_weights: PeerWeights,
 }
 
 impl InitPeers {
@@ -69,6 +72,8 @@ impl InitPeers {
             curr_v_set: curr_v_subset.clone(),
             curr_v_subset,
             next_v_set: vec![],
+            prev_v_weights: Default::default(),
+            curr_v_weights: Default::default(),
         }
     }
 }
@@ -95,6 +100,7 @@ impl PeerSchedule {
             &[],
             genesis_round.prev(),
             &[merged_conf.genesis_author()],
+            &PeerWeights::default(),
             "Init genesis pseudo validator subset",
         );
         self.apply_scheduled_impl(&mut locked, genesis_round.prev());
@@ -108,6 +114,7 @@ impl PeerSchedule {
                 &init.prev_v_set,
                 prev_start,
                 &init.prev_v_subset,
+                &init.prev_v_weights,
                 "Init prev validator subset",
             );
             self.apply_scheduled_impl(&mut locked, prev_start);
@@ -119,6 +126,7 @@ impl PeerSchedule {
             &init.curr_v_set,
             curr_start,
             &init.curr_v_subset,
+            &init.curr_v_weights,
             "Init current validator subset",
         );
 
@@ -137,6 +145,7 @@ impl PeerSchedule {
             &peers.prev_v_set,
             Round(peers.prev_start_round),
             &peers.prev_v_subset,
+            &peers.prev_v_weights,
             "Apply prev validator subset",
         );
         self.apply_scheduled_impl(&mut locked, Round(peers.prev_start_round));
@@ -146,6 +155,7 @@ impl PeerSchedule {
             &peers.curr_v_set,
             Round(peers.curr_start_round),
             &peers.curr_v_subset,
+            &peers.curr_v_weights,
             "Apply current validator subset",
         );
 
@@ -174,6 +184,7 @@ impl PeerSchedule {
         validator_set: &[PeerId],
         next_round: Round,
         working_subset: &[PeerId],
+        weights: &PeerWeights,
         message: &'static str,
     ) {
         if next_round <= locked.data.curr_epoch_start() || working_subset.is_empty() {
@@ -202,7 +213,7 @@ impl PeerSchedule {
 
         // atomic part is updated under lock too
         self.update_atomic(|stateless| {
-            stateless.set_next_peers(working_subset);
+            stateless.set_next_peers(working_subset, weights);
             stateless.next_epoch_start = Some(next_round);
         });
 
@@ -6,7 +6,7 @@ use tycho_network::PeerId;
 use tycho_util::FastHashSet;
 
 use crate::effects::{AltFmt, AltFormat};
-use crate::models::Round;
+use crate::models::{PeerWeights, Round};
 
 #[derive(Clone)]
 pub struct PeerScheduleStateless {
@@ -15,6 +15,8 @@ pub struct PeerScheduleStateless {
     /// order matters to derive leader in `AnchorStage`
     peer_vecs: [Arc<Vec<PeerId>>; 4],
     peer_sets: [Arc<FastHashSet<PeerId>>; 4],
+    /// equal unless set explicitly for an epoch
+    peer_weights: [Arc<PeerWeights>; 4],
     epoch_starts: [Round; 3],
     pub(super) next_epoch_start: Option<Round>,
     empty_vec: Arc<Vec<PeerId>>,
@@ -27,6 +29,7 @@ impl PeerScheduleStateless {
             local_keys,
             peer_vecs: Default::default(),
             peer_sets: Default::default(),
+            peer_weights: Default::default(),
             epoch_starts: [Round::BOTTOM, Round::BOTTOM, Round::BOTTOM],
             next_epoch_start: None,
             empty_vec: Default::default(),
@@ -62,6 +65,10 @@ impl PeerScheduleStateless {
         }
     }
 
+    pub fn local_id(&self) -> PeerId {
+        PeerId::from(self.local_keys.public_key)
+    }
+
     pub(super) fn curr_epoch_start(&self) -> Round {
         self.epoch_starts[2]
     }
@@ -110,9 +117,23 @@ impl PeerScheduleStateless {
         result
     }
 
-    pub(super) fn set_next_peers(&mut self, peers: &[PeerId]) {
+    /// equal weights are returned for rounds out of known epochs, as for an empty peer set
+    pub fn weights_for(&self, round: Round) -> &Arc<PeerWeights> {
+        if self.next_epoch_start.is_some_and(|r| round >= r) {
+            &self.peer_weights[3]
+        } else if round >= self.epoch_starts[2] {
+            &self.peer_weights[2]
+        } else if round >= self.epoch_starts[1] {
+            &self.peer_weights[1]
+        } else {
+            &self.peer_weights[0]
+        }
+    }
+
+    pub(super) fn set_next_peers(&mut self, peers: &[PeerId], weights: &PeerWeights) {
         self.peer_sets[3] = Arc::new(peers.iter().copied().collect());
         self.peer_vecs[3] = Arc::new(peers.to_vec());
+        self.peer_weights[3] = Arc::new(weights.clone());
         self.meter();
     }
 
@@ -141,12 +162,14 @@ impl PeerScheduleStateless {
 
         self.peer_sets.rotate_left(1);
         self.peer_vecs.rotate_left(1);
+        self.peer_weights.rotate_left(1);
         self.meter();
     }
 
     pub(super) fn forget_oldest(&mut self) {
         self.peer_sets[0] = Default::default();
         self.peer_vecs[0] = Default::default();
+        self.peer_weights[0] = Default::default();
         self.meter();
     }
 
@@ -11,9 +11,13 @@ pub mod prelude {
     pub use crate::engine::lifecycle::{EngineBinding, EngineNetworkArgs, EngineSession};
     pub use crate::engine::round_watch::{RoundWatch, TopKnownAnchor};
     pub use crate::engine::{
-        ConsensusConfigExt, InputBuffer, MempoolConfigBuilder, MempoolMergedConfig,
-        MempoolNodeConfig,
+        AnchorSink, AnchorSinkClosed, AnchorSinkRef, ConsensusConfigExt, InputBuffer,
+        InputSourceId, MempoolConfigBuilder, MempoolMergedConfig, MempoolNodeConfig, NodeConfig,
+        NodeConfigUpdateError,
+    };
+    pub use crate::intercom::{BanEvent, BanReason, InitPeers};
+    pub use crate::models::{
+        AnchorData, AnchorOrderCheck, AnchorOrderError, AnchorResync, MempoolOutput, PeerWeights,
+        PointInfo,
     };
-    pub use crate::intercom::InitPeers;
-    pub use crate::models::{AnchorData, MempoolOutput, PointInfo};
 }
@@ -1,3 +1,4 @@
+use crate::engine::round_watch::{RoundWatch, TopKnownAnchor};
 use crate::models::{PointInfo, Round};
 
 pub struct AnchorData {
@@ -10,9 +11,136 @@ pub struct AnchorData {
 pub enum MempoolOutput {
     // tells the mempool adapter which anchors to skip because some first ones after a gap
     // have incomplete history that should not be taken into account
-    // (it's no harm to use it for deduplication - it will be evicted after buffer is refilled)
+    // (it's no harm to use it for deduplication - it will be evicted after buffer is refilled);
+    // anchors before the new full history bottom are lost and will never be sent,
+    // consumer should resume with `AnchorResync::new_start`
     NewStartAfterGap(Round),
     NextAnchor(AnchorData),
     Running,
     Paused,
 }
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum AnchorOrderError {
+    #[error("anchor {got} is not after the last one {last}")]
+    NotAfterLast { last: u32, got: u32 },
+    #[error("anchor {got} is linked to {prev:?} instead of the last one {last}")]
+    Gap {
+        last: u32,
+        prev: Option<u32>,
+        got: u32,
+    },
+}
+
+/// Consumer side check of the anchor stream: every next anchor must be after the last one
+/// and must be linked to it with [`AnchorData::prev_anchor`].
+///
+/// Keep it for as long as the consumer state lives, i.e. across engine restarts,
+/// and [`reset`](Self::reset) it on [`MempoolOutput::NewStartAfterGap`].
+#[derive(Default)]
+pub struct AnchorOrderCheck {
+    last: Option<Round>,
+}
+
+impl AnchorOrderCheck {
+    /// Anchor that is not after the last one is not remembered, so it may be skipped.
+    /// Anchor after a gap becomes the last one, so the gap is reported only once.
+    pub fn check(&mut self, data: &AnchorData) -> Result<(), AnchorOrderError> {
+        let round = data.anchor.round();
+        let Some(last) = self.last else {
+            self.last = Some(round);
+            return Ok(());
+        };
+        if round <= last {
+            return Err(AnchorOrderError::NotAfterLast {
+                last: last.0,
+                got: round.0,
+            });
+        }
+        self.last = Some(round);
+        if data.prev_anchor != Some(last) {
+            return Err(AnchorOrderError::Gap {
+                last: last.0,
+                prev: data.prev_anchor.map(|r| r.0),
+                got: round.0,
+            });
+        }
+        Ok(())
+    }
+
+    /// The first anchor after a new start is not linked to any previous one.
+    pub fn reset(&mut self) {
+        self.last = None;
+    }
+}
+
+/// Consumer side protocol to resume after [`MempoolOutput::NewStartAfterGap`].
+///
+/// Anchors continue to come in order, but their rounds jump over lost history:
+/// * top known anchor is raised to the new bottom, because no block can reference a lost anchor,
+///   and mempool must not wait for it in pause;
+/// * anchors are not executable until the deduplication window is filled after the new bottom.
+pub struct AnchorResync {
+    deduplicate_rounds: u16,
+    first_executable: Option<Round>,
+}
+
+impl AnchorResync {
+    pub fn new(deduplicate_rounds: u16) -> Self {
+        Self {
+            deduplicate_rounds,
+            first_executable: None,
+        }
+    }
+
+    /// Returns the round of the first anchor that can be executed after the gap.
+    pub fn new_start(
+        &mut self,
+        bottom: Round,
+        top_known_anchor: &RoundWatch<TopKnownAnchor>,
+    ) -> Round {
+        top_known_anchor.set_max(bottom);
+        let after_gap = bottom + self.deduplicate_rounds;
+        let first_executable = (self.first_executable).map_or(after_gap, |f| f.max(after_gap));
+        self.first_executable = Some(first_executable);
+        first_executable
+    }
+
+    /// Anchors before the first gap are always executable.
+    pub fn is_executable(&self, anchor_round: Round) -> bool {
+        (self.first_executable).is_none_or(|first| anchor_round >= first)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn consumer_resumes_after_gap() {
+        let top_known_anchor = RoundWatch::<TopKnownAnchor>::default();
+        let mut resync = AnchorResync::new(20);
+
+        // Anchors before any gap are executed as usual
+        assert!(resync.is_executable(Round(10)));
+        top_known_anchor.set_max(Round(10));
+
+        // Anchors down to round 100 are lost
+        let first_executable = resync.new_start(Round(100), &top_known_anchor);
+        assert_eq!(first_executable, Round(120));
+        assert_eq!(top_known_anchor.get(), Round(100));
+
+        // Anchors with incomplete deduplication window are skipped
+        assert!(!resync.is_executable(Round(104)));
+        assert!(!resync.is_executable(Round(119)));
+        assert!(resync.is_executable(Round(120)));
+        assert!(resync.is_executable(Round(128)));
+
+        // Collator has already moved further, so its top known anchor is kept
+        top_known_anchor.set_max(Round(200));
+        let first_executable = resync.new_start(Round(150), &top_known_anchor);
+        assert_eq!(first_executable, Round(170));
+        assert_eq!(top_known_anchor.get(), Round(200));
+        assert!(!resync.is_executable(Round(160)));
+    }
+}
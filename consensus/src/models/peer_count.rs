@@ -1,3 +1,6 @@
+use tycho_network::PeerId;
+use tycho_util::FastHashMap;
+
 #[derive(Clone, Copy, PartialEq)]
 pub struct PeerCount(u8);
 
@@ -60,3 +63,164 @@ impl PeerCount {
     // self.0
     // }
 }
+
+/// Optional stake weights of peers scheduled for the same round.
+/// Empty weights are considered equal, so thresholds match headcount ones of [`PeerCount`].
+#[derive(Clone, Default, Debug)]
+pub struct PeerWeights(FastHashMap<PeerId, u64>);
+
+impl PeerWeights {
+    pub fn new(weights: FastHashMap<PeerId, u64>) -> Self {
+        Self(weights)
+    }
+
+    pub fn is_equal(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// peers without stake (i.e. not in validator set) have no weight unless weights are equal
+    pub fn weight(&self, peer_id: &PeerId) -> u64 {
+        if self.is_equal() {
+            1
+        } else {
+            self.0.get(peer_id).copied().unwrap_or_default()
+        }
+    }
+
+    /// weighted counterpart of [`PeerCount::majority_of_others`]:
+    /// the same 2/3 ratio as 2F out of 3F others, but applied to their total stake;
+    /// `None` if others have no stake at all, so they must be weighted equally
+    pub fn majority_of_others<'a>(
+        &self,
+        peer_count: PeerCount,
+        peers: impl IntoIterator<Item = &'a PeerId>,
+        excluded: &PeerId,
+    ) -> Option<u64> {
+        if self.is_equal() {
+            return Some(peer_count.majority_of_others() as u64);
+        }
+        let total: u64 = peers
+            .into_iter()
+            .filter(|peer_id| *peer_id != excluded)
+            .map(|peer_id| self.weight(peer_id))
+            .sum();
+        (total > 0).then(|| (total * 2).div_ceil(3))
+    }
+}
+
+/// Reliable "not found" responses: both headcount and stake thresholds must be reached,
+/// so neither a single heavy peer nor a crowd of light ones decides alone
+#[derive(Debug)]
+pub struct NotFoundQuorum {
+    peers_threshold: usize,
+    weight_threshold: u64,
+    peers: usize,
+    weight: u64,
+}
+
+impl NotFoundQuorum {
+    pub fn new(peer_count: PeerCount, weight_threshold: u64) -> Self {
+        Self {
+            peers_threshold: peer_count.majority_of_others(),
+            weight_threshold,
+            peers: 0,
+            weight: 0,
+        }
+    }
+
+    pub fn add(&mut self, weight: u64) {
+        self.peers = self.peers.saturating_add(1);
+        self.weight = self.weight.saturating_add(weight);
+    }
+
+    pub fn is_reached(&self) -> bool {
+        self.peers >= self.peers_threshold && self.weight >= self.weight_threshold
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn weighted_quorum_differs_from_headcount() {
+        let local = PeerId([0; 32]);
+        let peers = [local, PeerId([1; 32]), PeerId([2; 32]), PeerId([3; 32])];
+        let peer_count = PeerCount::try_from(peers.len()).expect("enough peers");
+
+        let equal = PeerWeights::default();
+        let threshold = equal
+            .majority_of_others(peer_count, &peers, &local)
+            .unwrap();
+        assert_eq!(threshold, peer_count.majority_of_others() as u64);
+        // two light peers are enough by headcount
+        let light: u64 = peers[2..].iter().map(|p| equal.weight(p)).sum();
+        assert!(light >= threshold);
+
+        let weighted = PeerWeights::new(FastHashMap::from_iter([
+            (local, 10),
+            (peers[1], 100),
+            (peers[2], 1),
+            (peers[3], 1),
+        ]));
+        let threshold = weighted
+            .majority_of_others(peer_count, &peers, &local)
+            .unwrap();
+        assert_eq!(threshold, 68);
+        // but not enough by stake
+        let light: u64 = peers[2..].iter().map(|p| weighted.weight(p)).sum();
+        assert!(light < threshold);
+        let heavy: u64 = peers[1..3].iter().map(|p| weighted.weight(p)).sum();
+        assert!(heavy >= threshold);
+
+        // others without stake cannot reach any weighted threshold
+        let stakeless = PeerWeights::new(FastHashMap::from_iter([(local, 10)]));
+        assert_eq!(
+            stakeless.majority_of_others(peer_count, &peers, &local),
+            None
+        );
+    }
+
+    #[test]
+    fn whale_alone_is_not_a_not_found_quorum() {
+        let local = PeerId([0; 32]);
+        let whale = PeerId([1; 32]);
+        let peers = [
+            local,
+            whale,
+            PeerId([2; 32]),
+            PeerId([3; 32]),
+            PeerId([4; 32]),
+            PeerId([5; 32]),
+            PeerId([6; 32]),
+        ];
+        let peer_count = PeerCount::try_from(peers.len()).expect("enough peers");
+        let weights = PeerWeights::new(FastHashMap::from_iter(
+            peers
+                .iter()
+                .map(|p| (*p, if *p == whale { 1000 } else { 1 })),
+        ));
+        let threshold = weights
+            .majority_of_others(peer_count, &peers, &local)
+            .unwrap();
+
+        // whale has enough stake, but it is a single peer
+        let mut quorum = NotFoundQuorum::new(peer_count, threshold);
+        quorum.add(weights.weight(&whale));
+        assert!(!quorum.is_reached());
+        for peer_id in &peers[2..4] {
+            quorum.add(weights.weight(peer_id));
+        }
+        assert!(!quorum.is_reached());
+        // majority of others by headcount with the whale among them
+        quorum.add(weights.weight(&peers[4]));
+        assert!(quorum.is_reached());
+
+        // light peers have enough headcount, but not enough stake
+        let mut quorum = NotFoundQuorum::new(peer_count, threshold);
+        for peer_id in &peers[2..] {
+            quorum.add(weights.weight(peer_id));
+        }
+        assert!(!quorum.is_reached());
+    }
+}
@@ -8,7 +8,7 @@ use crate::models::point::{Digest, Round, UnixTime};
 use crate::models::proto_utils::{evidence_btree_map, points_btree_map};
 use crate::models::{PeerCount, Signature};
 
-#[derive(Clone, Copy, Debug, PartialEq, TlRead, TlWrite)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, TlRead, TlWrite)]
 #[tl(boxed, id = "consensus.pointId", scheme = "proto.tl")]
 pub struct PointId {
     pub author: PeerId,
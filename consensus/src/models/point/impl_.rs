@@ -17,13 +17,6 @@ use crate::models::{PointInfo, Round};
 pub enum PointIntegrityError {
     #[error("hash mismatch")]
     BadHash,
-    /// The point may be created by someone else:
-    /// blame every dependent point author and the sender of this point,
-    /// do not use the author from point's body
-    #[error("signature does not match author")]
-    BadSig,
-    #[error("bad signature in evidence map")]
-    EvidenceSig,
     #[error("unusable due to some maps issue")]
     BadMaps, // TODO: separate error for each violation
 }
@@ -130,19 +123,10 @@ impl Point {
         })
     }
 
+    /// Signatures are not checked here, see [`Verifier::verify`](crate::dag::Verifier::verify)
     pub fn parse(serialized: Vec<u8>) -> Result<Result<Self, PointIntegrityError>, TlError> {
-        fn is_evidence_ok(info: &PointInfo) -> bool {
-            info.prev_digest().is_none_or(|prev_proof| {
-                (info.evidence().iter()).all(|(peer, sig)| sig.verifies(peer, prev_proof))
-            })
-        }
-
         let raw = PointRawRead::<'_>::read_from(&mut &serialized[..])?;
 
-        if !(raw.signature).verifies(&raw.author()?, &raw.digest) {
-            return Ok(Err(PointIntegrityError::BadSig));
-        };
-
         if raw.digest != Digest::new(raw.body.as_ref()) {
             return Ok(Err(PointIntegrityError::BadHash));
         };
@@ -153,10 +137,6 @@ impl Point {
             return Ok(Err(PointIntegrityError::BadMaps));
         }
 
-        if !is_evidence_ok(point.info()) {
-            return Ok(Err(PointIntegrityError::EvidenceSig));
-        }
-
         Ok(Ok(point))
     }
 
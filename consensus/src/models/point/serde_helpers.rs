@@ -27,7 +27,7 @@ pub struct PointRead<'tl> {
 #[tl(boxed, id = "consensus.point", scheme = "proto.tl")]
 pub struct PointRawRead<'tl> {
     pub digest: Digest,
-    pub signature: Signature,
+    pub _signature: Signature,
     pub body: RawBytes<'tl, tl_proto::Boxed>,
 }
 
@@ -53,16 +53,6 @@ pub struct PointBodyRead<'tl> {
 }
 
 impl PointRawRead<'_> {
-    pub fn author(&self) -> TlResult<PeerId> {
-        #[derive(TlRead)]
-        #[tl(boxed, id = "consensus.pointBody", scheme = "proto.tl")]
-        struct PointBodyPrefix {
-            author: PeerId,
-        }
-        let body = <PointBodyPrefix>::read_from(&mut self.body.as_ref())?;
-        Ok(body.author)
-    }
-
     pub fn payload(&self) -> TlResult<Vec<&[u8]>> {
         #[derive(TlRead)]
         #[tl(boxed, id = "consensus.pointBody", scheme = "proto.tl")]
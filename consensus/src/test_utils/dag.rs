@@ -11,11 +11,11 @@ use rand::{thread_rng, RngCore};
 use tycho_network::{Network, OverlayId, PeerId, PrivateOverlay, Router};
 use tycho_util::FastHashMap;
 
-use crate::dag::{AnchorStage, DagRound, ValidateResult, Verifier};
+use crate::dag::{AnchorStage, DagRound, ValidateResult, VerificationMode, Verifier};
 use crate::effects::{Ctx, EngineCtx, MempoolStore, RoundCtx, TaskTracker, ValidateCtx};
 use crate::engine::round_watch::{Consensus, RoundWatch};
 use crate::engine::MempoolConfig;
-use crate::intercom::{Dispatcher, Downloader, InitPeers, PeerSchedule, Responder};
+use crate::intercom::{BanEvents, Dispatcher, Downloader, InitPeers, PeerSchedule, Responder};
 use crate::models::{
     AnchorStageRole, Cert, Digest, Link, PeerCount, Point, PointData, PointId, Round, Signature,
     Through, UnixTime,
@@ -49,8 +49,12 @@ pub fn make_engine_parts<const PEER_COUNT: usize>(
     peer_schedule.init(&merged_conf, &init_peers);
 
     let stub_consensus_round = RoundWatch::<Consensus>::default();
-    let stub_downloader =
-        Downloader::new(&dispatcher, &peer_schedule, stub_consensus_round.receiver());
+    let stub_downloader = Downloader::new(
+        &dispatcher,
+        &peer_schedule,
+        stub_consensus_round.receiver(),
+        &BanEvents::default(),
+    );
 
     (peer_schedule, stub_downloader, genesis, engine_ctx)
 }
@@ -126,7 +130,13 @@ pub async fn populate_points<const PEER_COUNT: usize>(
         Point::parse(point.serialized().to_vec())
             .expect("point tl serde is broken")
             .expect("point integrity check is broken");
-        Verifier::verify(point.info(), peer_schedule, round_ctx.conf()).expect("well-formed point");
+        Verifier::verify(
+            point.info(),
+            peer_schedule,
+            round_ctx.conf(),
+            VerificationMode::Full,
+        )
+        .expect("well-formed point");
         let validate_ctx = ValidateCtx::new(round_ctx, point.info());
         let validated = Verifier::validate(
             point.info().clone(),
@@ -8,3 +8,9 @@ mod bootstrap;
 mod dag;
 mod last_anchor_file;
 pub mod test_logger;
+
+/// internals exposed for benches
+pub mod bench {
+    pub use crate::intercom::{PointByIdRequests, QueryRequest};
+    pub use crate::models::{Digest, PointId, Round};
+}
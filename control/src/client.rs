@@ -70,6 +70,13 @@ impl ControlClient {
             .map_err(Into::into)
     }
 
+    pub async fn get_compaction_status(&self) -> ClientResult<CompactionStatusResponse> {
+        self.inner
+            .get_compaction_status(current_context())
+            .await
+            .map_err(Into::into)
+    }
+
     pub async fn set_memory_profiler_enabled(&self, enabled: bool) -> ClientResult<bool> {
         self.inner
             .set_memory_profiler_enabled(current_context(), enabled)
@@ -31,6 +31,9 @@ pub trait ControlServer {
     /// Trigger manual compaction.
     async fn trigger_compaction(req: TriggerCompactionRequest);
 
+    /// Get manual compaction progress.
+    async fn get_compaction_status() -> CompactionStatusResponse;
+
     /// Sets memory profiler state. Returns whether the state was changed.
     async fn set_memory_profiler_enabled(enabled: bool) -> bool;
 
@@ -132,6 +135,29 @@ pub enum TriggerCompactionRequest {
     Rpc,
 }
 
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize)]
+pub struct CompactionStatusResponse {
+    /// Database which is being compacted right now.
+    pub running: Option<TriggerCompactionRequest>,
+    /// Column families of the running compaction (only for `BaseDb`).
+    pub progress: Option<CompactionProgress>,
+    /// The last finished manual compaction.
+    pub last_finished: Option<FinishedCompaction>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct CompactionProgress {
+    pub compacted_cfs: usize,
+    pub total_cfs: usize,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct FinishedCompaction {
+    pub database: TriggerCompactionRequest,
+    pub finished_at: u32,
+    pub duration_ms: u64,
+}
+
 impl TryFrom<&str> for TriggerCompactionRequest {
     type Error = anyhow::Error;
 
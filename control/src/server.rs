@@ -2,6 +2,7 @@ use std::borrow::Cow;
 use std::num::NonZeroU64;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, OnceLock};
+use std::time::Instant;
 
 use anyhow::{Context as _, Result};
 use arc_swap::ArcSwapOption;
@@ -450,6 +451,10 @@ impl proto::ControlServer for ControlServer {
         self.inner.manual_compaction.trigger_compaction(req);
     }
 
+    async fn get_compaction_status(self, _: Context) -> proto::CompactionStatusResponse {
+        self.inner.manual_compaction.status()
+    }
+
     async fn set_memory_profiler_enabled(self, _: Context, enabled: bool) -> bool {
         self.inner.memory_profiler.set_enabled(enabled).await
     }
@@ -900,6 +905,7 @@ fn extend_signature_with_id(data: &[u8], signature_id: Option<i32>) -> Cow<'_, [
 #[derive(Clone)]
 struct ManualCompaction {
     trigger: ManualTriggerTx,
+    status: watch::Receiver<proto::CompactionStatusResponse>,
     handle: AbortHandle,
 }
 
@@ -907,11 +913,17 @@ impl ManualCompaction {
     pub fn new(storage: Storage) -> Self {
         let (compaction_trigger, manual_compaction_rx) =
             watch::channel(None::<proto::TriggerCompactionRequest>);
+        let (status_tx, status) = watch::channel(proto::CompactionStatusResponse::default());
 
-        let watcher = tokio::spawn(Self::watcher(manual_compaction_rx, storage.clone()));
+        let watcher = tokio::spawn(Self::watcher(
+            manual_compaction_rx,
+            status_tx,
+            storage.clone(),
+        ));
 
         Self {
             trigger: compaction_trigger,
+            status,
             handle: watcher.abort_handle(),
         }
     }
@@ -920,8 +932,16 @@ impl ManualCompaction {
         self.trigger.send_replace(Some(trigger));
     }
 
+    pub fn status(&self) -> proto::CompactionStatusResponse {
+        *self.status.borrow()
+    }
+
     #[tracing::instrument(skip_all)]
-    async fn watcher(mut manual_rx: ManualTriggerRx, storage: Storage) {
+    async fn watcher(
+        mut manual_rx: ManualTriggerRx,
+        status_tx: watch::Sender<proto::CompactionStatusResponse>,
+        storage: Storage,
+    ) {
         tracing::info!("manager started");
         defer! {
             tracing::info!("manager stopped");
@@ -936,9 +956,14 @@ impl ManualCompaction {
                 continue;
             };
 
+            status_tx.send_modify(|status| status.running = Some(trigger));
+            let started_at = Instant::now();
+
             match trigger {
                 proto::TriggerCompactionRequest::Base => {
-                    storage.base_db().trigger_compaction().await;
+                    if let Err(e) = Self::compact_base(&storage, &status_tx).await {
+                        tracing::error!("failed to compact base db: {e:?}");
+                    }
                 }
                 proto::TriggerCompactionRequest::Mempool => {
                     storage.mempool_db().trigger_compaction().await;
@@ -949,6 +974,43 @@ impl ManualCompaction {
                     }
                 }
             }
+
+            status_tx.send_modify(|status| {
+                status.running = None;
+                status.progress = None;
+                status.last_finished = Some(proto::FinishedCompaction {
+                    database: trigger,
+                    finished_at: tycho_util::time::now_sec(),
+                    duration_ms: started_at.elapsed().as_millis() as u64,
+                });
+            });
+        }
+    }
+
+    async fn compact_base(
+        storage: &Storage,
+        status_tx: &watch::Sender<proto::CompactionStatusResponse>,
+    ) -> Result<()> {
+        let handle = storage.compact_all()?;
+        let mut progress = handle.subscribe();
+
+        let finished = handle.wait();
+        tokio::pin!(finished);
+
+        loop {
+            let tycho_storage::CompactionProgress { compacted, total } =
+                *progress.borrow_and_update();
+            status_tx.send_modify(|status| {
+                status.progress = Some(proto::CompactionProgress {
+                    compacted_cfs: compacted,
+                    total_cfs: total,
+                });
+            });
+
+            tokio::select! {
+                res = &mut finished => break res,
+                Ok(()) = progress.changed() => {}
+            }
         }
     }
 }
@@ -20,9 +20,11 @@ pub use self::provider::{
     ArchiveBlockProvider, ArchiveBlockProviderConfig, BlockProvider, BlockProviderExt,
     BlockchainBlockProvider, BlockchainBlockProviderConfig, ChainBlockProvider, CheckProof,
     EmptyBlockProvider, OptionalBlockStuff, ProofChecker, RetryConfig, StorageBlockProvider,
+    VecBlockProvider,
 };
 pub use self::starter::{
-    ColdBootType, FileZerostateProvider, Starter, StarterConfig, ZerostateProvider,
+    ColdBootType, FileZerostateProvider, KeyBlocksBatchConfig, Starter, StarterConfig,
+    ZerostateProvider,
 };
 pub use self::state::{
     BlockStriderState, CommitMasterBlock, CommitShardBlock, PersistentBlockStriderState,
@@ -36,6 +38,7 @@ pub use self::subscriber::{
     BlockSubscriberContext, BlockSubscriberExt, ChainSubscriber, DelayedTasks,
     DelayedTasksJoinHandle, DelayedTasksSpawner, GcSubscriber, ManualGcTrigger, MetricsSubscriber,
     NoopSubscriber, PsSubscriber, StateSubscriber, StateSubscriberContext, StateSubscriberExt,
+    TransactionIndexSubscriber,
 };
 
 mod archive_handler;
@@ -375,7 +378,8 @@ where
         let provider = self.provider.clone();
         let prev_block_id = *prev_block_id;
         async move {
-            let res = provider.get_next_block(&prev_block_id).await?;
+            let res = provider.get_next_block(&prev_block_id).await;
+            let res = provider::served_by(provider.as_ref(), res)?;
             Some(res.with_context(|| {
                 format!(
                     "BUGGY PROVIDER. failed to fetch next master block after prev: {prev_block_id}"
@@ -385,7 +389,8 @@ where
     }
 
     async fn fetch_block(&self, block_id_relation: &BlockIdRelation) -> Result<BlockStuffAug> {
-        match self.provider.get_block(block_id_relation).await {
+        let res = self.provider.get_block(block_id_relation).await;
+        match provider::served_by(self.provider.as_ref(), res) {
             Some(Ok(block)) => Ok(block),
             Some(Err(e)) => {
                 anyhow::bail!(
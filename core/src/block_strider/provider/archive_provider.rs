@@ -15,7 +15,7 @@ use tycho_block_util::archive::Archive;
 use tycho_block_util::block::{BlockIdRelation, BlockStuffAug};
 use tycho_storage::{MappedFile, Storage};
 
-use crate::block_strider::provider::{BlockProvider, CheckProof, OptionalBlockStuff, ProofChecker};
+use crate::block_strider::provider::{BlockProvider, OptionalBlockStuff, ProofChecker};
 use crate::blockchain_rpc::{BlockchainRpcClient, PendingArchive, PendingArchiveResponse};
 use crate::overlay_client::{Neighbour, PunishReason};
 
@@ -129,23 +129,10 @@ impl ArchiveBlockProvider {
         mc_block_id: &BlockId,
         block_id: &BlockId,
     ) -> Result<BlockStuffAug> {
-        let (block, ref proof, ref queue_diff) = match archive.get_entry_by_id(block_id).await {
-            Ok(entry) => entry,
-            Err(e) => anyhow::bail!("archive is corrupted: {e:?}"),
-        };
-
         self.inner
             .proof_checker
-            .check_proof(CheckProof {
-                mc_block_id,
-                block: &block,
-                proof,
-                queue_diff,
-                store_on_success: true,
-            })
-            .await?;
-
-        Ok(block)
+            .check_archive_entry(archive, mc_block_id, block_id, true)
+            .await
     }
 }
 
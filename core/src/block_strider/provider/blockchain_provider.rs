@@ -179,8 +179,15 @@ impl BlockchainBlockProvider {
                 self.fallback.is_some(),
                 || {
                     tracing::debug!(%block_id, "get_block_full requested");
-                    self.client
-                        .get_block_full(block_id, DataRequirement::Expected)
+                    let requirement = DataRequirement::Expected;
+                    match self.client.overlay_client().config().neighbors.hedge_delay {
+                        Some(delay) => Either::Left(self.client.get_block_full_hedged(
+                            block_id,
+                            requirement,
+                            delay,
+                        )),
+                        None => Either::Right(self.client.get_block_full(block_id, requirement)),
+                    }
                 },
                 |res| async move {
                     match res {
@@ -43,6 +43,14 @@ impl BlockProvider for BoxBlockProvider {
     fn cleanup_until(&self, mc_seqno: u32) -> Self::CleanupFut<'_> {
         unsafe { (self.vtable.cleanup_until)(&self.data, mc_seqno) }
     }
+
+    fn name(&self) -> &str {
+        unsafe { (self.vtable.name)(&self.data) }
+    }
+
+    fn is_composite(&self) -> bool {
+        unsafe { (self.vtable.is_composite)(&self.data) }
+    }
 }
 
 impl Drop for BoxBlockProvider {
@@ -59,6 +67,8 @@ struct Vtable {
     get_next_block: GetNextBlockFn,
     get_block: GetBlockFn,
     cleanup_until: CleanupFn,
+    name: NameFn,
+    is_composite: IsCompositeFn,
     drop: DropFn,
 }
 
@@ -77,6 +87,14 @@ impl Vtable {
                 let provider = unsafe { &*ptr.load(Ordering::Relaxed).cast::<P>() };
                 provider.cleanup_until(mc_seqno).boxed()
             },
+            name: |ptr| {
+                let provider = unsafe { &*ptr.load(Ordering::Relaxed).cast::<P>() };
+                provider.name()
+            },
+            is_composite: |ptr| {
+                let provider = unsafe { &*ptr.load(Ordering::Relaxed).cast::<P>() };
+                provider.is_composite()
+            },
             drop: |ptr| {
                 drop(unsafe { Box::<P>::from_raw(ptr.get_mut().cast::<P>()) });
             },
@@ -87,6 +105,8 @@ impl Vtable {
 type GetNextBlockFn = for<'a> unsafe fn(&AtomicPtr<()>, &'a BlockId) -> GetBlockFut<'a>;
 type GetBlockFn = for<'a> unsafe fn(&AtomicPtr<()>, &'a BlockIdRelation) -> GetBlockFut<'a>;
 type CleanupFn = for<'a> unsafe fn(&AtomicPtr<()>, u32) -> ClenaupFut<'_>;
+type NameFn = for<'a> unsafe fn(&'a AtomicPtr<()>) -> &'a str;
+type IsCompositeFn = unsafe fn(&AtomicPtr<()>) -> bool;
 type DropFn = unsafe fn(&mut AtomicPtr<()>);
 
 type GetBlockFut<'a> = BoxFuture<'a, OptionalBlockStuff>;
@@ -8,7 +8,9 @@ use anyhow::{Context, Result};
 use arc_swap::{ArcSwapAny, ArcSwapOption};
 use everscale_types::models::BlockId;
 use futures_util::future::{self, BoxFuture};
+use futures_util::FutureExt;
 use serde::{Deserialize, Serialize};
+use tycho_block_util::archive::Archive;
 use tycho_block_util::block::{
     check_with_master_state, check_with_prev_key_block_proof, BlockIdRelation, BlockProofStuff,
     BlockProofStuffAug, BlockStuff, BlockStuffAug,
@@ -24,12 +26,14 @@ pub use self::blockchain_provider::{BlockchainBlockProvider, BlockchainBlockProv
 pub use self::box_provider::BoxBlockProvider;
 use self::futures::SelectNonEmptyFut;
 pub use self::storage_provider::StorageBlockProvider;
+pub use self::vec_provider::VecBlockProvider;
 
 mod archive_provider;
 mod blockchain_provider;
 mod box_provider;
 mod futures;
 mod storage_provider;
+mod vec_provider;
 
 pub type OptionalBlockStuff = Option<Result<BlockStuffAug>>;
 
@@ -45,6 +49,45 @@ pub trait BlockProvider: Send + Sync + 'static {
     fn get_block<'a>(&'a self, block_id_relation: &'a BlockIdRelation) -> Self::GetBlockFut<'a>;
     /// Clear resources until (and including) the specified masterchain block seqno.
     fn cleanup_until(&self, mc_seqno: u32) -> Self::CleanupFut<'_>;
+
+    /// Provider name used in metrics.
+    ///
+    /// Default: type name without the module path.
+    fn name(&self) -> &str {
+        short_type_name::<Self>()
+    }
+
+    /// Whether the provider only combines other providers.
+    ///
+    /// Blocks returned by a composite provider are attributed
+    /// to the inner provider which actually returned them.
+    fn is_composite(&self) -> bool {
+        false
+    }
+}
+
+/// Accounts a block returned by the provider in metrics.
+///
+/// NOTE: Composite providers are skipped, so each block is counted once
+/// for the leaf provider.
+pub(crate) fn served_by<P: BlockProvider + ?Sized>(
+    provider: &P,
+    res: OptionalBlockStuff,
+) -> OptionalBlockStuff {
+    if res.is_some() && !provider.is_composite() {
+        metrics::counter!(
+            "tycho_core_block_provider_blocks",
+            "provider" => provider.name().to_owned()
+        )
+        .increment(1);
+    }
+    res
+}
+
+fn short_type_name<T: ?Sized>() -> &'static str {
+    let name = std::any::type_name::<T>();
+    let name = name.split_once('<').map_or(name, |(name, _)| name);
+    name.rsplit("::").next().unwrap_or(name)
 }
 
 impl<T: BlockProvider> BlockProvider for Box<T> {
@@ -63,6 +106,14 @@ impl<T: BlockProvider> BlockProvider for Box<T> {
     fn cleanup_until(&self, mc_seqno: u32) -> Self::CleanupFut<'_> {
         <T as BlockProvider>::cleanup_until(self, mc_seqno)
     }
+
+    fn name(&self) -> &str {
+        <T as BlockProvider>::name(self)
+    }
+
+    fn is_composite(&self) -> bool {
+        <T as BlockProvider>::is_composite(self)
+    }
 }
 
 impl<T: BlockProvider> BlockProvider for Arc<T> {
@@ -81,6 +132,14 @@ impl<T: BlockProvider> BlockProvider for Arc<T> {
     fn cleanup_until(&self, mc_seqno: u32) -> Self::CleanupFut<'_> {
         <T as BlockProvider>::cleanup_until(self, mc_seqno)
     }
+
+    fn name(&self) -> &str {
+        <T as BlockProvider>::name(self)
+    }
+
+    fn is_composite(&self) -> bool {
+        <T as BlockProvider>::is_composite(self)
+    }
 }
 
 pub trait BlockProviderExt: Sized {
@@ -167,7 +226,7 @@ impl<T1: BlockProvider, T2: BlockProvider> BlockProvider for ChainBlockProvider<
                 return Box::pin(async move {
                     let res = left.get_next_block(prev_block_id).await;
                     if res.is_some() {
-                        return res;
+                        return served_by(left.as_ref(), res);
                     }
 
                     // Schedule left provider cleanup for the next block.
@@ -175,22 +234,34 @@ impl<T1: BlockProvider, T2: BlockProvider> BlockProvider for ChainBlockProvider<
                         .store(prev_block_id.seqno.saturating_add(1), Ordering::Release);
 
                     // Fallback to right
-                    self.right.get_next_block(prev_block_id).await
+                    let res = self.right.get_next_block(prev_block_id).await;
+                    served_by(&self.right, res)
                 });
             }
         }
 
-        Box::pin(self.right.get_next_block(prev_block_id))
+        Box::pin(
+            self.right
+                .get_next_block(prev_block_id)
+                .map(|res| served_by(&self.right, res)),
+        )
     }
 
     fn get_block<'a>(&'a self, block_id_relation: &'a BlockIdRelation) -> Self::GetBlockFut<'a> {
         if self.cleanup_left_at.load(Ordering::Acquire) == u32::MAX {
             if let Some(left) = self.left.load_full() {
-                return Box::pin(async move { left.get_block(block_id_relation).await });
+                return Box::pin(async move {
+                    let res = left.get_block(block_id_relation).await;
+                    served_by(left.as_ref(), res)
+                });
             }
         }
 
-        Box::pin(self.right.get_block(block_id_relation))
+        Box::pin(
+            self.right
+                .get_block(block_id_relation)
+                .map(|res| served_by(&self.right, res)),
+        )
     }
 
     fn cleanup_until(&self, mc_seqno: u32) -> Self::CleanupFut<'_> {
@@ -219,6 +290,10 @@ impl<T1: BlockProvider, T2: BlockProvider> BlockProvider for ChainBlockProvider<
             self.right.cleanup_until(mc_seqno).await
         })
     }
+
+    fn is_composite(&self) -> bool {
+        true
+    }
 }
 
 pub struct CycleBlockProvider<T1, T2> {
@@ -237,9 +312,9 @@ impl<T1: BlockProvider, T2: BlockProvider> BlockProvider for CycleBlockProvider<
             let is_right = self.is_right.load(Ordering::Acquire);
 
             let res = if !is_right {
-                self.left.get_next_block(prev_block_id).await
+                served_by(&self.left, self.left.get_next_block(prev_block_id).await)
             } else {
-                self.right.get_next_block(prev_block_id).await
+                served_by(&self.right, self.right.get_next_block(prev_block_id).await)
             };
 
             if res.is_some() {
@@ -250,18 +325,26 @@ impl<T1: BlockProvider, T2: BlockProvider> BlockProvider for CycleBlockProvider<
             self.is_right.store(is_right, Ordering::Release);
 
             if !is_right {
-                self.left.get_next_block(prev_block_id).await
+                served_by(&self.left, self.left.get_next_block(prev_block_id).await)
             } else {
-                self.right.get_next_block(prev_block_id).await
+                served_by(&self.right, self.right.get_next_block(prev_block_id).await)
             }
         })
     }
 
     fn get_block<'a>(&'a self, block_id_relation: &'a BlockIdRelation) -> Self::GetBlockFut<'a> {
         if self.is_right.load(Ordering::Acquire) {
-            Box::pin(self.right.get_block(block_id_relation))
+            Box::pin(
+                self.right
+                    .get_block(block_id_relation)
+                    .map(|res| served_by(&self.right, res)),
+            )
         } else {
-            Box::pin(self.left.get_block(block_id_relation))
+            Box::pin(
+                self.left
+                    .get_block(block_id_relation)
+                    .map(|res| served_by(&self.left, res)),
+            )
         }
     }
 
@@ -275,6 +358,10 @@ impl<T1: BlockProvider, T2: BlockProvider> BlockProvider for CycleBlockProvider<
             }
         })
     }
+
+    fn is_composite(&self) -> bool {
+        true
+    }
 }
 
 pub struct RetryBlockProvider<T> {
@@ -312,6 +399,14 @@ impl<T: BlockProvider> BlockProvider for RetryBlockProvider<T> {
     fn cleanup_until(&self, mc_seqno: u32) -> Self::CleanupFut<'_> {
         self.inner.cleanup_until(mc_seqno)
     }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn is_composite(&self) -> bool {
+        self.inner.is_composite()
+    }
 }
 
 macro_rules! impl_provider_tuple {
@@ -330,7 +425,8 @@ macro_rules! impl_provider_tuple {
                 &'a self,
                 prev_block_id: &'a BlockId,
             ) -> Self::GetNextBlockFut<'a> {
-                $(let $var = self.$n.get_next_block(prev_block_id));*;
+                $(let $var = self.$n.get_next_block(prev_block_id)
+                    .map(|res| served_by(&self.$n, res)));*;
 
                 Box::pin(async move {
                     $(let $var = pin!($var));*;
@@ -339,7 +435,8 @@ macro_rules! impl_provider_tuple {
             }
 
             fn get_block<'a>(&'a self, block_id_relation: &'a BlockIdRelation) -> Self::GetBlockFut<'a> {
-                $(let $var = self.$n.get_block(block_id_relation));*;
+                $(let $var = self.$n.get_block(block_id_relation)
+                    .map(|res| served_by(&self.$n, res)));*;
 
                 Box::pin(async move {
                     $(let $var = pin!($var));*;
@@ -357,6 +454,10 @@ macro_rules! impl_provider_tuple {
                     }
                 })
             }
+
+            fn is_composite(&self) -> bool {
+                true
+            }
         }
     };
 }
@@ -432,6 +533,33 @@ impl ProofChecker {
         }
     }
 
+    /// Loads the block entry from the archive and checks its proof and queue diff.
+    ///
+    /// Archives may come from untrusted sources, so no entry must be used without this check.
+    pub async fn check_archive_entry(
+        &self,
+        archive: &Arc<Archive>,
+        mc_block_id: &BlockId,
+        block_id: &BlockId,
+        store_on_success: bool,
+    ) -> Result<BlockStuffAug> {
+        let (block, ref proof, ref queue_diff) = match archive.get_entry_by_id(block_id).await {
+            Ok(entry) => entry,
+            Err(e) => anyhow::bail!("archive is corrupted: {e:?}"),
+        };
+
+        self.check_proof(CheckProof {
+            mc_block_id,
+            block: &block,
+            proof,
+            queue_diff,
+            store_on_success,
+        })
+        .await?;
+
+        Ok(block)
+    }
+
     pub async fn check_proof(&self, ctx: CheckProof<'_>) -> Result<NewBlockMeta> {
         // TODO: Add labels with shard?
         let _histogram = HistogramGuard::begin("tycho_core_check_block_proof_time");
@@ -739,6 +867,98 @@ mod test {
         assert!(block.is_none());
     }
 
+    #[test]
+    fn chain_block_provider_attributes_blocks() {
+        use tycho_util::test::TestRecorder;
+
+        const PROVIDED_BLOCKS: &str = "tycho_core_block_provider_blocks";
+
+        struct NamedProvider {
+            name: &'static str,
+            inner: Arc<MockBlockProvider>,
+        }
+
+        impl BlockProvider for NamedProvider {
+            type GetNextBlockFut<'a> = BoxFuture<'a, OptionalBlockStuff>;
+            type GetBlockFut<'a> = BoxFuture<'a, OptionalBlockStuff>;
+            type CleanupFut<'a> = future::Ready<Result<()>>;
+
+            fn get_next_block<'a>(
+                &'a self,
+                prev_block_id: &'a BlockId,
+            ) -> Self::GetNextBlockFut<'a> {
+                self.inner.get_next_block(prev_block_id)
+            }
+
+            fn get_block<'a>(&'a self, block_id: &'a BlockIdRelation) -> Self::GetBlockFut<'a> {
+                self.inner.get_block(block_id)
+            }
+
+            fn cleanup_until(&self, mc_seqno: u32) -> Self::CleanupFut<'_> {
+                self.inner.cleanup_until(mc_seqno)
+            }
+
+            fn name(&self) -> &str {
+                self.name
+            }
+        }
+
+        let left_provider = Arc::new(MockBlockProvider {
+            has_block: AtomicBool::new(true),
+        });
+        let right_provider = Arc::new(MockBlockProvider {
+            has_block: AtomicBool::new(false),
+        });
+
+        let chain_provider = ChainBlockProvider::new(
+            NamedProvider {
+                name: "archive",
+                inner: left_provider.clone(),
+            },
+            NamedProvider {
+                name: "blockchain",
+                inner: right_provider.clone(),
+            },
+        );
+        assert_eq!(chain_provider.name(), "ChainBlockProvider");
+
+        let recorder = TestRecorder::default();
+        let provided =
+            |provider| recorder.counter_with_label(PROVIDED_BLOCKS, "provider", provider);
+        metrics::with_local_recorder(&recorder, || {
+            // Same as the strider does with its provider
+            let next_block = || {
+                let res = chain_provider
+                    .get_next_block(&get_default_block_id())
+                    .now_or_never()
+                    .unwrap();
+                served_by(&chain_provider, res)
+            };
+
+            next_block().unwrap().unwrap();
+            next_block().unwrap().unwrap();
+            assert_eq!(provided("archive"), 2);
+            assert_eq!(provided("blockchain"), 0);
+
+            // Blocks are counted only once for the leaf provider
+            assert_eq!(provided("ChainBlockProvider"), 0);
+
+            // Switch to the right provider
+            left_provider.has_block.store(false, Ordering::Release);
+            right_provider.has_block.store(true, Ordering::Release);
+
+            next_block().unwrap().unwrap();
+            assert_eq!(provided("archive"), 2);
+            assert_eq!(provided("blockchain"), 1);
+
+            // Nothing is attributed when there are no blocks
+            right_provider.has_block.store(false, Ordering::Release);
+            assert!(next_block().is_none());
+            assert_eq!(provided("archive"), 2);
+            assert_eq!(provided("blockchain"), 1);
+        });
+    }
+
     fn get_empty_block() -> BlockStuffAug {
         let block_data = include_bytes!("../../../tests/data/empty_block.bin");
         let root = Boc::decode(block_data).unwrap();
@@ -0,0 +1,146 @@
+use anyhow::Result;
+use everscale_types::models::BlockId;
+use futures_util::future;
+use tycho_block_util::block::{BlockIdRelation, BlockStuffAug};
+
+use crate::block_strider::provider::{BlockProvider, OptionalBlockStuff};
+
+/// Serves a fixed set of blocks, i.e. for deterministic tests or a replay.
+///
+/// Returns `None` when the requested block is not in the set.
+#[derive(Default, Clone)]
+pub struct VecBlockProvider {
+    blocks: Vec<BlockStuffAug>,
+}
+
+impl VecBlockProvider {
+    pub fn new<I>(blocks: I) -> Self
+    where
+        I: IntoIterator<Item = BlockStuffAug>,
+    {
+        Self {
+            blocks: blocks.into_iter().collect(),
+        }
+    }
+
+    pub fn blocks(&self) -> &[BlockStuffAug] {
+        &self.blocks
+    }
+
+    /// Finds a block with the same shard and seqno, its hashes must match the `block_id`.
+    fn find(&self, block_id: &BlockId) -> OptionalBlockStuff {
+        let block = self.blocks.iter().find(|block| {
+            let id = block.id();
+            id.shard == block_id.shard && id.seqno == block_id.seqno
+        })?;
+
+        if block.id() != block_id {
+            return Some(Err(anyhow::anyhow!(
+                "block id mismatch: requested {block_id}, stored {}",
+                block.id()
+            )));
+        }
+
+        Some(Ok(block.clone()))
+    }
+
+    fn next(&self, prev_block_id: &BlockId) -> OptionalBlockStuff {
+        if let Some(Err(e)) = self.find(prev_block_id) {
+            return Some(Err(e.context("unknown previous block")));
+        }
+
+        let next_seqno = prev_block_id.seqno.checked_add(1)?;
+        let block = self.blocks.iter().find(|block| {
+            let id = block.id();
+            id.shard == prev_block_id.shard && id.seqno == next_seqno
+        })?;
+
+        Some(Ok(block.clone()))
+    }
+}
+
+impl BlockProvider for VecBlockProvider {
+    type GetNextBlockFut<'a> = future::Ready<OptionalBlockStuff>;
+    type GetBlockFut<'a> = future::Ready<OptionalBlockStuff>;
+    type CleanupFut<'a> = future::Ready<Result<()>>;
+
+    fn get_next_block<'a>(&'a self, prev_block_id: &'a BlockId) -> Self::GetNextBlockFut<'a> {
+        future::ready(self.next(prev_block_id))
+    }
+
+    fn get_block<'a>(&'a self, block_id_relation: &'a BlockIdRelation) -> Self::GetBlockFut<'a> {
+        future::ready(self.find(&block_id_relation.block_id))
+    }
+
+    fn cleanup_until(&self, _mc_seqno: u32) -> Self::CleanupFut<'_> {
+        future::ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use everscale_types::models::ShardIdent;
+    use tycho_block_util::block::BlockStuff;
+
+    use super::*;
+
+    fn make_block(shard: ShardIdent, seqno: u32) -> BlockStuffAug {
+        BlockStuff::new_empty(shard, seqno).with_archive_data(Vec::new())
+    }
+
+    fn relation(block_id: &BlockId) -> BlockIdRelation {
+        BlockIdRelation {
+            mc_block_id: *block_id,
+            block_id: *block_id,
+        }
+    }
+
+    #[tokio::test]
+    async fn serves_blocks_until_exhausted() -> Result<()> {
+        let blocks = (1..=3).map(|seqno| make_block(ShardIdent::MASTERCHAIN, seqno));
+        let provider = VecBlockProvider::new(blocks);
+
+        let mut prev_block_id = BlockId {
+            shard: ShardIdent::MASTERCHAIN,
+            seqno: 0,
+            ..Default::default()
+        };
+        for seqno in 1..=3 {
+            let block = provider.get_next_block(&prev_block_id).await.unwrap()?;
+            assert_eq!(block.id().seqno, seqno);
+            prev_block_id = *block.id();
+        }
+
+        assert!(provider.get_next_block(&prev_block_id).await.is_none());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn get_block_out_of_order() -> Result<()> {
+        let provider = VecBlockProvider::new([
+            make_block(ShardIdent::MASTERCHAIN, 1),
+            make_block(ShardIdent::BASECHAIN, 1),
+            make_block(ShardIdent::MASTERCHAIN, 2),
+        ]);
+
+        for block in provider.blocks().iter().rev() {
+            let served = provider.get_block(&relation(block.id())).await.unwrap()?;
+            assert_eq!(served.id(), block.id());
+        }
+
+        // Unknown block
+        let unknown_id = *make_block(ShardIdent::BASECHAIN, 2).id();
+        assert!(provider.get_block(&relation(&unknown_id)).await.is_none());
+
+        // Same shard and seqno but different hash
+        let mut wrong_id = *provider.blocks()[0].id();
+        wrong_id.root_hash = [1; 32].into();
+        let res = provider.get_block(&relation(&wrong_id)).await;
+        assert!(matches!(res, Some(Err(_))));
+        let res = provider.get_next_block(&wrong_id).await;
+        assert!(matches!(res, Some(Err(_))));
+
+        Ok(())
+    }
+}
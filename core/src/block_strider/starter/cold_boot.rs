@@ -1,7 +1,7 @@
 use std::fs::File;
 use std::pin::pin;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
 use everscale_types::models::*;
@@ -13,15 +13,15 @@ use tycho_block_util::block::{BlockProofStuff, BlockProofStuffAug, BlockStuff};
 use tycho_block_util::queue::QueueDiffStuff;
 use tycho_block_util::state::{MinRefMcStateTracker, ShardStateStuff};
 use tycho_storage::{
-    BlockHandle, FileBuilder, KeyBlocksDirection, MaybeExistingHandle, NewBlockMeta,
-    PersistentStateKind, Storage,
+    BlockHandle, BlockWriteBatchConfig, FileBuilder, KeyBlocksDirection, MaybeExistingHandle,
+    NewBlockMeta, PersistentStateKind, Storage,
 };
 use tycho_util::futures::JoinTask;
 use tycho_util::sync::rayon_run;
 use tycho_util::time::now_sec;
-use tycho_util::FastHashMap;
+use tycho_util::{FastHashMap, FastHashSet};
 
-use super::{ColdBootType, StarterInner, ZerostateProvider};
+use super::{ColdBootType, KeyBlocksBatchConfig, StarterInner, ZerostateProvider};
 use crate::block_strider::{CheckProof, ProofChecker};
 use crate::blockchain_rpc::{BlockchainRpcClient, DataRequirement};
 use crate::overlay_client::PunishReason;
@@ -51,7 +51,11 @@ impl StarterInner {
                 let init_block = self.prepare_init_block(zerostates).await?;
 
                 // Ensure that all key blocks until now (with some offset) are downloaded
-                self.download_key_blocks(init_block).await?;
+                let block_storage = self.storage.block_storage();
+                block_storage.begin_write_batch(KEY_BLOCKS_WRITE_BATCH);
+                let res = self.download_key_blocks(init_block).await;
+                block_storage.end_write_batch()?;
+                res?;
 
                 // Choose the latest key block with persistent state
                 let last_key_block = self.choose_key_block()?;
@@ -67,9 +71,13 @@ impl StarterInner {
             }
         };
 
-        self.storage
-            .node_state()
-            .store_last_mc_block_id(&last_mc_block_id);
+        // NOTE: The starting block is not applied by the block strider,
+        // so it is marked as committed here to be a valid resume point.
+        let node_state = self.storage.node_state();
+        match (self.storage.block_handle_storage()).load_handle(&last_mc_block_id) {
+            Some(handle) => node_state.commit_last_mc_block(&handle),
+            None => node_state.store_last_mc_block_id(&last_mc_block_id),
+        }
         tracing::info!(last_mc_block_id = %last_mc_block_id, "finished");
 
         Ok(last_mc_block_id)
@@ -129,7 +137,6 @@ impl StarterInner {
 
     /// Download all key blocks since the initial block.
     async fn download_key_blocks(&self, mut prev_key_block: InitBlock) -> Result<()> {
-        const BLOCKS_PER_BATCH: u32 = 10;
         const PARALLEL_REQUESTS: usize = 10;
 
         let (ids_tx, mut ids_rx) = mpsc::unbounded_channel();
@@ -137,15 +144,18 @@ impl StarterInner {
 
         tokio::spawn({
             let blockchain_rpc_client = self.blockchain_rpc_client.clone();
+            let mut batch_size = KeyBlocksBatchSize::new(self.config.key_blocks_batch);
 
             async move {
                 while let Some(block_id) = tasks_rx.recv().await {
                     // TODO: add retry count to interrupt infinite loop
                     'inner: loop {
-                        tracing::debug!(%block_id, "start downloading next key blocks");
+                        let max_size = batch_size.get();
+                        tracing::debug!(%block_id, max_size, "start downloading next key blocks");
 
+                        let started_at = Instant::now();
                         let res = blockchain_rpc_client
-                            .get_next_key_block_ids(&block_id, BLOCKS_PER_BATCH)
+                            .get_next_key_block_ids(&block_id, max_size)
                             .await;
 
                         match res {
@@ -153,6 +163,9 @@ impl StarterInner {
                                 let (handle, data) = res.split();
                                 handle.accept();
 
+                                let roundtrip = started_at.elapsed();
+                                batch_size.on_response(roundtrip, data.incomplete);
+
                                 if ids_tx.send((block_id, data.block_ids)).is_err() {
                                     tracing::debug!(%block_id, "stop downloading next key blocks");
                                     return;
@@ -162,6 +175,7 @@ impl StarterInner {
                             }
                             Err(e) => {
                                 tracing::warn!(%block_id, "failed to download key block ids: {e:?}");
+                                batch_size.on_error();
 
                                 tokio::time::sleep(Duration::from_secs(1)).await;
                             }
@@ -218,6 +232,8 @@ impl StarterInner {
 
                         // Update init_mc_block_id
                         if BlockStuff::compute_is_persistent(block_utime, prev_utime) {
+                            // NOTE: Init block must not point to a not yet written proof
+                            self.storage.block_storage().flush()?;
                             self.storage
                                 .node_state()
                                 .store_init_mc_block_id(handle.id());
@@ -282,40 +298,29 @@ impl StarterInner {
 
     /// Select the latest suitable key block with persistent state
     fn choose_key_block(&self) -> Result<BlockHandle> {
+        let stored = self.stored_persistent_key_blocks();
+        select_key_block(&self.storage, &stored)
+    }
+
+    /// Returns ids of key blocks with a stored masterchain persistent state,
+    /// judging by block handles only.
+    ///
+    /// NOTE: Masterchain state is stored after all shard states of its block,
+    /// and shard states missing after an interrupted boot are downloaded again.
+    fn stored_persistent_key_blocks(&self) -> FastHashSet<BlockId> {
         let block_handle_storage = self.storage.block_handle_storage();
 
-        let mut key_blocks = block_handle_storage
-            .key_blocks_iterator(KeyBlocksDirection::Backward)
-            .map(|block_id| {
+        (self.storage.persistent_state_storage().persistent_states())
+            .into_iter()
+            .filter(|block_id| block_id.is_masterchain() && block_id.seqno != 0)
+            .filter(|block_id| {
                 block_handle_storage
-                    .load_handle(&block_id)
-                    .context("Key block handle not found")
+                    .load_handle(block_id)
+                    .is_some_and(|handle| {
+                        handle.is_key_block() && handle.has_persistent_shard_state()
+                    })
             })
-            .peekable();
-
-        // Iterate all key blocks in reverse order (from the latest to the oldest)
-        while let Some(handle) = key_blocks.next().transpose()? {
-            let handle_utime = handle.gen_utime();
-            let prev_utime = match key_blocks.peek() {
-                Some(Ok(prev_block)) => prev_block.gen_utime(),
-                Some(Err(e)) => anyhow::bail!("failed to load previous key block: {e:?}"),
-                None => 0,
-            };
-
-            // Skip not persistent
-            let is_persistent = BlockStuff::compute_is_persistent(handle_utime, prev_utime);
-            if !is_persistent {
-                tracing::debug!(seq_no = handle.id().seqno, "skipping key block");
-                continue;
-            }
-
-            // Use first suitable key block
-            tracing::info!(block_id = %handle.id(), "found best key block handle");
-            return Ok(handle);
-        }
-
-        // NOTE: Should be unreachable since we will definitely have a zerostate
-        anyhow::bail!("no suitable key block found")
+            .collect()
     }
 
     async fn download_start_blocks_and_states(&self, mc_block_id: &BlockId) -> Result<()> {
@@ -330,7 +335,7 @@ impl StarterInner {
         );
 
         // Download and save blocks and states from other shards
-        for (_, block_id) in init_mc_block.shard_blocks()? {
+        for block_id in init_mc_block.shard_block_ids()? {
             let (handle, _) = self
                 .download_block_with_states(mc_block_id, &block_id)
                 .await?;
@@ -530,9 +535,13 @@ impl StarterInner {
 
         // TODO: add retry count to interrupt infinite loop
         'outer: loop {
+            // NOTE: Block data is decompressed into a temp file so that only
+            //       the parsed block is kept in memory.
+            let file = self.storage.temp_file_storage().unnamed_file().open()?;
+
             let (full, neighbour) = 'res: {
                 match rpc
-                    .get_block_full(block_id, DataRequirement::Expected)
+                    .get_block_full_to_file(block_id, DataRequirement::Expected, file)
                     .await
                 {
                     Ok(res) => match res.data {
@@ -952,5 +961,201 @@ impl InitBlock {
     }
 }
 
+/// Adjusts the number of requested key block ids by the response time:
+/// grows while full batches are received fast and shrinks on slow or failed responses.
+///
+/// A batch is full when it reached `min(requested, server limit)`, i.e. the server
+/// did not mark it as incomplete (which only happens at the end of the chain).
+struct KeyBlocksBatchSize {
+    config: KeyBlocksBatchConfig,
+    current: u32,
+}
+
+impl KeyBlocksBatchSize {
+    fn new(config: KeyBlocksBatchConfig) -> Self {
+        let min = config.min.max(1);
+        let max = config.max.max(min);
+        Self {
+            config: KeyBlocksBatchConfig { min, max, ..config },
+            current: config.initial.clamp(min, max),
+        }
+    }
+
+    fn get(&self) -> u32 {
+        self.current
+    }
+
+    fn on_response(&mut self, roundtrip: Duration, incomplete: bool) {
+        if roundtrip > self.config.target_roundtrip {
+            self.shrink();
+        } else if !incomplete {
+            // NOTE: The server might return less ids than requested due to its own limit,
+            // but other servers might allow more.
+            self.current = self.current.saturating_mul(2).min(self.config.max);
+        }
+    }
+
+    fn on_error(&mut self) {
+        self.shrink();
+    }
+
+    fn shrink(&mut self) {
+        self.current = (self.current / 2).max(self.config.min);
+    }
+}
+
 const MAX_EMPTY_PROOF_RETRIES: usize = 10;
 const MAX_PERSISTENT_STATE_RETRIES: usize = 10;
+
+const KEY_BLOCKS_WRITE_BATCH: BlockWriteBatchConfig = BlockWriteBatchConfig {
+    max_entries: 100,
+    max_delay: Duration::from_millis(500),
+};
+
+/// Iterates all key blocks in reverse order (from the latest to the oldest)
+/// and returns the first one which either starts a new persistent state period
+/// or has its persistent state already stored (e.g. after an interrupted boot).
+fn select_key_block(storage: &Storage, stored: &FastHashSet<BlockId>) -> Result<BlockHandle> {
+    let block_handle_storage = storage.block_handle_storage();
+
+    let mut key_blocks = block_handle_storage
+        .key_blocks_iterator(KeyBlocksDirection::Backward)
+        .map(|block_id| {
+            block_handle_storage
+                .load_handle(&block_id)
+                .context("Key block handle not found")
+        })
+        .peekable();
+
+    while let Some(handle) = key_blocks.next().transpose()? {
+        let handle_utime = handle.gen_utime();
+        let prev_utime = match key_blocks.peek() {
+            Some(Ok(prev_block)) => prev_block.gen_utime(),
+            Some(Err(e)) => anyhow::bail!("failed to load previous key block: {e:?}"),
+            None => 0,
+        };
+
+        if stored.contains(handle.id()) {
+            tracing::info!(block_id = %handle.id(), "found stored persistent state");
+            return Ok(handle);
+        }
+
+        // Skip not persistent
+        let is_persistent = BlockStuff::compute_is_persistent(handle_utime, prev_utime);
+        if !is_persistent {
+            tracing::debug!(seq_no = handle.id().seqno, "skipping key block");
+            continue;
+        }
+
+        // Use first suitable key block
+        tracing::info!(block_id = %handle.id(), "found best key block handle");
+        return Ok(handle);
+    }
+
+    // NOTE: Should be unreachable since we will definitely have a zerostate
+    anyhow::bail!("no suitable key block found")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_blocks_batch_size_adjusts_within_bounds() {
+        let config = KeyBlocksBatchConfig {
+            initial: 10,
+            min: 2,
+            max: 50,
+            target_roundtrip: Duration::from_millis(500),
+        };
+        let fast = Duration::from_millis(100);
+        let slow = Duration::from_secs(2);
+
+        let mut batch_size = KeyBlocksBatchSize::new(config);
+        assert_eq!(batch_size.get(), 10);
+
+        // fast full responses grow the batch up to max,
+        // even if the server limit is lower than requested
+        let mut sizes = Vec::new();
+        for _ in 0..5 {
+            batch_size.on_response(fast, false);
+            sizes.push(batch_size.get());
+        }
+        assert_eq!(sizes, [20, 40, 50, 50, 50]);
+
+        // the end of the chain neither grows nor shrinks it
+        let mut batch_size = KeyBlocksBatchSize::new(config);
+        batch_size.on_response(fast, true);
+        assert_eq!(batch_size.get(), 10);
+        let mut batch_size = KeyBlocksBatchSize::new(KeyBlocksBatchConfig {
+            initial: 50,
+            ..config
+        });
+
+        // slow responses and errors shrink it down to min
+        let mut sizes = Vec::new();
+        for i in 0..6 {
+            match i % 2 {
+                0 => batch_size.on_response(slow, false),
+                _ => batch_size.on_error(),
+            }
+            sizes.push(batch_size.get());
+        }
+        assert_eq!(sizes, [25, 12, 6, 3, 2, 2]);
+
+        // initial value is clamped by bounds
+        let config = KeyBlocksBatchConfig {
+            initial: 1000,
+            ..config
+        };
+        assert_eq!(KeyBlocksBatchSize::new(config).get(), 50);
+    }
+
+    #[tokio::test]
+    async fn select_key_block_prefers_latest_suitable() -> Result<()> {
+        const PERIOD: u32 = 1 << 17;
+
+        let (storage, _tmp_dir) = Storage::new_temp().await?;
+
+        let key_block_id = |seqno: u32| BlockId {
+            shard: ShardIdent::MASTERCHAIN,
+            seqno,
+            root_hash: HashBytes([seqno as u8; 32]),
+            file_hash: HashBytes([seqno as u8; 32]),
+        };
+
+        // Zerostate and two key blocks per persistent period
+        let handles = storage.block_handle_storage();
+        for (seqno, gen_utime) in [(0, 0), (10, PERIOD), (20, PERIOD + 1), (30, 2 * PERIOD)] {
+            let (handle, _) = handles.create_or_load_handle(&key_block_id(seqno), NewBlockMeta {
+                is_key_block: true,
+                gen_utime,
+                ref_by_mc_seqno: seqno,
+            });
+            assert!(handle.is_key_block());
+        }
+
+        let select = |stored: &[u32]| {
+            let stored = stored.iter().map(|&seqno| key_block_id(seqno)).collect();
+            select_key_block(&storage, &stored).map(|handle| handle.id().seqno)
+        };
+
+        // The latest persistent key block
+        assert_eq!(select(&[])?, 30);
+
+        // A newer persistent key block wins over older stored states
+        assert_eq!(select(&[10])?, 30);
+        assert_eq!(select(&[0, 20])?, 30);
+
+        // Stored states are used even if the key block is not a persistent one
+        handles.create_or_load_handle(&key_block_id(40), NewBlockMeta {
+            is_key_block: true,
+            gen_utime: 2 * PERIOD + 1,
+            ref_by_mc_seqno: 40,
+        });
+        assert_eq!(select(&[])?, 30);
+        assert_eq!(select(&[40])?, 40);
+
+        Ok(())
+    }
+}
@@ -8,12 +8,13 @@ use everscale_types::models::{BlockId, ShardStateUnsplit};
 use serde::{Deserialize, Serialize};
 use tycho_block_util::state::{MinRefMcStateTracker, ShardStateStuff};
 use tycho_storage::Storage;
-use tycho_util::serde_helpers;
+use tycho_util::{serde_helpers, FastHashSet};
 
 use crate::blockchain_rpc::BlockchainRpcClient;
-use crate::global_config::ZerostateId;
+use crate::global_config::{ZerostateId, ZerostateIds};
 
 mod cold_boot;
+mod warm_boot;
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
@@ -23,6 +24,54 @@ pub struct StarterConfig {
     /// Default: None
     #[serde(with = "serde_helpers::humantime")]
     pub custom_boot_offset: Option<Duration>,
+
+    /// Check that data, proof and state of the last stored block are present
+    /// and consistent before a warm boot, and fall back to a cold boot if not.
+    /// Loads the full state, so it slows down the start.
+    ///
+    /// Default: false
+    #[serde(default)]
+    pub verify_warm_boot: bool,
+
+    /// Number of key block ids requested at once during cold boot.
+    #[serde(default)]
+    pub key_blocks_batch: KeyBlocksBatchConfig,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeyBlocksBatchConfig {
+    /// Batch size of the first request.
+    ///
+    /// Default: 10
+    pub initial: u32,
+
+    /// Batch is halved (but not below this value) when a response is slower
+    /// than `target_roundtrip` or fails.
+    ///
+    /// Default: 1
+    pub min: u32,
+
+    /// Batch is doubled (but not above this value) when a full batch is received
+    /// faster than `target_roundtrip`. A batch limited by the server is also full.
+    ///
+    /// Default: 100
+    pub max: u32,
+
+    /// Default: 1 second
+    #[serde(with = "serde_helpers::humantime")]
+    pub target_roundtrip: Duration,
+}
+
+impl Default for KeyBlocksBatchConfig {
+    fn default() -> Self {
+        Self {
+            initial: 10,
+            min: 1,
+            max: 100,
+            target_roundtrip: Duration::from_secs(1),
+        }
+    }
 }
 
 /// Bootstrapping utils.
@@ -50,10 +99,54 @@ impl Starter {
         }
     }
 
+    /// Selects the zerostate of the network among candidates from the global config:
+    /// the one which is already stored, or the first one found among zerostate files
+    /// to import, or the primary one (it will be downloaded).
+    ///
+    /// Must be called before the network is setup since overlay ids depend on it.
+    pub fn select_zerostate(
+        storage: &Storage,
+        candidates: &ZerostateIds,
+        import_zerostates: Option<&[PathBuf]>,
+    ) -> Result<ZerostateId> {
+        let handles = storage.block_handle_storage();
+        if let Some(stored) = candidates.find_first(|id| handles.load_handle(id).is_some()) {
+            return Ok(*stored);
+        }
+
+        let Some(paths) = import_zerostates else {
+            return Ok(*candidates.primary());
+        };
+
+        let tracker = MinRefMcStateTracker::new();
+        let mut provided = FastHashSet::default();
+        for path in paths {
+            let state = load_zerostate(&tracker, path)
+                .with_context(|| format!("failed to load zerostate {}", path.display()))?;
+            provided.insert(*state.block_id());
+        }
+
+        candidates
+            .find_first(|id| provided.contains(id))
+            .copied()
+            .context("none of the zerostate files matches the global config")
+    }
+
     pub fn config(&self) -> &StarterConfig {
         &self.inner.config
     }
 
+    /// Boot type when the node has already synced some blocks
+    ///
+    /// Returns the last masterchain block id if the node can continue from it,
+    /// `None` if the cold boot is required.
+    ///
+    /// Fails if the stored last block is inconsistent,
+    /// since the cold boot can't be used over a populated DB.
+    pub async fn try_warm_boot(&self) -> Result<Option<BlockId>> {
+        self.inner.try_warm_boot().await
+    }
+
     /// Boot type when the node has not yet started syncing
     ///
     /// Returns the last masterchain key block id.
@@ -130,3 +223,55 @@ fn load_zerostate(tracker: &MinRefMcStateTracker, path: &PathBuf) -> Result<Shar
 
     ShardStateStuff::from_root(&block_id, root, tracker)
 }
+
+#[cfg(test)]
+mod tests {
+    use everscale_types::prelude::HashBytes;
+    use tycho_storage::NewBlockMeta;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn select_zerostate_candidate() -> Result<()> {
+        let (storage, _tmp_dir) = Storage::new_temp().await?;
+
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data/zerostate.boc");
+        let provided = *load_zerostate(&MinRefMcStateTracker::new(), &path)?.block_id();
+        let provided = ZerostateId {
+            root_hash: provided.root_hash,
+            file_hash: provided.file_hash,
+        };
+        let other = ZerostateId {
+            root_hash: HashBytes([1; 32]),
+            file_hash: HashBytes([2; 32]),
+        };
+
+        let candidates = ZerostateIds::new(vec![other, provided])?;
+        let files = [path];
+
+        // Primary one is downloaded
+        let selected = Starter::select_zerostate(&storage, &candidates, None)?;
+        assert_eq!(selected, other);
+
+        // The first candidate found among files
+        let selected = Starter::select_zerostate(&storage, &candidates, Some(files.as_slice()))?;
+        assert_eq!(selected, provided);
+
+        let only_other = ZerostateIds::from(other);
+        assert!(Starter::select_zerostate(&storage, &only_other, Some(files.as_slice())).is_err());
+
+        // Already imported zerostate is used after restart
+        storage.block_handle_storage().create_or_load_handle(
+            &provided.as_block_id(),
+            NewBlockMeta {
+                is_key_block: true,
+                gen_utime: 0,
+                ref_by_mc_seqno: 0,
+            },
+        );
+        let selected = Starter::select_zerostate(&storage, &candidates, None)?;
+        assert_eq!(selected, provided);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,139 @@
+use anyhow::{Context, Result};
+use everscale_types::models::BlockId;
+use tycho_block_util::block::{BlockProofStuff, BlockStuff};
+use tycho_storage::Storage;
+
+use super::StarterInner;
+
+impl StarterInner {
+    #[tracing::instrument(skip_all)]
+    pub async fn try_warm_boot(&self) -> Result<Option<BlockId>> {
+        find_warm_boot_block(&self.storage, self.config.verify_warm_boot).await
+    }
+}
+
+async fn find_warm_boot_block(storage: &Storage, verify: bool) -> Result<Option<BlockId>> {
+    let Some(last_mc_block_id) = storage.node_state().load_last_mc_block_id() else {
+        return Ok(None);
+    };
+
+    if verify {
+        if let Err(e) = verify_stored_block(storage, &last_mc_block_id).await {
+            tracing::warn!(
+                %last_mc_block_id,
+                "stored last block is inconsistent, falling back to cold boot: {e:?}"
+            );
+            return Ok(None);
+        }
+    }
+
+    Ok(Some(last_mc_block_id))
+}
+
+/// Checks that the block handle, data, proof and state are stored and match each other.
+async fn verify_stored_block(storage: &Storage, block_id: &BlockId) -> Result<()> {
+    // NOTE: Handle flags and stored entries are checked against each other
+    // as they were at the same moment.
+    let snapshot = storage.snapshot();
+    let handle = storage
+        .block_handle_storage()
+        .load_handle_at(&snapshot, block_id)
+        .context("block handle not found")?;
+    anyhow::ensure!(handle.has_state(), "block state is not marked as stored");
+
+    let states = storage.shard_state_storage();
+    states
+        .load_state_root_at(&snapshot, block_id)
+        .context("block state root not found")?;
+
+    // NOTE: Cells are shared between states, so the full state can only be loaded
+    // from the live storage. Its root was checked in the snapshot above.
+    let state = states
+        .load_state(block_id)
+        .await
+        .context("failed to load block state")?;
+
+    if block_id.seqno == 0 {
+        // Zerostate has no block data and proof
+        return Ok(());
+    }
+
+    let block_storage = storage.block_storage();
+
+    // NOTE: Root hash is checked on deserialization
+    let block = block_storage
+        .load_block_data_raw_at(&snapshot, &handle)
+        .and_then(|data| BlockStuff::deserialize(block_id, data.as_ref()))
+        .context("failed to load block data")?;
+    let proof = block_storage
+        .load_block_proof_raw_at(&snapshot, &handle)
+        .and_then(|data| BlockProofStuff::deserialize(block_id, data.as_ref()))
+        .context("failed to load block proof")?;
+    anyhow::ensure!(proof.id() == block_id, "block proof id mismatch");
+
+    let state_update = block.block().state_update.load()?;
+    anyhow::ensure!(
+        &state_update.new_hash == state.root_cell().repr_hash(),
+        "block state hash mismatch"
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use everscale_types::models::ShardIdent;
+    use tycho_storage::NewBlockMeta;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn missing_state_fails_check() -> Result<()> {
+        let (storage, _tmp_dir) = Storage::new_temp().await?;
+
+        let block_id = BlockId {
+            shard: ShardIdent::MASTERCHAIN,
+            seqno: 1,
+            ..Default::default()
+        };
+
+        let err = verify_stored_block(&storage, &block_id).await.unwrap_err();
+        assert!(err.to_string().contains("handle"), "{err:?}");
+
+        let (_handle, _) =
+            storage
+                .block_handle_storage()
+                .create_or_load_handle(&block_id, NewBlockMeta {
+                    is_key_block: false,
+                    gen_utime: 0,
+                    ref_by_mc_seqno: 1,
+                });
+        let err = verify_stored_block(&storage, &block_id).await.unwrap_err();
+        assert!(err.to_string().contains("state"), "{err:?}");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn missing_state_falls_back_to_cold_boot() -> Result<()> {
+        let (storage, _tmp_dir) = Storage::new_temp().await?;
+
+        // Empty DB requires a cold boot
+        assert_eq!(find_warm_boot_block(&storage, true).await?, None);
+
+        let block_id = BlockId {
+            shard: ShardIdent::MASTERCHAIN,
+            seqno: 1,
+            ..Default::default()
+        };
+        storage.node_state().store_last_mc_block_id(&block_id);
+
+        // Last block state is missing
+        assert_eq!(find_warm_boot_block(&storage, true).await?, None);
+
+        // Unless the check is disabled
+        assert_eq!(find_warm_boot_block(&storage, false).await?, Some(block_id));
+
+        Ok(())
+    }
+}
@@ -1,8 +1,9 @@
 use std::sync::Mutex;
 
+use anyhow::{Context, Result};
 use everscale_types::models::BlockId;
 use tycho_block_util::block::ShardHeights;
-use tycho_storage::Storage;
+use tycho_storage::{BlockConnection, Storage};
 
 #[derive(Debug, Clone, Copy)]
 pub struct CommitMasterBlock<'a> {
@@ -37,6 +38,51 @@ impl PersistentBlockStriderState {
             storage,
         }
     }
+
+    /// Validates the persisted position against the storage and rewinds it
+    /// to the latest fully applied masterchain block.
+    ///
+    /// Must be called before the block strider is started.
+    pub fn resume_from(&self) -> Result<BlockId> {
+        let node_state = self.storage.node_state();
+        let handles = self.storage.block_handle_storage();
+        let connections = self.storage.block_connection_storage();
+
+        let Some(last_mc_block_id) = node_state.load_last_mc_block_id() else {
+            return Ok(self.zerostate_id);
+        };
+        let init_mc_block_id = node_state.load_init_mc_block_id();
+
+        let mut block_id = last_mc_block_id;
+        loop {
+            // Starting points are not applied by the block strider
+            if block_id.seqno == 0 || Some(block_id) == init_mc_block_id {
+                break;
+            }
+
+            let handle = handles
+                .load_handle(&block_id)
+                .with_context(|| format!("mc block handle not found: {block_id}"))?;
+            if handle.is_committed() {
+                break;
+            }
+
+            block_id = connections
+                .load_connection(&block_id, BlockConnection::Prev1)
+                .with_context(|| format!("prev mc block not found: {block_id}"))?;
+        }
+
+        if block_id != last_mc_block_id {
+            tracing::warn!(
+                %last_mc_block_id,
+                resume_from = %block_id,
+                "last mc block was not fully applied, rewinding",
+            );
+            node_state.store_last_mc_block_id(&block_id);
+        }
+
+        Ok(block_id)
+    }
 }
 
 impl BlockStriderState for PersistentBlockStriderState {
@@ -61,9 +107,19 @@ impl BlockStriderState for PersistentBlockStriderState {
 
     fn commit_master(&self, ctx: CommitMasterBlock<'_>) {
         assert!(ctx.block_id.is_masterchain());
-        self.storage
-            .node_state()
-            .store_last_mc_block_id(ctx.block_id);
+
+        let node_state = self.storage.node_state();
+        let handles = self.storage.block_handle_storage();
+        match handles.load_handle(ctx.block_id) {
+            Some(handle) => node_state.commit_last_mc_block(&handle),
+            None => {
+                tracing::warn!(
+                    block_id = %ctx.block_id,
+                    "committing master block without a block handle",
+                );
+                node_state.store_last_mc_block_id(ctx.block_id);
+            }
+        }
     }
 
     fn commit_shard(&self, ctx: CommitShardBlock<'_>) {
@@ -121,3 +177,71 @@ impl BlockStriderState for TempBlockStriderState {
         // TODO: Update shard height
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use everscale_types::models::ShardIdent;
+    use everscale_types::prelude::HashBytes;
+    use tycho_storage::NewBlockMeta;
+
+    use super::*;
+
+    fn mc_block_id(seqno: u32) -> BlockId {
+        BlockId {
+            shard: ShardIdent::MASTERCHAIN,
+            seqno,
+            root_hash: HashBytes([seqno as u8; 32]),
+            file_hash: HashBytes([seqno as u8; 32]),
+        }
+    }
+
+    #[tokio::test]
+    async fn resume_after_crash_mid_apply() -> Result<()> {
+        let (storage, _tmp_dir) = Storage::new_temp().await?;
+
+        let zerostate_id = mc_block_id(0);
+        let state = PersistentBlockStriderState::new(zerostate_id, storage.clone());
+        assert_eq!(state.resume_from()?, zerostate_id);
+
+        let handles = storage.block_handle_storage();
+        let connections = storage.block_connection_storage();
+        for seqno in 1..=3 {
+            let (handle, _) = handles.create_or_load_handle(&mc_block_id(seqno), NewBlockMeta {
+                is_key_block: false,
+                gen_utime: seqno,
+                ref_by_mc_seqno: seqno,
+            });
+            connections.store_connection(&handle, BlockConnection::Prev1, &mc_block_id(seqno - 1));
+        }
+
+        let shard_heights = ShardHeights::default();
+        for seqno in 1..=2 {
+            state.commit_master(CommitMasterBlock {
+                block_id: &mc_block_id(seqno),
+                is_key_block: false,
+                shard_heights: &shard_heights,
+            });
+        }
+
+        // Crash while applying the 3rd block: position is advanced only on commit
+        assert_eq!(state.resume_from()?, mc_block_id(2));
+        assert!(!handles.load_handle(&mc_block_id(3)).unwrap().is_committed());
+
+        // Position was persisted but the block was not fully applied
+        storage.node_state().store_last_mc_block_id(&mc_block_id(3));
+        assert_eq!(state.resume_from()?, mc_block_id(2));
+        assert_eq!(state.load_last_mc_block_id(), mc_block_id(2));
+        assert!(!state.is_committed(&mc_block_id(3)));
+
+        // Cold boot from a key block without known previous blocks
+        let (key_block, _) = handles.create_or_load_handle(&mc_block_id(10), NewBlockMeta {
+            is_key_block: true,
+            gen_utime: 10,
+            ref_by_mc_seqno: 10,
+        });
+        storage.node_state().commit_last_mc_block(&key_block);
+        assert_eq!(state.resume_from()?, mc_block_id(10));
+
+        Ok(())
+    }
+}
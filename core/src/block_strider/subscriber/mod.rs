@@ -15,11 +15,13 @@ pub use self::futures::{
 pub use self::gc_subscriber::{GcSubscriber, ManualGcTrigger};
 pub use self::metrics_subscriber::MetricsSubscriber;
 pub use self::ps_subscriber::PsSubscriber;
+pub use self::tx_index_subscriber::TransactionIndexSubscriber;
 
 mod futures;
 mod gc_subscriber;
 mod metrics_subscriber;
 mod ps_subscriber;
+mod tx_index_subscriber;
 
 // === trait BlockSubscriber ===
 
@@ -0,0 +1,172 @@
+use anyhow::{Context, Result};
+use futures_util::future::BoxFuture;
+use tokio::task::JoinHandle;
+use tycho_storage::{BlacklistedAccounts, Storage};
+
+use crate::block_strider::{BlockSubscriber, BlockSubscriberContext};
+
+/// Writes transactions of every applied block into the RPC storage index,
+/// so they can be queried without running the full RPC state.
+///
+/// Index schema (see `tycho_storage::tables`):
+/// - `transactions`: `workchain: i8, account: [u8; 32], lt: u64 (BE)` ->
+///   `mask: u8, tx_hash: [u8; 32], in_msg_hash: [u8; 32] (if any), tx BOC`.
+///   Queried by account with [`RpcStorage::get_transactions`].
+/// - `transactions_by_hash`: `tx_hash: [u8; 32]` -> transaction key and block id.
+///   Queried with [`RpcStorage::get_transaction`]
+///   and [`RpcStorage::get_transaction_block_id`].
+/// - `transactions_by_in_msg`: `in_msg_hash: [u8; 32]` -> transaction key.
+///   Queried with [`RpcStorage::get_dst_transaction`].
+///
+/// Reads use a snapshot which is updated after each masterchain block,
+/// so shard transactions become visible together with the referencing masterchain block.
+///
+/// NOTE: Must not be used together with the RPC block subscriber since both write the same index.
+///
+/// [`RpcStorage::get_transactions`]: tycho_storage::RpcStorage::get_transactions
+/// [`RpcStorage::get_transaction`]: tycho_storage::RpcStorage::get_transaction
+/// [`RpcStorage::get_transaction_block_id`]: tycho_storage::RpcStorage::get_transaction_block_id
+/// [`RpcStorage::get_dst_transaction`]: tycho_storage::RpcStorage::get_dst_transaction
+#[derive(Clone)]
+pub struct TransactionIndexSubscriber {
+    storage: Storage,
+    blacklist: Option<BlacklistedAccounts>,
+}
+
+impl TransactionIndexSubscriber {
+    /// Fails if the storage was built without RPC storage.
+    pub fn new(storage: Storage) -> Result<Self> {
+        storage
+            .rpc_storage()
+            .context("transaction index requires rpc storage")?;
+
+        Ok(Self {
+            storage,
+            blacklist: None,
+        })
+    }
+
+    /// Skip transactions of the specified accounts.
+    pub fn with_blacklist(mut self, blacklist: BlacklistedAccounts) -> Self {
+        self.blacklist = Some(blacklist);
+        self
+    }
+}
+
+impl BlockSubscriber for TransactionIndexSubscriber {
+    type Prepared = JoinHandle<Result<()>>;
+
+    type PrepareBlockFut<'a> = futures_util::future::Ready<Result<Self::Prepared>>;
+    type HandleBlockFut<'a> = BoxFuture<'a, Result<()>>;
+
+    fn prepare_block<'a>(&'a self, cx: &'a BlockSubscriberContext) -> Self::PrepareBlockFut<'a> {
+        let handle = tokio::task::spawn({
+            let this = self.clone();
+            let block = cx.block.clone();
+            async move {
+                let rpc_storage = this.storage.rpc_storage().expect("checked on creation");
+                rpc_storage.update(block, this.blacklist.as_ref()).await
+            }
+        });
+
+        futures_util::future::ready(Ok(handle))
+    }
+
+    fn handle_block<'a>(
+        &'a self,
+        cx: &'a BlockSubscriberContext,
+        prepared: Self::Prepared,
+    ) -> Self::HandleBlockFut<'a> {
+        Box::pin(async move {
+            prepared.await??;
+
+            // NOTE: Masterchain block is handled after all its shard blocks.
+            if cx.block.id().is_masterchain() {
+                let rpc_storage = self.storage.rpc_storage().expect("checked on creation");
+                rpc_storage.update_snapshot();
+            }
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use everscale_types::boc::Boc;
+    use everscale_types::cell::HashBytes;
+    use everscale_types::models::{Block, BlockId, StdAddr};
+    use tycho_block_util::block::BlockStuff;
+    use tycho_storage::StorageConfig;
+
+    use super::*;
+    use crate::block_strider::DelayedTasks;
+
+    #[tokio::test]
+    async fn block_transactions_are_indexed() -> Result<()> {
+        let tmp_dir = tempfile::tempdir()?;
+        let storage = Storage::builder()
+            .with_config(StorageConfig::new_potato(tmp_dir.path()))
+            .with_rpc_storage(true)
+            .build()
+            .await?;
+
+        let block_data = include_bytes!("../../../tests/data/block.bin");
+        let root = Boc::decode(block_data)?;
+        let block = root.parse::<Block>()?;
+        let info = block.load_info()?;
+        let block_id = BlockId {
+            shard: info.shard,
+            seqno: info.seqno,
+            root_hash: *root.repr_hash(),
+            file_hash: Boc::file_hash_blake(block_data),
+        };
+        let block = BlockStuff::from_block_and_root(&block_id, block, root, block_data.len())
+            .with_archive_data(block_data.as_slice());
+
+        // (account, tx hash) in the order of lt
+        let mut expected = Vec::<(HashBytes, HashBytes)>::new();
+        for item in block.data.load_extra()?.account_blocks.load()?.iter() {
+            let (account, _, account_block) = item?;
+            for item in account_block.transactions.values() {
+                let (_, tx_cell) = item?;
+                expected.push((account, *tx_cell.inner().repr_hash()));
+            }
+        }
+        assert!(!expected.is_empty(), "test block must contain transactions");
+
+        let subscriber = TransactionIndexSubscriber::new(storage.clone())?;
+        let (delayed_handle, delayed) = DelayedTasks::new();
+        let cx = BlockSubscriberContext {
+            mc_block_id: block_id,
+            mc_is_key_block: false,
+            is_key_block: false,
+            block: block.data,
+            archive_data: block.archive_data,
+            delayed,
+        };
+        let delayed_handle = delayed_handle.spawn();
+        let prepared = subscriber.prepare_block(&cx).await?;
+        subscriber.handle_block(&cx, prepared).await?;
+        delayed_handle.join().await?;
+
+        let rpc_storage = storage.rpc_storage().unwrap();
+        let workchain = block_id.shard.workchain() as i8;
+        for (account, tx_hash) in &expected {
+            // by hash
+            let tx = rpc_storage.get_transaction(tx_hash)?.expect("tx by hash");
+            assert_eq!(Boc::decode(tx)?.repr_hash(), tx_hash);
+            let tx_block_id = rpc_storage.get_transaction_block_id(tx_hash)?;
+            assert_eq!(tx_block_id, Some(block_id));
+
+            // by account
+            let account = StdAddr::new(workchain, *account);
+            let found = rpc_storage
+                .get_transactions(&account, None, 0)?
+                .map(|boc| Some(*Boc::decode(boc).ok()?.repr_hash()))
+                .any(|hash| hash == *tx_hash);
+            assert!(found, "tx {tx_hash} not found for account {account}");
+        }
+
+        Ok(())
+    }
+}
@@ -1,5 +1,5 @@
 use std::future::Future;
-use std::io::Write;
+use std::io::{Seek, Write};
 use std::num::{NonZeroU32, NonZeroU64};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -15,13 +15,14 @@ use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc;
 use tycho_block_util::archive::ArchiveVerifier;
 use tycho_network::{PublicOverlay, Request};
-use tycho_storage::PersistentStateKind;
+use tycho_storage::{MappedFile, PersistentStateKind};
 use tycho_util::compression::ZstdDecompressStream;
 use tycho_util::futures::JoinTask;
 use tycho_util::serde_helpers;
 
 use crate::overlay_client::{
-    Error, Neighbour, NeighbourType, PublicOverlayClient, QueryResponse, QueryResponseHandle,
+    Error, Neighbour, NeighbourType, Neighbours, PublicOverlayClient, QueryKind, QueryResponse,
+    QueryResponseHandle,
 };
 use crate::proto::blockchain::*;
 use crate::proto::overlay::BroadcastPrefix;
@@ -54,6 +55,12 @@ pub struct BlockchainRpcClientConfig {
     ///
     /// Default: 10.
     pub download_retries: usize,
+
+    /// Number of distinct neighbours to try a query with
+    /// before giving up on a retryable error
+    ///
+    /// Default: 3.
+    pub query_attempts: usize,
 }
 
 impl Default for BlockchainRpcClientConfig {
@@ -62,6 +69,7 @@ impl Default for BlockchainRpcClientConfig {
             min_broadcast_timeout: Duration::from_millis(100),
             too_new_archive_threshold: 4,
             download_retries: 10,
+            query_attempts: 3,
         }
     }
 }
@@ -250,20 +258,69 @@ impl BlockchainRpcClient {
         block: &BlockId,
         max_size: u32,
     ) -> Result<QueryResponse<KeyBlockIds>, Error> {
-        let client = &self.inner.overlay_client;
-        let data = client
-            .query::<_, KeyBlockIds>(&rpc::GetNextKeyBlockIds {
-                block_id: *block,
-                max_size,
-            })
-            .await?;
-        Ok(data)
+        self.query_with_failover::<KeyBlockIds>(Request::from_tl(rpc::GetNextKeyBlockIds {
+            block_id: *block,
+            max_size,
+        }))
+        .await
     }
 
     pub async fn get_block_full(
         &self,
         block: &BlockId,
         requirement: DataRequirement,
+    ) -> Result<BlockDataFullWithNeighbour, Error> {
+        self.get_block_full_impl(block, requirement, None).await
+    }
+
+    /// Same as [`Self::get_block_full`] but also asks another neighbour
+    /// if the first one doesn't respond in `hedge_delay`.
+    pub async fn get_block_full_hedged(
+        &self,
+        block: &BlockId,
+        requirement: DataRequirement,
+        hedge_delay: Duration,
+    ) -> Result<BlockDataFullWithNeighbour, Error> {
+        self.get_block_full_impl(block, requirement, Some(hedge_delay))
+            .await
+    }
+
+    /// Same as [`Self::get_block_full`] but decompresses the block data
+    /// into the specified file instead of memory. The returned block data
+    /// is mapped from this file.
+    ///
+    /// Can be used for large blocks to keep only their parsed representation in memory.
+    pub async fn get_block_full_to_file(
+        &self,
+        block: &BlockId,
+        requirement: DataRequirement,
+        file: std::fs::File,
+    ) -> Result<BlockDataFullWithNeighbour, Error> {
+        let overlay_client = self.inner.overlay_client.clone();
+
+        let Some(neighbour) = overlay_client.neighbours().choose() else {
+            return Err(Error::NoNeighbours);
+        };
+
+        let retries = self.inner.config.download_retries;
+
+        download_block_inner(
+            Request::from_tl(rpc::GetBlockFull { block_id: *block }),
+            overlay_client,
+            neighbour,
+            requirement,
+            retries,
+            None,
+            Some(file),
+        )
+        .await
+    }
+
+    async fn get_block_full_impl(
+        &self,
+        block: &BlockId,
+        requirement: DataRequirement,
+        hedge_delay: Option<Duration>,
     ) -> Result<BlockDataFullWithNeighbour, Error> {
         let overlay_client = self.inner.overlay_client.clone();
 
@@ -279,6 +336,8 @@ impl BlockchainRpcClient {
             neighbour,
             requirement,
             retries,
+            hedge_delay,
+            None,
         )
         .await
     }
@@ -304,6 +363,8 @@ impl BlockchainRpcClient {
             neighbour,
             requirement,
             retries,
+            None,
+            None,
         )
         .await
     }
@@ -312,26 +373,35 @@ impl BlockchainRpcClient {
         &self,
         block_id: &BlockId,
     ) -> Result<QueryResponse<KeyBlockProof>, Error> {
-        let client = &self.inner.overlay_client;
-        let data = client
-            .query::<_, KeyBlockProof>(&rpc::GetKeyBlockProof {
-                block_id: *block_id,
-            })
-            .await?;
-        Ok(data)
+        self.query_with_failover::<KeyBlockProof>(Request::from_tl(rpc::GetKeyBlockProof {
+            block_id: *block_id,
+        }))
+        .await
     }
 
     pub async fn get_persistent_state_info(
         &self,
         block_id: &BlockId,
     ) -> Result<QueryResponse<PersistentStateInfo>, Error> {
-        let client = &self.inner.overlay_client;
-        let data = client
-            .query::<_, PersistentStateInfo>(&rpc::GetPersistentShardStateInfo {
+        self.query_with_failover::<PersistentStateInfo>(Request::from_tl(
+            rpc::GetPersistentShardStateInfo {
                 block_id: *block_id,
-            })
-            .await?;
-        Ok(data)
+            },
+        ))
+        .await
+    }
+
+    async fn query_with_failover<A>(&self, req: Request) -> Result<QueryResponse<A>, Error>
+    where
+        for<'a> A: tl_proto::TlRead<'a, Repr = tl_proto::Boxed>,
+    {
+        let overlay_client = &self.inner.overlay_client;
+        query_with_failover(
+            overlay_client.neighbours(),
+            self.inner.config.query_attempts,
+            |neighbour| overlay_client.query_raw::<A>(neighbour, req.clone()),
+        )
+        .await
     }
 
     pub async fn get_persistent_state_part(
@@ -342,12 +412,13 @@ impl BlockchainRpcClient {
     ) -> Result<QueryResponse<Data>, Error> {
         let client = &self.inner.overlay_client;
         let data = client
-            .query_raw::<Data>(
+            .query_raw_with_kind::<Data>(
                 neighbour.clone(),
                 Request::from_tl(rpc::GetPersistentShardStateChunk {
                     block_id: *block_id,
                     offset,
                 }),
+                QueryKind::PersistentStateChunk,
             )
             .await?;
         Ok(data)
@@ -676,10 +747,20 @@ async fn download_block_inner(
     neighbour: Neighbour,
     requirement: DataRequirement,
     retries: usize,
+    hedge_delay: Option<Duration>,
+    output_file: Option<std::fs::File>,
 ) -> Result<BlockDataFullWithNeighbour, Error> {
-    let response = overlay_client
-        .query_raw::<BlockFull>(neighbour.clone(), req)
-        .await?;
+    let response = if let Some(delay) = hedge_delay {
+        overlay_client
+            .query_raw_hedged::<BlockFull>(neighbour, req, delay)
+            .await?
+    } else {
+        overlay_client
+            .query_raw::<BlockFull>(neighbour, req)
+            .await?
+    };
+    // NOTE: The rest of the block is downloaded from the neighbour which responded first
+    let neighbour = response.neighbour().clone();
 
     let (handle, block_full) = response.split();
 
@@ -729,9 +810,19 @@ async fn download_block_inner(
 
         // Buffer for decompressed data
         let mut decompressed = Vec::new();
+        // NOTE: Decompressed data is moved to the file after each chunk (if any)
+        let mut output_file = output_file.map(std::io::BufWriter::new);
+        let mut flush_decompressed = |decompressed: &mut Vec<u8>| -> std::io::Result<()> {
+            if let Some(file) = &mut output_file {
+                file.write_all(decompressed)?;
+                decompressed.clear();
+            }
+            Ok(())
+        };
 
         // Decompress chunk
         zstd_decoder.write(block_data.data.as_ref(), &mut decompressed)?;
+        flush_decompressed(&mut decompressed)?;
 
         // Receive and process chunks
         let mut downloaded = block_data.data.len() as u32;
@@ -752,6 +843,7 @@ async fn download_block_inner(
 
             // Decompress chunk
             zstd_decoder.write(chunk.as_ref(), &mut decompressed)?;
+            flush_decompressed(&mut decompressed)?;
 
             ScopeGuard::into_inner(guard).accept(); // defuse the guard
         }
@@ -761,7 +853,14 @@ async fn download_block_inner(
             "block size mismatch (target size: {target_size}; downloaded: {downloaded})",
         );
 
-        Ok(decompressed)
+        match output_file {
+            None => Ok(Bytes::from(decompressed)),
+            Some(file) => {
+                let mut file = file.into_inner().map_err(|e| e.into_error())?;
+                file.seek(std::io::SeekFrom::Start(0))?;
+                Ok(MappedFile::from_existing_file(file).map(Bytes::from_owner)?)
+            }
+        }
     });
 
     let mut stream =
@@ -792,7 +891,6 @@ async fn download_block_inner(
     let block_data = processing_task
         .await
         .map_err(|e| Error::Internal(anyhow::anyhow!("Failed to join blocking task: {e}")))?
-        .map(Bytes::from)
         .map_err(Error::Internal)?;
 
     Ok(BlockDataFullWithNeighbour {
@@ -899,10 +997,12 @@ async fn download_with_retries(
     neighbour: Neighbour,
     max_retries: usize,
 ) -> DownloadedChunkResult {
+    let kind = query_kind(&req);
+
     let mut retries = 0;
     loop {
         match overlay_client
-            .query_raw::<Data>(neighbour.clone(), req.clone())
+            .query_raw_with_kind::<Data>(neighbour.clone(), req.clone(), kind)
             .await
         {
             Ok(r) => {
@@ -922,6 +1022,53 @@ async fn download_with_retries(
     }
 }
 
+/// Chooses a timeout group by the request constructor.
+fn query_kind(req: &Request) -> QueryKind {
+    let Some(tl_id) = req.body.first_chunk() else {
+        return QueryKind::Default;
+    };
+
+    match u32::from_le_bytes(*tl_id) {
+        rpc::GetArchiveChunk::TL_ID => QueryKind::ArchiveChunk,
+        rpc::GetPersistentShardStateChunk::TL_ID | rpc::GetPersistentQueueStateChunk::TL_ID => {
+            QueryKind::PersistentStateChunk
+        }
+        _ => QueryKind::Default,
+    }
+}
+
+/// Sends a query to up to `attempts` distinct neighbours, stopping
+/// at the first success or at the first non-retryable error.
+///
+/// Neighbour stats are updated by the query itself, so a failed
+/// neighbour is less likely to be chosen by subsequent requests.
+async fn query_with_failover<T, F, Fut>(
+    neighbours: &Neighbours,
+    attempts: usize,
+    mut query: F,
+) -> Result<T, Error>
+where
+    F: FnMut(Neighbour) -> Fut,
+    Fut: Future<Output = Result<T, Error>>,
+{
+    let candidates = neighbours.choose_multiple(attempts.max(1), NeighbourType::All);
+
+    let mut last_error = Error::NoNeighbours;
+    for neighbour in candidates {
+        let peer_id = *neighbour.peer_id();
+        match query(neighbour).await {
+            Ok(res) => return Ok(res),
+            Err(e) if e.is_retryable() => {
+                tracing::debug!(%peer_id, "query failed, trying another neighbour: {e}");
+                last_error = e;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    Err(last_error)
+}
+
 type DownloadedChunkResult = Result<(QueryResponseHandle, Bytes), Error>;
 
 #[cfg(test)]
@@ -971,4 +1118,67 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn archive_chunk_uses_longer_timeout() {
+        let config = crate::overlay_client::NeighborsConfig::default();
+
+        let req = Request::from_tl(rpc::GetArchiveChunk {
+            archive_id: 1,
+            offset: 0,
+        });
+        let kind = query_kind(&req);
+        assert_eq!(kind, QueryKind::ArchiveChunk);
+        assert!(config.query_timeout_for(kind) > config.query_timeout);
+
+        let req = Request::from_tl(rpc::GetBlockFull {
+            block_id: BlockId::default(),
+        });
+        assert_eq!(query_kind(&req), QueryKind::Default);
+        assert_eq!(
+            config.query_timeout_for(query_kind(&req)),
+            config.query_timeout
+        );
+    }
+
+    #[tokio::test]
+    async fn query_fails_over_to_another_neighbour() -> Result<()> {
+        let default_roundtrip = Duration::from_millis(100);
+        let neighbours = Neighbours::new(
+            vec![
+                Neighbour::new(PeerId([1; 32]), u32::MAX, &default_roundtrip),
+                Neighbour::new(PeerId([2; 32]), u32::MAX, &default_roundtrip),
+            ],
+            2,
+            0,
+        );
+
+        // The first chosen neighbour times out, the second one responds
+        let mut tried = Vec::new();
+        let res = query_with_failover(&neighbours, 3, |neighbour| {
+            tried.push(*neighbour.peer_id());
+            futures_util::future::ready(match tried.len() {
+                1 => Err(Error::Timeout),
+                _ => Ok(*neighbour.peer_id()),
+            })
+        })
+        .await?;
+
+        assert_eq!(tried.len(), 2);
+        assert_ne!(tried[0], tried[1]);
+        assert_eq!(res, tried[1]);
+
+        // Non-retryable errors are returned as is
+        let mut attempts = 0;
+        let res = query_with_failover(&neighbours, 3, |_| {
+            attempts += 1;
+            futures_util::future::ready(Err::<(), _>(Error::NotFound))
+        })
+        .await;
+
+        assert!(matches!(res, Err(Error::NotFound)));
+        assert_eq!(attempts, 1);
+
+        Ok(())
+    }
 }
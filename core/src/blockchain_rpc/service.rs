@@ -6,9 +6,14 @@ use bytes::{Buf, Bytes};
 use everscale_types::models::BlockId;
 use futures_util::Future;
 use serde::{Deserialize, Serialize};
+use tokio::sync::Semaphore;
 use tycho_block_util::message::validate_external_message;
-use tycho_network::{try_handle_prefix, InboundRequestMeta, Response, Service, ServiceRequest};
-use tycho_storage::{ArchiveId, BlockConnection, KeyBlocksDirection, PersistentStateKind, Storage};
+use tycho_network::{
+    try_handle_prefix, InboundRequestMeta, Priority, Response, Service, ServiceRequest,
+};
+use tycho_storage::{
+    ArchiveId, ArchivedBlock, BlockConnection, KeyBlocksDirection, PersistentStateKind, Storage,
+};
 use tycho_util::futures::BoxFutureOrNoop;
 use tycho_util::metrics::HistogramGuard;
 
@@ -53,6 +58,21 @@ pub struct BlockchainRpcServiceConfig {
     ///
     /// Default: yes.
     pub serve_persistent_states: bool,
+
+    /// Whether to serve blocks from committed archives
+    /// when they are no longer in the live storage.
+    ///
+    /// NOTE: Each archive is decompressed to serve its block,
+    /// so this is expensive and is intended for archive nodes.
+    ///
+    /// Default: no.
+    pub serve_archived_blocks: bool,
+
+    /// The maximum number of archived blocks loaded at the same time.
+    /// Other requests for blocks which are not cached are rejected.
+    ///
+    /// Default: 1.
+    pub max_archived_block_loads: usize,
 }
 
 impl Default for BlockchainRpcServiceConfig {
@@ -60,6 +80,8 @@ impl Default for BlockchainRpcServiceConfig {
         Self {
             max_key_blocks_list_len: 8,
             serve_persistent_states: true,
+            serve_archived_blocks: false,
+            max_archived_block_loads: 1,
         }
     }
 }
@@ -76,11 +98,14 @@ where
     pub fn build(self) -> BlockchainRpcService<B> {
         let (broadcast_listener, storage) = self.mandatory_fields;
 
+        let archived_block_loads = Semaphore::new(self.config.max_archived_block_loads);
+
         BlockchainRpcService {
             inner: Arc::new(Inner {
                 storage,
                 config: self.config,
                 broadcast_listener,
+                archived_block_loads,
             }),
         }
     }
@@ -161,6 +186,10 @@ impl<B: BroadcastListener> Service<ServiceRequest> for BlockchainRpcService<B> {
             }
         };
 
+        // Block and state data is sent with a low priority
+        // to not delay other traffic on the same connection.
+        let bulk_response = || req.response_priority.set(Priority::Low);
+
         tycho_network::match_tl_request!(body, tag = constructor, {
             overlay::Ping as _ => BoxFutureOrNoop::future(async {
                 Some(Response::from_tl(overlay::Pong))
@@ -180,6 +209,7 @@ impl<B: BroadcastListener> Service<ServiceRequest> for BlockchainRpcService<B> {
             },
             rpc::GetBlockFull as req => {
                 tracing::debug!(block_id = %req.block_id, "getBlockFull");
+                bulk_response();
 
                 let inner = self.inner.clone();
                 BoxFutureOrNoop::future(async move {
@@ -189,6 +219,7 @@ impl<B: BroadcastListener> Service<ServiceRequest> for BlockchainRpcService<B> {
             },
             rpc::GetNextBlockFull as req => {
                 tracing::debug!(prev_block_id = %req.prev_block_id, "getNextBlockFull");
+                bulk_response();
 
                 let inner = self.inner.clone();
                 BoxFutureOrNoop::future(async move {
@@ -198,10 +229,11 @@ impl<B: BroadcastListener> Service<ServiceRequest> for BlockchainRpcService<B> {
             },
             rpc::GetBlockDataChunk as req => {
                 tracing::debug!(block_id = %req.block_id, offset = %req.offset, "getBlockDataChunk");
+                bulk_response();
 
                 let inner = self.inner.clone();
                 BoxFutureOrNoop::future(async move {
-                    let res = inner.handle_get_block_data_chunk(&req);
+                    let res = inner.handle_get_block_data_chunk(&req).await;
                     Some(Response::from_tl(res))
                 })
             },
@@ -238,6 +270,7 @@ impl<B: BroadcastListener> Service<ServiceRequest> for BlockchainRpcService<B> {
                     offset = %req.offset,
                     "getPersistentShardStateChunk"
                 );
+                bulk_response();
 
                 let inner = self.inner.clone();
                 BoxFutureOrNoop::future(async move {
@@ -251,6 +284,7 @@ impl<B: BroadcastListener> Service<ServiceRequest> for BlockchainRpcService<B> {
                     offset = %req.offset,
                     "getPersistentQueueStateChunk"
                 );
+                bulk_response();
 
                 let inner = self.inner.clone();
                 BoxFutureOrNoop::future(async move {
@@ -273,6 +307,7 @@ impl<B: BroadcastListener> Service<ServiceRequest> for BlockchainRpcService<B> {
                     offset = %req.offset,
                     "getArchiveChunk"
                 );
+                bulk_response();
 
                 let inner = self.inner.clone();
                 BoxFutureOrNoop::future(async move {
@@ -348,6 +383,7 @@ struct Inner<B> {
     storage: Storage,
     config: BlockchainRpcServiceConfig,
     broadcast_listener: B,
+    archived_block_loads: Semaphore,
 }
 
 impl<B> Inner<B> {
@@ -447,15 +483,24 @@ impl<B> Inner<B> {
         }
     }
 
-    fn handle_get_block_data_chunk(&self, req: &rpc::GetBlockDataChunk) -> overlay::Response<Data> {
+    async fn handle_get_block_data_chunk(
+        &self,
+        req: &rpc::GetBlockDataChunk,
+    ) -> overlay::Response<Data> {
         let label = [("method", "getBlockDataChunk")];
         let _hist = HistogramGuard::begin_with_labels(RPC_METHOD_TIMINGS_METRIC, &label);
 
         let block_storage = self.storage.block_storage();
-        match block_storage.get_block_data_chunk(&req.block_id, req.offset) {
-            Ok(Some(data)) => overlay::Response::Ok(Data {
-                data: Bytes::from_owner(data),
-            }),
+        let get_block_data_chunk = async {
+            if let Some(data) = block_storage.get_block_data_chunk(&req.block_id, req.offset)? {
+                return Ok(Some(Bytes::from_owner(data)));
+            }
+            self.get_archived_block_data_chunk(&req.block_id, req.offset)
+                .await
+        };
+
+        match get_block_data_chunk.await {
+            Ok(Some(data)) => overlay::Response::Ok(Data { data }),
             Ok(None) => overlay::Response::Err(NOT_FOUND_ERROR_CODE),
             Err(e) => {
                 tracing::warn!("get_block_data_chunk failed: {e:?}");
@@ -612,11 +657,11 @@ impl<B> Inner<B> {
 
         let handle = match block_handle_storage.load_handle(block_id) {
             Some(handle) if handle.has_all_block_parts() => handle,
-            _ => return Ok(BlockFull::NotFound),
+            _ => return self.get_archived_block_full(block_id).await,
         };
 
         let Some(data) = block_storage.get_block_data_chunk(block_id, 0)? else {
-            return Ok(BlockFull::NotFound);
+            return self.get_archived_block_full(block_id).await;
         };
 
         let data_chunk_size = block_storage.block_data_chunk_size();
@@ -650,6 +695,76 @@ impl<B> Inner<B> {
         })
     }
 
+    async fn get_archived_block_full(&self, block_id: &BlockId) -> anyhow::Result<BlockFull> {
+        if !self.config.serve_archived_blocks {
+            return Ok(BlockFull::NotFound);
+        }
+
+        let block_storage = self.storage().block_storage();
+        let Some(archived) = self.load_archived_block(block_id).await? else {
+            return Ok(BlockFull::NotFound);
+        };
+
+        let data_chunk_size = block_storage.block_data_chunk_size();
+        let data_size = NonZeroU32::new(archived.data.len() as u32).context("empty block data")?;
+        let first_chunk_len = std::cmp::min(data_size, data_chunk_size).get() as usize;
+
+        Ok(BlockFull::Found {
+            block_id: *block_id,
+            block: BlockData {
+                data: archived.data.slice(..first_chunk_len),
+                size: data_size,
+                chunk_size: data_chunk_size,
+            },
+            proof: archived.proof,
+            queue_diff: archived.queue_diff,
+        })
+    }
+
+    async fn get_archived_block_data_chunk(
+        &self,
+        block_id: &BlockId,
+        offset: u32,
+    ) -> anyhow::Result<Option<Bytes>> {
+        if !self.config.serve_archived_blocks {
+            return Ok(None);
+        }
+
+        let block_storage = self.storage().block_storage();
+        let chunk_size = block_storage.block_data_chunk_size().get();
+        anyhow::ensure!(offset % chunk_size == 0, "invalid block data chunk offset");
+
+        let Some(archived) = self.load_archived_block(block_id).await? else {
+            return Ok(None);
+        };
+
+        let offset = offset as usize;
+        if offset >= archived.data.len() {
+            return Ok(None);
+        }
+        let end = std::cmp::min(offset + chunk_size as usize, archived.data.len());
+        Ok(Some(archived.data.slice(offset..end)))
+    }
+
+    async fn load_archived_block(
+        &self,
+        block_id: &BlockId,
+    ) -> anyhow::Result<Option<ArchivedBlock>> {
+        let block_storage = self.storage().block_storage();
+        if let Some(archived) = block_storage.get_cached_archived_block(block_id) {
+            return Ok(Some(archived));
+        }
+
+        // NOTE: Reject instead of waiting to not accumulate requests which decompress archives
+        let Ok(_permit) = self.archived_block_loads.try_acquire() else {
+            metrics::counter!("tycho_blockchain_rpc_archived_block_loads_rejected_total")
+                .increment(1);
+            anyhow::bail!("too many archived block loads");
+        };
+
+        block_storage.load_archived_block(block_id).await
+    }
+
     fn read_persistent_state_info(
         &self,
         block_id: &BlockId,
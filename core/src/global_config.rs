@@ -11,7 +11,7 @@ use crate::proto::blockchain::OverlayIdData;
 #[derive(Default, Debug, Serialize, Deserialize)]
 pub struct GlobalConfig {
     pub bootstrap_peers: Vec<PeerInfo>,
-    pub zerostate: ZerostateId,
+    pub zerostate: ZerostateIds,
     #[serde(default)]
     pub mempool: Option<MempoolGlobalConfig>,
 }
@@ -29,7 +29,7 @@ impl GlobalConfig {
     }
 }
 
-#[derive(Default, Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ZerostateId {
     pub root_hash: HashBytes,
     pub file_hash: HashBytes,
@@ -53,6 +53,81 @@ impl ZerostateId {
     }
 }
 
+/// An ordered non-empty list of acceptable zerostates.
+///
+/// The first id is the primary one and identifies the network (e.g. overlay ids).
+/// Other ids are accepted when importing zerostates during the cold boot,
+/// which eases network upgrades with multiple valid genesis variants.
+///
+/// Can be specified either as a single zerostate id or as a list.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "ZerostateIdsRepr", into = "ZerostateIdsRepr")]
+pub struct ZerostateIds(Vec<ZerostateId>);
+
+impl ZerostateIds {
+    pub fn new(ids: Vec<ZerostateId>) -> Result<Self> {
+        anyhow::ensure!(!ids.is_empty(), "zerostate ids list must not be empty");
+        Ok(Self(ids))
+    }
+
+    pub fn primary(&self) -> &ZerostateId {
+        &self.0[0]
+    }
+
+    pub fn as_slice(&self) -> &[ZerostateId] {
+        &self.0
+    }
+
+    /// Returns the first candidate for which `is_available` returns `true`.
+    pub fn find_first<F>(&self, mut is_available: F) -> Option<&ZerostateId>
+    where
+        F: FnMut(&BlockId) -> bool,
+    {
+        self.0.iter().find(|id| is_available(&id.as_block_id()))
+    }
+}
+
+impl Default for ZerostateIds {
+    fn default() -> Self {
+        Self(vec![ZerostateId::default()])
+    }
+}
+
+impl From<ZerostateId> for ZerostateIds {
+    #[inline]
+    fn from(value: ZerostateId) -> Self {
+        Self(vec![value])
+    }
+}
+
+impl TryFrom<ZerostateIdsRepr> for ZerostateIds {
+    type Error = anyhow::Error;
+
+    fn try_from(value: ZerostateIdsRepr) -> Result<Self, Self::Error> {
+        match value {
+            ZerostateIdsRepr::Single(id) => Ok(id.into()),
+            ZerostateIdsRepr::Multiple(ids) => Self::new(ids),
+        }
+    }
+}
+
+impl From<ZerostateIds> for ZerostateIdsRepr {
+    fn from(mut value: ZerostateIds) -> Self {
+        if value.0.len() == 1 {
+            Self::Single(value.0.remove(0))
+        } else {
+            Self::Multiple(value.0)
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum ZerostateIdsRepr {
+    Single(ZerostateId),
+    Multiple(Vec<ZerostateId>),
+}
+
 /// Default zeros for start round and genesis time are the same in
 /// [`ConsensusInfo`](everscale_types::models::ConsensusInfo).
 ///
@@ -78,3 +153,39 @@ pub struct MempoolGlobalConfig {
     #[serde(flatten)]
     pub consensus_config: Option<ConsensusConfig>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zerostate_candidates() -> Result<()> {
+        let first = ZerostateId {
+            root_hash: HashBytes([1; 32]),
+            file_hash: HashBytes([2; 32]),
+        };
+        let second = ZerostateId {
+            root_hash: HashBytes([3; 32]),
+            file_hash: HashBytes([4; 32]),
+        };
+
+        // Single id is parsed as a one-element list
+        let single = serde_json::from_str::<ZerostateIds>(&serde_json::to_string(&first)?)?;
+        assert_eq!(single.as_slice(), &[first]);
+        assert_eq!(serde_json::to_value(&single)?, serde_json::to_value(first)?);
+
+        let ids = serde_json::from_str::<ZerostateIds>(&serde_json::to_string(&[first, second])?)?;
+        assert_eq!(ids.primary(), &first);
+
+        // The first available candidate wins
+        let available = second.as_block_id();
+        assert_eq!(ids.find_first(|id| id == &available), Some(&second));
+        assert_eq!(ids.find_first(|_| true), Some(&first));
+        assert_eq!(ids.find_first(|_| false), None);
+
+        // Empty list is not allowed
+        assert!(serde_json::from_str::<ZerostateIds>("[]").is_err());
+
+        Ok(())
+    }
+}
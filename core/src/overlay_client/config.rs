@@ -39,6 +39,12 @@ pub struct NeighborsConfig {
     /// Default: 5.
     pub keep: usize,
 
+    /// The minimum number of neighbours to keep even if they are
+    /// temporarily unreliable.
+    ///
+    /// Default: 2.
+    pub keep_min: usize,
+
     /// The maximum number of ping tasks to run concurrently.
     ///
     /// Default: 5.
@@ -59,6 +65,35 @@ pub struct NeighborsConfig {
     ///
     /// Default: 1s.
     pub query_timeout: Duration,
+
+    /// Query timeout for an archive chunk.
+    ///
+    /// Default: 10s.
+    #[serde(with = "serde_helpers::humantime")]
+    pub archive_chunk_query_timeout: Duration,
+
+    /// Query timeout for a persistent state chunk.
+    ///
+    /// Default: 10s.
+    #[serde(with = "serde_helpers::humantime")]
+    pub persistent_state_chunk_query_timeout: Duration,
+
+    /// Delay after which a hedged block query is also sent to another neighbour.
+    /// Hedging is disabled when `None`.
+    ///
+    /// Default: None
+    #[serde(with = "serde_helpers::humantime")]
+    pub hedge_delay: Option<Duration>,
+}
+
+impl NeighborsConfig {
+    pub fn query_timeout_for(&self, kind: QueryKind) -> Duration {
+        match kind {
+            QueryKind::Default => self.query_timeout,
+            QueryKind::ArchiveChunk => self.archive_chunk_query_timeout,
+            QueryKind::PersistentStateChunk => self.persistent_state_chunk_query_timeout,
+        }
+    }
 }
 
 impl Default for NeighborsConfig {
@@ -68,14 +103,30 @@ impl Default for NeighborsConfig {
             ping_interval: Duration::from_secs(30),
             apply_score_interval: Duration::from_secs(10),
             keep: 5,
+            keep_min: 2,
             max_ping_tasks: 5,
             default_roundtrip: Duration::from_millis(300),
             send_timeout: Duration::from_millis(500),
             query_timeout: Duration::from_secs(1),
+            archive_chunk_query_timeout: Duration::from_secs(10),
+            persistent_state_chunk_query_timeout: Duration::from_secs(10),
+            hedge_delay: None,
         }
     }
 }
 
+/// Request type which determines the query timeout.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum QueryKind {
+    /// Small queries, e.g. for a single block.
+    #[default]
+    Default,
+    /// Large transfers of archive chunks.
+    ArchiveChunk,
+    /// Large transfers of persistent state chunks.
+    PersistentStateChunk,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct ValidatorsConfig {
@@ -1,12 +1,14 @@
+use std::future::Future;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use anyhow::Result;
 use bytes::Bytes;
+use futures_util::future::{self, Either};
 use tokio::task::AbortHandle;
 use tycho_network::{ConnectionError, Network, PublicOverlay, Request, UnknownPeerError};
 
-pub use self::config::{NeighborsConfig, PublicOverlayClientConfig, ValidatorsConfig};
+pub use self::config::{NeighborsConfig, PublicOverlayClientConfig, QueryKind, ValidatorsConfig};
 pub use self::neighbour::{Neighbour, NeighbourStats, PunishReason};
 pub use self::neighbours::{NeighbourType, Neighbours};
 pub use self::validators::{Validator, ValidatorSetPeers, ValidatorsResolver};
@@ -45,7 +47,7 @@ impl PublicOverlayClient {
             })
             .collect::<Vec<_>>();
 
-        let neighbours = Neighbours::new(entries, config.neighbors.keep);
+        let neighbours = Neighbours::new(entries, neighbors_config.keep, neighbors_config.keep_min);
         let validators_resolver =
             ValidatorsResolver::new(network.clone(), overlay.clone(), config.validators.clone());
 
@@ -139,7 +141,88 @@ impl PublicOverlayClient {
     where
         for<'a> A: tl_proto::TlRead<'a, Repr = tl_proto::Boxed>,
     {
-        self.inner.query_impl(neighbour, req).await?.parse()
+        self.inner
+            .query_impl(neighbour, req, QueryKind::Default)
+            .await?
+            .parse()
+    }
+
+    /// Same as [`Self::query_raw`] but with a timeout for the specified request type.
+    pub async fn query_raw_with_kind<A>(
+        &self,
+        neighbour: Neighbour,
+        req: Request,
+        kind: QueryKind,
+    ) -> Result<QueryResponse<A>, Error>
+    where
+        for<'a> A: tl_proto::TlRead<'a, Repr = tl_proto::Boxed>,
+    {
+        self.inner.query_impl(neighbour, req, kind).await?.parse()
+    }
+
+    /// Same as [`Self::query_raw`] but also sends the request to another neighbour
+    /// if there is no response after `delay`.
+    ///
+    /// The first successful response wins, the other query is cancelled.
+    pub async fn query_raw_hedged<A>(
+        &self,
+        neighbour: Neighbour,
+        req: Request,
+        delay: Duration,
+    ) -> Result<QueryResponse<A>, Error>
+    where
+        for<'a> A: tl_proto::TlRead<'a, Repr = tl_proto::Boxed>,
+    {
+        let hedge = (self.inner.neighbours.choose_multiple(2, NeighbourType::All))
+            .into_iter()
+            .find(|item| item.peer_id() != neighbour.peer_id());
+
+        query_hedged(neighbour, hedge, delay, |neighbour| {
+            let req = req.clone();
+            async move {
+                self.inner
+                    .query_impl(neighbour, req, QueryKind::Default)
+                    .await?
+                    .parse()
+            }
+        })
+        .await
+    }
+}
+
+/// Runs the query for the `neighbour` and, if it doesn't finish in `delay`,
+/// races it with the same query for the `hedge` neighbour.
+///
+/// A retryable error before the `delay` sends the query to the `hedge` immediately.
+/// At most two queries are made.
+async fn query_hedged<T, F, Fut>(
+    neighbour: Neighbour,
+    hedge: Option<Neighbour>,
+    delay: Duration,
+    mut query: F,
+) -> Result<T, Error>
+where
+    F: FnMut(Neighbour) -> Fut,
+    Fut: Future<Output = Result<T, Error>>,
+{
+    let first = std::pin::pin!(query(neighbour));
+    let Some(hedge) = hedge else {
+        return first.await;
+    };
+
+    let timer = std::pin::pin!(tokio::time::sleep(delay));
+    let first = match future::select(first, timer).await {
+        Either::Left((Err(e), _)) if e.is_retryable() => return query(hedge).await,
+        Either::Left((res, _)) => return res,
+        Either::Right((_, first)) => first,
+    };
+
+    tracing::debug!(peer_id = %hedge.peer_id(), "sending hedged query");
+    let second = std::pin::pin!(query(hedge));
+    match future::select(first, second).await {
+        Either::Left((Ok(res), _)) | Either::Right((Ok(res), _)) => Ok(res),
+        Either::Left((Err(_), second)) => second.await,
+        Either::Right((Err(_), first)) => first.await,
     }
 }
 
@@ -161,6 +244,19 @@ pub enum Error {
     Timeout,
 }
 
+impl Error {
+    /// Whether the same query could succeed if sent to another neighbour.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            Self::NetworkError(_)
+                | Self::InvalidResponse(_)
+                | Self::RequestFailed(_)
+                | Self::Timeout
+        )
+    }
+}
+
 struct Inner {
     network: Network,
     overlay: PublicOverlay,
@@ -209,7 +305,10 @@ impl Inner {
             };
 
             let peer_id = *neighbour.peer_id();
-            match self.query_impl(neighbour.clone(), req.clone()).await {
+            match self
+                .query_impl(neighbour.clone(), req.clone(), QueryKind::Default)
+                .await
+            {
                 Ok(res) => match tl_proto::deserialize::<overlay::Pong>(&res.data) {
                     Ok(_) => {
                         res.accept();
@@ -335,7 +434,7 @@ impl Inner {
             return Err(Error::NoNeighbours);
         };
 
-        self.query_impl(neighbour, Request::from_tl(data))
+        self.query_impl(neighbour, Request::from_tl(data), QueryKind::Default)
             .await?
             .parse()
     }
@@ -373,11 +472,12 @@ impl Inner {
         &self,
         neighbour: Neighbour,
         req: Request,
+        kind: QueryKind,
     ) -> Result<QueryResponse<Bytes>, Error> {
         let started_at = Instant::now();
 
         let res = tokio::time::timeout(
-            self.config.neighbors.query_timeout,
+            self.config.neighbors.query_timeout_for(kind),
             self.overlay.query(&self.network, neighbour.peer_id(), req),
         )
         .await;
@@ -431,6 +531,10 @@ impl<A> QueryResponse<A> {
         &self.data
     }
 
+    pub fn neighbour(&self) -> &Neighbour {
+        &self.neighbour
+    }
+
     pub fn split(self) -> (QueryResponseHandle, A) {
         let handle = QueryResponseHandle::with_roundtrip_ms(self.neighbour, self.roundtrip_ms);
         (handle, self.data)
@@ -529,3 +633,41 @@ fn apply_network_error(error: &anyhow::Error, neighbour: &Neighbour) {
         _ => {}
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use tycho_network::PeerId;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn hedged_query_wins_over_stalled_neighbour() {
+        let default_roundtrip = Duration::from_millis(100);
+        let slow = Neighbour::new(PeerId([1; 32]), u32::MAX, &default_roundtrip);
+        let fast = Neighbour::new(PeerId([2; 32]), u32::MAX, &default_roundtrip);
+
+        let attempts = AtomicUsize::new(0);
+        let query = |neighbour: Neighbour| {
+            attempts.fetch_add(1, Ordering::Relaxed);
+            async move {
+                if neighbour.peer_id() == &PeerId([1; 32]) {
+                    future::pending::<()>().await;
+                }
+                Ok::<_, Error>(*neighbour.peer_id())
+            }
+        };
+
+        // The first neighbour stalls, so the hedge wins
+        let delay = Duration::from_millis(10);
+        let res = query_hedged(slow.clone(), Some(fast.clone()), delay, query).await;
+        assert_eq!(res.unwrap(), PeerId([2; 32]));
+        assert_eq!(attempts.swap(0, Ordering::Relaxed), 2);
+
+        // No hedge is sent when the first neighbour responds in time
+        let res = query_hedged(fast, Some(slow), delay, query).await;
+        assert_eq!(res.unwrap(), PeerId([2; 32]));
+        assert_eq!(attempts.load(Ordering::Relaxed), 1);
+    }
+}
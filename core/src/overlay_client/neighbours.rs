@@ -15,13 +15,16 @@ pub struct Neighbours {
 }
 
 impl Neighbours {
-    pub fn new(entries: Vec<Neighbour>, max_neighbours: usize) -> Self {
+    /// Creates a pool of at most `max_neighbours` entries, which keeps at least
+    /// `min_neighbours` entries even if they are temporarily unreliable.
+    pub fn new(entries: Vec<Neighbour>, max_neighbours: usize, min_neighbours: usize) -> Self {
         let mut selection_index = SelectionIndex::new(max_neighbours);
         selection_index.update(&entries);
 
         Self {
             inner: Arc::new(Inner {
                 max_neighbours,
+                min_neighbours: std::cmp::min(min_neighbours, max_neighbours),
                 entries: ArcSwap::new(Arc::new(entries)),
                 selection_index: Mutex::new(selection_index),
                 changed: Notify::new(),
@@ -61,12 +64,7 @@ impl Neighbours {
         let entires_arc = self.inner.entries.load_full();
 
         let mut entries = entires_arc.as_ref().clone();
-        let mut entries_changed = false;
-        entries.retain(|x| {
-            let retain = x.is_reliable() && x.expires_at_secs() > now;
-            entries_changed |= !retain;
-            retain
-        });
+        let entries_changed = self.retain_reliable(&mut entries, now, |_| {});
         let new_entries_arc = Arc::new(entries);
 
         let mut lock = self.inner.selection_index.lock();
@@ -117,15 +115,10 @@ impl Neighbours {
         let mut entries = self.inner.entries.load().as_slice().to_vec();
 
         // Remove unreliable and expired neighbours.
-        let mut changed = false;
-        entries.retain(|x| {
+        let mut changed = self.retain_reliable(&mut entries, now, |x| {
             // Remove the existing peer from the `new_peers` list to prevent it
             // from appearing in the same list again (especially if it was unreliable).
             new_peer_ids.remove(x.peer_id());
-
-            let retain = x.is_reliable() && x.expires_at_secs() > now;
-            changed |= !retain;
-            retain
         });
 
         // If all neighbours are reliable and valid then remove the worst
@@ -165,10 +158,44 @@ impl Neighbours {
             self.inner.changed.notify_waiters();
         }
     }
+
+    /// Removes expired and unreliable neighbours, but keeps at least `min_neighbours`
+    /// of the least bad unreliable ones so that the pool is never emptied by a network blip.
+    ///
+    /// Returns `true` if some neighbours were removed.
+    fn retain_reliable<F>(&self, entries: &mut Vec<Neighbour>, now: u32, mut visit: F) -> bool
+    where
+        F: FnMut(&Neighbour),
+    {
+        let original_len = entries.len();
+
+        let mut unreliable = Vec::new();
+        entries.retain(|x| {
+            visit(x);
+
+            if x.expires_at_secs() <= now {
+                return false;
+            }
+            if !x.is_reliable() {
+                unreliable.push(x.clone());
+                return false;
+            }
+            true
+        });
+
+        let missing = self.inner.min_neighbours.saturating_sub(entries.len());
+        if missing > 0 && !unreliable.is_empty() {
+            unreliable.sort_by(|l, r| r.cmp_score(l));
+            entries.extend(unreliable.into_iter().take(missing));
+        }
+
+        entries.len() != original_len
+    }
 }
 
 struct Inner {
     max_neighbours: usize,
+    min_neighbours: usize,
     entries: ArcSwap<Vec<Neighbour>>,
     selection_index: Mutex<SelectionIndex>,
     changed: Notify,
@@ -200,13 +227,21 @@ impl SelectionIndex {
             }
         }
 
+        // Fallback to uniform sample from any neighbour
+        // when all of them are temporarily unreliable
+        if total_weight == 0 {
+            for neighbour in neighbours {
+                total_weight += 1;
+                self.indices_with_weights
+                    .push((neighbour.clone(), total_weight));
+            }
+        }
+
         self.distribution = if total_weight != 0 {
             Some(UniformInt::new(0, total_weight))
         } else {
             None
         };
-
-        // TODO: fallback to uniform sample from any neighbour
     }
 
     fn choose<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<Neighbour> {
@@ -308,3 +343,47 @@ pub enum NeighbourType {
     All,
     Reliable,
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tycho_network::PeerId;
+
+    use super::*;
+    use crate::overlay_client::PunishReason;
+
+    #[test]
+    fn floor_keeps_least_bad_neighbours() {
+        let default_roundtrip = Duration::from_millis(100);
+        let entries = (1..=4)
+            .map(|i| Neighbour::new(PeerId([i; 32]), u32::MAX, &default_roundtrip))
+            .collect::<Vec<_>>();
+
+        // All neighbours become unreliable, but some are worse than others
+        for (i, neighbour) in entries.iter().enumerate() {
+            for _ in 0..13 + i {
+                neighbour.punish(PunishReason::Dumb);
+            }
+            assert!(!neighbour.is_reliable());
+        }
+
+        let neighbours = Neighbours::new(entries.clone(), 4, 2);
+        assert!(neighbours.try_apply_score(tycho_util::time::now_sec()));
+
+        let active = neighbours.get_active_neighbours();
+        assert_eq!(active.len(), 2);
+        assert!(active.iter().any(|x| x.peer_id() == entries[0].peer_id()));
+        assert!(active.iter().any(|x| x.peer_id() == entries[1].peer_id()));
+
+        let chosen = neighbours
+            .choose()
+            .expect("floor neighbours must be chosen");
+        assert!(active.iter().any(|x| x.peer_id() == chosen.peer_id()));
+        assert_eq!(neighbours.choose_multiple(4, NeighbourType::All).len(), 2);
+
+        // Expired neighbours are removed regardless of the floor
+        assert!(neighbours.try_apply_score(u32::MAX));
+        assert!(neighbours.choose().is_none());
+    }
+}
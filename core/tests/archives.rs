@@ -18,7 +18,9 @@ use tycho_core::block_strider::{
     OptionalBlockStuff, PersistentBlockStriderState, ProofChecker, ShardStateApplier,
     StateSubscriber, StateSubscriberContext,
 };
-use tycho_core::blockchain_rpc::{BlockchainRpcClient, DataRequirement};
+use tycho_core::blockchain_rpc::{
+    BlockchainRpcClient, BlockchainRpcServiceConfig, DataRequirement,
+};
 use tycho_core::overlay_client::PublicOverlayClient;
 use tycho_network::PeerId;
 use tycho_storage::{ArchiveId, ArchivesGcConfig, NewBlockMeta, Storage, StorageConfig};
@@ -249,6 +251,44 @@ async fn prepare_storage(config: StorageConfig, zerostate: ShardStateStuff) -> R
     Ok(storage)
 }
 
+#[tokio::test]
+async fn tampered_archive_entry_errors() -> Result<()> {
+    tycho_util::test::init_logger("tampered_archive_entry_errors", "debug");
+
+    let tmp_dir = tempfile::tempdir()?;
+
+    let zerostate_data = utils::read_file("zerostate.boc")?;
+    let zerostate = utils::parse_zerostate(&zerostate_data)?;
+    let storage = prepare_storage(StorageConfig::new_potato(tmp_dir.path()), zerostate).await?;
+
+    let proof_checker = ProofChecker::new(storage.clone());
+
+    let archive_data = utils::read_file("archive_1.bin")?;
+    let archive = utils::parse_archive(&archive_data).map(Arc::new)?;
+
+    let mut mc_block_ids = archive.mc_block_ids.values().copied();
+    let first = mc_block_ids.next().unwrap();
+    let second = mc_block_ids.next().unwrap();
+
+    // Valid entry passes the check
+    proof_checker
+        .check_archive_entry(&archive, &first, &first, false)
+        .await?;
+
+    // Entry with a proof for another block
+    let mut tampered = utils::parse_archive(&archive_data)?;
+    let other_proof = tampered.blocks[&second].proof.clone();
+    tampered.blocks.get_mut(&first).unwrap().proof = other_proof;
+    let tampered = Arc::new(tampered);
+
+    let res = proof_checker
+        .check_archive_entry(&tampered, &first, &first, false)
+        .await;
+    assert!(res.is_err(), "tampered entry must be rejected");
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn archives() -> Result<()> {
     tycho_util::test::init_logger("archives", "debug");
@@ -264,10 +304,13 @@ async fn archives() -> Result<()> {
         archive_chunk_size: ByteSize::kb(1024),
         rocksdb_enable_metrics: false,
         split_block_tasks: 100,
+        archive_writer_tasks: 2,
         archives_gc: Some(ArchivesGcConfig::default()),
         states_gc: None,
         blocks_gc: None,
         blocks_cache: Default::default(),
+        block_data_compression: Default::default(),
+        wal: Default::default(),
     };
 
     let zerostate_data = utils::read_file("zerostate.boc")?;
@@ -363,6 +406,126 @@ async fn archives() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn archived_blocks_are_served_after_pruning() -> Result<()> {
+    tycho_util::test::init_logger("archived_blocks_are_served_after_pruning", "debug");
+
+    let tmp_dir = tempfile::tempdir()?;
+
+    let zerostate_data = utils::read_file("zerostate.boc")?;
+    let zerostate = utils::parse_zerostate(&zerostate_data)?;
+    let zerostate_id = *zerostate.block_id();
+    let storage = prepare_storage(StorageConfig::new_potato(tmp_dir.path()), zerostate).await?;
+
+    let mut archives = ArchivesSet::new();
+    let files = [
+        (1, "archive_1.bin"),
+        (101, "archive_2.bin"),
+        (201, "archive_3.bin"),
+    ];
+    for (id, file) in files {
+        let data = utils::read_file(file)?;
+        archives.insert(id, utils::parse_archive(&data).map(Arc::new)?);
+    }
+    let first_archive = archives[&1].clone();
+    let last_mc_seqno = *archives[&201].mc_block_ids.keys().next().unwrap();
+
+    let archive_provider = ArchiveProvider {
+        archives: parking_lot::Mutex::new(archives),
+        proof_checker: ProofChecker::new(storage.clone()),
+    };
+
+    let block_strider = BlockStrider::builder()
+        .with_provider(archive_provider)
+        .with_state(PersistentBlockStriderState::new(
+            zerostate_id,
+            storage.clone(),
+        ))
+        .with_block_subscriber(ShardStateApplier::new(storage.clone(), DummySubscriber))
+        .build();
+
+    block_strider.run().await?;
+    storage.block_storage().wait_for_archive_commit().await?;
+
+    // Remove blocks from the live storage, only archives are left
+    storage
+        .block_storage()
+        .remove_outdated_blocks(last_mc_seqno, None)
+        .await?;
+
+    // Archived blocks index must survive the restart
+    drop(block_strider);
+    drop(storage);
+    let storage = Storage::builder()
+        .with_config(StorageConfig::new_potato(tmp_dir.path()))
+        .build()
+        .await?;
+
+    let (&mc_seqno, &mc_block_id) = first_archive.mc_block_ids.iter().nth(1).unwrap();
+    assert_eq!(
+        storage.block_storage().get_archive_id(mc_seqno),
+        ArchiveId::Found(1)
+    );
+    assert!(storage
+        .block_storage()
+        .get_block_data_chunk(&mc_block_id, 0)?
+        .is_none());
+
+    let shard_block_id = first_archive
+        .blocks
+        .keys()
+        .find(|id| !id.is_masterchain())
+        .copied()
+        .expect("archive must contain shard blocks");
+    assert_eq!(
+        storage
+            .block_storage()
+            .find_block_archive_id(&shard_block_id),
+        Some(1)
+    );
+
+    let mut config = BlockchainRpcServiceConfig::default();
+    config.serve_archived_blocks = true;
+    let nodes = network::make_network_with_config(storage.clone(), 2, config);
+    network::discover(&nodes).await?;
+
+    let peers = nodes
+        .iter()
+        .map(|x| *x.network().peer_id())
+        .collect::<Vec<PeerId>>();
+    for node in &nodes {
+        node.force_update_validators(peers.clone());
+    }
+
+    let node = nodes.first().unwrap();
+    let client = BlockchainRpcClient::builder()
+        .with_public_overlay_client(PublicOverlayClient::new(
+            node.network().clone(),
+            node.public_overlay().clone(),
+            Default::default(),
+        ))
+        .build();
+
+    let result = client
+        .get_block_full(&mc_block_id, DataRequirement::Required)
+        .await?;
+    let block_full = result.data.expect("archived block must be found");
+
+    let block = BlockStuff::deserialize_checked(&mc_block_id, &block_full.block_data)?;
+    let (archive_block, _, _) = first_archive.get_entry_by_id(&mc_block_id).await?;
+    assert_eq!(block.block(), archive_block.block());
+
+    let result = client
+        .get_block_full(&shard_block_id, DataRequirement::Required)
+        .await?;
+    let block_full = result.data.expect("archived shard block must be found");
+    let block = BlockStuff::deserialize_checked(&shard_block_id, &block_full.block_data)?;
+    let (archive_block, _, _) = first_archive.get_entry_by_id(&shard_block_id).await?;
+    assert_eq!(block.block(), archive_block.block());
+
+    Ok(())
+}
+
 #[tokio::test]
 #[ignore]
 async fn heavy_archives() -> Result<()> {
@@ -383,10 +546,13 @@ async fn heavy_archives() -> Result<()> {
         archive_chunk_size: ByteSize::kb(1024),
         rocksdb_enable_metrics: false,
         split_block_tasks: 100,
+        archive_writer_tasks: 2,
         archives_gc: Some(ArchivesGcConfig::default()),
         states_gc: None,
         blocks_gc: None,
         blocks_cache: Default::default(),
+        block_data_compression: Default::default(),
+        wal: Default::default(),
     };
 
     let zerostate_path = integration_test_path.join("zerostate.boc");
@@ -642,9 +808,17 @@ async fn check_archive(
             .block_storage()
             .get_archive_chunk(archive_id, offset as u64)
             .await?;
+        assert!(chunk.len() <= archive_chunk_size, "Chunk is too big");
         got_archive.extend_from_slice(&chunk);
     }
 
+    // Chunks are served only from chunk boundaries
+    let unaligned = storage
+        .block_storage()
+        .get_archive_chunk(archive_id, 1)
+        .await;
+    assert!(unaligned.is_err(), "Unaligned offset must be rejected");
+
     let original_decompressed = decompress(original_archive);
     let got_decompressed = decompress(&got_archive);
 
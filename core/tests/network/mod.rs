@@ -6,7 +6,7 @@ use std::time::Duration;
 use everscale_crypto::ed25519;
 use futures_util::stream::FuturesUnordered;
 use futures_util::StreamExt;
-use tycho_core::blockchain_rpc::BlockchainRpcService;
+use tycho_core::blockchain_rpc::{BlockchainRpcService, BlockchainRpcServiceConfig};
 use tycho_network::{
     DhtClient, DhtConfig, DhtService, Network, OverlayConfig, OverlayId, OverlayService, PeerId,
     PeerResolver, PublicOverlay, Router,
@@ -95,7 +95,7 @@ impl Node {
         &self.public_overlay
     }
 
-    fn with_random_key(storage: Storage) -> Self {
+    fn with_random_key(storage: Storage, config: BlockchainRpcServiceConfig) -> Self {
         let NodeBase {
             network,
             dht_service,
@@ -106,6 +106,7 @@ impl Node {
             .with_peer_resolver(peer_resolver.clone())
             .build(
                 BlockchainRpcService::builder()
+                    .with_config(config)
                     .with_storage(storage)
                     .without_broadcast_listener()
                     .build(),
@@ -123,8 +124,16 @@ impl Node {
 }
 
 pub fn make_network(storage: Storage, node_count: usize) -> Vec<Node> {
+    make_network_with_config(storage, node_count, Default::default())
+}
+
+pub fn make_network_with_config(
+    storage: Storage,
+    node_count: usize,
+    config: BlockchainRpcServiceConfig,
+) -> Vec<Node> {
     let nodes = (0..node_count)
-        .map(|_| Node::with_random_key(storage.clone()))
+        .map(|_| Node::with_random_key(storage.clone(), config.clone()))
         .collect::<Vec<_>>();
 
     let common_peer_info = nodes.first().unwrap().network.sign_peer_info(0, u32::MAX);
@@ -28,7 +28,7 @@ pub async fn test() {
 
     println!("{}", initial_peers.len());
 
-    let neighbours = Neighbours::new(initial_peers.clone(), max_neighbours);
+    let neighbours = Neighbours::new(initial_peers.clone(), max_neighbours, 0);
     println!("{}", neighbours.get_active_neighbours().len());
 
     let first_success_rate = [0.2, 0.8];
@@ -84,7 +84,15 @@ impl CmdRun {
         let public_ip = resolve_public_ip(node_config.public_ip).await?;
         let socket_addr = SocketAddr::new(public_ip, node_config.port);
 
-        Node::new(socket_addr, keys, node_config, global_config).await
+        let import_zerostate = self.import_zerostate.as_deref();
+        Node::new(
+            socket_addr,
+            keys,
+            node_config,
+            global_config,
+            import_zerostate,
+        )
+        .await
     }
 }
 
@@ -112,6 +120,7 @@ impl<C> Node<C> {
         keys: NodeKeys,
         node_config: NodeConfig<C>,
         global_config: GlobalConfig,
+        import_zerostate: Option<&[PathBuf]>,
     ) -> Result<Node<C>>
     where
         C: Clone,
@@ -186,7 +195,9 @@ impl<C> Node<C> {
         );
 
         // Setup blockchain rpc
-        let zerostate = global_config.zerostate;
+        let zerostate =
+            Starter::select_zerostate(&storage, &global_config.zerostate, import_zerostate)?;
+        tracing::info!(zerostate_id = %zerostate.as_block_id(), "selected zerostate");
 
         let blockchain_rpc_service = BlockchainRpcService::builder()
             .with_config(node_config.blockchain_rpc_service)
@@ -263,19 +274,19 @@ impl<C> Node<C> {
         boot_type: ColdBootType,
         zerostates: Option<Vec<PathBuf>>,
     ) -> Result<BlockId> {
-        let node_state = self.storage.node_state();
+        let starter = Starter::new(
+            self.storage.clone(),
+            self.blockchain_rpc_client.clone(),
+            self.zerostate,
+            self.starter_config.clone(),
+        );
 
-        let last_mc_block_id = match node_state.load_last_mc_block_id() {
+        let last_mc_block_id = match starter.try_warm_boot().await? {
             Some(block_id) => block_id,
             None => {
-                Starter::new(
-                    self.storage.clone(),
-                    self.blockchain_rpc_client.clone(),
-                    self.zerostate,
-                    self.starter_config.clone(),
-                )
-                .cold_boot(boot_type, zerostates.map(FileZerostateProvider))
-                .await?
+                starter
+                    .cold_boot(boot_type, zerostates.map(FileZerostateProvider))
+                    .await?
             }
         };
 
@@ -294,6 +305,7 @@ impl<C> Node<C> {
     {
         let strider_state =
             PersistentBlockStriderState::new(self.zerostate.as_block_id(), self.storage.clone());
+        strider_state.resume_from()?;
 
         let gc_subscriber = GcSubscriber::new(self.storage.clone());
 
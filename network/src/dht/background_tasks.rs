@@ -1,4 +1,5 @@
 use std::collections::hash_map;
+use std::path::Path;
 use std::sync::Arc;
 
 use anyhow::Result;
@@ -8,7 +9,7 @@ use tokio::sync::{broadcast, Semaphore};
 use tokio::task::JoinHandle;
 use tycho_util::time::{now_sec, shifted_interval};
 
-use crate::dht::{random_key_at_distance, DhtInner, DhtQueryMode, Query};
+use crate::dht::{persist, random_key_at_distance, DhtInner, DhtQueryMode, Query};
 use crate::network::{Network, WeakNetwork};
 use crate::proto::dht::{PeerValueKeyName, ValueRef};
 use crate::types::PeerInfo;
@@ -19,6 +20,7 @@ impl DhtInner {
             RefreshLocalPeerInfo,
             AnnounceLocalPeerInfo,
             RefreshRoutingTable,
+            PersistRoutingTable,
             AddPeer(Arc<PeerInfo>),
         }
 
@@ -33,18 +35,35 @@ impl DhtInner {
             self.config.routing_table_refresh_period_max_jitter,
         );
 
+        let persist_path = self.config.routing_table_persist_path.clone();
+        let mut persist_routing_table_interval =
+            tokio::time::interval(self.config.routing_table_persist_period);
+        // NOTE: The first tick completes immediately, skip it to not
+        // overwrite the saved table with an empty one.
+        persist_routing_table_interval.reset();
+
         let mut announced_peers = self.announced_peers.subscribe();
 
         let this = Arc::downgrade(self);
         tokio::spawn(async move {
             tracing::debug!("background DHT loop started");
 
+            if let Some(path) = &persist_path {
+                let (Some(this), Some(network)) = (this.upgrade(), network.upgrade()) else {
+                    return;
+                };
+                this.load_routing_table(&network, path);
+            }
+
             let mut prev_refresh_routing_table_fut = None::<JoinHandle<()>>;
             loop {
                 let action = tokio::select! {
                     _ = refresh_peer_info_interval.tick() => Action::RefreshLocalPeerInfo,
                     _ = announce_peer_info_interval.tick() => Action::AnnounceLocalPeerInfo,
                     _ = refresh_routing_table_interval.tick() => Action::RefreshRoutingTable,
+                    _ = persist_routing_table_interval.tick(), if persist_path.is_some() => {
+                        Action::PersistRoutingTable
+                    }
                     peer = announced_peers.recv() => match peer {
                         Ok(peer) => Action::AddPeer(peer),
                         Err(broadcast::error::RecvError::Closed) => return,
@@ -84,6 +103,11 @@ impl DhtInner {
                             this.refresh_routing_table(&network).await;
                         }));
                     }
+                    Action::PersistRoutingTable => {
+                        if let Some(path) = &persist_path {
+                            this.persist_routing_table(path).await;
+                        }
+                    }
                     Action::AddPeer(peer_info) => {
                         let peer_id = peer_info.id;
                         let mut signature_checked = false;
@@ -108,6 +132,39 @@ impl DhtInner {
         });
     }
 
+    fn load_routing_table(&self, network: &Network, path: &Path) {
+        let peers = match persist::load_peers(path, now_sec()) {
+            Ok(peers) => peers,
+            Err(e) => {
+                tracing::warn!(path = %path.display(), "failed to load routing table: {e:?}");
+                return;
+            }
+        };
+
+        let total = peers.len();
+        let mut added = 0usize;
+        for peer_info in peers {
+            added += self.add_peer_info(network, peer_info) as usize;
+        }
+        tracing::info!(total, added, "loaded routing table");
+    }
+
+    async fn persist_routing_table(&self, path: &Path) {
+        let peers = self.routing_table.lock().unwrap().peers();
+        let count = peers.len();
+
+        let path_buf = path.to_owned();
+        let res = tokio::task::spawn_blocking(move || persist::save_peers(&path_buf, &peers)).await;
+        match res {
+            Ok(Ok(())) => tracing::debug!(count, "saved routing table"),
+            Ok(Err(e)) => {
+                tracing::warn!(path = %path.display(), "failed to save routing table: {e:?}");
+            }
+            Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+            Err(_) => {}
+        }
+    }
+
     fn refresh_local_peer_info(&self, network: &Network) {
         let peer_info = self.make_local_peer_info(network, now_sec());
         *self.local_peer_info.lock().unwrap() = Some(peer_info);
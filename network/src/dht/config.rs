@@ -1,3 +1,4 @@
+use std::path::PathBuf;
 use std::time::Duration;
 
 use bytesize::ByteSize;
@@ -66,10 +67,36 @@ pub struct DhtConfig {
     #[serde(with = "serde_helpers::humantime")]
     pub routing_table_refresh_period_max_jitter: Duration,
 
+    /// A file to periodically save the routing table to.
+    /// Saved peers are loaded back when the background tasks start.
+    ///
+    /// Default: disabled.
+    pub routing_table_persist_path: Option<PathBuf>,
+
+    /// A period of saving the routing table to `routing_table_persist_path`.
+    ///
+    /// Default: 5 minutes.
+    #[serde(with = "serde_helpers::humantime")]
+    pub routing_table_persist_period: Duration,
+
     /// The capacity of the announced peers channel.
     ///
     /// Default: 10.
     pub announced_peers_channel_capacity: usize,
+
+    /// Whether the node should only use the DHT without storing
+    /// or serving values for other nodes.
+    ///
+    /// NOTE: The routing table is still maintained for lookups.
+    ///
+    /// Default: false.
+    pub client_only: bool,
+
+    /// Limits the rate of values stored by each remote peer
+    /// to make flooding of the storage more expensive.
+    ///
+    /// Default: disabled.
+    pub store_rate_limit: Option<DhtStoreRateLimit>,
 }
 
 impl Default for DhtConfig {
@@ -85,7 +112,39 @@ impl Default for DhtConfig {
             local_info_announce_period_max_jitter: Duration::from_secs(60),
             routing_table_refresh_period: Duration::from_secs(600),
             routing_table_refresh_period_max_jitter: Duration::from_secs(60),
+            routing_table_persist_path: None,
+            routing_table_persist_period: Duration::from_secs(300),
             announced_peers_channel_capacity: 10,
+            client_only: false,
+            store_rate_limit: None,
+        }
+    }
+}
+
+/// Token bucket for values stored by a single remote peer.
+///
+/// NOTE: A peer refreshes its values once per announce period,
+/// so even the default limit is orders of magnitude above the legit rate.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DhtStoreRateLimit {
+    /// Maximum number of values which can be stored at once.
+    ///
+    /// Default: 20.
+    pub burst: u32,
+
+    /// Time to restore a single value from the burst.
+    ///
+    /// Default: 1 second.
+    #[serde(with = "serde_helpers::humantime")]
+    pub refill_interval: Duration,
+}
+
+impl Default for DhtStoreRateLimit {
+    fn default() -> Self {
+        Self {
+            burst: 20,
+            refill_interval: Duration::from_secs(1),
         }
     }
 }
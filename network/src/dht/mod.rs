@@ -1,26 +1,28 @@
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use anyhow::Result;
 use bytes::{Buf, Bytes};
 use rand::RngCore;
 use tl_proto::TlRead;
 use tokio::sync::{broadcast, Notify};
+use tycho_util::futures::JoinTask;
 use tycho_util::realloc_box_enum;
 use tycho_util::time::now_sec;
 
-pub use self::config::DhtConfig;
+pub use self::config::{DhtConfig, DhtStoreRateLimit};
 pub use self::peer_resolver::{
-    PeerResolver, PeerResolverBuilder, PeerResolverConfig, PeerResolverHandle,
+    PeerResolveError, PeerResolver, PeerResolverBuilder, PeerResolverConfig, PeerResolverHandle,
 };
 pub use self::query::DhtQueryMode;
 use self::query::{Query, QueryCache, StoreValue};
 use self::routing::HandlesRoutingTable;
 use self::storage::Storage;
-pub use self::storage::{DhtValueMerger, DhtValueSource, StorageError};
+pub use self::storage::{DhtObserver, DhtValueMerger, DhtValueSource, StorageError};
 use crate::network::Network;
 use crate::proto::dht::{
     rpc, NodeInfoResponse, NodeResponse, PeerValue, PeerValueKey, PeerValueKeyName,
-    PeerValueKeyRef, PeerValueRef, Value, ValueRef, ValueResponseRaw,
+    PeerValueKeyRef, PeerValueRef, Value, ValueRef, ValueResponseRaw, DEFAULT_NAMESPACE,
 };
 use crate::types::{PeerId, PeerInfo, Request, Response, Service, ServiceRequest};
 use crate::util::{NetworkExt, Routable};
@@ -28,6 +30,7 @@ use crate::util::{NetworkExt, Routable};
 mod background_tasks;
 mod config;
 mod peer_resolver;
+mod persist;
 mod query;
 mod routing;
 mod storage;
@@ -46,6 +49,7 @@ const METRIC_IN_REQ_STORE_TOTAL: &str = "tycho_net_dht_in_req_store_value_total"
 pub struct DhtClient {
     inner: Arc<DhtInner>,
     network: Network,
+    namespace: u32,
 }
 
 impl DhtClient {
@@ -74,10 +78,30 @@ impl DhtClient {
         Ok(info)
     }
 
+    /// Returns a client which reads and writes peer values in the specified namespace.
+    ///
+    /// Values with the same key name and owner never collide across namespaces.
+    /// Use [`DEFAULT_NAMESPACE`] to get the client for the shared keyspace.
+    ///
+    /// [`DEFAULT_NAMESPACE`]: crate::proto::dht::DEFAULT_NAMESPACE
+    pub fn with_namespace(&self, namespace: u32) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            network: self.network.clone(),
+            namespace,
+        }
+    }
+
+    #[inline]
+    pub fn namespace(&self) -> u32 {
+        self.namespace
+    }
+
     pub fn entry(&self, name: PeerValueKeyName) -> DhtQueryBuilder<'_> {
         DhtQueryBuilder {
             inner: &self.inner,
             network: &self.network,
+            namespace: self.namespace,
             name,
             idx: 0,
         }
@@ -93,8 +117,9 @@ impl DhtClient {
 
 #[derive(Clone, Copy)]
 pub struct DhtQueryBuilder<'a> {
-    inner: &'a DhtInner,
+    inner: &'a Arc<DhtInner>,
     network: &'a Network,
+    namespace: u32,
     name: PeerValueKeyName,
     idx: u32,
 }
@@ -111,6 +136,7 @@ impl<'a> DhtQueryBuilder<'a> {
         for<'tl> T: tl_proto::TlRead<'tl>,
     {
         let key_hash = tl_proto::hash(PeerValueKeyRef {
+            namespace: self.namespace,
             name: self.name,
             peer_id,
         });
@@ -137,6 +163,7 @@ impl<'a> DhtQueryBuilder<'a> {
         peer_id: &PeerId,
     ) -> Result<Box<PeerValue>, FindValueError> {
         let key_hash = tl_proto::hash(PeerValueKeyRef {
+            namespace: self.namespace,
             name: self.name,
             peer_id,
         });
@@ -219,12 +246,65 @@ impl DhtQueryWithDataBuilder<'_> {
         dht.store_value_locally(&ValueRef::Peer(value))
     }
 
+    /// Spawns a background task which signs and stores the value
+    /// every `interval` until the returned guard is dropped.
+    ///
+    /// Each store uses a fresh expiration time, so [`with_time`] is ignored.
+    /// The interval must be shorter than the value TTL, otherwise the value
+    /// would expire between two stores.
+    ///
+    /// [`with_time`]: Self::with_time
+    pub fn republish(&self, interval: Duration) -> Result<DhtRepublishGuard, RepublishError> {
+        let ttl = Duration::from_secs(self.ttl as u64);
+        if ttl > self.inner.inner.config.max_stored_value_ttl {
+            return Err(RepublishError::UnsupportedTtl);
+        }
+        if interval.is_zero() || interval >= ttl {
+            return Err(RepublishError::InvalidInterval);
+        }
+
+        let dht = self.inner.inner.clone();
+        let network = self.inner.network.clone();
+        let (namespace, name, idx) = (self.namespace, self.name, self.idx);
+        let data = self.data.clone();
+        let ttl = self.ttl;
+        let with_peer_info = self.with_peer_info;
+
+        let task = JoinTask::new(async move {
+            let builder = DhtQueryWithDataBuilder {
+                inner: DhtQueryBuilder {
+                    inner: &dht,
+                    network: &network,
+                    namespace,
+                    name,
+                    idx,
+                },
+                data,
+                at: None,
+                ttl,
+                with_peer_info,
+            };
+
+            let mut interval = tokio::time::interval(interval);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                if let Err(e) = builder.store().await {
+                    tracing::warn!(?name, "failed to republish DHT value: {e}");
+                }
+            }
+        });
+
+        Ok(DhtRepublishGuard { _task: task })
+    }
+
     pub fn into_signed_value(self) -> PeerValue {
         let dht = self.inner.inner;
         let network = self.inner.network;
 
         let mut value = PeerValue {
             key: PeerValueKey {
+                namespace: self.namespace,
                 name: self.name,
                 peer_id: dht.local_id,
             },
@@ -239,6 +319,7 @@ impl DhtQueryWithDataBuilder<'_> {
     fn make_unsigned_value_ref(&self) -> PeerValueRef<'_> {
         PeerValueRef {
             key: PeerValueKeyRef {
+                namespace: self.inner.namespace,
                 name: self.inner.name,
                 peer_id: &self.inner.inner.local_id,
             },
@@ -265,6 +346,12 @@ impl std::ops::DerefMut for DhtQueryWithDataBuilder<'_> {
     }
 }
 
+/// Keeps the value republished while alive. Dropping it stops the task.
+#[must_use = "the value is no longer republished when the guard is dropped"]
+pub struct DhtRepublishGuard {
+    _task: JoinTask<()>,
+}
+
 pub struct DhtServiceBackgroundTasks {
     inner: Arc<DhtInner>,
 }
@@ -279,6 +366,7 @@ impl DhtServiceBackgroundTasks {
 pub struct DhtServiceBuilder {
     local_id: PeerId,
     config: Option<DhtConfig>,
+    observer: Option<Arc<dyn DhtObserver>>,
 }
 
 impl DhtServiceBuilder {
@@ -287,13 +375,20 @@ impl DhtServiceBuilder {
         self
     }
 
+    pub fn with_observer(mut self, observer: Arc<dyn DhtObserver>) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
     pub fn build(self) -> (DhtServiceBackgroundTasks, DhtService) {
         let config = self.config.unwrap_or_default();
 
         let storage = {
             let mut builder = Storage::builder()
                 .with_max_capacity(config.max_storage_capacity)
-                .with_max_ttl(config.max_stored_value_ttl);
+                .with_max_ttl(config.max_stored_value_ttl)
+                .with_observer(self.observer)
+                .with_rate_limit(config.store_rate_limit);
 
             if let Some(time_to_idle) = config.storage_item_time_to_idle {
                 builder = builder.with_max_idle(time_to_idle);
@@ -338,6 +433,7 @@ impl DhtService {
         DhtServiceBuilder {
             local_id,
             config: None,
+            observer: None,
         }
     }
 
@@ -349,6 +445,7 @@ impl DhtService {
         DhtClient {
             inner: self.0.clone(),
             network: network.clone(),
+            namespace: DEFAULT_NAMESPACE,
         }
     }
 
@@ -464,7 +561,7 @@ impl Service<ServiceRequest> for DhtService {
                 tracing::debug!("store");
                 metrics::counter!(METRIC_IN_REQ_STORE_TOTAL).increment(1);
 
-                if let Err(e) = self.0.handle_store(r) {
+                if let Err(e) = self.0.handle_store(&req.metadata.peer_id, r) {
                     tracing::debug!("failed to store value: {e}");
                     has_error = true;
                 }
@@ -537,7 +634,9 @@ impl DhtInner {
         value: &ValueRef<'_>,
         with_peer_info: bool,
     ) -> Result<()> {
-        self.storage.insert(DhtValueSource::Local, value)?;
+        if !self.config.client_only {
+            self.storage.insert(DhtValueSource::Local, value)?;
+        }
 
         let local_peer_info = if with_peer_info {
             let mut node_info = self.local_peer_info.lock().unwrap();
@@ -564,6 +663,9 @@ impl DhtInner {
     }
 
     fn store_value_locally(&self, value: &ValueRef<'_>) -> Result<bool, StorageError> {
+        if self.config.client_only {
+            return Ok(false);
+        }
         self.storage.insert(DhtValueSource::Local, value)
     }
 
@@ -596,6 +698,7 @@ impl DhtInner {
     ) -> PeerValueRef<'a> {
         PeerValueRef {
             key: PeerValueKeyRef {
+                namespace: DEFAULT_NAMESPACE,
                 name,
                 peer_id: &self.local_id,
             },
@@ -635,8 +738,15 @@ impl DhtInner {
         Ok((constructor, body))
     }
 
-    fn handle_store(&self, req: &rpc::StoreRef<'_>) -> Result<bool, StorageError> {
-        self.storage.insert(DhtValueSource::Remote, &req.value)
+    fn handle_store(
+        &self,
+        peer_id: &PeerId,
+        req: &rpc::StoreRef<'_>,
+    ) -> Result<bool, StorageError> {
+        if self.config.client_only {
+            return Err(StorageError::ClientOnly);
+        }
+        self.storage.insert_from_peer(peer_id, &req.value)
     }
 
     fn handle_find_node(&self, req: &rpc::FindNode) -> NodeResponse {
@@ -650,7 +760,13 @@ impl DhtInner {
     }
 
     fn handle_find_value(&self, req: &rpc::FindValue) -> ValueResponseRaw {
-        if let Some(value) = self.storage.get(&req.key) {
+        let value = match self.config.client_only {
+            // Client-only nodes never serve values
+            true => None,
+            false => self.storage.get(&req.key),
+        };
+
+        if let Some(value) = value {
             ValueResponseRaw::Found(value)
         } else {
             let nodes = self
@@ -712,8 +828,20 @@ pub enum FindValueError {
     NotFound,
 }
 
+#[derive(Debug, thiserror::Error)]
+pub enum RepublishError {
+    #[error("value ttl exceeds the max stored value ttl")]
+    UnsupportedTtl,
+    #[error("republish interval must be non-zero and shorter than the value ttl")]
+    InvalidInterval,
+}
+
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
+    use everscale_crypto::ed25519;
+
     use super::*;
 
     #[test]
@@ -727,4 +855,165 @@ mod tests {
         println!("{distance}");
         assert!(distance <= 23);
     }
+
+    #[test]
+    fn client_only_node_rejects_store() {
+        let keypair = ed25519::KeyPair::generate(&mut rand::thread_rng());
+        let peer_id = PeerId::from(keypair.public_key);
+
+        let mut value = PeerValueRef {
+            key: PeerValueKeyRef {
+                namespace: DEFAULT_NAMESPACE,
+                name: PeerValueKeyName::NodeInfo,
+                peer_id: &peer_id,
+            },
+            data: &[1, 2, 3],
+            expires_at: now_sec() + 600,
+            signature: &[0; 64],
+        };
+        let signature = keypair.sign(&value);
+        value.signature = &signature;
+        let key_hash = tl_proto::hash(&value.key);
+        let value = ValueRef::Peer(value);
+
+        let make_dht = |client_only: bool| {
+            let (_, dht) = DhtService::builder(rand::random())
+                .with_config(DhtConfig {
+                    client_only,
+                    ..Default::default()
+                })
+                .build();
+            dht
+        };
+        let find_value = rpc::FindValue {
+            key: key_hash,
+            k: 6,
+        };
+
+        // Regular node stores and serves remote values
+        let dht = make_dht(false);
+        let store = rpc::StoreRef::wrap(&value);
+        assert!(dht.0.handle_store(&peer_id, store).unwrap());
+        assert!(matches!(
+            dht.0.handle_find_value(&find_value),
+            ValueResponseRaw::Found(_)
+        ));
+
+        // Client-only node does neither
+        let dht = make_dht(true);
+        assert!(matches!(
+            dht.0.handle_store(&peer_id, rpc::StoreRef::wrap(&value)),
+            Err(StorageError::ClientOnly)
+        ));
+        assert!(!dht.store_value_locally(&value).unwrap());
+        assert!(matches!(
+            dht.0.handle_find_value(&find_value),
+            ValueResponseRaw::NotFound(_)
+        ));
+    }
+
+    #[test]
+    fn observer_sees_insert() {
+        #[derive(Default)]
+        struct Inserts(parking_lot::Mutex<Vec<([u8; 32], usize)>>);
+
+        impl DhtObserver for Inserts {
+            fn on_insert(&self, key: &[u8; 32], size: usize) {
+                self.0.lock().push((*key, size));
+            }
+        }
+
+        let keypair = ed25519::KeyPair::generate(&mut rand::thread_rng());
+        let peer_id = PeerId::from(keypair.public_key);
+
+        let mut value = PeerValueRef {
+            key: PeerValueKeyRef {
+                namespace: DEFAULT_NAMESPACE,
+                name: PeerValueKeyName::NodeInfo,
+                peer_id: &peer_id,
+            },
+            data: &[1, 2, 3],
+            expires_at: now_sec() + 600,
+            signature: &[0; 64],
+        };
+        let signature = keypair.sign(&value);
+        value.signature = &signature;
+        let key_hash = tl_proto::hash(&value.key);
+        let value = ValueRef::Peer(value);
+
+        let observer = Arc::new(Inserts::default());
+        let (_, dht) = DhtService::builder(rand::random())
+            .with_observer(observer.clone())
+            .build();
+
+        assert!(dht.store_value_locally(&value).unwrap());
+        // Same value is not inserted twice
+        assert!(!dht.store_value_locally(&value).unwrap());
+
+        let inserts = observer.0.lock();
+        assert_eq!(inserts.len(), 1);
+        assert_eq!(inserts[0].0, key_hash);
+        assert!(inserts[0].1 > 0);
+    }
+
+    #[test]
+    fn store_rate_is_limited_per_peer() {
+        let make_value = |keypair: &ed25519::KeyPair, data: &[u8]| {
+            let peer_id = PeerId::from(keypair.public_key);
+            let mut value = PeerValueRef {
+                key: PeerValueKeyRef {
+                    namespace: DEFAULT_NAMESPACE,
+                    name: PeerValueKeyName::NodeInfo,
+                    peer_id: &peer_id,
+                },
+                data,
+                expires_at: now_sec() + 600,
+                signature: &[0; 64],
+            };
+            let signature = keypair.sign(&value);
+            value.signature = &signature;
+            tl_proto::serialize(ValueRef::Peer(value))
+        };
+        let store = |dht: &DhtService, peer_id: &PeerId, value: &[u8]| {
+            let value = tl_proto::deserialize::<ValueRef<'_>>(value).unwrap();
+            dht.0.handle_store(peer_id, rpc::StoreRef::wrap(&value))
+        };
+
+        let refill_interval = Duration::from_millis(200);
+        let (_, dht) = DhtService::builder(rand::random())
+            .with_config(DhtConfig {
+                store_rate_limit: Some(DhtStoreRateLimit {
+                    burst: 3,
+                    refill_interval,
+                }),
+                ..Default::default()
+            })
+            .build();
+
+        let keypair = ed25519::KeyPair::generate(&mut rand::thread_rng());
+        let abuser = PeerId::from(keypair.public_key);
+        let values = (0..5u8)
+            .map(|i| make_value(&keypair, &[i]))
+            .collect::<Vec<_>>();
+
+        // Burst is allowed, the rest is throttled
+        for value in &values[..3] {
+            assert!(store(&dht, &abuser, value).is_ok());
+        }
+        for value in &values[3..] {
+            let res = store(&dht, &abuser, value);
+            assert!(matches!(res, Err(StorageError::RateLimited)));
+        }
+
+        // Other peers are not affected
+        let keypair = ed25519::KeyPair::generate(&mut rand::thread_rng());
+        let peer_id = PeerId::from(keypair.public_key);
+        assert!(store(&dht, &peer_id, &make_value(&keypair, &[0])).unwrap());
+
+        // Stores are allowed again at the refill rate
+        for value in &values[3..] {
+            std::thread::sleep(refill_interval);
+            assert!(store(&dht, &abuser, value).is_ok());
+        }
+    }
 }
@@ -4,13 +4,14 @@ use std::sync::{Arc, Mutex, Weak};
 use std::time::Duration;
 
 use exponential_backoff::Backoff;
+use futures_util::{Stream, StreamExt};
 use serde::{Deserialize, Serialize};
 use tokio::sync::{Notify, Semaphore};
 use tycho_util::futures::JoinTask;
 use tycho_util::time::now_sec;
-use tycho_util::{serde_helpers, FastDashMap};
+use tycho_util::{serde_helpers, FastDashMap, FastHashSet};
 
-use crate::dht::DhtService;
+use crate::dht::{DhtService, FindValueError};
 use crate::network::{KnownPeerHandle, KnownPeersError, Network, PeerBannedError, WeakNetwork};
 use crate::proto::dht;
 use crate::types::{PeerId, PeerInfo};
@@ -33,7 +34,7 @@ impl PeerResolverBuilder {
             inner: Arc::new(PeerResolverInner {
                 weak_network: Network::downgrade(network),
                 dht_service: self.dht_service,
-                config: Default::default(),
+                config: self.inner,
                 tasks: Default::default(),
                 semaphore,
             }),
@@ -45,6 +46,7 @@ impl PeerResolverBuilder {
 #[serde(default)]
 pub struct PeerResolverConfig {
     /// Maximum number of parallel resolve requests.
+    /// Also limits the number of lookups started by [`PeerResolver::resolve_many`].
     ///
     /// Default: 100.
     pub max_parallel_resolve_requests: usize,
@@ -82,11 +84,18 @@ pub struct PeerResolverConfig {
     #[serde(with = "serde_helpers::humantime")]
     pub max_retry_interval: Duration,
 
-    /// Interval between the stale retries.
+    /// Initial interval between the stale retries.
+    /// It is doubled after each failed attempt.
     ///
     /// Default: 600 seconds.
     #[serde(with = "serde_helpers::humantime")]
     pub stale_retry_interval: Duration,
+
+    /// Maximal interval between the stale retries.
+    ///
+    /// Default: 1 hour.
+    #[serde(with = "serde_helpers::humantime")]
+    pub max_stale_retry_interval: Duration,
 }
 
 impl Default for PeerResolverConfig {
@@ -100,6 +109,7 @@ impl Default for PeerResolverConfig {
             min_retry_interval: Duration::from_secs(1),
             max_retry_interval: Duration::from_secs(120),
             stale_retry_interval: Duration::from_secs(600),
+            max_stale_retry_interval: Duration::from_secs(3600),
         }
     }
 }
@@ -143,6 +153,35 @@ impl PeerResolver {
             },
         }
     }
+
+    /// Resolves each peer once and yields results as they complete.
+    ///
+    /// Known peers are returned without a lookup. Duplicate ids are resolved once,
+    /// and concurrent DHT lookups for the same peer are shared between callers.
+    pub fn resolve_many(
+        &self,
+        peer_ids: &[PeerId],
+    ) -> impl Stream<Item = (PeerId, Result<Arc<PeerInfo>, PeerResolveError>)> + Send + 'static
+    {
+        let mut unique = FastHashSet::default();
+        let peer_ids = peer_ids
+            .iter()
+            .filter(|peer_id| unique.insert(**peer_id))
+            .copied()
+            .collect::<Vec<_>>();
+
+        let inner = self.inner.clone();
+        let max_parallel = inner.config.max_parallel_resolve_requests.max(1);
+        futures_util::stream::iter(peer_ids)
+            .map(move |peer_id| {
+                let inner = inner.clone();
+                async move {
+                    let res = inner.resolve_once(&peer_id).await;
+                    (peer_id, res)
+                }
+            })
+            .buffer_unordered(max_parallel)
+    }
 }
 
 struct PeerResolverInner {
@@ -242,45 +281,16 @@ impl PeerResolverInner {
         data: &PeerResolverHandleData,
         prev_timings: &Option<PeerResolverTimings>,
     ) -> Option<(Network, Arc<PeerInfo>)> {
-        struct Iter<'a> {
-            backoff: Option<exponential_backoff::Iter<'a>>,
-            data: &'a PeerResolverHandleData,
-            stale_retry_interval: &'a Duration,
-        }
-
-        impl Iterator for Iter<'_> {
-            type Item = Duration;
-
-            fn next(&mut self) -> Option<Self::Item> {
-                Some(loop {
-                    match self.backoff.as_mut() {
-                        // Get next duration from the backoff iterator.
-                        Some(backoff) => match backoff.next() {
-                            // Use it for the first attempts.
-                            Some(duration) => break duration,
-                            // Set `is_stale` flag on last attempt and continue wih only
-                            // the `stale_retry_interval` for all subsequent iterations.
-                            None => {
-                                self.data.set_stale(true);
-                                self.backoff = None;
-                            }
-                        },
-                        // Use `stale_retry_interval` after the max retry count is reached.
-                        None => break *self.stale_retry_interval,
-                    }
-                })
-            }
-        }
-
         let backoff = Backoff::new(
             self.config.fast_retry_count,
             self.config.min_retry_interval,
             Some(self.config.max_retry_interval),
         );
-        let mut iter = Iter {
+        let mut iter = RetryIntervals {
             backoff: Some(backoff.iter()),
             data,
-            stale_retry_interval: &self.config.stale_retry_interval,
+            stale_retry_interval: self.config.stale_retry_interval,
+            max_stale_retry_interval: self.config.max_stale_retry_interval,
         };
 
         // "Fast" path
@@ -300,6 +310,7 @@ impl PeerResolverInner {
                             is_stale,
                             "peer info exists",
                         );
+                        data.set_stale(false);
                         return Some((network, peer_info));
                     }
                 }
@@ -322,6 +333,7 @@ impl PeerResolverInner {
                         // NOTE: We only need a NEW peer info, otherwise the `resolve_peer`
                         // method will be called again and again and again... without any progress.
                         if PeerResolverTimings::is_new_info(prev_timings, &peer_info) {
+                            data.set_stale(false);
                             return Some((network, Arc::new(peer_info)));
                         }
                     }
@@ -349,6 +361,31 @@ impl PeerResolverInner {
         }
     }
 
+    async fn resolve_once(&self, peer_id: &PeerId) -> Result<Arc<PeerInfo>, PeerResolveError> {
+        let network = self
+            .weak_network
+            .upgrade()
+            .ok_or(PeerResolveError::NetworkShutdown)?;
+
+        if let Some(peer_info) = network.known_peers().get(peer_id) {
+            return Ok(peer_info);
+        }
+
+        let dht_client = self.dht_service.make_client(&network);
+        let peer_info = {
+            let _permit = self.semaphore.acquire().await.unwrap();
+            dht_client
+                .entry(dht::PeerValueKeyName::NodeInfo)
+                .find_value::<PeerInfo>(peer_id)
+                .await?
+        };
+
+        if peer_info.id != *peer_id || !peer_info.verify(now_sec()) {
+            return Err(PeerResolveError::InvalidPeerInfo);
+        }
+        Ok(Arc::new(peer_info))
+    }
+
     fn compute_timings(&self, peer_info: &PeerInfo) -> PeerResolverTimings {
         let real_ttl = peer_info
             .expires_at
@@ -364,6 +401,44 @@ impl PeerResolverInner {
     }
 }
 
+/// Infinite sequence of intervals between resolve attempts.
+struct RetryIntervals<'a> {
+    backoff: Option<exponential_backoff::Iter<'a>>,
+    data: &'a PeerResolverHandleData,
+    stale_retry_interval: Duration,
+    max_stale_retry_interval: Duration,
+}
+
+impl Iterator for RetryIntervals<'_> {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(loop {
+            match self.backoff.as_mut() {
+                // Get next duration from the backoff iterator.
+                Some(backoff) => match backoff.next() {
+                    // Use it for the first attempts.
+                    Some(duration) => break duration,
+                    // Set `is_stale` flag on last attempt and continue wih only
+                    // the `stale_retry_interval` for all subsequent iterations.
+                    None => {
+                        self.data.set_stale(true);
+                        self.backoff = None;
+                    }
+                },
+                // Double `stale_retry_interval` after each stale attempt
+                // so dead peers don't consume constant lookup traffic.
+                None => {
+                    let interval = self.stale_retry_interval;
+                    self.stale_retry_interval =
+                        std::cmp::min(interval.saturating_mul(2), self.max_stale_retry_interval);
+                    break std::cmp::min(interval, self.max_stale_retry_interval);
+                }
+            }
+        })
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 struct PeerResolverTimings {
     created_at: u32,
@@ -508,3 +583,59 @@ impl PeerResolverHandleData {
 
 const STALE_FLAG: u32 = 0b1;
 const RESOLVED_FLAG: u32 = 0b10;
+
+#[derive(Debug, thiserror::Error)]
+pub enum PeerResolveError {
+    #[error("network is shut down")]
+    NetworkShutdown,
+    #[error(transparent)]
+    NotResolved(#[from] FindValueError),
+    #[error("received an invalid peer info")]
+    InvalidPeerInfo,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stale_retry_interval_grows_until_max() {
+        let config = PeerResolverConfig {
+            fast_retry_count: 3,
+            min_retry_interval: Duration::from_secs(1),
+            max_retry_interval: Duration::from_secs(10),
+            stale_retry_interval: Duration::from_secs(60),
+            max_stale_retry_interval: Duration::from_secs(200),
+            ..Default::default()
+        };
+
+        let data = PeerResolverHandleData::new(&PeerId([0; 32]), None);
+        let backoff = Backoff::new(
+            config.fast_retry_count,
+            config.min_retry_interval,
+            Some(config.max_retry_interval),
+        );
+        let mut iter = RetryIntervals {
+            backoff: Some(backoff.iter()),
+            data: &data,
+            stale_retry_interval: config.stale_retry_interval,
+            max_stale_retry_interval: config.max_stale_retry_interval,
+        };
+
+        // The first stale interval is returned by the same call which marks the peer as stale
+        let mut fast = Vec::new();
+        while !data.is_stale() {
+            fast.push(iter.next().unwrap());
+        }
+        let first_stale = fast.pop().unwrap();
+        assert!(fast.len() <= config.fast_retry_count as usize);
+        assert!(fast.iter().all(|d| *d <= config.max_retry_interval));
+
+        let stale = std::iter::once(first_stale)
+            .chain(iter.take(3))
+            .map(|d| d.as_secs())
+            .collect::<Vec<_>>();
+        assert_eq!(stale, [60, 120, 200, 200]);
+        assert!(data.is_stale());
+    }
+}
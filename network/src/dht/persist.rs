@@ -0,0 +1,72 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+
+use crate::types::PeerInfo;
+
+/// Writes peers to the file, replacing it atomically.
+pub(crate) fn save_peers(path: &Path, peers: &[Arc<PeerInfo>]) -> Result<()> {
+    let data = tl_proto::serialize(peers);
+
+    let temp_path = path.with_extension("tmp");
+    std::fs::write(&temp_path, data).context("failed to write routing table")?;
+    std::fs::rename(&temp_path, path).context("failed to replace routing table")?;
+    Ok(())
+}
+
+/// Reads peers from the file, skipping expired or invalid entries.
+/// A missing file is treated as empty.
+pub(crate) fn load_peers(path: &Path, now: u32) -> Result<Vec<Arc<PeerInfo>>> {
+    let data = match std::fs::read(path) {
+        Ok(data) => data,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).context("failed to read routing table"),
+    };
+
+    let mut peers = tl_proto::deserialize::<Vec<Arc<PeerInfo>>>(&data)
+        .context("failed to deserialize routing table")?;
+    peers.retain(|peer| peer.verify(now));
+    Ok(peers)
+}
+
+#[cfg(test)]
+mod tests {
+    use everscale_crypto::ed25519;
+
+    use super::*;
+    use crate::types::Address;
+
+    fn make_signed_peer_info(expires_at: u32) -> Arc<PeerInfo> {
+        let keypair = ed25519::KeyPair::generate(&mut rand::thread_rng());
+
+        let mut peer_info = PeerInfo {
+            id: keypair.public_key.into(),
+            address_list: Box::new([Address::from((std::net::Ipv4Addr::LOCALHOST, 1234))]),
+            created_at: 0,
+            expires_at,
+            signature: Box::new([0; 64]),
+        };
+        *peer_info.signature = keypair.sign(&peer_info);
+        Arc::new(peer_info)
+    }
+
+    #[test]
+    fn loaded_peers_are_verified() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("routing_table");
+
+        // Missing file is not an error
+        assert!(load_peers(&path, 100)?.is_empty());
+
+        let valid = make_signed_peer_info(u32::MAX);
+        let expired = make_signed_peer_info(10);
+        let mut forged = make_signed_peer_info(u32::MAX).as_ref().clone();
+        forged.expires_at -= 1;
+
+        save_peers(&path, &[valid.clone(), expired, Arc::new(forged)])?;
+        assert_eq!(load_peers(&path, 100)?, vec![valid]);
+
+        Ok(())
+    }
+}
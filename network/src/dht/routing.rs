@@ -58,6 +58,14 @@ impl<T: AsPeerInfo> RoutingTable<T> {
             .insert(peer, max_k, node_ttl, f)
     }
 
+    pub fn peers(&self) -> Vec<Arc<PeerInfo>> {
+        self.buckets
+            .values()
+            .flat_map(|bucket| bucket.nodes.iter())
+            .map(|node| node.data.load_peer_info())
+            .collect()
+    }
+
     pub fn closest(&self, key: &[u8; 32], count: usize) -> Vec<Arc<PeerInfo>> {
         if count == 0 {
             return Vec::new();
@@ -625,6 +633,7 @@ mod tests {
         // Announce peer info (aka push)
         for peer_info in &peer_info {
             let key_hash = tl_proto::hash(crate::proto::dht::PeerValueKeyRef {
+                namespace: crate::proto::dht::DEFAULT_NAMESPACE,
                 name: crate::proto::dht::PeerValueKeyName::NodeInfo,
                 peer_id: &peer_info.id,
             });
@@ -662,6 +671,7 @@ mod tests {
 
             for random_id in random_ids {
                 let key_hash = tl_proto::hash(crate::proto::dht::PeerValueKeyRef {
+                    namespace: crate::proto::dht::DEFAULT_NAMESPACE,
                     name: crate::proto::dht::PeerValueKeyName::NodeInfo,
                     peer_id: &random_id,
                 });
@@ -1,17 +1,21 @@
 use std::cell::RefCell;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use anyhow::Result;
 use bytes::{Bytes, BytesMut};
 use bytesize::ByteSize;
 use moka::sync::{Cache, CacheBuilder};
 use moka::Expiry;
+use parking_lot::Mutex;
 use tl_proto::TlWrite;
 use tycho_util::time::now_sec;
 use tycho_util::FastDashMap;
 
+use crate::dht::config::DhtStoreRateLimit;
 use crate::proto::dht::{MergedValue, MergedValueRef, PeerValueRef, ValueRef};
+use crate::types::PeerId;
+use crate::util::TokenBucket;
 
 type DhtCache<S> = Cache<StorageKeyId, StoredValue, S>;
 type DhtCacheBuilder<S> = CacheBuilder<StorageKeyId, StoredValue, DhtCache<S>>;
@@ -37,10 +41,35 @@ pub trait DhtValueMerger: Send + Sync + 'static {
     ) -> bool;
 }
 
+/// Observes stored values without affecting them.
+///
+/// Methods are called synchronously from the storage, so they must not block.
+pub trait DhtObserver: Send + Sync + 'static {
+    /// A new value was stored or the existing one was replaced.
+    fn on_insert(&self, key: &StorageKeyId, size: usize) {
+        _ = key;
+        _ = size;
+    }
+
+    /// A stored value was served.
+    fn on_get(&self, key: &StorageKeyId, size: usize) {
+        _ = key;
+        _ = size;
+    }
+
+    /// A stored value was removed because it expired or the storage is full.
+    fn on_evict(&self, key: &StorageKeyId, size: usize) {
+        _ = key;
+        _ = size;
+    }
+}
+
 pub(crate) struct StorageBuilder {
     cache_builder: DhtCacheBuilder<std::hash::RandomState>,
     value_mergers: FastDashMap<[u8; 32], Arc<dyn DhtValueMerger>>,
     max_ttl: Duration,
+    observer: Option<Arc<dyn DhtObserver>>,
+    rate_limit: Option<DhtStoreRateLimit>,
 }
 
 impl Default for StorageBuilder {
@@ -49,6 +78,8 @@ impl Default for StorageBuilder {
             cache_builder: Default::default(),
             value_mergers: Default::default(),
             max_ttl: Duration::from_secs(3600),
+            observer: None,
+            rate_limit: None,
         }
     }
 }
@@ -61,15 +92,26 @@ impl StorageBuilder {
                 + value.data.len() as u32
         }
 
+        let mut cache_builder = self
+            .cache_builder
+            .time_to_live(self.max_ttl)
+            .weigher(weigher)
+            .expire_after(ValueExpiry);
+
+        if let Some(observer) = self.observer.clone() {
+            cache_builder = cache_builder.eviction_listener(move |key, value, cause| {
+                if cause.was_evicted() {
+                    observer.on_evict(&key, value.data.len());
+                }
+            });
+        }
+
         Storage {
-            cache: self
-                .cache_builder
-                .time_to_live(self.max_ttl)
-                .weigher(weigher)
-                .expire_after(ValueExpiry)
-                .build_with_hasher(ahash::RandomState::default()),
+            cache: cache_builder.build_with_hasher(ahash::RandomState::default()),
             value_mergers: self.value_mergers,
             max_ttl_sec: self.max_ttl.as_secs().try_into().unwrap_or(u32::MAX),
+            observer: self.observer,
+            rate_limiter: self.rate_limit.map(StoreRateLimiter::new),
         }
     }
 
@@ -97,12 +139,24 @@ impl StorageBuilder {
         self.cache_builder = self.cache_builder.time_to_idle(duration);
         self
     }
+
+    pub fn with_observer(mut self, observer: Option<Arc<dyn DhtObserver>>) -> Self {
+        self.observer = observer;
+        self
+    }
+
+    pub fn with_rate_limit(mut self, rate_limit: Option<DhtStoreRateLimit>) -> Self {
+        self.rate_limit = rate_limit;
+        self
+    }
 }
 
 pub(crate) struct Storage {
     cache: DhtCache<ahash::RandomState>,
     value_mergers: FastDashMap<[u8; 32], Arc<dyn DhtValueMerger>>,
     max_ttl_sec: u32,
+    observer: Option<Arc<dyn DhtObserver>>,
+    rate_limiter: Option<StoreRateLimiter>,
 }
 
 impl Storage {
@@ -126,7 +180,30 @@ impl Storage {
 
     pub fn get(&self, key: &[u8; 32]) -> Option<Bytes> {
         let stored_value = self.cache.get(key)?;
-        (stored_value.expires_at > now_sec()).then_some(stored_value.data)
+        if stored_value.expires_at <= now_sec() {
+            return None;
+        }
+
+        if let Some(observer) = &self.observer {
+            observer.on_get(key, stored_value.data.len());
+        }
+        Some(stored_value.data)
+    }
+
+    /// Same as [`insert`] but also checks the store rate limit of the remote peer.
+    ///
+    /// [`insert`]: Self::insert
+    pub fn insert_from_peer(
+        &self,
+        peer_id: &PeerId,
+        value: &ValueRef<'_>,
+    ) -> Result<bool, StorageError> {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            if !rate_limiter.try_acquire(peer_id, Instant::now()) {
+                return Err(StorageError::RateLimited);
+            }
+        }
+        self.insert(DhtValueSource::Remote, value)
     }
 
     pub fn insert(
@@ -157,14 +234,15 @@ impl Storage {
             return Err(StorageError::InvalidSignature);
         }
 
-        Ok(self
+        let entry = self
             .cache
             .entry(tl_proto::hash(&value.key))
             .or_insert_with_if(
                 || StoredValue::new(value, value.expires_at),
                 |prev| prev.expires_at < value.expires_at,
-            )
-            .is_fresh())
+            );
+
+        Ok(self.observe_insert(&entry))
     }
 
     fn insert_merged_value(
@@ -195,7 +273,7 @@ impl Storage {
 
         let new_value = RefCell::new(MergedValueCow::Borrowed(value));
 
-        Ok(self
+        let entry = self
             .cache
             .entry(tl_proto::hash(&value.key))
             .or_insert_with_if(
@@ -216,8 +294,52 @@ impl Storage {
                         false
                     }
                 },
-            )
-            .is_fresh())
+            );
+
+        Ok(self.observe_insert(&entry))
+    }
+
+    fn observe_insert(&self, entry: &moka::Entry<StorageKeyId, StoredValue>) -> bool {
+        let is_fresh = entry.is_fresh();
+        if let Some(observer) = &self.observer {
+            if is_fresh {
+                observer.on_insert(entry.key(), entry.value().data.len());
+            }
+        }
+        is_fresh
+    }
+}
+
+struct StoreRateLimiter {
+    config: DhtStoreRateLimit,
+    buckets: Cache<PeerId, Arc<Mutex<TokenBucket>>, ahash::RandomState>,
+}
+
+impl StoreRateLimiter {
+    const MAX_PEERS: u64 = 10000;
+
+    fn new(mut config: DhtStoreRateLimit) -> Self {
+        config.burst = config.burst.max(1);
+        config.refill_interval = config.refill_interval.max(Duration::from_millis(1));
+
+        // NOTE: An idle bucket is full again, so it can be safely forgotten.
+        let time_to_idle = config.refill_interval.saturating_mul(config.burst);
+
+        Self {
+            config,
+            buckets: Cache::builder()
+                .max_capacity(Self::MAX_PEERS)
+                .time_to_idle(time_to_idle)
+                .build_with_hasher(Default::default()),
+        }
+    }
+
+    fn try_acquire(&self, peer_id: &PeerId, now: Instant) -> bool {
+        let bucket = self.buckets.get_with_by_ref(peer_id, || {
+            Arc::new(Mutex::new(TokenBucket::new(self.config.burst, now)))
+        });
+        let mut bucket = bucket.lock();
+        bucket.try_acquire(self.config.burst, self.config.refill_interval, now)
     }
 }
 
@@ -286,4 +408,8 @@ pub enum StorageError {
     ValueTooBig,
     #[error("invalid source")]
     InvalidSource,
+    #[error("storage is disabled in client-only mode")]
+    ClientOnly,
+    #[error("too many values stored by the peer")]
+    RateLimited,
 }
@@ -1,20 +1,22 @@
 pub use dht::{
-    xor_distance, DhtClient, DhtConfig, DhtQueryBuilder, DhtQueryMode, DhtQueryWithDataBuilder,
-    DhtService, DhtServiceBackgroundTasks, DhtServiceBuilder, DhtValueMerger, DhtValueSource,
-    FindValueError, PeerResolver, PeerResolverBuilder, PeerResolverConfig, PeerResolverHandle,
-    StorageError,
+    xor_distance, DhtClient, DhtConfig, DhtObserver, DhtQueryBuilder, DhtQueryMode,
+    DhtQueryWithDataBuilder, DhtRepublishGuard, DhtService, DhtServiceBackgroundTasks,
+    DhtServiceBuilder, DhtStoreRateLimit, DhtValueMerger, DhtValueSource, FindValueError,
+    PeerResolveError, PeerResolver, PeerResolverBuilder, PeerResolverConfig, PeerResolverHandle,
+    RepublishError, StorageError,
 };
 pub use network::{
-    BindError, Connection, ConnectionError, KnownPeerHandle, KnownPeers, KnownPeersError, Network,
-    NetworkBuilder, NetworkConfig, Peer, PeerBannedError, QuicConfig, RecvStream, SendStream,
-    ToSocket, WeakKnownPeerHandle, WeakNetwork,
+    BindError, CongestionController, Connection, ConnectionError, ConnectionEvent,
+    InboundRateLimit, KnownPeerHandle, KnownPeers, KnownPeersError, Network, NetworkBuilder,
+    NetworkConfig, Peer, PeerBannedError, QuicConfig, RecvStream, SendStream, ToSocket,
+    WeakKnownPeerHandle, WeakNetwork,
 };
 pub use quinn;
 pub use types::{
     service_message_fn, service_query_fn, Address, BoxCloneService, BoxService, Direction,
     DisconnectReason, InboundRequestMeta, PeerAffinity, PeerEvent, PeerEventData, PeerId, PeerInfo,
-    Request, Response, RpcQuery, Service, ServiceExt, ServiceMessageFn, ServiceQueryFn,
-    ServiceRequest, Version,
+    Priority, Request, Response, ResponsePriority, RpcQuery, Service, ServiceExt, ServiceMessageFn,
+    ServiceQueryFn, ServiceRequest, Version,
 };
 
 pub use self::overlay::{
@@ -26,8 +28,9 @@ pub use self::overlay::{
     PublicOverlayEntryData, UnknownPeersQueue,
 };
 pub use self::util::{
-    check_peer_signature, try_handle_prefix, try_handle_prefix_with_offset, NetworkExt, Routable,
-    Router, RouterBuilder, UnknownPeerError,
+    check_peer_signature, try_handle_prefix, try_handle_prefix_with_offset, DatagramChunkError,
+    DatagramChunker, DatagramReassembler, NetworkExt, Routable, Router, RouterBuilder,
+    UnknownPeerError,
 };
 
 mod dht;
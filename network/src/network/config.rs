@@ -1,7 +1,7 @@
 use std::sync::Arc;
 use std::time::Duration;
 
-use anyhow::{Context, Result};
+use anyhow::{ensure, Context, Result};
 use quinn::crypto::rustls::{QuicClientConfig, QuicServerConfig};
 use rustls::crypto::CryptoProvider;
 use rustls::sign::CertifiedKey;
@@ -31,6 +31,14 @@ pub struct NetworkConfig {
     /// Default: 8 MiB.
     pub max_frame_size: bytesize::ByteSize,
 
+    /// Max size of an inbound request body.
+    /// Larger requests are rejected right after reading the frame header.
+    ///
+    /// NOTE: Effectively limited by `max_frame_size`.
+    ///
+    /// Default: 4 MiB.
+    pub max_request_size: bytesize::ByteSize,
+
     /// Default: 10 seconds.
     #[serde(with = "serde_helpers::humantime")]
     pub connect_timeout: Duration,
@@ -64,6 +72,12 @@ pub struct NetworkConfig {
     /// Default: 128.
     pub max_concurrent_requests_per_peer: usize,
 
+    /// Limits the rate of inbound requests (uni and bi streams) from a single peer.
+    /// Requests above the limit are rejected.
+    ///
+    /// Default: disabled.
+    pub inbound_rate_limit: Option<InboundRateLimit>,
+
     /// Default: 1 minute.
     #[serde(with = "serde_helpers::humantime")]
     pub shutdown_idle_timeout: Duration,
@@ -73,6 +87,14 @@ pub struct NetworkConfig {
 
     /// Default: disabled.
     pub connection_metrics: Option<ConnectionMetricsLevel>,
+
+    /// Close connections without any streams for this duration
+    /// if the peer is not referenced by any overlay or resolver (i.e. is not a known peer).
+    /// Checked with `connectivity_check_interval`.
+    ///
+    /// Default: disabled.
+    #[serde(with = "serde_helpers::humantime")]
+    pub idle_connection_timeout: Option<Duration>,
 }
 
 impl Default for NetworkConfig {
@@ -82,6 +104,7 @@ impl Default for NetworkConfig {
             connection_manager_channel_capacity: 128,
             connectivity_check_interval: Duration::from_millis(5000),
             max_frame_size: bytesize::ByteSize::mib(8),
+            max_request_size: bytesize::ByteSize::mib(4),
             connect_timeout: Duration::from_secs(10),
             connection_backoff: Duration::from_secs(10),
             max_connection_backoff: Duration::from_secs(60),
@@ -90,9 +113,62 @@ impl Default for NetworkConfig {
             max_concurrent_connections: None,
             active_peers_event_channel_capacity: 128,
             max_concurrent_requests_per_peer: 128,
+            inbound_rate_limit: None,
             shutdown_idle_timeout: Duration::from_secs(60),
             enable_0rtt: false,
             connection_metrics: None,
+            idle_connection_timeout: None,
+        }
+    }
+}
+
+/// Token bucket rate limit for inbound requests of each peer.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InboundRateLimit {
+    /// Maximum number of requests which can be accepted at once.
+    ///
+    /// Default: 100.
+    pub burst: u32,
+
+    /// Time to restore a single request from the burst.
+    ///
+    /// Default: 10 ms.
+    #[serde(with = "serde_helpers::humantime")]
+    pub refill_interval: Duration,
+
+    /// Close the connection after this number of requests
+    /// in a row were rejected by the limit.
+    ///
+    /// Default: 1000.
+    pub disconnect_after: Option<u32>,
+
+    /// Reject incoming connections from a peer for this duration
+    /// after it was disconnected by the limit.
+    ///
+    /// Default: 1 minute.
+    #[serde(with = "serde_helpers::humantime")]
+    pub reconnect_cooldown: Duration,
+}
+
+impl InboundRateLimit {
+    /// Creates a limit of `per_peer_qps` requests per second on average.
+    pub fn with_qps(per_peer_qps: u32, burst: u32) -> Self {
+        Self {
+            burst,
+            refill_interval: Duration::from_secs(1) / per_peer_qps.max(1),
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for InboundRateLimit {
+    fn default() -> Self {
+        Self {
+            burst: 100,
+            refill_interval: Duration::from_millis(10),
+            disconnect_after: Some(1000),
+            reconnect_cooldown: Duration::from_secs(60),
         }
     }
 }
@@ -116,9 +192,23 @@ pub struct QuicConfig {
     pub max_concurrent_bidi_streams: u64,
     /// Default: 100.
     pub max_concurrent_uni_streams: u64,
-    /// Default: auto.
+    /// Maximum number of bytes the peer may send on a single stream
+    /// without being acknowledged. Larger windows speed up big transfers
+    /// (e.g. states and archives) over links with a high latency.
+    ///
+    /// NOTE: Up to `stream_receive_window * max_concurrent_*_streams` bytes
+    /// can be buffered for each connection, bounded by `receive_window`.
+    ///
+    /// Must be at least 64 KiB.
+    ///
+    /// Default: auto (1.25 MB).
     pub stream_receive_window: Option<u64>,
-    /// Default: auto.
+    /// Maximum number of bytes the peer may send across all streams of a connection
+    /// without being acknowledged. Limits the memory used by a single connection.
+    ///
+    /// Must be at least 64 KiB and not less than `stream_receive_window`.
+    ///
+    /// Default: auto (unlimited).
     pub receive_window: Option<u64>,
     /// Default: auto.
     pub send_window: Option<u64>,
@@ -130,6 +220,31 @@ pub struct QuicConfig {
     pub socket_recv_buffer_size: Option<usize>,
     /// Default: true.
     pub use_pmtu: bool,
+    /// Send keep-alive packets to keep idle connections through NATs and firewalls.
+    /// Must be less than `max_idle_timeout`.
+    ///
+    /// Default: disabled.
+    #[serde(with = "serde_helpers::humantime")]
+    pub keep_alive_interval: Option<Duration>,
+    /// Default: 30 seconds.
+    #[serde(with = "serde_helpers::humantime")]
+    pub max_idle_timeout: Duration,
+    /// Congestion control algorithm. BBR might perform better
+    /// on links with a high latency.
+    ///
+    /// Default: Cubic.
+    pub congestion_controller: CongestionController,
+    /// Initial congestion window in bytes.
+    ///
+    /// Default: auto (depends on the congestion controller).
+    pub initial_window: Option<u64>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CongestionController {
+    #[default]
+    Cubic,
+    Bbr,
 }
 
 impl Default for QuicConfig {
@@ -143,12 +258,18 @@ impl Default for QuicConfig {
             socket_send_buffer_size: None,
             socket_recv_buffer_size: None,
             use_pmtu: true,
+            keep_alive_interval: None,
+            max_idle_timeout: Duration::from_secs(30),
+            congestion_controller: CongestionController::Cubic,
+            initial_window: None,
         }
     }
 }
 
 impl QuicConfig {
-    pub fn make_transport_config(&self) -> quinn::TransportConfig {
+    const MIN_RECEIVE_WINDOW: u64 = 64 * 1024;
+
+    pub fn make_transport_config(&self) -> Result<quinn::TransportConfig> {
         fn make_varint(value: u64) -> quinn::VarInt {
             quinn::VarInt::from_u64(value).unwrap_or(quinn::VarInt::MAX)
         }
@@ -160,20 +281,74 @@ impl QuicConfig {
         config.datagram_receive_buffer_size(None);
 
         if let Some(stream_receive_window) = self.stream_receive_window {
+            ensure!(
+                stream_receive_window >= Self::MIN_RECEIVE_WINDOW,
+                "stream receive window {stream_receive_window} is too small"
+            );
             config.stream_receive_window(make_varint(stream_receive_window));
         }
         if let Some(receive_window) = self.receive_window {
+            ensure!(
+                receive_window >= Self::MIN_RECEIVE_WINDOW,
+                "receive window {receive_window} is too small"
+            );
+            if let Some(stream_receive_window) = self.stream_receive_window {
+                ensure!(
+                    receive_window >= stream_receive_window,
+                    "receive window {receive_window} must not be less than \
+                     stream receive window {stream_receive_window}"
+                );
+            }
             config.receive_window(make_varint(receive_window));
         }
         if let Some(send_window) = self.send_window {
-            config.receive_window(make_varint(send_window));
+            config.send_window(send_window);
         }
         if self.use_pmtu {
             let mtu = quinn::MtuDiscoveryConfig::default();
             config.mtu_discovery_config(Some(mtu));
         }
 
-        config
+        if let Some(keep_alive_interval) = self.keep_alive_interval {
+            ensure!(
+                keep_alive_interval < self.max_idle_timeout,
+                "keep alive interval {keep_alive_interval:?} must be less than \
+                 max idle timeout {:?}",
+                self.max_idle_timeout,
+            );
+        }
+        config.keep_alive_interval(self.keep_alive_interval);
+
+        let max_idle_timeout = quinn::IdleTimeout::try_from(self.max_idle_timeout)
+            .context("max idle timeout is too big")?;
+        config.max_idle_timeout(Some(max_idle_timeout));
+
+        config.congestion_controller_factory(self.make_congestion_controller_factory());
+
+        Ok(config)
+    }
+
+    fn make_congestion_controller_factory(
+        &self,
+    ) -> Arc<dyn quinn::congestion::ControllerFactory + Send + Sync> {
+        use quinn::congestion::{BbrConfig, CubicConfig};
+
+        match self.congestion_controller {
+            CongestionController::Cubic => {
+                let mut config = CubicConfig::default();
+                if let Some(initial_window) = self.initial_window {
+                    config.initial_window(initial_window);
+                }
+                Arc::new(config)
+            }
+            CongestionController::Bbr => {
+                let mut config = BbrConfig::default();
+                if let Some(initial_window) = self.initial_window {
+                    config.initial_window(initial_window);
+                }
+                Arc::new(config)
+            }
+        }
     }
 }
 
@@ -358,3 +533,64 @@ static DEFAULT_KX_GROUPS: &[&dyn rustls::crypto::SupportedKxGroup] =
     &[rustls::crypto::ring::kx_group::X25519];
 
 static DEFAULT_PROTOCOL_VERSIONS: &[&rustls::SupportedProtocolVersion] = &[&rustls::version::TLS13];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transport_config_keep_alive() -> Result<()> {
+        let keep_alive = Some(Duration::from_secs(5));
+        let config = QuicConfig {
+            keep_alive_interval: keep_alive,
+            max_idle_timeout: Duration::from_secs(20),
+            ..Default::default()
+        };
+        let transport = format!("{:?}", config.make_transport_config()?);
+
+        let max_idle = Some(quinn::VarInt::from_u32(20_000));
+        assert!(transport.contains(&format!("keep_alive_interval: {keep_alive:?}")));
+        assert!(transport.contains(&format!("max_idle_timeout: {max_idle:?}")));
+
+        let config = QuicConfig {
+            keep_alive_interval: Some(Duration::from_secs(20)),
+            max_idle_timeout: Duration::from_secs(20),
+            ..Default::default()
+        };
+        assert!(config.make_transport_config().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn transport_config_receive_windows() -> Result<()> {
+        let config = QuicConfig {
+            stream_receive_window: Some(8 << 20),
+            receive_window: Some(64 << 20),
+            ..Default::default()
+        };
+        let transport = format!("{:?}", config.make_transport_config()?);
+
+        let stream_window = quinn::VarInt::from_u32(8 << 20);
+        let window = quinn::VarInt::from_u32(64 << 20);
+        assert!(transport.contains(&format!("stream_receive_window: {stream_window:?}")));
+        assert!(transport.contains(&format!("receive_window: {window:?}")));
+
+        // too small
+        let config = QuicConfig {
+            stream_receive_window: Some(1024),
+            ..Default::default()
+        };
+        assert!(config.make_transport_config().is_err());
+
+        // connection window is less than stream window
+        let config = QuicConfig {
+            stream_receive_window: Some(8 << 20),
+            receive_window: Some(1 << 20),
+            ..Default::default()
+        };
+        assert!(config.make_transport_config().is_err());
+
+        Ok(())
+    }
+}
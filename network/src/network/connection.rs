@@ -29,6 +29,8 @@ macro_rules! emit_gauges {
 
 impl Connection {
     pub const LIMIT_EXCEEDED_ERROR_CODE: VarInt = VarInt::from_u32(0xdead);
+    pub const REQUEST_TOO_LARGE_ERROR_CODE: VarInt = VarInt::from_u32(0xb16);
+    pub const RATE_LIMITED_ERROR_CODE: VarInt = VarInt::from_u32(0x429);
 
     pub fn with_peer_id(
         inner: quinn::Connection,
@@ -6,6 +6,8 @@ use std::time::{Duration, Instant};
 
 use anyhow::Result;
 use arc_swap::{ArcSwap, AsRaw};
+use futures_util::stream::FuturesUnordered;
+use futures_util::StreamExt;
 use tokio::sync::{broadcast, mpsc, oneshot};
 use tokio::task::{AbortHandle, JoinSet};
 use tokio_util::time::{delay_queue, DelayQueue};
@@ -14,9 +16,9 @@ use tycho_util::{FastDashMap, FastHashMap};
 use crate::network::config::NetworkConfig;
 use crate::network::connection::Connection;
 use crate::network::endpoint::{Connecting, ConnectionInitError, Endpoint, Into0RttResult};
-use crate::network::request_handler::InboundRequestHandler;
+use crate::network::request_handler::{InboundRateLimiters, InboundRequestHandler};
 use crate::network::wire::{handshake, HandshakeError};
-use crate::network::ConnectionError;
+use crate::network::{ConnectionError, ConnectionEvent};
 use crate::types::{
     Address, BoxCloneService, Direction, DisconnectReason, PeerAffinity, PeerEvent, PeerId,
     PeerInfo, Response, ServiceRequest,
@@ -43,7 +45,7 @@ const METRIC_KNOWN_PEERS: &str = "tycho_net_known_peers";
 
 #[derive(Debug)]
 pub(crate) enum ConnectionManagerRequest {
-    Connect(Address, PeerId, CallbackTx),
+    Connect(Vec<Address>, PeerId, CallbackTx),
     Shutdown(oneshot::Sender<()>),
 }
 
@@ -61,9 +63,12 @@ pub(crate) struct ConnectionManager {
 
     pending_dials: FastHashMap<PeerId, CallbackRx>,
     dial_backoff_states: FastHashMap<PeerId, DialBackoffState>,
+    preferred_addresses: FastHashMap<PeerId, Address>,
+    idle_states: FastHashMap<PeerId, IdleState>,
 
     active_peers: ActivePeers,
     known_peers: KnownPeers,
+    rate_limiters: Option<InboundRateLimiters>,
 
     service: BoxCloneService<ServiceRequest, Response>,
 }
@@ -87,6 +92,7 @@ impl ConnectionManager {
         service: BoxCloneService<ServiceRequest, Response>,
     ) -> (Self, mpsc::Sender<ConnectionManagerRequest>) {
         let (mailbox_tx, mailbox) = mpsc::channel(config.connection_manager_channel_capacity);
+        let rate_limiters = config.inbound_rate_limit.map(InboundRateLimiters::new);
         let connection_manager = Self {
             config,
             endpoint,
@@ -98,8 +104,11 @@ impl ConnectionManager {
             delayed_callbacks: Default::default(),
             pending_dials: Default::default(),
             dial_backoff_states: Default::default(),
+            preferred_addresses: Default::default(),
+            idle_states: Default::default(),
             active_peers,
             known_peers,
+            rate_limiters,
             service,
         };
         (connection_manager, mailbox_tx)
@@ -124,8 +133,8 @@ impl ConnectionManager {
                     };
 
                     match request {
-                        ConnectionManagerRequest::Connect(address, peer_id, callback) => {
-                            self.handle_connect_request(address, &peer_id, callback);
+                        ConnectionManagerRequest::Connect(addresses, peer_id, callback) => {
+                            self.handle_connect_request(addresses, &peer_id, callback);
                         }
                         ConnectionManagerRequest::Shutdown(oneshot) => {
                             shutdown_notifier = Some(oneshot);
@@ -205,6 +214,10 @@ impl ConnectionManager {
     fn handle_connectivity_check(&mut self, now: Instant) {
         use std::collections::hash_map::Entry;
 
+        if let Some(rate_limiters) = &self.rate_limiters {
+            rate_limiters.remove_expired(now);
+        }
+
         self.pending_dials
             .retain(|peer_id, oneshot| match oneshot.try_recv() {
                 Ok(Ok(returned_peer_id)) => {
@@ -266,23 +279,61 @@ impl ConnectionManager {
             .collect::<Vec<_>>();
 
         for peer_info in outstanding_connections {
-            // TODO: handle multiple addresses
-            let address = peer_info
-                .iter_addresses()
-                .next()
-                .cloned()
-                .expect("address list must have at least one item");
+            let addresses = peer_info.iter_addresses().cloned().collect();
 
             let (tx, rx) = oneshot::channel();
-            self.dial_peer(address, &peer_info.id, tx);
+            self.dial_peer(addresses, &peer_info.id, tx);
             self.pending_dials.insert(peer_info.id, rx);
         }
 
         metrics::gauge!(METRIC_CONNECTIONS_PENDING_DIALS).set(self.pending_dials.len() as f64);
+
+        // Forget addresses of peers which will not be dialed again.
+        self.preferred_addresses
+            .retain(|peer_id, _| self.known_peers.contains(peer_id));
+
+        self.reap_idle_connections(now);
+    }
+
+    fn reap_idle_connections(&mut self, now: Instant) {
+        let Some(idle_timeout) = self.config.idle_connection_timeout else {
+            return;
+        };
+
+        let mut idle_states = FastHashMap::default();
+        for connection in self.active_peers.connections() {
+            let peer_id = *connection.peer_id();
+            let state = IdleState::new(&connection, now);
+            let state = match self.idle_states.remove(&peer_id) {
+                Some(prev) if prev.is_same_as(&state) => prev,
+                _ => state,
+            };
+
+            // Peers referenced by overlays or resolvers are always in known peers
+            if now.saturating_duration_since(state.active_at) >= idle_timeout
+                && !self.known_peers.contains(&peer_id)
+            {
+                tracing::debug!(%peer_id, "closing idle connection");
+                self.active_peers.remove_with_stable_id(
+                    &peer_id,
+                    state.stable_id,
+                    DisconnectReason::IdleTimeout,
+                );
+                continue;
+            }
+
+            idle_states.insert(peer_id, state);
+        }
+        self.idle_states = idle_states;
     }
 
-    fn handle_connect_request(&mut self, address: Address, peer_id: &PeerId, callback: CallbackTx) {
-        self.dial_peer(address, peer_id, callback);
+    fn handle_connect_request(
+        &mut self,
+        addresses: Vec<Address>,
+        peer_id: &PeerId,
+        callback: CallbackTx,
+    ) {
+        self.dial_peer(addresses, peer_id, callback);
     }
 
     fn handle_incoming(&mut self, connecting: Connecting) {
@@ -375,6 +426,7 @@ impl ConnectionManager {
                 connecting_result: ManuallyDrop::new(connecting_result),
                 target_address,
                 target_peer_id,
+                connected_address: None,
                 origin: Direction::Inbound,
             }
         }
@@ -382,6 +434,18 @@ impl ConnectionManager {
         let remote_addr = connection.remote_address();
 
         // Check if the peer is allowed before doing anything else.
+        if let Some(rate_limiters) = &self.rate_limiters {
+            if rate_limiters.is_cooling_down(connection.peer_id(), Instant::now()) {
+                tracing::debug!(
+                    %remote_addr,
+                    peer_id = %connection.peer_id(),
+                    "rejecting connection of a rate limited peer",
+                );
+                connection.close();
+                return;
+            }
+        }
+
         match self.known_peers.get_affinity(connection.peer_id()) {
             Some(PeerAffinity::High | PeerAffinity::Allowed) => {}
             Some(PeerAffinity::Never) => {
@@ -502,6 +566,10 @@ impl ConnectionManager {
                     "new connection",
                 );
 
+                if let Some(address) = res.connected_address.take() {
+                    self.preferred_addresses.insert(res.target_peer_id, address);
+                }
+
                 let connection = self.add_peer(connection);
                 self.delayed_callbacks.execute_resolved(&connection);
 
@@ -524,6 +592,12 @@ impl ConnectionManager {
                 .increment(1);
 
                 let brief_error = e.as_brief();
+                self.active_peers.connection_failed(
+                    &res.target_peer_id,
+                    res.target_address.clone(),
+                    res.origin,
+                    brief_error,
+                );
 
                 // Delay sending the error to callbacks as the target peer might be
                 // in the process of connecting to us.
@@ -554,6 +628,7 @@ impl ConnectionManager {
                     connection.clone(),
                     self.service.clone(),
                     self.active_peers.clone(),
+                    self.rate_limiters.clone(),
                 );
 
                 metrics::counter!(match origin {
@@ -577,49 +652,96 @@ impl ConnectionManager {
         fields(
             local_id = %self.endpoint.peer_id(),
             peer_id = %peer_id,
-            remote_addr = %address,
+            remote_addr = tracing::field::Empty,
         ),
     )]
-    fn dial_peer(&mut self, address: Address, peer_id: &PeerId, callback: CallbackTx) {
+    fn dial_peer(&mut self, mut addresses: Vec<Address>, peer_id: &PeerId, callback: CallbackTx) {
+        /// A delay before dialing other addresses when the preferred one is known.
+        const HEAD_START: Duration = Duration::from_millis(250);
+
+        async fn connect_to_address(
+            endpoint: &Endpoint,
+            address: &Address,
+            peer_id: &PeerId,
+        ) -> Result<ConnectionClosedOnDrop, FullConnectionError> {
+            let address = address
+                .resolve()
+                .await
+                .map_err(FullConnectionError::InvalidAddress)?;
+
+            let connecting = endpoint
+                .connect_with_expected_id(&address, peer_id)
+                .map_err(|e| FullConnectionError::InvalidAddress(std::io::Error::other(e)))?;
+
+            let connection = ConnectionClosedOnDrop::new(connecting.await?);
+            match handshake(&connection).await {
+                Ok(()) => Ok(connection),
+                Err(e) => Err(FullConnectionError::HandshakeFailed(e)),
+            }
+        }
+
         async fn dial_peer_task(
             seqno: u32,
             endpoint: Arc<Endpoint>,
-            address: Address,
+            mut addresses: Vec<Address>,
+            head_start: bool,
             peer_id: PeerId,
             config: Arc<NetworkConfig>,
         ) -> ConnectingOutput {
+            // NOTE: All addresses are dialed concurrently and the first
+            // completed handshake wins. Other connections are closed on drop.
             let fut = async {
-                let address = address
-                    .resolve()
-                    .await
-                    .map_err(FullConnectionError::InvalidAddress)?;
-
-                let connecting = endpoint
-                    .connect_with_expected_id(&address, &peer_id)
-                    .map_err(|e| FullConnectionError::InvalidAddress(std::io::Error::other(e)))?;
-
-                let connection = ConnectionClosedOnDrop::new(connecting.await?);
-                match handshake(&connection).await {
-                    Ok(()) => Ok(connection),
-                    Err(e) => Err(FullConnectionError::HandshakeFailed(e)),
+                let mut attempts = addresses
+                    .iter()
+                    .enumerate()
+                    .map(|(i, address)| {
+                        let delay = if head_start && i > 0 {
+                            HEAD_START
+                        } else {
+                            Duration::ZERO
+                        };
+                        let endpoint = endpoint.as_ref();
+                        async move {
+                            tokio::time::sleep(delay).await;
+                            let res = connect_to_address(endpoint, address, &peer_id).await;
+                            (address, res)
+                        }
+                    })
+                    .collect::<FuturesUnordered<_>>();
+
+                let mut last_error = None;
+                while let Some((address, res)) = attempts.next().await {
+                    match res {
+                        Ok(connection) => return Ok((address.clone(), connection)),
+                        Err(e) => last_error = Some(e),
+                    }
                 }
+                Err(last_error.unwrap_or_else(|| {
+                    FullConnectionError::InvalidAddress(std::io::Error::other("no addresses"))
+                }))
             };
 
             let started_at = Instant::now();
 
-            let connecting_result = match tokio::time::timeout(config.connect_timeout, fut).await {
-                Ok(res) => res.map(ConnectionClosedOnDrop::disarm),
-                Err(_) => Err(FullConnectionError::Timeout),
-            };
+            let (connecting_result, connected_address) =
+                match tokio::time::timeout(config.connect_timeout, fut).await {
+                    Ok(Ok((address, connection))) => (Ok(connection.disarm()), Some(address)),
+                    Ok(Err(e)) => (Err(e), None),
+                    Err(_) => (Err(FullConnectionError::Timeout), None),
+                };
 
             metrics::histogram!(METRIC_CONNECTION_OUT_TIME).record(started_at.elapsed());
 
+            // Only remember the winner when there was a choice.
+            let connected_address = connected_address.filter(|_| addresses.len() > 1);
+
             ConnectingOutput {
                 seqno,
                 drop_result: true,
                 connecting_result: ManuallyDrop::new(connecting_result),
-                target_address: address,
+                target_address: addresses.swap_remove(0),
                 target_peer_id: peer_id,
+                connected_address,
                 origin: Direction::Outbound,
             }
         }
@@ -630,8 +752,23 @@ impl ConnectionManager {
             return;
         }
 
+        if addresses.is_empty() {
+            tracing::debug!("peer has no addresses");
+            _ = callback.send(Err(ConnectionError::InvalidAddress));
+            return;
+        }
+
         tracing::trace!("connecting to peer");
 
+        // Dial the last successful address first.
+        let preferred = self.preferred_addresses.get(peer_id);
+        let preferred = preferred.and_then(|a| addresses.iter().position(|b| a == b));
+        if let Some(index) = preferred {
+            addresses[..=index].rotate_right(1);
+        }
+        let address = addresses[0].clone();
+        tracing::Span::current().record("remote_addr", tracing::field::display(&address));
+
         let entry = match self.pending_connection_callbacks.entry(address.clone()) {
             hash_map::Entry::Vacant(entry) => Some(entry.insert(PendingConnectionCallbacks {
                 last_seqno: 0,
@@ -675,7 +812,8 @@ impl ConnectionManager {
             entry.abort_handle = Some(self.pending_connections.spawn(dial_peer_task(
                 entry.last_seqno,
                 self.endpoint.clone(),
-                address.clone(),
+                addresses,
+                preferred.is_some(),
                 *peer_id,
                 self.config.clone(),
             )));
@@ -702,6 +840,8 @@ struct ConnectingOutput {
     connecting_result: ManuallyDrop<Result<Connection, FullConnectionError>>,
     target_address: Address,
     target_peer_id: PeerId,
+    /// The address which won the dial when multiple addresses were tried.
+    connected_address: Option<Address>,
     origin: Direction,
 }
 
@@ -879,6 +1019,28 @@ impl DelayedCallbacks {
 }
 
 #[derive(Debug)]
+struct IdleState {
+    stable_id: usize,
+    /// Any request or response is sent in a stream, unlike keep-alive pings
+    stream_frames: u64,
+    active_at: Instant,
+}
+
+impl IdleState {
+    fn new(connection: &Connection, now: Instant) -> Self {
+        let stats = connection.stats();
+        Self {
+            stable_id: connection.stable_id(),
+            stream_frames: stats.frame_rx.stream + stats.frame_tx.stream,
+            active_at: now,
+        }
+    }
+
+    fn is_same_as(&self, other: &Self) -> bool {
+        self.stable_id == other.stable_id && self.stream_frames == other.stream_frames
+    }
+}
+
 struct DialBackoffState {
     next_attempt_at: Instant,
     attempts: usize,
@@ -1004,6 +1166,34 @@ impl ActivePeers {
         self.0.subscribe()
     }
 
+    pub fn subscribe_connection_events(&self) -> broadcast::Receiver<ConnectionEvent> {
+        self.0.connection_events_tx.subscribe()
+    }
+
+    pub fn connection_failed(
+        &self,
+        peer_id: &PeerId,
+        remote_addr: Address,
+        origin: Direction,
+        error: ConnectionError,
+    ) {
+        let event = ConnectionEvent::ConnectionFailed {
+            peer_id: *peer_id,
+            remote_addr,
+            origin,
+            error,
+        };
+        self.0.send_connection_event(event);
+    }
+
+    pub fn connections(&self) -> Vec<Connection> {
+        self.0.connections()
+    }
+
+    pub fn peer_ids(&self) -> Vec<PeerId> {
+        self.0.peer_ids()
+    }
+
     pub fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
@@ -1017,15 +1207,18 @@ struct ActivePeersInner {
     connections: FastDashMap<PeerId, Connection>,
     connections_len: AtomicUsize,
     events_tx: broadcast::Sender<PeerEvent>,
+    connection_events_tx: broadcast::Sender<ConnectionEvent>,
 }
 
 impl ActivePeersInner {
     fn new(channel_size: usize) -> Self {
         let (events_tx, _) = broadcast::channel(channel_size);
+        let (connection_events_tx, _) = broadcast::channel(channel_size);
         Self {
             connections: Default::default(),
             connections_len: Default::default(),
             events_tx,
+            connection_events_tx,
         }
     }
 
@@ -1058,6 +1251,7 @@ impl ActivePeersInner {
                     let old_connection = entry.insert(new_connection.clone());
                     old_connection.close();
                     self.send_event(PeerEvent::lost_peer(*peer_id, DisconnectReason::Requested));
+                    self.send_disconnected(peer_id, DisconnectReason::Requested);
                 } else {
                     tracing::debug!(%peer_id, "closing new connection to mitigate simultaneous dial");
                     new_connection.close();
@@ -1072,6 +1266,11 @@ impl ActivePeersInner {
         }
 
         self.send_event(PeerEvent::new_peer(*peer_id));
+        self.send_connection_event(ConnectionEvent::Connected {
+            peer_id: *peer_id,
+            remote_addr: new_connection.remote_address(),
+            origin: new_connection.origin(),
+        });
 
         if added {
             metrics::gauge!(METRIC_ACTIVE_PEERS).increment(1);
@@ -1084,6 +1283,7 @@ impl ActivePeersInner {
             connection.close();
             self.connections_len.fetch_sub(1, Ordering::Release);
             self.send_event(PeerEvent::lost_peer(*peer_id, reason));
+            self.send_disconnected(peer_id, reason);
 
             metrics::gauge!(METRIC_ACTIVE_PEERS).decrement(1);
         }
@@ -1097,6 +1297,7 @@ impl ActivePeersInner {
             connection.close();
             self.connections_len.fetch_sub(1, Ordering::Release);
             self.send_event(PeerEvent::lost_peer(*peer_id, reason));
+            self.send_disconnected(peer_id, reason);
 
             metrics::gauge!(METRIC_ACTIVE_PEERS).decrement(1);
         }
@@ -1106,10 +1307,32 @@ impl ActivePeersInner {
         self.events_tx.subscribe()
     }
 
+    fn connections(&self) -> Vec<Connection> {
+        self.connections
+            .iter()
+            .map(|item| item.value().clone())
+            .collect()
+    }
+
+    fn peer_ids(&self) -> Vec<PeerId> {
+        self.connections.iter().map(|item| *item.key()).collect()
+    }
+
     fn send_event(&self, event: PeerEvent) {
         _ = self.events_tx.send(event);
     }
 
+    fn send_disconnected(&self, peer_id: &PeerId, reason: DisconnectReason) {
+        self.send_connection_event(ConnectionEvent::Disconnected {
+            peer_id: *peer_id,
+            reason,
+        });
+    }
+
+    fn send_connection_event(&self, event: ConnectionEvent) {
+        _ = self.connection_events_tx.send(event);
+    }
+
     fn is_empty(&self) -> bool {
         self.connections.is_empty()
     }
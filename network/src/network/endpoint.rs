@@ -44,6 +44,17 @@ impl Endpoint {
         &self.config.peer_id
     }
 
+    /// Switches to a new UDP socket, keeping the old one on error.
+    ///
+    /// Outbound connections migrate to the new address, while inbound
+    /// connections will be lost since remote peers don't know it.
+    pub fn rebind(&self, socket: std::net::UdpSocket) -> Result<()> {
+        let local_addr = socket.local_addr()?;
+        self.inner.rebind(socket)?;
+        *self.local_addr.write().unwrap() = local_addr;
+        Ok(())
+    }
+
     /// Close all of this endpoint's connections immediately and cease accepting new connections.
     pub fn close(&self) {
         tracing::trace!("closing endpoint");
@@ -8,7 +8,7 @@ use everscale_crypto::ed25519;
 use tokio::sync::{broadcast, mpsc, oneshot};
 
 use self::config::EndpointConfig;
-pub use self::config::{NetworkConfig, QuicConfig};
+pub use self::config::{CongestionController, InboundRateLimit, NetworkConfig, QuicConfig};
 pub use self::connection::{Connection, RecvStream, SendStream};
 use self::connection_manager::{ActivePeers, ConnectionManager, ConnectionManagerRequest};
 pub use self::connection_manager::{
@@ -17,8 +17,8 @@ pub use self::connection_manager::{
 use self::endpoint::Endpoint;
 pub use self::peer::Peer;
 use crate::types::{
-    Address, DisconnectReason, PeerEvent, PeerId, PeerInfo, Response, Service, ServiceExt,
-    ServiceRequest,
+    Address, Direction, DisconnectReason, PeerEvent, PeerId, PeerInfo, Response, Service,
+    ServiceExt, ServiceRequest,
 };
 
 mod config;
@@ -39,6 +39,7 @@ pub struct NetworkBuilder<MandatoryFields = ([u8; 32],)> {
 struct BuilderFields {
     config: Option<NetworkConfig>,
     remote_addr: Option<Address>,
+    inbound_rate_limit: Option<InboundRateLimit>,
 }
 
 impl<MandatoryFields> NetworkBuilder<MandatoryFields> {
@@ -51,6 +52,14 @@ impl<MandatoryFields> NetworkBuilder<MandatoryFields> {
         self.optional_fields.remote_addr = Some(addr.into());
         self
     }
+
+    /// Limits inbound requests of each peer to `per_peer_qps` on average
+    /// with at most `burst` requests at once. Overrides the limit from the config.
+    pub fn with_inbound_rate_limit(mut self, per_peer_qps: u32, burst: u32) -> Self {
+        let limit = InboundRateLimit::with_qps(per_peer_qps, burst);
+        self.optional_fields.inbound_rate_limit = Some(limit);
+        self
+    }
 }
 
 impl NetworkBuilder<((),)> {
@@ -72,7 +81,10 @@ impl NetworkBuilder {
         S: Send + Sync + Clone + 'static,
         S: Service<ServiceRequest, QueryResponse = Response>,
     {
-        let config = self.optional_fields.config.unwrap_or_default();
+        let mut config = self.optional_fields.config.unwrap_or_default();
+        if let Some(limit) = self.optional_fields.inbound_rate_limit {
+            config.inbound_rate_limit = Some(limit);
+        }
         let quic_config = config.quic.clone().unwrap_or_default();
         let (private_key,) = self.mandatory_fields;
 
@@ -81,32 +93,14 @@ impl NetworkBuilder {
         let endpoint_config = EndpointConfig::builder()
             .with_private_key(private_key)
             .with_0rtt_enabled(config.enable_0rtt)
-            .with_transport_config(quic_config.make_transport_config())
+            .with_transport_config(quic_config.make_transport_config()?)
             .with_connection_metrics(config.connection_metrics)
             .build()?;
 
-        let socket = bind_address.to_socket().map(socket2::Socket::from)?;
-
-        let max_socket_size = MaxBufferSize::read()?;
-
-        set_socket_buffer(
-            &socket,
-            quic_config.socket_send_buffer_size,
-            max_socket_size.map(|m| m.send),
-            |s, size| s.set_send_buffer_size(size),
-            "send",
-        );
-
-        set_socket_buffer(
-            &socket,
-            quic_config.socket_recv_buffer_size,
-            max_socket_size.map(|m| m.recv),
-            |s, size| s.set_recv_buffer_size(size),
-            "recv",
-        );
+        let socket = bind_socket(bind_address, &quic_config)?;
 
         let config = Arc::new(config);
-        let endpoint = Arc::new(Endpoint::new(endpoint_config, socket.into())?);
+        let endpoint = Arc::new(Endpoint::new(endpoint_config, socket)?);
         let active_peers = ActivePeers::new(config.active_peers_event_channel_capacity);
         let known_peers = KnownPeers::new();
 
@@ -140,6 +134,33 @@ impl NetworkBuilder {
     }
 }
 
+fn bind_socket<T: ToSocket>(
+    bind_address: T,
+    quic_config: &QuicConfig,
+) -> Result<std::net::UdpSocket> {
+    let socket = bind_address.to_socket().map(socket2::Socket::from)?;
+
+    let max_socket_size = MaxBufferSize::read()?;
+
+    set_socket_buffer(
+        &socket,
+        quic_config.socket_send_buffer_size,
+        max_socket_size.map(|m| m.send),
+        |s, size| s.set_send_buffer_size(size),
+        "send",
+    );
+
+    set_socket_buffer(
+        &socket,
+        quic_config.socket_recv_buffer_size,
+        max_socket_size.map(|m| m.recv),
+        |s, size| s.set_recv_buffer_size(size),
+        "recv",
+    );
+
+    Ok(socket.into())
+}
+
 fn set_socket_buffer(
     socket: &socket2::Socket,
     config_size: Option<usize>,
@@ -208,6 +229,16 @@ impl Network {
         self.0.active_peers.contains(peer_id)
     }
 
+    /// Returns ids of all currently connected peers.
+    pub fn connected_peers(&self) -> Vec<PeerId> {
+        self.0.active_peers.peer_ids()
+    }
+
+    /// Returns the number of currently connected peers.
+    pub fn connection_count(&self) -> usize {
+        self.0.active_peers.len()
+    }
+
     /// Returns a connection wrapper for the specified peer.
     pub fn peer(&self, peer_id: &PeerId) -> Option<Peer> {
         self.0.peer(peer_id)
@@ -223,18 +254,78 @@ impl Network {
         self.0.active_peers.subscribe()
     }
 
+    /// Subscribe to connection lifecycle events.
+    ///
+    /// NOTE: The channel is bounded by `active_peers_event_channel_capacity`.
+    /// Slow subscribers lose the oldest events instead of blocking the network.
+    pub fn subscribe_events(&self) -> broadcast::Receiver<ConnectionEvent> {
+        self.0.active_peers.subscribe_connection_events()
+    }
+
     /// Initiate a connection to the specified peer.
     pub async fn connect<T>(&self, addr: T, peer_id: &PeerId) -> Result<Peer, ConnectionError>
     where
         T: Into<Address>,
     {
-        self.0.connect(addr.into(), peer_id).await
+        self.0.connect(vec![addr.into()], peer_id).await
+    }
+
+    /// Connects to the peer using all of its addresses concurrently.
+    ///
+    /// The first address to complete the handshake wins and is dialed
+    /// first next time. Other pending connections are closed.
+    ///
+    /// Returns [`ConnectionError::InvalidAddress`] if the peer has no addresses.
+    pub async fn connect_to_peer_info(
+        &self,
+        peer_info: &PeerInfo,
+    ) -> Result<Peer, ConnectionError> {
+        let addresses = peer_info.iter_addresses().cloned().collect();
+        self.0.connect(addresses, &peer_info.id).await
+    }
+
+    /// Initiates connections to the specified known peers in parallel.
+    ///
+    /// Peer addresses are resolved using [`known_peers`]. Already connected
+    /// peers are returned as is. Results are in the same order as `peer_ids`.
+    ///
+    /// [`known_peers`]: Self::known_peers
+    pub async fn connect_many(
+        &self,
+        peer_ids: &[PeerId],
+    ) -> Vec<(PeerId, Result<Peer, ConnectionError>)> {
+        use futures_util::StreamExt;
+
+        const MAX_PARALLEL_CONNECTIONS: usize = 16;
+
+        futures_util::stream::iter(peer_ids)
+            .map(|peer_id| async move {
+                let res = match self.peer(peer_id) {
+                    Some(peer) => Ok(peer),
+                    None => match self.known_peers().get(peer_id) {
+                        Some(peer_info) => self.connect_to_peer_info(&peer_info).await,
+                        None => Err(ConnectionError::UnknownPeer),
+                    },
+                };
+                (*peer_id, res)
+            })
+            .buffered(MAX_PARALLEL_CONNECTIONS)
+            .collect()
+            .await
     }
 
     pub fn disconnect(&self, peer_id: &PeerId) {
         self.0.disconnect(peer_id);
     }
 
+    /// Moves the endpoint to a new UDP socket without dropping the network.
+    ///
+    /// Outbound connections are migrated to the new address. Inbound connections
+    /// can't be migrated by the remote side, so they are reconnected instead.
+    pub async fn rebind<T: ToSocket>(&self, bind_address: T) -> Result<()> {
+        self.0.rebind(bind_address).await
+    }
+
     pub async fn shutdown(&self) {
         self.0.shutdown().await;
     }
@@ -296,10 +387,18 @@ impl NetworkInner {
         self.endpoint.peer_id()
     }
 
-    async fn connect(&self, addr: Address, peer_id: &PeerId) -> Result<Peer, ConnectionError> {
+    async fn connect(
+        &self,
+        addresses: Vec<Address>,
+        peer_id: &PeerId,
+    ) -> Result<Peer, ConnectionError> {
+        if addresses.is_empty() {
+            return Err(ConnectionError::InvalidAddress);
+        }
+
         let (tx, rx) = oneshot::channel();
         self.connection_manager_handle
-            .send(ConnectionManagerRequest::Connect(addr, *peer_id, tx))
+            .send(ConnectionManagerRequest::Connect(addresses, *peer_id, tx))
             .await
             .map_err(|_e| ConnectionError::Shutdown)?;
 
@@ -315,6 +414,52 @@ impl NetworkInner {
             .remove(peer_id, DisconnectReason::Requested);
     }
 
+    async fn rebind<T: ToSocket>(&self, bind_address: T) -> Result<()> {
+        use futures_util::StreamExt;
+
+        const MAX_PARALLEL_CONNECTIONS: usize = 16;
+
+        let quic_config = self.config.quic.clone().unwrap_or_default();
+        let socket = bind_socket(bind_address, &quic_config)?;
+
+        // NOTE: Inbound connections are closed while the old socket is still
+        //       used, so that remote peers receive the close frame.
+        let inbound = self
+            .active_peers
+            .connections()
+            .into_iter()
+            .filter(|connection| connection.origin() == Direction::Inbound)
+            .map(|connection| {
+                let peer_id = *connection.peer_id();
+                self.active_peers.remove_with_stable_id(
+                    &peer_id,
+                    connection.stable_id(),
+                    DisconnectReason::Requested,
+                );
+                (peer_id, connection.remote_address())
+            })
+            .collect::<Vec<_>>();
+
+        let old_addr = self.local_addr();
+        let res = self.endpoint.rebind(socket);
+        match &res {
+            Ok(()) => {
+                tracing::info!(%old_addr, new_addr = %self.local_addr(), "rebound endpoint");
+            }
+            Err(e) => tracing::error!(%old_addr, "failed to rebind endpoint: {e:?}"),
+        }
+
+        futures_util::stream::iter(inbound)
+            .for_each_concurrent(MAX_PARALLEL_CONNECTIONS, |(peer_id, addr)| async move {
+                if let Err(e) = self.connect(vec![addr.into()], &peer_id).await {
+                    tracing::warn!(%peer_id, %addr, "failed to reconnect after rebind: {e}");
+                }
+            })
+            .await;
+
+        res
+    }
+
     fn peer(&self, peer_id: &PeerId) -> Option<Peer> {
         let connection = self.active_peers.get(peer_id)?;
         Some(Peer::new(connection, self.config.clone()))
@@ -485,10 +630,34 @@ impl MaxBufferSize {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConnectionEvent {
+    /// A new connection became active.
+    Connected {
+        peer_id: PeerId,
+        remote_addr: SocketAddr,
+        origin: Direction,
+    },
+    /// An active connection was closed.
+    Disconnected {
+        peer_id: PeerId,
+        reason: DisconnectReason,
+    },
+    /// A connection attempt failed before becoming active (e.g. during the handshake).
+    ConnectionFailed {
+        peer_id: PeerId,
+        remote_addr: Address,
+        origin: Direction,
+        error: ConnectionError,
+    },
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
 pub enum ConnectionError {
     #[error("invalid address")]
     InvalidAddress,
+    #[error("unknown peer")]
+    UnknownPeer,
     #[error("connection init failed")]
     ConnectionInitFailed,
     #[error("invalid certificate")]
@@ -503,11 +672,17 @@ pub enum ConnectionError {
 
 #[cfg(test)]
 mod tests {
+    use std::future::Future;
+    use std::time::Duration;
+
     use futures_util::stream::FuturesUnordered;
     use futures_util::StreamExt;
 
     use super::*;
-    use crate::types::{service_message_fn, service_query_fn, BoxCloneService, PeerInfo, Request};
+    use crate::types::{
+        service_message_fn, service_query_fn, BoxCloneService, PeerEventData, PeerInfo, Priority,
+        Request,
+    };
     use crate::util::{NetworkExt, UnknownPeerError};
 
     fn echo_service() -> BoxCloneService<ServiceRequest, Response> {
@@ -561,6 +736,283 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn connected_peers_are_listed() -> Result<()> {
+        tycho_util::test::init_logger("connected_peers_are_listed", "debug");
+
+        let node = make_network()?;
+        assert!(node.connected_peers().is_empty());
+        assert_eq!(node.connection_count(), 0);
+
+        let peers = (0..3).map(|_| make_network()).collect::<Result<Vec<_>>>()?;
+        for peer in &peers {
+            node.connect(peer.local_addr(), peer.peer_id()).await?;
+        }
+
+        let mut expected = peers.iter().map(|peer| *peer.peer_id()).collect::<Vec<_>>();
+        expected.sort_unstable();
+
+        let mut connected = node.connected_peers();
+        connected.sort_unstable();
+        assert_eq!(connected, expected);
+        assert_eq!(node.connection_count(), peers.len());
+
+        node.disconnect(peers[0].peer_id());
+        assert!(!node.connected_peers().contains(peers[0].peer_id()));
+        assert_eq!(node.connection_count(), peers.len() - 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn connect_many_prewarms_connections() -> Result<()> {
+        tycho_util::test::init_logger("connect_many_prewarms_connections", "debug");
+
+        let node = make_network()?;
+        let peers = (0..3).map(|_| make_network()).collect::<Result<Vec<_>>>()?;
+        let _handles = peers
+            .iter()
+            .map(|peer| node.known_peers().insert(make_peer_info(peer), false))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let unknown_peer_id = PeerId([0xaa; 32]);
+        let mut peer_ids = peers.iter().map(|peer| *peer.peer_id()).collect::<Vec<_>>();
+        peer_ids.push(unknown_peer_id);
+
+        let results = node.connect_many(&peer_ids).await;
+        assert_eq!(results.len(), peer_ids.len());
+
+        for ((peer_id, res), expected_id) in results.iter().zip(&peer_ids) {
+            assert_eq!(peer_id, expected_id);
+            match res {
+                Ok(peer) => {
+                    assert_eq!(peer.peer_id(), peer_id);
+                    assert!(node.is_active(peer_id));
+                    assert!(node.peer(peer_id).is_some());
+                }
+                Err(e) => {
+                    assert_eq!(peer_id, &unknown_peer_id);
+                    assert_eq!(e, &ConnectionError::UnknownPeer);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn make_wide_window_network(
+        service: BoxCloneService<ServiceRequest, Response>,
+    ) -> Result<Network> {
+        // Flow control must not block the streams, otherwise
+        // the lower priority stream is sent while waiting.
+        Network::builder()
+            .with_config(NetworkConfig {
+                quic: Some(QuicConfig {
+                    stream_receive_window: Some(8 << 20),
+                    receive_window: Some(32 << 20),
+                    send_window: Some(32 << 20),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+            .with_random_private_key()
+            .build("127.0.0.1:0", service)
+    }
+
+    async fn first_completed(
+        bulk: impl Future<Output = Result<Response>>,
+        urgent: impl Future<Output = Result<Response>>,
+    ) -> Result<Priority> {
+        tokio::pin!(bulk, urgent);
+
+        // Bulk stream is opened first and starts sending
+        let first = tokio::select! {
+            biased;
+            res = &mut bulk => {
+                res?;
+                Priority::Low
+            }
+            res = &mut urgent => {
+                res?;
+                Priority::High
+            }
+        };
+
+        // Other query is not affected
+        if first == Priority::High {
+            bulk.await?;
+        } else {
+            urgent.await?;
+        }
+
+        Ok(first)
+    }
+
+    #[tokio::test]
+    async fn high_priority_request_overtakes_bulk() -> Result<()> {
+        tycho_util::test::init_logger("high_priority_request_overtakes_bulk", "debug");
+
+        let service = service_query_fn(|_: ServiceRequest| {
+            futures_util::future::ready(Some(Response {
+                version: Default::default(),
+                body: "ok".into(),
+            }))
+        })
+        .boxed_clone();
+
+        let peer1 = make_wide_window_network(service.clone())?;
+        let peer2 = make_wide_window_network(service)?;
+
+        let peer = peer1.connect(peer2.local_addr(), peer2.peer_id()).await?;
+
+        // NOTE: Urgent request is larger so that it would complete
+        //       after the bulk one with the round-robin scheduling.
+        let make_request = |len: usize| Request {
+            version: Default::default(),
+            body: vec![0xaa; len].into(),
+        };
+        let bulk = peer.rpc_with_priority(make_request(512 << 10), Priority::Low);
+        let urgent = peer.rpc_with_priority(make_request(3 << 20), Priority::High);
+
+        assert_eq!(first_completed(bulk, urgent).await?, Priority::High);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn high_priority_response_overtakes_bulk() -> Result<()> {
+        tycho_util::test::init_logger("high_priority_response_overtakes_bulk", "debug");
+
+        // Both responses must be ready to be sent at the same time
+        let barrier = Arc::new(tokio::sync::Barrier::new(2));
+        let service = service_query_fn(move |req: ServiceRequest| {
+            let barrier = barrier.clone();
+            async move {
+                barrier.wait().await;
+
+                // NOTE: Urgent response is larger so that it would complete
+                //       after the bulk one with the round-robin scheduling.
+                let len = if req.body.as_ref() == b"urgent" {
+                    req.response_priority.set(Priority::High);
+                    3 << 20
+                } else {
+                    req.response_priority.set(Priority::Low);
+                    512 << 10
+                };
+
+                Some(Response {
+                    version: Default::default(),
+                    body: vec![0xaa; len].into(),
+                })
+            }
+        })
+        .boxed_clone();
+
+        let peer1 = make_wide_window_network(service.clone())?;
+        let peer2 = make_wide_window_network(service)?;
+
+        let peer = peer1.connect(peer2.local_addr(), peer2.peer_id()).await?;
+
+        let make_request = |body: &'static str| Request {
+            version: Default::default(),
+            body: body.into(),
+        };
+        let bulk = peer.rpc(make_request("bulk"));
+        let urgent = peer.rpc(make_request("urgent"));
+
+        assert_eq!(first_completed(bulk, urgent).await?, Priority::High);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn rebind_keeps_active_connections() -> Result<()> {
+        tycho_util::test::init_logger("rebind_keeps_active_connections", "debug");
+
+        let make_network = |private_key: [u8; 32]| {
+            Network::builder()
+                .with_private_key(private_key)
+                .build("127.0.0.1:0", echo_service())
+        };
+
+        // NOTE: Reconnected peer must win the simultaneous dial tie-breaking
+        //       in case it has not yet processed the close of the old connection.
+        let mut keys = [rand::random::<[u8; 32]>(), rand::random::<[u8; 32]>()];
+        keys.sort_by_key(|key| {
+            let secret = ed25519::SecretKey::from_bytes(*key);
+            PeerId::from(ed25519::PublicKey::from(&secret))
+        });
+
+        let client = make_network(keys[0])?;
+        let node = make_network(keys[1])?;
+        let server = make_network(rand::random())?;
+
+        let peer = node.connect(server.local_addr(), server.peer_id()).await?;
+        client.connect(node.local_addr(), node.peer_id()).await?;
+
+        let ping = || Request {
+            version: Default::default(),
+            body: "ping".into(),
+        };
+        assert_eq!(peer.rpc(ping()).await?.body, "ping");
+
+        let outbound = node.0.active_peers.get(server.peer_id()).unwrap();
+        let inbound = node.0.active_peers.get(client.peer_id()).unwrap();
+        assert_eq!(inbound.origin(), Direction::Inbound);
+
+        let old_addr = node.local_addr();
+        node.rebind("127.0.0.1:0").await?;
+        assert_ne!(node.local_addr(), old_addr);
+
+        // Outbound connection is migrated
+        assert_eq!(peer.rpc(ping()).await?.body, "ping");
+        let migrated = node.0.active_peers.get(server.peer_id()).unwrap();
+        assert_eq!(migrated.stable_id(), outbound.stable_id());
+
+        // Inbound connection is reconnected from the new address
+        let reconnected = node.0.active_peers.get(client.peer_id()).unwrap();
+        assert_ne!(reconnected.stable_id(), inbound.stable_id());
+        assert_eq!(reconnected.origin(), Direction::Outbound);
+
+        let peer = node.peer(client.peer_id()).unwrap();
+        assert_eq!(peer.rpc(ping()).await?.body, "ping");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn idle_unreferenced_connection_is_reaped() -> Result<()> {
+        tycho_util::test::init_logger("idle_unreferenced_connection_is_reaped", "debug");
+
+        let peer1 = Network::builder()
+            .with_config(NetworkConfig {
+                connectivity_check_interval: Duration::from_millis(100),
+                idle_connection_timeout: Some(Duration::from_millis(200)),
+                ..Default::default()
+            })
+            .with_random_private_key()
+            .build("127.0.0.1:0", echo_service())?;
+        let peer2 = make_network()?;
+
+        let mut events = peer1.subscribe();
+        peer1
+            .connect(peer2.local_addr(), peer2.peer_id())
+            .await
+            .unwrap();
+
+        let reaped = tokio::time::timeout(Duration::from_secs(10), async {
+            loop {
+                let event = events.recv().await.unwrap();
+                if event.data == PeerEventData::Lost(DisconnectReason::IdleTimeout) {
+                    break event.peer_id;
+                }
+            }
+        })
+        .await?;
+        assert_eq!(&reaped, peer2.peer_id());
+        assert!(peer1.peer(peer2.peer_id()).is_none());
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn invalid_peer_id_detectable() -> Result<()> {
         tycho_util::test::init_logger("invalid_peer_id_detectable", "debug");
@@ -729,4 +1181,142 @@ mod tests {
             assert_eq!(socket_size.recv, 100000);
         }
     }
+
+    #[tokio::test]
+    async fn all_peer_addresses_are_dialed() -> Result<()> {
+        tycho_util::test::init_logger("all_peer_addresses_are_dialed", "debug");
+
+        let peer1 = make_network()?;
+        let peer2 = make_network()?;
+
+        // Packets sent to this address are never answered
+        let black_hole = std::net::UdpSocket::bind("127.0.0.1:0")?;
+
+        let peer_info = PeerInfo {
+            id: *peer2.peer_id(),
+            address_list: vec![black_hole.local_addr()?.into(), peer2.remote_addr().clone()]
+                .into_boxed_slice(),
+            created_at: 0,
+            expires_at: u32::MAX,
+            signature: Box::new([0; 64]),
+        };
+
+        let connect = peer1.connect_to_peer_info(&peer_info);
+        let peer = tokio::time::timeout(Duration::from_secs(5), connect).await??;
+        assert_eq!(peer.peer_id(), peer2.peer_id());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn peer_without_addresses_is_rejected() -> Result<()> {
+        tycho_util::test::init_logger("peer_without_addresses_is_rejected", "debug");
+
+        let peer1 = make_network()?;
+        let peer2 = make_network()?;
+
+        let peer_info = PeerInfo {
+            id: *peer2.peer_id(),
+            address_list: Box::default(),
+            created_at: 0,
+            expires_at: u32::MAX,
+            signature: Box::new([0; 64]),
+        };
+
+        let res = peer1.connect_to_peer_info(&peer_info).await;
+        assert!(matches!(res, Err(ConnectionError::InvalidAddress)));
+
+        // Connection manager is still alive
+        let peer = peer1.connect(peer2.local_addr(), peer2.peer_id()).await?;
+        assert_eq!(peer.peer_id(), peer2.peer_id());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn connection_events_are_emitted() -> Result<()> {
+        tycho_util::test::init_logger("connection_events_are_emitted", "debug");
+
+        let peer1 = make_network()?;
+        let peer2 = make_network()?;
+
+        let mut events = peer1.subscribe_events();
+
+        peer1.connect(peer2.local_addr(), peer2.peer_id()).await?;
+        assert_eq!(events.recv().await?, ConnectionEvent::Connected {
+            peer_id: *peer2.peer_id(),
+            remote_addr: peer2.local_addr(),
+            origin: Direction::Outbound,
+        });
+
+        peer1.disconnect(peer2.peer_id());
+        assert_eq!(events.recv().await?, ConnectionEvent::Disconnected {
+            peer_id: *peer2.peer_id(),
+            reason: DisconnectReason::Requested,
+        });
+
+        // Peer id doesn't match the certificate
+        let invalid_id = PeerId([0; 32]);
+        let err = peer1.connect(peer2.local_addr(), &invalid_id).await;
+        assert_eq!(err.unwrap_err(), ConnectionError::InvalidCertificate);
+        assert_eq!(events.recv().await?, ConnectionEvent::ConnectionFailed {
+            peer_id: invalid_id,
+            remote_addr: peer2.local_addr().into(),
+            origin: Direction::Outbound,
+            error: ConnectionError::InvalidCertificate,
+        });
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn congestion_controller_is_applied() -> Result<()> {
+        tycho_util::test::init_logger("congestion_controller_is_applied", "debug");
+
+        const INITIAL_WINDOW: u64 = 4 << 20;
+
+        fn make_network_with(congestion_controller: CongestionController) -> Result<Network> {
+            Network::builder()
+                .with_config(NetworkConfig {
+                    quic: Some(QuicConfig {
+                        congestion_controller,
+                        initial_window: Some(INITIAL_WINDOW),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                })
+                .with_random_private_key()
+                .build("127.0.0.1:0", echo_service())
+        }
+
+        for congestion_controller in [CongestionController::Cubic, CongestionController::Bbr] {
+            let peer1 = make_network_with(congestion_controller)?;
+            let peer2 = make_network_with(congestion_controller)?;
+
+            let peer = peer1.connect(peer2.local_addr(), peer2.peer_id()).await?;
+            let request = Request {
+                version: Default::default(),
+                body: "ping".into(),
+            };
+            assert_eq!(peer.rpc(request).await?.body, "ping");
+
+            // The default initial window is much smaller, so the window
+            // can only be this large if the custom config was applied
+            let connection = peer1.0.active_peers.get(peer2.peer_id()).unwrap();
+            let cwnd = connection.stats().path.cwnd;
+            assert!(
+                cwnd >= INITIAL_WINDOW,
+                "{congestion_controller:?}: cwnd {cwnd} is less than {INITIAL_WINDOW}"
+            );
+        }
+
+        // Default config must keep the quinn defaults
+        let peer1 = make_network()?;
+        let peer2 = make_network()?;
+        peer1.connect(peer2.local_addr(), peer2.peer_id()).await?;
+        let connection = peer1.0.active_peers.get(peer2.peer_id()).unwrap();
+        assert!(connection.stats().path.cwnd < INITIAL_WINDOW);
+
+        Ok(())
+    }
 }
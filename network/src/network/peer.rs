@@ -7,7 +7,7 @@ use tycho_util::metrics::{GaugeGuard, HistogramGuard};
 use crate::network::config::NetworkConfig;
 use crate::network::connection::Connection;
 use crate::network::wire::{make_codec, recv_response, send_request};
-use crate::types::{PeerId, Request, Response};
+use crate::types::{PeerId, Priority, Request, Response};
 
 // Histograms
 const METRIC_OUT_QUERIES_TIME: &str = "tycho_net_out_queries_time";
@@ -37,11 +37,20 @@ impl Peer {
     }
 
     pub async fn rpc(&self, request: Request) -> Result<Response> {
+        self.rpc_with_priority(request, Priority::Normal).await
+    }
+
+    pub async fn rpc_with_priority(
+        &self,
+        request: Request,
+        priority: Priority,
+    ) -> Result<Response> {
         metrics::counter!(METRIC_OUT_QUERIES_TOTAL).increment(1);
         let _gauge = GaugeGuard::increment(METRIC_OUT_QUERIES, 1);
         let _histogram = HistogramGuard::begin(METRIC_OUT_QUERIES_TIME);
 
-        let (send_stream, recv_stream) = self.connection.open_bi().await?;
+        let (mut send_stream, recv_stream) = self.connection.open_bi().await?;
+        send_stream.set_priority(priority.to_stream_priority())?;
         let mut send_stream = FramedWrite::new(send_stream, make_codec(&self.config));
         let mut recv_stream = FramedRead::new(recv_stream, make_codec(&self.config));
 
@@ -52,11 +61,21 @@ impl Peer {
     }
 
     pub async fn send_message(&self, request: Request) -> Result<()> {
+        self.send_message_with_priority(request, Priority::Normal)
+            .await
+    }
+
+    pub async fn send_message_with_priority(
+        &self,
+        request: Request,
+        priority: Priority,
+    ) -> Result<()> {
         metrics::counter!(METRIC_OUT_MESSAGES_TOTAL).increment(1);
         let _gauge = GaugeGuard::increment(METRIC_OUT_MESSAGES, 1);
         let _histogram = HistogramGuard::begin(METRIC_OUT_MESSAGES_TIME);
 
-        let send_stream = self.connection.open_uni().await?;
+        let mut send_stream = self.connection.open_uni().await?;
+        send_stream.set_priority(priority.to_stream_priority())?;
         let mut send_stream = FramedWrite::new(send_stream, make_codec(&self.config));
 
         send_request(&mut send_stream, request).await?;
@@ -1,21 +1,26 @@
 use std::future::IntoFuture;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use anyhow::Result;
 use futures_util::stream::FuturesUnordered;
 use futures_util::StreamExt;
-use quinn::ConnectionError;
 use tokio::task::JoinHandle;
 use tokio_util::codec::{FramedRead, FramedWrite, LengthDelimitedCodec};
 use tycho_util::metrics::HistogramGuard;
+use tycho_util::FastDashMap;
 
-use crate::network::config::NetworkConfig;
+use crate::network::config::{InboundRateLimit, NetworkConfig};
 use crate::network::connection::{Connection, RecvStream, SendStream};
 use crate::network::connection_manager::ActivePeers;
-use crate::network::wire::{make_codec, recv_request, send_response};
+use crate::network::wire::{
+    is_request_too_large, make_codec, make_request_codec, recv_request, send_response,
+};
 use crate::types::{
-    BoxCloneService, DisconnectReason, InboundRequestMeta, Response, Service, ServiceRequest,
+    BoxCloneService, DisconnectReason, InboundRequestMeta, PeerId, Response, ResponsePriority,
+    Service, ServiceRequest,
 };
+use crate::util::TokenBucket;
 
 // Histograms
 const METRIC_IN_QUERIES_TIME: &str = "tycho_net_in_queries_time";
@@ -25,6 +30,10 @@ const METRIC_IN_MESSAGES_TIME: &str = "tycho_net_in_messages_time";
 const METRIC_IN_QUERIES_TOTAL: &str = "tycho_net_in_queries_total";
 const METRIC_IN_MESSAGES_TOTAL: &str = "tycho_net_in_messages_total";
 const METRIC_IN_REQUESTS_REJECTED_TOTAL: &str = "tycho_net_in_requests_rejected_total";
+const METRIC_IN_REQUESTS_TOO_LARGE_TOTAL: &str = "tycho_net_in_requests_too_large_total";
+const METRIC_IN_REQUESTS_RATE_LIMITED_TOTAL: &str = "tycho_net_in_requests_rate_limited_total";
+const METRIC_IN_REQUESTS_RATE_LIMITED_PER_PEER: &str =
+    "tycho_net_in_requests_rate_limited_per_peer";
 
 // Gauges
 const METRIC_REQ_HANDLERS: &str = "tycho_net_req_handlers";
@@ -35,6 +44,7 @@ pub(crate) struct InboundRequestHandler {
     connection: Connection,
     service: BoxCloneService<ServiceRequest, Response>,
     active_peers: ActivePeers,
+    rate_limiters: Option<InboundRateLimiters>,
 }
 
 impl InboundRequestHandler {
@@ -43,24 +53,38 @@ impl InboundRequestHandler {
         connection: Connection,
         service: BoxCloneService<ServiceRequest, Response>,
         active_peers: ActivePeers,
+        rate_limiters: Option<InboundRateLimiters>,
     ) -> Self {
         Self {
             config,
             connection,
             service,
             active_peers,
+            rate_limiters,
         }
     }
 
     pub async fn start(self) {
         tracing::debug!(peer_id = %self.connection.peer_id(), "request handler started");
 
-        let mut tracker =
-            RequestTracker::new(self.config.as_ref(), &self.connection, &self.active_peers);
+        let mut tracker = RequestTracker::new(
+            self.config.as_ref(),
+            &self.connection,
+            &self.active_peers,
+            self.rate_limiters.as_ref(),
+        );
 
-        let reason: ConnectionError = loop {
+        let reason: DisconnectReason = loop {
             tracker.update_inflight_metrics();
 
+            if tracker.should_disconnect() {
+                tracing::debug!(
+                    peer_id = %self.connection.peer_id(),
+                    "too many rate limited requests, closing connection"
+                );
+                break DisconnectReason::RateLimited;
+            }
+
             tokio::select! {
                 biased;
 
@@ -72,7 +96,7 @@ impl InboundRequestHandler {
                     Ok(stream) => tracker.track_uni(&self.service, stream),
                     Err(e) => {
                         tracing::trace!("failed to accept an incoming uni stream: {e:?}");
-                        break e;
+                        break e.into();
                     }
                 },
 
@@ -81,13 +105,13 @@ impl InboundRequestHandler {
                     Ok((tx, rx)) => tracker.track_bi(&self.service, tx, rx),
                     Err(e) => {
                         tracing::trace!("failed to accept an incoming bi stream: {e:?}");
-                        break e;
+                        break e.into();
                     }
                 },
             }
         };
 
-        tracker.reason = reason.into();
+        tracker.reason = reason;
         tracker.shutdown().await;
     }
 }
@@ -98,6 +122,7 @@ struct RequestTracker<'a> {
     active_peers: &'a ActivePeers,
     inflight_requests_len: usize,
     inflight_requests: FuturesUnordered<JoinHandle<()>>,
+    rate_limiters: Option<&'a InboundRateLimiters>,
     reason: DisconnectReason,
     peer_id_str: Arc<str>,
 }
@@ -107,6 +132,7 @@ impl<'a> RequestTracker<'a> {
         config: &'a NetworkConfig,
         connection: &'a Connection,
         active_peers: &'a ActivePeers,
+        rate_limiters: Option<&'a InboundRateLimiters>,
     ) -> Self {
         let peer_id_str = Arc::from(connection.peer_id().to_string());
 
@@ -116,6 +142,7 @@ impl<'a> RequestTracker<'a> {
             active_peers,
             inflight_requests_len: 0,
             inflight_requests: Default::default(),
+            rate_limiters,
             reason: DisconnectReason::LocallyClosed,
             peer_id_str,
         }
@@ -125,6 +152,29 @@ impl<'a> RequestTracker<'a> {
         self.inflight_requests_len >= self.config.max_concurrent_requests_per_peer
     }
 
+    fn is_rate_limited(&mut self) -> bool {
+        let Some(rate_limiters) = self.rate_limiters else {
+            return false;
+        };
+        if rate_limiters.try_acquire(self.connection.peer_id(), Instant::now()) {
+            return false;
+        }
+
+        metrics::counter!(METRIC_IN_REQUESTS_RATE_LIMITED_TOTAL).increment(1);
+        let metrics = &self.config.connection_metrics;
+        if metrics.is_some_and(|x| x.should_export_peer_id()) {
+            let peer_id = self.peer_id_str.clone();
+            metrics::counter!(METRIC_IN_REQUESTS_RATE_LIMITED_PER_PEER, "peer_id" => peer_id)
+                .increment(1);
+        }
+        true
+    }
+
+    fn should_disconnect(&self) -> bool {
+        self.rate_limiters
+            .is_some_and(|x| x.should_disconnect(self.connection.peer_id()))
+    }
+
     async fn shutdown(&mut self) {
         // Abort all tasks.
         for handle in &self.inflight_requests {
@@ -169,6 +219,14 @@ impl<'a> RequestTracker<'a> {
             metrics::counter!(METRIC_IN_REQUESTS_REJECTED_TOTAL).increment(1);
             return;
         }
+        if self.is_rate_limited() {
+            tracing::debug!(
+                peer_id = %self.peer_id_str,
+                "rate limit exceeded, rejecting uni stream"
+            );
+            let _ = stream.stop(Connection::RATE_LIMITED_ERROR_CODE);
+            return;
+        }
 
         let handler = UniStreamRequestHandler::new(
             self.config,
@@ -199,6 +257,15 @@ impl<'a> RequestTracker<'a> {
             metrics::counter!(METRIC_IN_REQUESTS_REJECTED_TOTAL).increment(1);
             return;
         }
+        if self.is_rate_limited() {
+            tracing::debug!(
+                peer_id = %self.peer_id_str,
+                "rate limit exceeded, rejecting bi stream"
+            );
+            let _ = tx.reset(Connection::RATE_LIMITED_ERROR_CODE);
+            let _ = rx.stop(Connection::RATE_LIMITED_ERROR_CODE);
+            return;
+        }
 
         let handler = BiStreamRequestHandler::new(
             self.config,
@@ -245,10 +312,137 @@ impl Drop for RequestTracker<'_> {
             self.connection.stable_id(),
             self.reason,
         );
+
+        if let Some(rate_limiters) = self.rate_limiters {
+            let peer_id = self.connection.peer_id();
+            rate_limiters.remove_peer(
+                peer_id,
+                self.reason,
+                self.active_peers.contains(peer_id),
+                Instant::now(),
+            );
+        }
+
         tracing::debug!(peer_id = %self.peer_id_str, "request handler stopped");
     }
 }
 
+/// Inbound rate limits shared between all connections of the same peer.
+#[derive(Clone)]
+pub(crate) struct InboundRateLimiters {
+    inner: Arc<InboundRateLimitersInner>,
+}
+
+struct InboundRateLimitersInner {
+    config: InboundRateLimit,
+    limiters: FastDashMap<PeerId, InboundRateLimiter>,
+    cooldowns: FastDashMap<PeerId, Instant>,
+}
+
+impl InboundRateLimiters {
+    pub fn new(mut config: InboundRateLimit) -> Self {
+        config.burst = config.burst.max(1);
+        config.refill_interval = config.refill_interval.max(Duration::from_micros(1));
+
+        Self {
+            inner: Arc::new(InboundRateLimitersInner {
+                config,
+                limiters: Default::default(),
+                cooldowns: Default::default(),
+            }),
+        }
+    }
+
+    /// Returns `true` if the peer was recently disconnected by the limit
+    /// and must not be accepted yet.
+    pub fn is_cooling_down(&self, peer_id: &PeerId, now: Instant) -> bool {
+        let cooling_down = match self.inner.cooldowns.get(peer_id) {
+            Some(until) => *until > now,
+            None => return false,
+        };
+        if !cooling_down {
+            self.inner
+                .cooldowns
+                .remove_if(peer_id, |_, until| *until <= now);
+        }
+        cooling_down
+    }
+
+    /// Removes expired cooldowns.
+    pub fn remove_expired(&self, now: Instant) {
+        self.inner.cooldowns.retain(|_, until| *until > now);
+    }
+
+    fn try_acquire(&self, peer_id: &PeerId, now: Instant) -> bool {
+        let config = &self.inner.config;
+        self.inner
+            .limiters
+            .entry(*peer_id)
+            .or_insert_with(|| InboundRateLimiter::new(config.burst, now))
+            .try_acquire(config, now)
+    }
+
+    fn should_disconnect(&self, peer_id: &PeerId) -> bool {
+        let config = &self.inner.config;
+        self.inner
+            .limiters
+            .get(peer_id)
+            .is_some_and(|x| x.should_disconnect(config))
+    }
+
+    fn remove_peer(
+        &self,
+        peer_id: &PeerId,
+        reason: DisconnectReason,
+        still_connected: bool,
+        now: Instant,
+    ) {
+        let inner = self.inner.as_ref();
+        if reason == DisconnectReason::RateLimited {
+            inner
+                .cooldowns
+                .insert(*peer_id, now + inner.config.reconnect_cooldown);
+        }
+
+        // Keep the bucket while there is another connection with this peer.
+        if !still_connected {
+            inner.limiters.remove(peer_id);
+        }
+    }
+}
+
+struct InboundRateLimiter {
+    bucket: TokenBucket,
+    rejected_in_a_row: u32,
+}
+
+impl InboundRateLimiter {
+    fn new(burst: u32, now: Instant) -> Self {
+        Self {
+            bucket: TokenBucket::new(burst, now),
+            rejected_in_a_row: 0,
+        }
+    }
+
+    fn try_acquire(&mut self, config: &InboundRateLimit, now: Instant) -> bool {
+        if self
+            .bucket
+            .try_acquire(config.burst, config.refill_interval, now)
+        {
+            self.rejected_in_a_row = 0;
+            true
+        } else {
+            self.rejected_in_a_row = self.rejected_in_a_row.saturating_add(1);
+            false
+        }
+    }
+
+    fn should_disconnect(&self, config: &InboundRateLimit) -> bool {
+        let rejected = self.rejected_in_a_row;
+        matches!(config.disconnect_after, Some(n) if rejected > 0 && rejected >= n)
+    }
+}
+
 struct UniStreamRequestHandler {
     meta: Arc<InboundRequestMeta>,
     service: BoxCloneService<ServiceRequest, Response>,
@@ -265,7 +459,7 @@ impl UniStreamRequestHandler {
         Self {
             meta,
             service,
-            recv_stream: FramedRead::new(recv_stream, make_codec(config)),
+            recv_stream: FramedRead::new(recv_stream, make_request_codec(config)),
         }
     }
 
@@ -278,11 +472,22 @@ impl UniStreamRequestHandler {
     }
 
     async fn do_handle(mut self) -> Result<()> {
-        let req = recv_request(&mut self.recv_stream).await?;
+        let req = match recv_request(&mut self.recv_stream).await {
+            Ok(req) => req,
+            Err(e) => {
+                if is_request_too_large(&e) {
+                    metrics::counter!(METRIC_IN_REQUESTS_TOO_LARGE_TOTAL).increment(1);
+                    let code = Connection::REQUEST_TOO_LARGE_ERROR_CODE;
+                    _ = self.recv_stream.get_mut().stop(code);
+                }
+                return Err(e.into());
+            }
+        };
         self.service
             .on_message(ServiceRequest {
                 metadata: self.meta,
                 body: req.body,
+                response_priority: Default::default(),
             })
             .await;
         Ok(())
@@ -308,7 +513,7 @@ impl BiStreamRequestHandler {
             meta,
             service,
             send_stream: FramedWrite::new(send_stream, make_codec(config)),
-            recv_stream: FramedRead::new(recv_stream, make_codec(config)),
+            recv_stream: FramedRead::new(recv_stream, make_request_codec(config)),
         }
     }
 
@@ -321,16 +526,31 @@ impl BiStreamRequestHandler {
     }
 
     async fn do_handle(mut self) -> Result<()> {
-        let req = recv_request(&mut self.recv_stream).await?;
+        let req = match recv_request(&mut self.recv_stream).await {
+            Ok(req) => req,
+            Err(e) => {
+                if is_request_too_large(&e) {
+                    metrics::counter!(METRIC_IN_REQUESTS_TOO_LARGE_TOTAL).increment(1);
+                    let code = Connection::REQUEST_TOO_LARGE_ERROR_CODE;
+                    _ = self.send_stream.get_mut().reset(code);
+                    _ = self.recv_stream.get_mut().stop(code);
+                }
+                return Err(e.into());
+            }
+        };
+        let response_priority = ResponsePriority::default();
         let handler = self.service.on_query(ServiceRequest {
             metadata: self.meta,
             body: req.body,
+            response_priority: response_priority.clone(),
         });
 
         let stopped = self.send_stream.get_mut().stopped();
         tokio::select! {
             res = handler => {
                 if let Some(res) = res {
+                    let priority = response_priority.get().to_stream_priority();
+                    self.send_stream.get_mut().set_priority(priority)?;
                     send_response(&mut self.send_stream, res).await?;
                 }
                 self.send_stream.get_mut().finish().expect("must not be closed twise");
@@ -2,16 +2,26 @@ use anyhow::Result;
 use futures_util::sink::SinkExt;
 use futures_util::StreamExt;
 use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
-use tokio_util::codec::{FramedRead, FramedWrite, LengthDelimitedCodec};
+use tokio_util::codec::{FramedRead, FramedWrite, LengthDelimitedCodec, LengthDelimitedCodecError};
 
 use crate::network::config::NetworkConfig;
 use crate::network::connection::Connection;
 use crate::types::{Direction, Request, Response, Version};
 
 pub(crate) fn make_codec(config: &NetworkConfig) -> LengthDelimitedCodec {
+    make_codec_with_max_len(config.max_frame_size.0 as usize)
+}
+
+/// Codec for inbound requests, limited by `max_request_size`.
+pub(crate) fn make_request_codec(config: &NetworkConfig) -> LengthDelimitedCodec {
+    let max_len = std::cmp::min(config.max_request_size.0, config.max_frame_size.0);
+    make_codec_with_max_len(max_len as usize)
+}
+
+fn make_codec_with_max_len(max_len: usize) -> LengthDelimitedCodec {
     let mut builder = LengthDelimitedCodec::builder();
 
-    builder.max_frame_length(config.max_frame_size.0 as usize);
+    builder.max_frame_length(max_len);
 
     builder.length_field_length(4).big_endian().new_codec()
 }
@@ -74,15 +84,22 @@ pub(crate) async fn send_request<T: AsyncWrite + Unpin>(
     send_stream.send(request.body).await
 }
 
+/// Returns [`WireError::RequestTooLarge`] as soon as the frame header
+/// exceeds the codec limit, without buffering the body.
 pub(crate) async fn recv_request<T: AsyncRead + Unpin>(
     recv_stream: &mut FramedRead<T, LengthDelimitedCodec>,
 ) -> std::io::Result<Request> {
     let version = recv_version(recv_stream.get_mut()).await?;
     match recv_stream.next().await {
-        Some(body) => Ok(Request {
+        Some(Ok(body)) => Ok(Request {
             version,
-            body: body?.freeze(),
+            body: body.freeze(),
         }),
+        Some(Err(e)) if is_frame_too_large(&e) => Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            WireError::RequestTooLarge,
+        )),
+        Some(Err(e)) => Err(e),
         None => Err(std::io::Error::new(
             std::io::ErrorKind::UnexpectedEof,
             WireError::UnexpectedEof,
@@ -144,6 +161,19 @@ async fn recv_version<T: AsyncRead + Unpin>(recv_stream: &mut T) -> std::io::Res
     }
 }
 
+pub(crate) fn is_request_too_large(error: &std::io::Error) -> bool {
+    matches!(
+        error.get_ref().and_then(|e| e.downcast_ref::<WireError>()),
+        Some(WireError::RequestTooLarge)
+    )
+}
+
+fn is_frame_too_large(error: &std::io::Error) -> bool {
+    error
+        .get_ref()
+        .is_some_and(|e| e.is::<LengthDelimitedCodecError>())
+}
+
 const MAGIC: &[u8; 5] = b"tycho";
 
 #[derive(Clone, Copy, Debug, thiserror::Error)]
@@ -156,4 +186,6 @@ enum WireError {
     UnexpectedEof,
     #[error("0-rtt rejected")]
     ZeroRttRejected,
+    #[error("request too large")]
+    RequestTooLarge,
 }
@@ -15,7 +15,9 @@ use crate::network::Network;
 use crate::overlay::metrics::Metrics;
 use crate::overlay::OverlayId;
 use crate::proto::overlay::rpc;
-use crate::types::{BoxService, PeerId, Request, Response, Service, ServiceExt, ServiceRequest};
+use crate::types::{
+    BoxService, PeerId, Priority, Request, Response, Service, ServiceExt, ServiceRequest,
+};
 use crate::util::NetworkExt;
 
 pub struct PrivateOverlayBuilder {
@@ -114,25 +116,44 @@ impl PrivateOverlay {
     }
 
     pub async fn query(
+        &self,
+        network: &Network,
+        peer_id: &PeerId,
+        request: Request,
+    ) -> Result<Response> {
+        self.query_with_priority(network, peer_id, request, Priority::Normal)
+            .await
+    }
+
+    pub async fn query_with_priority(
         &self,
         network: &Network,
         peer_id: &PeerId,
         mut request: Request,
+        priority: Priority,
     ) -> Result<Response> {
         self.inner.metrics.record_rx(request.body.len());
         self.prepend_prefix_to_body(&mut request.body);
-        network.query(peer_id, request).await
+        network
+            .query_with_priority(peer_id, request, priority)
+            .await
+    }
+
+    pub async fn send(&self, network: &Network, peer_id: &PeerId, request: Request) -> Result<()> {
+        self.send_with_priority(network, peer_id, request, Priority::Normal)
+            .await
     }
 
-    pub async fn send(
+    pub async fn send_with_priority(
         &self,
         network: &Network,
         peer_id: &PeerId,
         mut request: Request,
+        priority: Priority,
     ) -> Result<()> {
         self.inner.metrics.record_rx(request.body.len());
         self.prepend_prefix_to_body(&mut request.body);
-        network.send(peer_id, request).await
+        network.send_with_priority(peer_id, request, priority).await
     }
 
     pub fn write_entries(&self) -> PrivateOverlayEntriesWriteGuard<'_> {
@@ -1,5 +1,5 @@
 use std::borrow::Borrow;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -21,10 +21,14 @@ use crate::proto::overlay::{rpc, PublicEntry, PublicEntryToSign};
 use crate::types::{BoxService, PeerId, Request, Response, Service, ServiceExt, ServiceRequest};
 use crate::util::NetworkExt;
 
+// Counters
+const METRIC_PRUNED_ENTRIES_TOTAL: &str = "tycho_net_overlay_pruned_entries_total";
+
 pub struct PublicOverlayBuilder {
     overlay_id: OverlayId,
     min_capacity: usize,
     entry_ttl: Duration,
+    unresolved_entry_ttl: Duration,
     banned_peer_ids: FastDashSet<PeerId>,
     peer_resolver: Option<PeerResolver>,
     name: Option<&'static str>,
@@ -49,6 +53,15 @@ impl PublicOverlayBuilder {
         self
     }
 
+    /// Time after which entries that are still not resolved are pruned
+    /// from the overlay. Only used with the peer resolver.
+    ///
+    /// Default: 10 minutes.
+    pub fn with_unresolved_entry_ttl(mut self, unresolved_entry_ttl: Duration) -> Self {
+        self.unresolved_entry_ttl = unresolved_entry_ttl;
+        self
+    }
+
     /// Banned peers that will not be ignored by the overlay.
     pub fn with_banned_peers<I>(mut self, banned_peers: I) -> Self
     where
@@ -90,18 +103,25 @@ impl PublicOverlayBuilder {
         };
 
         let entry_ttl_sec = self.entry_ttl.as_secs().try_into().unwrap_or(u32::MAX);
+        let unresolved_entry_ttl_sec = self
+            .unresolved_entry_ttl
+            .as_secs()
+            .try_into()
+            .unwrap_or(u32::MAX);
 
         PublicOverlay {
             inner: Arc::new(Inner {
                 overlay_id: self.overlay_id,
                 min_capacity: self.min_capacity,
                 entry_ttl_sec,
+                unresolved_entry_ttl_sec,
                 peer_resolver: self.peer_resolver,
                 entries: RwLock::new(entries),
                 entries_added: Notify::new(),
                 entries_changed: Notify::new(),
                 entries_removed: Notify::new(),
                 entry_count: AtomicUsize::new(0),
+                pruned_entry_count: AtomicU64::new(0),
                 own_signed_entry: Default::default(),
                 unknown_peers_queue: UnknownPeersQueue::with_capacity(UNRESOLVED_QUEUE_CAPACITY),
                 banned_peer_ids: self.banned_peer_ids,
@@ -128,6 +148,7 @@ impl PublicOverlay {
             overlay_id,
             min_capacity: 100,
             entry_ttl: Duration::from_secs(3600),
+            unresolved_entry_ttl: Duration::from_secs(600),
             banned_peer_ids: Default::default(),
             peer_resolver: None,
             name: None,
@@ -143,6 +164,15 @@ impl PublicOverlay {
         self.inner.entry_ttl_sec
     }
 
+    pub fn unresolved_entry_ttl_sec(&self) -> u32 {
+        self.inner.unresolved_entry_ttl_sec
+    }
+
+    /// Total number of entries pruned because they were not resolved in time.
+    pub fn pruned_entry_count(&self) -> u64 {
+        self.inner.pruned_entry_count.load(Ordering::Relaxed)
+    }
+
     pub fn peer_resolver(&self) -> &Option<PeerResolver> {
         &self.inner.peer_resolver
     }
@@ -343,7 +373,7 @@ impl PublicOverlay {
                     continue;
                 }
 
-                let status = stored.insert(&this.peer_resolver, entry);
+                let status = stored.insert(&this.peer_resolver, entry, now);
                 changed |= status.is_changed();
                 added += status.is_added() as usize;
 
@@ -369,21 +399,45 @@ impl PublicOverlay {
         changed || added > 0
     }
 
-    /// Removes all expired and banned entries from the overlay.
+    /// Removes all expired, banned and stale unresolved entries from the overlay.
     pub(crate) fn remove_invalid_entries(&self, now: u32) {
         let this = self.inner.as_ref();
 
-        let mut should_notify = false;
+        // NOTE: Entries can't be resolved without the resolver
+        let prune_unresolved = this.peer_resolver.is_some();
+
+        let mut removed = 0usize;
+        let mut pruned = 0u64;
         let mut entries = this.entries.write();
         entries.retain(|item| {
-            let retain = !item.entry.is_expired(now, this.entry_ttl_sec)
-                && !this.banned_peer_ids.contains(&item.entry.peer_id);
-            should_notify |= !retain;
-            retain
+            if item.entry.is_expired(now, this.entry_ttl_sec)
+                || this.banned_peer_ids.contains(&item.entry.peer_id)
+            {
+                removed += 1;
+                return false;
+            }
+
+            if prune_unresolved
+                && item.added_at.saturating_add(this.unresolved_entry_ttl_sec) <= now
+                && !item.resolver_handle.is_resolved()
+            {
+                tracing::debug!(peer_id = %item.entry.peer_id, "pruned unresolved overlay entry");
+                removed += 1;
+                pruned += 1;
+                return false;
+            }
+
+            true
         });
+        drop(entries);
 
-        if should_notify {
-            self.inner.entries_removed.notify_waiters();
+        if removed > 0 {
+            this.entry_count.fetch_sub(removed, Ordering::Release);
+            this.entries_removed.notify_waiters();
+        }
+        if pruned > 0 {
+            this.pruned_entry_count.fetch_add(pruned, Ordering::Relaxed);
+            metrics::counter!(METRIC_PRUNED_ENTRIES_TOTAL).increment(pruned);
         }
     }
 
@@ -410,9 +464,11 @@ struct Inner {
     overlay_id: OverlayId,
     min_capacity: usize,
     entry_ttl_sec: u32,
+    unresolved_entry_ttl_sec: u32,
     peer_resolver: Option<PeerResolver>,
     entries: RwLock<PublicOverlayEntries>,
     entry_count: AtomicUsize,
+    pruned_entry_count: AtomicU64,
     entries_added: Notify,
     entries_changed: Notify,
     entries_removed: Notify,
@@ -488,7 +544,12 @@ impl PublicOverlayEntries {
         self.choose_multiple(rng, self.items.len())
     }
 
-    fn insert(&mut self, peer_resolver: &Option<PeerResolver>, item: &PublicEntry) -> UpdateStatus {
+    fn insert(
+        &mut self,
+        peer_resolver: &Option<PeerResolver>,
+        item: &PublicEntry,
+        now: u32,
+    ) -> UpdateStatus {
         match self.items.entry(item.peer_id) {
             // No entry for the peer_id, insert a new one
             indexmap::map::Entry::Vacant(entry) => {
@@ -500,6 +561,7 @@ impl PublicOverlayEntries {
                 entry.insert(PublicOverlayEntryData {
                     entry: Arc::new(item.clone()),
                     resolver_handle,
+                    added_at: now,
                 });
 
                 UpdateStatus::Added
@@ -533,6 +595,8 @@ impl PublicOverlayEntries {
 pub struct PublicOverlayEntryData {
     pub entry: Arc<PublicEntry>,
     pub resolver_handle: PeerResolverHandle,
+    /// Unix timestamp when the entry was added to the overlay.
+    pub added_at: u32,
 }
 
 impl PublicOverlayEntryData {
@@ -895,4 +959,36 @@ mod tests {
         assert_eq!(items.len(), 1);
         assert!(items.contains(&PeerId([0; 32])));
     }
+
+    #[tokio::test]
+    async fn unresolved_entries_are_pruned() {
+        let (_, dht_service) = crate::DhtService::builder(rand::random()).build();
+        let network = Network::builder()
+            .with_random_private_key()
+            .build("127.0.0.1:0", dht_service.clone())
+            .unwrap();
+
+        let overlay = PublicOverlay::builder(rand::random())
+            .with_unresolved_entry_ttl(Duration::from_secs(10))
+            .with_peer_resolver(dht_service.make_peer_resolver().build(&network))
+            .build(crate::service_query_fn(|_| {
+                futures_util::future::ready(None)
+            }));
+
+        // The DHT is empty, so the entry will never be resolved
+        let now = now_sec();
+        let entries = generate_public_entries(&overlay, now, 1);
+        assert!(overlay.add_untrusted_entries(network.peer_id(), &entries, now));
+        assert_eq!(count_entries(&overlay), 1);
+
+        // Entry is kept until the unresolved TTL elapses
+        overlay.remove_invalid_entries(now + 5);
+        assert_eq!(count_entries(&overlay), 1);
+        assert_eq!(overlay.pruned_entry_count(), 0);
+
+        // Stale entry is pruned
+        overlay.remove_invalid_entries(now + 10);
+        assert_eq!(count_entries(&overlay), 0);
+        assert_eq!(overlay.pruned_entry_count(), 1);
+    }
 }
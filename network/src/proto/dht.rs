@@ -21,24 +21,68 @@ pub enum MergedValueKeyName {
     PublicOverlayEntries,
 }
 
+/// Namespace of the values which are stored without an explicit namespace.
+///
+/// Keys in this namespace are serialized as `dht.peerValueKey`,
+/// so they are the same as before namespaces were introduced.
+pub const DEFAULT_NAMESPACE: u32 = 0;
+
+const PEER_VALUE_KEY_TL_ID: u32 = tl_proto::id!("dht.peerValueKey", scheme = "proto.tl");
+const PEER_VALUE_KEY_NS_TL_ID: u32 = tl_proto::id!("dht.peerValueKeyNs", scheme = "proto.tl");
+
 /// Key for values that can only be updated by the owner.
 ///
-/// See [`SignedValueKeyRef`] for the non-owned version of the struct.
-#[derive(Debug, Clone, PartialEq, Eq, TlRead, TlWrite)]
-#[tl(boxed, id = "dht.peerValueKey", scheme = "proto.tl")]
+/// See [`PeerValueKeyRef`] for the non-owned version of the struct.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PeerValueKey {
+    /// Key namespace.
+    pub namespace: u32,
     /// Key name.
     pub name: PeerValueKeyName,
     /// Public key of the owner.
     pub peer_id: PeerId,
 }
 
+impl PeerValueKey {
+    pub fn as_key_ref(&self) -> PeerValueKeyRef<'_> {
+        PeerValueKeyRef {
+            namespace: self.namespace,
+            name: self.name,
+            peer_id: &self.peer_id,
+        }
+    }
+}
+
+impl TlWrite for PeerValueKey {
+    type Repr = tl_proto::Boxed;
+
+    fn max_size_hint(&self) -> usize {
+        self.as_key_ref().max_size_hint()
+    }
+
+    fn write_to<P>(&self, packet: &mut P)
+    where
+        P: tl_proto::TlPacket,
+    {
+        self.as_key_ref().write_to(packet);
+    }
+}
+
+impl<'a> TlRead<'a> for PeerValueKey {
+    type Repr = tl_proto::Boxed;
+
+    fn read_from(packet: &mut &'a [u8]) -> tl_proto::TlResult<Self> {
+        PeerValueKeyRef::read_from(packet).map(|key| key.as_owned())
+    }
+}
+
 /// Key for values that can only be updated by the owner.
 ///
-/// See [`SignedValueKey`] for the owned version of the struct.
-#[derive(Debug, Clone, PartialEq, Eq, TlRead, TlWrite)]
-#[tl(boxed, id = "dht.peerValueKey", scheme = "proto.tl")]
+/// See [`PeerValueKey`] for the owned version of the struct.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PeerValueKeyRef<'tl> {
+    /// Key namespace.
+    pub namespace: u32,
     /// Key name.
     pub name: PeerValueKeyName,
     /// Public key of the owner.
@@ -48,12 +92,62 @@ pub struct PeerValueKeyRef<'tl> {
 impl PeerValueKeyRef<'_> {
     pub fn as_owned(&self) -> PeerValueKey {
         PeerValueKey {
+            namespace: self.namespace,
             name: self.name,
             peer_id: *self.peer_id,
         }
     }
 }
 
+impl TlWrite for PeerValueKeyRef<'_> {
+    type Repr = tl_proto::Boxed;
+
+    fn max_size_hint(&self) -> usize {
+        let namespace = if self.namespace == DEFAULT_NAMESPACE {
+            0
+        } else {
+            4
+        };
+        4 + namespace + self.name.max_size_hint() + self.peer_id.max_size_hint()
+    }
+
+    fn write_to<P>(&self, packet: &mut P)
+    where
+        P: tl_proto::TlPacket,
+    {
+        if self.namespace == DEFAULT_NAMESPACE {
+            PEER_VALUE_KEY_TL_ID.write_to(packet);
+        } else {
+            PEER_VALUE_KEY_NS_TL_ID.write_to(packet);
+            self.namespace.write_to(packet);
+        }
+        self.name.write_to(packet);
+        self.peer_id.write_to(packet);
+    }
+}
+
+impl<'a> TlRead<'a> for PeerValueKeyRef<'a> {
+    type Repr = tl_proto::Boxed;
+
+    fn read_from(packet: &mut &'a [u8]) -> tl_proto::TlResult<Self> {
+        let namespace = match u32::read_from(packet)? {
+            PEER_VALUE_KEY_TL_ID => DEFAULT_NAMESPACE,
+            PEER_VALUE_KEY_NS_TL_ID => match u32::read_from(packet)? {
+                // NOTE: Each key must have a single representation
+                DEFAULT_NAMESPACE => return Err(tl_proto::TlError::InvalidData),
+                namespace => namespace,
+            },
+            _ => return Err(tl_proto::TlError::UnknownConstructor),
+        };
+
+        Ok(Self {
+            namespace,
+            name: PeerValueKeyName::read_from(packet)?,
+            peer_id: <&PeerId>::read_from(packet)?,
+        })
+    }
+}
+
 /// Key for group-managed values.
 ///
 /// See [`MergedValueKeyRef`] for the non-owned version of the struct.
@@ -41,6 +41,10 @@ pub enum DisconnectReason {
     TimedOut,
     LocallyClosed,
     CidsExhausted,
+    /// Connection had no streams for too long and the peer is not referenced by anyone.
+    IdleTimeout,
+    /// Peer kept sending requests above the inbound rate limit.
+    RateLimited,
 }
 
 impl From<quinn::ConnectionError> for DisconnectReason {
@@ -1,4 +1,5 @@
 use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU8, Ordering};
 use std::sync::Arc;
 
 use bytes::Bytes;
@@ -120,6 +121,9 @@ impl AsRef<[u8]> for Response {
 pub struct ServiceRequest {
     pub metadata: Arc<InboundRequestMeta>,
     pub body: Bytes,
+    /// Priority of the response stream, can be changed by the service.
+    /// Ignored for messages.
+    pub response_priority: ResponsePriority,
 }
 
 impl ServiceRequest {
@@ -138,6 +142,63 @@ impl AsRef<[u8]> for ServiceRequest {
     }
 }
 
+/// Scheduling hint for outgoing streams (requests and responses).
+///
+/// Mapped to QUIC stream priority: data of streams with a higher priority
+/// is sent first when the connection is congested.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    /// Bulk transfers which can be delayed (e.g. block downloads).
+    Low,
+    #[default]
+    Normal,
+    /// Latency-critical traffic (e.g. consensus messages).
+    High,
+}
+
+impl Priority {
+    pub(crate) fn to_stream_priority(self) -> i32 {
+        match self {
+            Self::Low => -1,
+            Self::Normal => 0,
+            Self::High => 1,
+        }
+    }
+}
+
+/// Shared response priority of an inbound query.
+///
+/// Applied to the response stream right before the response is sent.
+#[derive(Debug, Clone)]
+pub struct ResponsePriority(Arc<AtomicU8>);
+
+impl Default for ResponsePriority {
+    fn default() -> Self {
+        let this = Self(Default::default());
+        this.set(Priority::default());
+        this
+    }
+}
+
+impl ResponsePriority {
+    pub fn get(&self) -> Priority {
+        match self.0.load(Ordering::Acquire) {
+            0 => Priority::Low,
+            2 => Priority::High,
+            _ => Priority::Normal,
+        }
+    }
+
+    pub fn set(&self, priority: Priority) {
+        let value = match priority {
+            Priority::Low => 0,
+            Priority::Normal => 1,
+            Priority::High => 2,
+        };
+        self.0.store(value, Ordering::Release);
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InboundRequestMeta {
     pub peer_id: PeerId,
@@ -199,3 +260,20 @@ mod serde_body {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn untouched_response_keeps_normal_priority() {
+        let priority = ResponsePriority::default();
+        assert_eq!(priority.get(), Priority::Normal);
+
+        let shared = priority.clone();
+        shared.set(Priority::Low);
+        assert_eq!(priority.get(), Priority::Low);
+        shared.set(Priority::High);
+        assert_eq!(priority.get(), Priority::High);
+    }
+}
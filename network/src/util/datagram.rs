@@ -0,0 +1,273 @@
+use std::time::{Duration, Instant};
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use tycho_util::FastHashMap;
+
+use crate::types::PeerId;
+
+/// Splits messages into chunks which fit into a single datagram.
+///
+/// Each chunk is prefixed with a header: `message_id: u32, index: u16, count: u16` (LE).
+/// Chunks are reassembled on the receiver side with [`DatagramReassembler`].
+pub struct DatagramChunker {
+    max_payload_len: usize,
+    next_message_id: u32,
+}
+
+impl DatagramChunker {
+    pub const HEADER_LEN: usize = 8;
+
+    /// Creates a chunker for datagrams of at most `max_datagram_len` bytes (including header).
+    pub fn new(max_datagram_len: usize) -> Self {
+        assert!(
+            max_datagram_len > Self::HEADER_LEN,
+            "datagram must be larger than the chunk header"
+        );
+
+        Self {
+            max_payload_len: max_datagram_len - Self::HEADER_LEN,
+            next_message_id: rand::random(),
+        }
+    }
+
+    pub fn split(&mut self, message: &[u8]) -> Result<Vec<Bytes>, DatagramChunkError> {
+        let count = std::cmp::max(1, message.len().div_ceil(self.max_payload_len));
+        let Ok(count) = u16::try_from(count) else {
+            return Err(DatagramChunkError::MessageTooLarge);
+        };
+
+        let message_id = self.next_message_id;
+        self.next_message_id = self.next_message_id.wrapping_add(1);
+
+        let mut chunks = Vec::with_capacity(count as usize);
+        for index in 0..count {
+            let offset = index as usize * self.max_payload_len;
+            let end = std::cmp::min(offset + self.max_payload_len, message.len());
+            let payload = &message[offset..end];
+
+            let mut chunk = BytesMut::with_capacity(Self::HEADER_LEN + payload.len());
+            chunk.put_u32_le(message_id);
+            chunk.put_u16_le(index);
+            chunk.put_u16_le(count);
+            chunk.put_slice(payload);
+            chunks.push(chunk.freeze());
+        }
+
+        Ok(chunks)
+    }
+}
+
+/// Collects chunks produced by [`DatagramChunker`] back into messages.
+///
+/// Chunks of the same message may arrive in any order. Incomplete messages
+/// are dropped after the timeout, so lost chunks don't hold memory forever.
+pub struct DatagramReassembler {
+    timeout: Duration,
+    max_pending: usize,
+    max_chunks: u16,
+    pending: FastHashMap<(PeerId, u32), PendingMessage>,
+}
+
+impl DatagramReassembler {
+    /// - `timeout` - time since the first received chunk after which the message is dropped;
+    /// - `max_pending` - maximum number of incomplete messages from all peers;
+    /// - `max_chunks` - maximum number of chunks in a single message.
+    pub fn new(timeout: Duration, max_pending: usize, max_chunks: u16) -> Self {
+        Self {
+            timeout,
+            max_pending,
+            max_chunks,
+            pending: Default::default(),
+        }
+    }
+
+    /// Number of incomplete messages.
+    pub fn pending_len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Returns the full message when the last missing chunk is received.
+    pub fn insert(
+        &mut self,
+        peer_id: &PeerId,
+        mut datagram: Bytes,
+        now: Instant,
+    ) -> Result<Option<Bytes>, DatagramChunkError> {
+        if datagram.len() < DatagramChunker::HEADER_LEN {
+            return Err(DatagramChunkError::InvalidHeader);
+        }
+        let message_id = datagram.get_u32_le();
+        let index = datagram.get_u16_le();
+        let count = datagram.get_u16_le();
+
+        if index >= count {
+            return Err(DatagramChunkError::InvalidHeader);
+        }
+        if count > self.max_chunks {
+            return Err(DatagramChunkError::MessageTooLarge);
+        }
+        if count == 1 {
+            return Ok(Some(datagram));
+        }
+
+        let key = (*peer_id, message_id);
+        if let Some(pending) = self.pending.get(&key) {
+            if pending.is_expired(now, self.timeout) {
+                self.pending.remove(&key);
+            }
+        }
+
+        if !self.pending.contains_key(&key) && self.pending.len() >= self.max_pending {
+            self.remove_expired(now);
+            if self.pending.len() >= self.max_pending {
+                return Err(DatagramChunkError::TooManyPending);
+            }
+        }
+
+        let pending = self.pending.entry(key).or_insert_with(|| PendingMessage {
+            chunks: vec![None; count as usize],
+            received: 0,
+            started_at: now,
+        });
+        if pending.chunks.len() != count as usize {
+            return Err(DatagramChunkError::InvalidHeader);
+        }
+
+        let slot = &mut pending.chunks[index as usize];
+        if slot.is_some() {
+            // Duplicate chunk
+            return Ok(None);
+        }
+        *slot = Some(datagram);
+        pending.received += 1;
+
+        if pending.received < pending.chunks.len() {
+            return Ok(None);
+        }
+
+        let pending = self.pending.remove(&key).expect("checked above");
+        let mut message = BytesMut::new();
+        for chunk in pending.chunks {
+            message.put(chunk.expect("all chunks received"));
+        }
+        Ok(Some(message.freeze()))
+    }
+
+    /// Drops incomplete messages which were not finished in time.
+    ///
+    /// Returns the number of dropped messages.
+    pub fn remove_expired(&mut self, now: Instant) -> usize {
+        let len = self.pending.len();
+        self.pending
+            .retain(|_, pending| !pending.is_expired(now, self.timeout));
+        len - self.pending.len()
+    }
+}
+
+struct PendingMessage {
+    chunks: Vec<Option<Bytes>>,
+    received: usize,
+    started_at: Instant,
+}
+
+impl PendingMessage {
+    fn is_expired(&self, now: Instant, timeout: Duration) -> bool {
+        now.saturating_duration_since(self.started_at) > timeout
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum DatagramChunkError {
+    #[error("invalid chunk header")]
+    InvalidHeader,
+    #[error("message is too large")]
+    MessageTooLarge,
+    #[error("too many pending messages")]
+    TooManyPending,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn insert(
+        reassembler: &mut DatagramReassembler,
+        chunk: &Bytes,
+        now: Instant,
+    ) -> Result<Option<Bytes>, DatagramChunkError> {
+        reassembler.insert(&PeerId([1; 32]), chunk.clone(), now)
+    }
+
+    #[test]
+    fn reassemble_out_of_order() {
+        let message = (0..10_000).map(|i| i as u8).collect::<Vec<_>>();
+
+        let mut chunker = DatagramChunker::new(1200);
+        let mut chunks = chunker.split(&message).unwrap();
+        assert_eq!(chunks.len(), 9);
+        assert!(chunks.iter().all(|chunk| chunk.len() <= 1200));
+
+        let now = Instant::now();
+        let mut reassembler = DatagramReassembler::new(Duration::from_secs(1), 16, 16);
+
+        // Interleave with another message and a duplicate chunk
+        let other = chunker.split(&[42; 3000]).unwrap();
+        assert_eq!(insert(&mut reassembler, &other[1], now), Ok(None));
+
+        chunks.swap(0, 5);
+        chunks.reverse();
+        let last = chunks.pop().unwrap();
+        for chunk in &chunks {
+            assert_eq!(insert(&mut reassembler, chunk, now), Ok(None));
+        }
+        assert_eq!(insert(&mut reassembler, &chunks[0], now), Ok(None));
+        assert_eq!(reassembler.pending_len(), 2);
+
+        let res = insert(&mut reassembler, &last, now).unwrap();
+        assert_eq!(res.as_deref(), Some(message.as_slice()));
+        assert_eq!(reassembler.pending_len(), 1);
+
+        // Single chunk messages are returned as is
+        let single = chunker.split(b"hello").unwrap();
+        assert_eq!(single.len(), 1);
+        let res = insert(&mut reassembler, &single[0], now).unwrap();
+        assert_eq!(res.as_deref(), Some(b"hello".as_slice()));
+    }
+
+    #[test]
+    fn stragglers_time_out() {
+        let timeout = Duration::from_secs(1);
+
+        let mut chunker = DatagramChunker::new(100);
+        let chunks = chunker.split(&[7; 250]).unwrap();
+        assert_eq!(chunks.len(), 3);
+
+        let start = Instant::now();
+        let mut reassembler = DatagramReassembler::new(timeout, 1, 16);
+        assert_eq!(insert(&mut reassembler, &chunks[0], start), Ok(None));
+        assert_eq!(insert(&mut reassembler, &chunks[1], start), Ok(None));
+
+        // Pending limit is reached
+        let other = chunker.split(&[8; 250]).unwrap();
+        assert_eq!(
+            insert(&mut reassembler, &other[0], start),
+            Err(DatagramChunkError::TooManyPending)
+        );
+
+        // Incomplete message is dropped after the timeout
+        let late = start + timeout * 2;
+        assert_eq!(reassembler.remove_expired(late), 1);
+        assert_eq!(reassembler.pending_len(), 0);
+
+        // A straggler starts a new message which can't be completed
+        assert_eq!(insert(&mut reassembler, &chunks[2], late), Ok(None));
+        assert_eq!(reassembler.pending_len(), 1);
+
+        // Too many chunks
+        let chunks = chunker.split(&[9; 2000]).unwrap();
+        assert_eq!(
+            insert(&mut reassembler, &chunks[0], late),
+            Err(DatagramChunkError::MessageTooLarge)
+        );
+    }
+}
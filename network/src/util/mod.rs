@@ -1,12 +1,16 @@
 use bytes::Buf;
 
+pub use self::datagram::{DatagramChunkError, DatagramChunker, DatagramReassembler};
 pub use self::router::{Routable, Router, RouterBuilder};
 #[cfg(test)]
 pub use self::test::make_peer_info_stub;
+pub(crate) use self::token_bucket::TokenBucket;
 pub use self::traits::{NetworkExt, UnknownPeerError};
 use crate::types::PeerId;
 
+mod datagram;
 mod router;
+mod token_bucket;
 mod traits;
 
 #[cfg(test)]
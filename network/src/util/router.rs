@@ -41,6 +41,16 @@ impl<Request, Q> RouterBuilder<Request, Q> {
         self
     }
 
+    /// Sets a service which handles requests with no routed constructor id.
+    pub fn fallback<S>(mut self, service: S) -> Self
+    where
+        S: Service<Request, QueryResponse = Q> + Send + Sync + 'static,
+    {
+        let prev = self.inner.fallback.replace(service.boxed());
+        assert!(prev.is_none(), "duplicate fallback service");
+        self
+    }
+
     pub fn build(self) -> Router<Request, Q> {
         Router {
             inner: Arc::new(self.inner),
@@ -53,6 +63,7 @@ impl<Request, Q> Default for RouterBuilder<Request, Q> {
         Self {
             inner: Inner {
                 services: Vec::new(),
+                fallback: None,
                 query_handlers: FastHashMap::default(),
                 message_handlers: FastHashMap::default(),
                 _response: PhantomData,
@@ -90,37 +101,38 @@ where
     type OnMessageFuture = BoxFutureOrNoop<()>;
 
     fn on_query(&self, req: Request) -> Self::OnQueryFuture {
-        match find_handler(&req, &self.inner.query_handlers, &self.inner.services) {
+        match find_handler(&req, &self.inner.query_handlers, &self.inner) {
             Some(service) => BoxFutureOrNoop::Boxed(service.on_query(req)),
             None => BoxFutureOrNoop::Noop,
         }
     }
 
     fn on_message(&self, req: Request) -> Self::OnMessageFuture {
-        match find_handler(&req, &self.inner.message_handlers, &self.inner.services) {
+        match find_handler(&req, &self.inner.message_handlers, &self.inner) {
             Some(service) => BoxFutureOrNoop::Boxed(service.on_message(req)),
             None => BoxFutureOrNoop::Noop,
         }
     }
 }
 
-fn find_handler<'a, T: AsRef<[u8]>, S>(
+fn find_handler<'a, T: AsRef<[u8]>, Request, Q>(
     req: &T,
     indices: &FastHashMap<u32, usize>,
-    handlers: &'a [S],
-) -> Option<&'a S> {
+    inner: &'a Inner<Request, Q>,
+) -> Option<&'a BoxService<Request, Q>> {
     if let Some(id) = read_le_u32(req.as_ref()) {
         if let Some(&index) = indices.get(&id) {
             // NOTE: intentionally panics if index is out of bounds as it is
             // an implementation error.
-            return Some(handlers.get(index).expect("index must be in bounds"));
+            return Some(inner.services.get(index).expect("index must be in bounds"));
         }
     }
-    None
+    inner.fallback.as_ref()
 }
 
 struct Inner<Request, Q> {
     services: Vec<BoxService<Request, Q>>,
+    fallback: Option<BoxService<Request, Q>>,
     query_handlers: FastHashMap<u32, usize>,
     message_handlers: FastHashMap<u32, usize>,
     _response: PhantomData<Q>,
@@ -135,3 +147,46 @@ fn read_le_u32(buf: &[u8]) -> Option<u32> {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use futures_util::future::{ready, Ready};
+
+    use super::*;
+
+    struct Echo(u32);
+
+    impl Service<Vec<u8>> for Echo {
+        type QueryResponse = u32;
+        type OnQueryFuture = Ready<Option<u32>>;
+        type OnMessageFuture = Ready<()>;
+
+        fn on_query(&self, _: Vec<u8>) -> Self::OnQueryFuture {
+            ready(Some(self.0))
+        }
+
+        fn on_message(&self, _: Vec<u8>) -> Self::OnMessageFuture {
+            ready(())
+        }
+    }
+
+    impl Routable for Echo {
+        fn query_ids(&self) -> impl IntoIterator<Item = u32> {
+            [1]
+        }
+    }
+
+    #[tokio::test]
+    async fn fallback_handles_unrouted_queries() {
+        let query = |id: u32| id.to_le_bytes().to_vec();
+
+        let router = Router::builder().route(Echo(10)).build();
+        assert_eq!(router.on_query(query(1)).await, Some(10));
+        assert_eq!(router.on_query(query(2)).await, None);
+
+        let router = Router::builder().route(Echo(10)).fallback(Echo(20)).build();
+        assert_eq!(router.on_query(query(1)).await, Some(10));
+        assert_eq!(router.on_query(query(2)).await, Some(20));
+        assert_eq!(router.on_query(vec![1]).await, Some(20));
+    }
+}
@@ -0,0 +1,37 @@
+use std::time::{Duration, Instant};
+
+/// A token bucket which is refilled by one token every `refill_interval`.
+pub(crate) struct TokenBucket {
+    tokens: u32,
+    updated_at: Instant,
+}
+
+impl TokenBucket {
+    pub fn new(burst: u32, now: Instant) -> Self {
+        Self {
+            tokens: burst,
+            updated_at: now,
+        }
+    }
+
+    pub fn try_acquire(&mut self, burst: u32, refill_interval: Duration, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(self.updated_at);
+        let refilled = elapsed.as_nanos() / refill_interval.as_nanos();
+        if refilled > 0 {
+            let refilled = refilled.min(burst as u128) as u32;
+            self.tokens = self.tokens.saturating_add(refilled).min(burst);
+            self.updated_at = match self.tokens == burst {
+                true => now,
+                false => self.updated_at + refill_interval * refilled,
+            };
+        }
+
+        match self.tokens.checked_sub(1) {
+            Some(tokens) => {
+                self.tokens = tokens;
+                true
+            }
+            None => false,
+        }
+    }
+}
@@ -3,7 +3,7 @@ use std::future::Future;
 use anyhow::Result;
 
 use crate::network::{Network, Peer};
-use crate::types::{PeerId, Request, Response};
+use crate::types::{PeerId, Priority, Request, Response};
 
 pub trait NetworkExt {
     fn query(
@@ -13,15 +13,56 @@ pub trait NetworkExt {
     ) -> impl Future<Output = Result<Response>> + Send;
 
     fn send(&self, peer_id: &PeerId, request: Request) -> impl Future<Output = Result<()>> + Send;
+
+    fn query_with_priority(
+        &self,
+        peer_id: &PeerId,
+        request: Request,
+        priority: Priority,
+    ) -> impl Future<Output = Result<Response>> + Send;
+
+    fn send_with_priority(
+        &self,
+        peer_id: &PeerId,
+        request: Request,
+        priority: Priority,
+    ) -> impl Future<Output = Result<()>> + Send;
 }
 
 impl NetworkExt for Network {
     async fn query(&self, peer_id: &PeerId, request: Request) -> Result<Response> {
-        on_connected_peer(self, Peer::rpc, peer_id, request).await
+        self.query_with_priority(peer_id, request, Priority::Normal)
+            .await
     }
 
     async fn send(&self, peer_id: &PeerId, request: Request) -> Result<()> {
-        on_connected_peer(self, Peer::send_message, peer_id, request).await
+        self.send_with_priority(peer_id, request, Priority::Normal)
+            .await
+    }
+
+    async fn query_with_priority(
+        &self,
+        peer_id: &PeerId,
+        request: Request,
+        priority: Priority,
+    ) -> Result<Response> {
+        on_connected_peer(self, Peer::rpc_with_priority, peer_id, request, priority).await
+    }
+
+    async fn send_with_priority(
+        &self,
+        peer_id: &PeerId,
+        request: Request,
+        priority: Priority,
+    ) -> Result<()> {
+        on_connected_peer(
+            self,
+            Peer::send_message_with_priority,
+            peer_id,
+            request,
+            priority,
+        )
+        .await
     }
 }
 
@@ -30,6 +71,7 @@ async fn on_connected_peer<T, F>(
     f: F,
     peer_id: &PeerId,
     request: Request,
+    priority: Priority,
 ) -> Result<T>
 where
     for<'a> F: PeerTask<'a, T>,
@@ -43,40 +85,31 @@ where
         // Try to connect
         match network.known_peers().get(peer_id) {
             // Initiate a connection of it is a known peer
-            Some(peer_info) => {
-                // TODO: try multiple addresses
-                let address = peer_info
-                    .iter_addresses()
-                    .next()
-                    .cloned()
-                    .expect("address list must have at least one item");
-
-                network.connect(address, peer_id).await?
-            }
+            Some(peer_info) => network.connect_to_peer_info(&peer_info).await?,
             // Error otherwise
             None => anyhow::bail!(UnknownPeerError { peer_id: *peer_id }),
         }
     };
 
-    f.call(&peer, request).await
+    f.call(&peer, request, priority).await
 }
 
 trait PeerTask<'a, T> {
     type Output: Future<Output = Result<T>> + 'a;
 
-    fn call(self, peer: &'a Peer, request: Request) -> Self::Output;
+    fn call(self, peer: &'a Peer, request: Request, priority: Priority) -> Self::Output;
 }
 
 impl<'a, T, F, Fut> PeerTask<'a, T> for F
 where
-    F: FnOnce(&'a Peer, Request) -> Fut,
+    F: FnOnce(&'a Peer, Request, Priority) -> Fut,
     Fut: Future<Output = Result<T>> + 'a,
 {
     type Output = Fut;
 
     #[inline]
-    fn call(self, peer: &'a Peer, request: Request) -> Fut {
-        self(peer, request)
+    fn call(self, peer: &'a Peer, request: Request, priority: Priority) -> Fut {
+        self(peer, request, priority)
     }
 }
 
@@ -10,9 +10,11 @@ use std::time::Duration;
 
 use anyhow::Result;
 use everscale_crypto::ed25519;
+use futures_util::StreamExt;
 use tl_proto::{TlRead, TlWrite};
 use tycho_network::{
-    proto, DhtClient, DhtConfig, DhtService, FindValueError, Network, PeerInfo, Router,
+    proto, DhtClient, DhtConfig, DhtService, FindValueError, Network, PeerInfo, PeerResolveError,
+    RepublishError, Router,
 };
 use tycho_util::time::now_sec;
 
@@ -132,6 +134,130 @@ async fn bootstrap_nodes_store_value() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn namespaced_values_do_not_collide() -> Result<()> {
+    tycho_util::test::init_logger("namespaced_values_do_not_collide", "debug");
+
+    #[derive(Debug, Clone, PartialEq, Eq, TlWrite, TlRead)]
+    struct SomeValue(u32);
+
+    let (nodes, _) = make_network(5, false);
+
+    let first = &nodes[0].dht;
+    let scoped = first.with_namespace(1);
+    let peer_id = first.network().peer_id();
+
+    // Store values with the same name and owner in different namespaces
+    first
+        .entry(proto::dht::PeerValueKeyName::NodeInfo)
+        .with_data(SomeValue(1))
+        .store()
+        .await?;
+    scoped
+        .entry(proto::dht::PeerValueKeyName::NodeInfo)
+        .with_data(SomeValue(2))
+        .store()
+        .await?;
+
+    // Each namespace sees only its own value
+    let second = &nodes[1].dht;
+    let value = second
+        .entry(proto::dht::PeerValueKeyName::NodeInfo)
+        .find_value::<SomeValue>(peer_id)
+        .await?;
+    assert_eq!(value, SomeValue(1));
+
+    let value = second
+        .with_namespace(1)
+        .entry(proto::dht::PeerValueKeyName::NodeInfo)
+        .find_value::<SomeValue>(peer_id)
+        .await?;
+    assert_eq!(value, SomeValue(2));
+
+    let res = second
+        .with_namespace(2)
+        .entry(proto::dht::PeerValueKeyName::NodeInfo)
+        .find_peer_value_raw(peer_id)
+        .await;
+    assert!(matches!(res, Err(FindValueError::NotFound)));
+
+    // Keys in the default namespace keep the old representation
+    let key = |namespace| proto::dht::PeerValueKeyRef {
+        namespace,
+        name: proto::dht::PeerValueKeyName::NodeInfo,
+        peer_id,
+    };
+    let default_key = tl_proto::serialize(key(proto::dht::DEFAULT_NAMESPACE));
+    assert_eq!(default_key.len(), 4 + 4 + 32);
+    assert_eq!(
+        tl_proto::deserialize::<proto::dht::PeerValueKeyRef<'_>>(&default_key)?,
+        key(proto::dht::DEFAULT_NAMESPACE)
+    );
+
+    let scoped_key = tl_proto::serialize(key(1));
+    assert_eq!(scoped_key.len(), 4 + 4 + 4 + 32);
+    assert_eq!(
+        tl_proto::deserialize::<proto::dht::PeerValueKeyRef<'_>>(&scoped_key)?,
+        key(1)
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn republished_value_is_refreshed() -> Result<()> {
+    tycho_util::test::init_logger("republished_value_is_refreshed", "debug");
+
+    #[derive(Debug, Clone, PartialEq, Eq, TlWrite, TlRead)]
+    struct SomeValue(u32);
+
+    let (nodes, _) = make_network(5, false);
+
+    let first = &nodes[0].dht;
+    let second = &nodes[1].dht;
+    let peer_id = first.network().peer_id();
+
+    let mut entry = first
+        .entry(proto::dht::PeerValueKeyName::NodeInfo)
+        .with_data(SomeValue(123));
+    entry.with_ttl(60);
+
+    // Intervals which would let the value expire are rejected
+    assert!(matches!(
+        entry.republish(Duration::ZERO),
+        Err(RepublishError::InvalidInterval)
+    ));
+    assert!(matches!(
+        entry.republish(Duration::from_secs(60)),
+        Err(RepublishError::InvalidInterval)
+    ));
+
+    let expires_at = || async {
+        second
+            .entry(proto::dht::PeerValueKeyName::NodeInfo)
+            .find_peer_value_raw(peer_id)
+            .await
+            .map(|value| value.expires_at)
+    };
+
+    // The value is stored again with a fresh expiration time
+    let guard = entry.republish(Duration::from_millis(200))?;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let initial = expires_at().await?;
+    tokio::time::sleep(Duration::from_millis(2100)).await;
+    let refreshed = expires_at().await?;
+    assert!(refreshed > initial);
+
+    // Nothing is republished after the guard is dropped
+    drop(guard);
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    let last = expires_at().await?;
+    tokio::time::sleep(Duration::from_millis(2100)).await;
+    assert_eq!(expires_at().await?, last);
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn connect_new_node_to_bootstrap() -> Result<()> {
     tycho_util::test::init_logger("connect_new_node_to_bootstrap", "debug");
@@ -179,6 +305,51 @@ async fn connect_new_node_to_bootstrap() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn resolve_many_peers() -> Result<()> {
+    tycho_util::test::init_logger("resolve_many_peers", "debug");
+
+    let (bootstrap_nodes, global_config) = make_network(3, false);
+
+    // A node which is known only through its DHT value
+    let node = Node::with_random_key(false);
+    for peer_info in &global_config {
+        node.dht.add_peer(peer_info.clone())?;
+    }
+    node.dht
+        .entry(proto::dht::PeerValueKeyName::NodeInfo)
+        .with_data(node.network.sign_peer_info(now_sec(), 3600))
+        .store()
+        .await?;
+
+    let first = &bootstrap_nodes[0];
+    let resolver = first
+        .dht
+        .service()
+        .make_peer_resolver()
+        .build(&first.network);
+
+    let node_id = *node.network.peer_id();
+    let known_id = *bootstrap_nodes[1].network.peer_id();
+    let unknown_id = rand::random();
+
+    let results = resolver
+        .resolve_many(&[node_id, known_id, node_id, unknown_id])
+        .collect::<BTreeMap<_, _>>()
+        .await;
+
+    // Duplicates are resolved once
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[&node_id].as_ref().unwrap().id, node_id);
+    assert_eq!(results[&known_id].as_ref().unwrap().id, known_id);
+    assert!(matches!(
+        results[&unknown_id],
+        Err(PeerResolveError::NotResolved(FindValueError::NotFound))
+    ));
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
 async fn startup_from_single_bootstrap_node() -> Result<()> {
     tycho_util::test::init_logger("startup_from_single_bootstrap_node", "debug");
@@ -1,4 +1,5 @@
 use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -6,8 +7,8 @@ use anyhow::Result;
 use futures_util::future::{ready, Ready};
 use tokio::time::sleep;
 use tycho_network::{
-    Connection, Network, NetworkConfig, NetworkExt, Request, Response, Routable, Router, Service,
-    ServiceRequest,
+    service_query_fn, Connection, DisconnectReason, InboundRateLimit, Network, NetworkConfig,
+    NetworkExt, PeerEventData, Request, Response, Routable, Router, Service, ServiceRequest,
 };
 use tycho_util::test::init_logger;
 
@@ -204,3 +205,138 @@ async fn test_inbound_request_rate_limit() -> Result<()> {
     tracing::info!("Test finished successfully.");
     Ok(())
 }
+
+#[tokio::test]
+async fn oversized_request_is_rejected() -> Result<()> {
+    init_logger("oversized_request_is_rejected", "debug");
+
+    let handled = Arc::new(AtomicUsize::new(0));
+    let service = service_query_fn({
+        let handled = handled.clone();
+        move |req: ServiceRequest| {
+            handled.fetch_add(1, Ordering::Relaxed);
+            ready(Some(Response {
+                version: Default::default(),
+                body: req.body,
+            }))
+        }
+    });
+
+    let mut config = NetworkConfig::default();
+    config.max_request_size = bytesize::ByteSize::kib(1);
+
+    let receiver_node = Network::builder()
+        .with_random_private_key()
+        .with_config(config)
+        .build((std::net::Ipv4Addr::LOCALHOST, 0), service.clone())?;
+    // Sender never receives queries, so sharing the counter is fine
+    let sender_node = Network::builder()
+        .with_random_private_key()
+        .build((std::net::Ipv4Addr::LOCALHOST, 0), service)?;
+
+    let receiver_peer_info = Arc::new(receiver_node.sign_peer_info(0, u32::MAX));
+    sender_node
+        .known_peers()
+        .insert(receiver_peer_info, false)?;
+
+    let make_request = |len: usize| Request {
+        version: Default::default(),
+        body: vec![0xaa; len].into(),
+    };
+
+    let err = sender_node
+        .query(receiver_node.peer_id(), make_request(4096))
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains(&Connection::REQUEST_TOO_LARGE_ERROR_CODE.to_string()),
+        "unexpected error: {err:?}"
+    );
+    assert_eq!(handled.load(Ordering::Relaxed), 0);
+
+    // Requests within the limit are still served over the same connection
+    let response = sender_node
+        .query(receiver_node.peer_id(), make_request(512))
+        .await?;
+    assert_eq!(response.body.len(), 512);
+    assert_eq!(handled.load(Ordering::Relaxed), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn peer_exceeding_rate_limit_is_disconnected() -> Result<()> {
+    init_logger("peer_exceeding_rate_limit_is_disconnected", "debug");
+
+    let service = service_query_fn(|req: ServiceRequest| {
+        ready(Some(Response {
+            version: Default::default(),
+            body: req.body,
+        }))
+    });
+
+    let mut config = NetworkConfig::default();
+    config.inbound_rate_limit = Some(InboundRateLimit {
+        burst: 2,
+        refill_interval: Duration::from_secs(3600),
+        disconnect_after: Some(2),
+        reconnect_cooldown: Duration::from_secs(3600),
+    });
+
+    let receiver_node = Network::builder()
+        .with_random_private_key()
+        .with_config(config)
+        .build((std::net::Ipv4Addr::LOCALHOST, 0), service.clone())?;
+    let sender_node = Network::builder()
+        .with_random_private_key()
+        .build((std::net::Ipv4Addr::LOCALHOST, 0), service)?;
+
+    let receiver_peer_info = Arc::new(receiver_node.sign_peer_info(0, u32::MAX));
+    sender_node
+        .known_peers()
+        .insert(receiver_peer_info, false)?;
+
+    let mut events = receiver_node.subscribe();
+    let query = |value: u8| {
+        let request = Request {
+            version: Default::default(),
+            body: vec![value].into(),
+        };
+        sender_node.query(receiver_node.peer_id(), request)
+    };
+
+    // Burst is served
+    for value in 0..2 {
+        assert_eq!(query(value).await?.body.as_ref(), [value]);
+    }
+
+    // Requests above the limit are rejected
+    for value in 2..4 {
+        let err = query(value).await.unwrap_err();
+        assert!(
+            err.to_string()
+                .contains(&Connection::RATE_LIMITED_ERROR_CODE.to_string()),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    // Too many rejected requests in a row close the connection
+    let reason = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            let event = events.recv().await.unwrap();
+            if let PeerEventData::Lost(reason) = event.data {
+                assert_eq!(&event.peer_id, sender_node.peer_id());
+                break reason;
+            }
+        }
+    })
+    .await?;
+    assert_eq!(reason, DisconnectReason::RateLimited);
+
+    // The peer can't reconnect while its cooldown is active
+    assert!(query(4).await.is_err());
+    assert!(receiver_node.peer(sender_node.peer_id()).is_none());
+
+    Ok(())
+}
@@ -320,8 +320,9 @@ impl RpcState {
         let blocks = self.inner.storage.block_storage();
         let handles = self.inner.storage.block_handle_storage();
 
-        let handle = handles.load_key_block_handle(key_block_seqno)?;
-        let data = blocks.load_block_proof_raw(&handle).await.ok()?;
+        let snapshot = self.inner.storage.snapshot();
+        let handle = handles.load_key_block_handle_at(&snapshot, key_block_seqno)?;
+        let data = blocks.load_block_proof_raw_at(&snapshot, &handle).ok()?;
         Some((*handle.id(), data))
     }
 
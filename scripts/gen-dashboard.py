@@ -632,6 +632,18 @@ def net_request_handler() -> RowPanel:
             "tycho_net_in_requests_rejected_total",
             "Number of rejected incoming messages over time",
         ),
+        create_counter_panel(
+            "tycho_net_in_requests_too_large_total",
+            "Number of incoming requests rejected by size",
+        ),
+        create_counter_panel(
+            "tycho_net_in_requests_rate_limited_total",
+            "Number of incoming requests rejected by rate limit",
+        ),
+        create_counter_panel(
+            "tycho_net_in_requests_rate_limited_per_peer",
+            "Number of incoming requests rejected by rate limit per peer",
+        ),
         create_gauge_panel(
             "tycho_net_req_handlers", "Current number of incoming request handlers"
         ),
@@ -892,6 +904,21 @@ def storage() -> RowPanel:
         create_heatmap_panel(
             "tycho_storage_commit_archive_time", "Time to commit archive"
         ),
+        create_gauge_panel(
+            "tycho_storage_active_archive_writers", "Active archive writers"
+        ),
+        create_counter_panel(
+            [
+                "tycho_storage_block_handle_cache_hits",
+                "tycho_storage_block_handle_cache_misses",
+            ],
+            "Block handle cache lookups",
+        ),
+        create_gauge_panel(
+            "tycho_storage_block_handle_cache_hit_ratio",
+            "Block handle cache hit ratio",
+            UNITS.PERCENT_UNIT,
+        ),
         create_heatmap_panel(
             "tycho_storage_split_block_data_time", "Time to split block data"
         ),
@@ -1194,6 +1221,15 @@ def collation_metrics() -> RowPanel:
             "Number of anchor import skipped",
             labels_selectors=['workchain=~"$workchain"'],
         ),
+        create_counter_panel(
+            "tycho_collator_empty_collation_attempts_count",
+            "Number of shard collation attempts without progress",
+            labels_selectors=['workchain=~"$workchain"'],
+        ),
+        create_counter_panel(
+            "tycho_collator_anchor_chain_time_regression_count",
+            "Number of imported anchors with regressed chain time",
+        ),
         create_gauge_panel(
             "tycho_do_collate_block_diff_tail_len",
             "Diff tail length",
@@ -1870,6 +1906,13 @@ def mempool_rounds() -> RowPanel:
             "tycho_mempool_last_anchor_round",
             "Adapter: last anchor round",
         ),
+        create_counter_panel(
+            [
+                "tycho_mempool_adapter_repeated_anchors",
+                "tycho_mempool_adapter_anchor_gaps",
+            ],
+            "Adapter: out of order anchors",
+        ),
         create_gauge_panel(
             "tycho_mempool_consensus_current_round",
             "Consensus: round determined by Broadcast Filter",
@@ -1882,6 +1925,10 @@ def mempool_rounds() -> RowPanel:
             "tycho_mempool_rounds_dag_length",
             "DAG length in memory",
         ),
+        create_gauge_panel(
+            "tycho_mempool_rounds_dag_front_length",
+            "DAG front length in memory",
+        ),
         create_gauge_panel(
             "tycho_mempool_rounds_consensus_ahead_committed",
             "Consensus ahead of committed: commit latency",
@@ -1936,6 +1983,10 @@ def mempool_payload_rates() -> RowPanel:
             "Adapter: removed duplicate externals size",
             unit_format=UNITS.BYTES_IEC,
         ),
+        create_counter_panel(
+            "tycho_mempool_externals_dropped_count",
+            "Adapter: dropped externals count (collator lags)",
+        ),
         create_counter_panel(
             "tycho_mempool_point_payload_count",
             "Engine: points payload count",
@@ -2003,6 +2054,16 @@ def mempool_engine_rates() -> RowPanel:
             "tycho_mempool_collected_includes_count",
             "Round task: collected includes to produce point",
         ),
+        create_heatmap_panel(
+            "tycho_mempool_point_includes_peers",
+            "Points: includes count",
+            yaxis(UNITS.NUMBER_FORMAT),
+        ),
+        create_heatmap_panel(
+            "tycho_mempool_point_witness_peers",
+            "Points: witness count",
+            yaxis(UNITS.NUMBER_FORMAT),
+        ),
         create_counter_panel(
             "tycho_mempool_signing_prev_round_count",
             "Signer: previous round broadcasts signed",
@@ -2059,6 +2120,15 @@ def mempool_engine() -> RowPanel:
             "Engine: points to produce skipped (total at moment)",
             legend_format="{{instance}} - {{kind}}",
         ),
+        create_counter_panel(
+            expr_sum_increase(
+                "tycho_mempool_engine_produce_failed",
+                range_selector="$__interval",
+                by_labels=["kind", "instance"],
+            ),
+            "Engine: points to produce failed (total at moment)",
+            legend_format="{{instance}} - {{kind}}",
+        ),
         create_heatmap_panel(
             "tycho_mempool_engine_commit_time",
             "Engine: commit duration",
@@ -2084,6 +2154,10 @@ def mempool_engine() -> RowPanel:
             "tycho_mempool_verifier_verify_time",
             "Verifier: verify() point structure and author's sig",
         ),
+        create_heatmap_panel(
+            "tycho_mempool_verifier_signature_time",
+            "Verifier: verify() author's and evidence sigs (skipped on trusted replay)",
+        ),
         create_heatmap_panel(
             "tycho_mempool_verifier_validate_time",
             "Verifier: validate() point dependencies in DAG and all-1 sigs",
@@ -2151,6 +2225,24 @@ def mempool_intercom() -> RowPanel:
             "tycho_mempool_download_query_dispatcher_time",
             "Dispatcher: Download request",
         ),
+        create_counter_panel(
+            expr_sum_increase(
+                "tycho_mempool_dispatcher_queries_count",
+                range_selector="$__interval",
+                by_labels=["kind", "instance"],
+            ),
+            "Dispatcher: queries by kind (total at moment)",
+            legend_format="{{instance}} - {{kind}}",
+        ),
+        create_counter_panel(
+            expr_sum_increase(
+                "tycho_mempool_dispatcher_query_errors_count",
+                range_selector="$__interval",
+                by_labels=["kind", "instance"],
+            ),
+            "Dispatcher: query errors by kind (total at moment)",
+            legend_format="{{instance}} - {{kind}}",
+        ),
         create_heatmap_panel(
             "tycho_mempool_signature_query_responder_data_time",
             "Responder: Signature send: send ready or sign or reject",
@@ -2209,6 +2301,13 @@ def mempool_intercom() -> RowPanel:
             ),
             "Downloader: queries network error (total at moment)",
         ),
+        create_counter_panel(
+            expr_sum_increase(
+                "tycho_mempool_download_query_timeout_count",
+                range_selector="$__interval",
+            ),
+            "Downloader: queries timed out (total at moment)",
+        ),
         create_counter_panel(
             expr_sum_increase(
                 "tycho_mempool_download_unreliable_responses",
@@ -2216,6 +2315,15 @@ def mempool_intercom() -> RowPanel:
             ),
             "Downloader: unreliable responses (total at moment)",
         ),
+        create_counter_panel(
+            expr_sum_increase(
+                "tycho_mempool_ban_events",
+                range_selector="$__interval",
+                by_labels=["reason", "instance"],
+            ),
+            "Downloader: ban events (total at moment)",
+            legend_format="{{instance}} - {{reason}}",
+        ),
         create_counter_panel(
             expr_sum_increase(
                 "tycho_mempool_signatures_unreliable_count",
@@ -38,6 +38,11 @@ pub struct StorageConfig {
     /// Default: 100.
     pub split_block_tasks: usize,
 
+    /// Number of archives which can be committed concurrently.
+    ///
+    /// Default: 2.
+    pub archive_writer_tasks: usize,
+
     /// Archives storage config.
     ///
     /// Archives are disabled if this field is `None`.
@@ -55,6 +60,16 @@ pub struct StorageConfig {
 
     /// Blocks cache config.
     pub blocks_cache: BlocksCacheConfig,
+
+    /// Compression of block data and archives column families.
+    ///
+    /// Default: `none` (data is already compressed).
+    pub block_data_compression: CompressionConfig,
+
+    /// `RocksDB` write-ahead log settings for all databases.
+    ///
+    /// Default: WAL is written on each write and synced by the OS.
+    pub wal: WalConfig,
 }
 
 impl StorageConfig {
@@ -67,10 +82,13 @@ impl StorageConfig {
             archive_chunk_size: ByteSize::kb(1024),
             rocksdb_enable_metrics: false,
             split_block_tasks: 100,
+            archive_writer_tasks: 2,
             archives_gc: None,
             states_gc: None,
             blocks_gc: None,
             blocks_cache: BlocksCacheConfig::default(),
+            block_data_compression: CompressionConfig::default(),
+            wal: WalConfig::default(),
         }
     }
 }
@@ -119,12 +137,15 @@ impl Default for StorageConfig {
             cells_cache_size,
             rocksdb_lru_capacity,
             split_block_tasks: 100,
+            archive_writer_tasks: 2,
             rocksdb_enable_metrics: true,
             archive_chunk_size: ByteSize::kb(1024),
             archives_gc: Some(ArchivesGcConfig::default()),
             states_gc: Some(StatesGcConfig::default()),
             blocks_gc: Some(BlocksGcConfig::default()),
             blocks_cache: BlocksCacheConfig::default(),
+            block_data_compression: CompressionConfig::default(),
+            wal: WalConfig::default(),
         }
     }
 }
@@ -238,3 +259,93 @@ impl Default for BlocksCacheConfig {
         }
     }
 }
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CompressionConfig {
+    #[default]
+    None,
+    Lz4,
+    Zstd {
+        /// Default: 3.
+        #[serde(default = "default_zstd_level")]
+        level: i32,
+    },
+}
+
+impl CompressionConfig {
+    /// `RocksDB` name of the compression type.
+    pub fn rocksdb_name(&self) -> &'static str {
+        match self {
+            Self::None => "kNoCompression",
+            Self::Lz4 => "kLZ4Compression",
+            Self::Zstd { .. } => "kZSTD",
+        }
+    }
+}
+
+fn default_zstd_level() -> i32 {
+    3
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WalConfig {
+    /// When WAL records are written and synced to disk.
+    ///
+    /// Default: `default`.
+    pub mode: WalMode,
+
+    /// Whether to use `fsync` instead of `fdatasync` when syncing files.
+    /// Slower, but safer for filesystems which don't persist metadata with `fdatasync`.
+    ///
+    /// Default: `false`.
+    pub use_fsync: bool,
+}
+
+/// Durability of writes.
+///
+/// NOTE: Nodes recover from the network after losing recent writes,
+/// but it may take a while, so a validator should stay with the default mode.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WalMode {
+    /// Each write is appended to the WAL immediately and is synced to disk by the OS.
+    ///
+    /// Nothing is lost on a process crash. Recent writes may be lost
+    /// on a power loss or an OS crash.
+    #[default]
+    Default,
+    /// Same as [`WalMode::Default`], but the WAL is also synced to disk periodically.
+    ///
+    /// Limits the writes lost on a power loss or an OS crash to the last `interval`.
+    Synced {
+        /// Default: 1 second.
+        #[serde(with = "serde_helpers::humantime", default = "default_wal_interval")]
+        interval: Duration,
+    },
+    /// WAL records are buffered in memory and written to the WAL periodically.
+    /// Gives higher write throughput.
+    ///
+    /// Writes made during the last `interval` are lost even on a process crash.
+    Buffered {
+        /// Default: 1 second.
+        #[serde(with = "serde_helpers::humantime", default = "default_wal_interval")]
+        interval: Duration,
+    },
+}
+
+impl WalMode {
+    /// Interval of the background WAL flush and whether to sync the WAL on it.
+    pub fn flush_interval(&self) -> Option<(Duration, bool)> {
+        match self {
+            Self::Default => None,
+            Self::Synced { interval } => Some((*interval, true)),
+            Self::Buffered { interval } => Some((*interval, false)),
+        }
+    }
+}
+
+fn default_wal_interval() -> Duration {
+    Duration::from_secs(1)
+}
@@ -0,0 +1,191 @@
+use std::ops::{Bound, RangeBounds};
+use std::time::Instant;
+
+use anyhow::{Context, Result};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use weedb::rocksdb::{CompactOptions, Options, DB};
+
+use crate::db::BaseDb;
+
+/// Manual compaction progress in column families.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CompactionProgress {
+    pub compacted: usize,
+    pub total: usize,
+}
+
+impl CompactionProgress {
+    pub fn is_finished(&self) -> bool {
+        self.compacted >= self.total
+    }
+}
+
+/// Handle of a manual compaction running in background.
+pub struct CompactionHandle {
+    progress: watch::Receiver<CompactionProgress>,
+    task: JoinHandle<Result<()>>,
+}
+
+impl CompactionHandle {
+    pub fn progress(&self) -> CompactionProgress {
+        *self.progress.borrow()
+    }
+
+    pub fn subscribe(&self) -> watch::Receiver<CompactionProgress> {
+        self.progress.clone()
+    }
+
+    /// Waits until all column families are compacted.
+    pub async fn wait(self) -> Result<()> {
+        self.task.await.context("compaction task panicked")?
+    }
+}
+
+pub(crate) struct CompactionRange {
+    pub cf: String,
+    pub from: Option<Vec<u8>>,
+    pub to: Option<Vec<u8>>,
+}
+
+impl CompactionRange {
+    /// NOTE: RocksDB compacts whole files, so excluded bounds are compacted as included.
+    pub fn new<'a, R: RangeBounds<&'a [u8]>>(cf: &str, range: R) -> Self {
+        fn to_key(bound: Bound<&&[u8]>) -> Option<Vec<u8>> {
+            match bound {
+                Bound::Included(key) | Bound::Excluded(key) => Some(key.to_vec()),
+                Bound::Unbounded => None,
+            }
+        }
+
+        Self {
+            cf: cf.to_owned(),
+            from: to_key(range.start_bound()),
+            to: to_key(range.end_bound()),
+        }
+    }
+
+    pub fn full(cf: &str) -> Self {
+        Self::new(cf, ..)
+    }
+
+    /// Full ranges of all column families which exist in the database.
+    pub fn all(db: &BaseDb) -> Result<Vec<Self>> {
+        let names = DB::list_cf(&Options::default(), db.rocksdb().path())?;
+        Ok(names.iter().map(|cf| Self::full(cf)).collect())
+    }
+}
+
+pub(crate) fn spawn_compaction(
+    db: BaseDb,
+    ranges: Vec<CompactionRange>,
+) -> Result<CompactionHandle> {
+    for range in &ranges {
+        anyhow::ensure!(
+            db.rocksdb().cf_handle(&range.cf).is_some(),
+            "unknown column family: {}",
+            range.cf
+        );
+    }
+
+    let (progress_tx, progress) = watch::channel(CompactionProgress {
+        compacted: 0,
+        total: ranges.len(),
+    });
+
+    let span = tracing::Span::current();
+    let task = tokio::task::spawn_blocking(move || {
+        let _span = span.enter();
+
+        // NOTE: manual compaction must not stop automatic ones
+        let mut opts = CompactOptions::default();
+        opts.set_exclusive_manual_compaction(false);
+
+        let started_at = Instant::now();
+        for range in ranges {
+            let cf = db
+                .rocksdb()
+                .cf_handle(&range.cf)
+                .with_context(|| format!("column family {} was dropped", range.cf))?;
+
+            let cf_started_at = Instant::now();
+            db.rocksdb()
+                .compact_range_cf_opt(&cf, range.from, range.to, &opts);
+            tracing::info!(
+                cf = range.cf,
+                elapsed = %humantime::format_duration(cf_started_at.elapsed()),
+                "compacted column family"
+            );
+
+            progress_tx.send_modify(|progress| progress.compacted += 1);
+        }
+
+        tracing::info!(
+            elapsed = %humantime::format_duration(started_at.elapsed()),
+            "manual compaction finished"
+        );
+        Ok(())
+    });
+
+    Ok(CompactionHandle { progress, task })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tables, Storage};
+
+    fn sst_files_size(db: &BaseDb, cf: &str) -> Result<u64> {
+        let cf = db.rocksdb().cf_handle(cf).context("no cf")?;
+        let size = db
+            .rocksdb()
+            .property_int_value_cf(&cf, "rocksdb.total-sst-files-size")?;
+        Ok(size.unwrap_or_default())
+    }
+
+    #[tokio::test]
+    async fn compaction_removes_tombstones() -> Result<()> {
+        let (storage, _tmp_dir) = Storage::new_temp().await?;
+        let db = storage.base_db();
+
+        let cf_name = <tables::FullBlockIds as weedb::ColumnFamily>::NAME;
+        let cf = &db.full_block_ids;
+        let value = [0xaa; 32];
+        for i in 0..10_000u32 {
+            cf.insert(i.to_be_bytes(), value)?;
+        }
+        db.rocksdb().flush_cf(&cf.cf())?;
+        for i in 0..10_000u32 {
+            cf.remove(i.to_be_bytes())?;
+        }
+        db.rocksdb().flush_cf(&cf.cf())?;
+
+        let size_before = sst_files_size(db, cf_name)?;
+        assert!(size_before > 0);
+
+        let handle = storage.compact_range(cf_name, ..)?;
+        let mut progress = handle.subscribe();
+        handle.wait().await?;
+        assert!(progress.borrow_and_update().is_finished());
+
+        let size_after = sst_files_size(db, cf_name)?;
+        assert!(
+            size_after < size_before,
+            "size before: {size_before}, after: {size_after}"
+        );
+
+        assert!(storage.compact_range("unknown", ..).is_err());
+
+        let handle = storage.compact_all()?;
+        let total = handle.progress().total;
+        assert!(total > 1);
+        let mut progress = handle.subscribe();
+        handle.wait().await?;
+        assert_eq!(*progress.borrow_and_update(), CompactionProgress {
+            compacted: total,
+            total,
+        });
+
+        Ok(())
+    }
+}
@@ -6,6 +6,8 @@ use weedb::{
     Caches, MigrationError, Semver, Tables, VersionProvider, WeeDb, WeeDbBuilder, WeeDbRaw,
 };
 
+use crate::config::CompressionConfig;
+
 pub mod refcount;
 pub mod tables;
 
@@ -70,6 +72,9 @@ pub type BaseDb = WeeDb<BaseTables>;
 
 pub trait BaseDbExt {
     fn normalize_version(&self) -> anyhow::Result<()>;
+
+    /// Sets compression of new files for block data and archives column families.
+    fn set_block_data_compression(&self, compression: CompressionConfig) -> anyhow::Result<()>;
 }
 
 impl BaseDbExt for BaseDb {
@@ -99,11 +104,29 @@ impl BaseDbExt for BaseDb {
         provider.set_version(self.raw(), [0, 0, 1])?;
         Ok(())
     }
+
+    fn set_block_data_compression(&self, compression: CompressionConfig) -> anyhow::Result<()> {
+        let name = compression.rocksdb_name();
+        let zstd_opts;
+
+        let mut opts = vec![("compression", name), ("blob_compression_type", name)];
+        if let CompressionConfig::Zstd { level } = compression {
+            zstd_opts = format!("{{level={level}}}");
+            opts.push(("compression_opts", zstd_opts.as_str()));
+        }
+
+        let rocksdb = self.rocksdb();
+        rocksdb.set_options_cf(&self.block_data_entries.cf(), &opts)?;
+        rocksdb.set_options_cf(&self.archives.cf(), &opts)?;
+
+        tracing::debug!(?compression, "block data compression set");
+        Ok(())
+    }
 }
 
 impl WithMigrations for BaseDb {
     const NAME: &'static str = "base";
-    const VERSION: Semver = [0, 0, 3];
+    const VERSION: Semver = [0, 0, 4];
 
     fn register_migrations(
         migrations: &mut Migrations<Self>,
@@ -113,6 +136,7 @@ impl WithMigrations for BaseDb {
             base_migrations::v0_0_1_to_0_0_2(db, cancelled.clone())
         })?;
         migrations.register([0, 0, 2], [0, 0, 3], base_migrations::v_0_0_2_to_v_0_0_3)?;
+        migrations.register([0, 0, 3], [0, 0, 4], base_migrations::v_0_0_3_to_v_0_0_4)?;
 
         Ok(())
     }
@@ -149,7 +173,8 @@ mod base_migrations {
     use weedb::rocksdb::CompactOptions;
 
     use super::*;
-    use crate::util::StoredValue;
+    use crate::store::{BlockFlags, BlockMeta, LAST_MC_BLOCK_ID};
+    use crate::util::{read_block_id_le, StoredValue};
 
     pub fn v0_0_1_to_0_0_2(db: &BaseDb, cancelled: CancellationFlag) -> Result<(), MigrationError> {
         let mut block_data_iter = db.package_entries.raw_iterator();
@@ -218,6 +243,30 @@ mod base_migrations {
 
         Ok(())
     }
+
+    /// Marks the last masterchain block as committed. It was stored only after
+    /// all subscribers had been applied, but without updating the block handle.
+    pub fn v_0_0_3_to_v_0_0_4(db: &BaseDb) -> Result<(), MigrationError> {
+        let Some(last_mc_block_id) = db.state.get(LAST_MC_BLOCK_ID)? else {
+            return Ok(());
+        };
+        let last_mc_block_id = read_block_id_le(&last_mc_block_id);
+
+        if db.block_handles.get(last_mc_block_id.root_hash)?.is_none() {
+            tracing::warn!(%last_mc_block_id, "last mc block handle not found");
+            return Ok(());
+        }
+
+        db.rocksdb().merge_cf_opt(
+            &db.block_handles.cf(),
+            last_mc_block_id.root_hash,
+            BlockMeta::flags_merge_operand(BlockFlags::IS_COMMITTED),
+            db.block_handles.write_config(),
+        )?;
+
+        tracing::info!(%last_mc_block_id, "marked last mc block as committed");
+        Ok(())
+    }
 }
 
 // === RPC DB ===
@@ -465,3 +514,149 @@ weedb::tables! {
         pub shard_internal_messages: tables::ShardInternalMessages,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use weedb::{ColumnFamily, Table};
+
+    use super::*;
+    use crate::{Storage, StorageConfig, WalConfig, WalMode};
+
+    #[tokio::test]
+    async fn block_data_compression_is_applied() -> anyhow::Result<()> {
+        let tmp_dir = tempfile::tempdir()?;
+        let mut config = StorageConfig::new_potato(tmp_dir.path());
+        config.block_data_compression = CompressionConfig::Zstd { level: 5 };
+
+        let storage = Storage::builder().with_config(config).build().await?;
+        let db = storage.base_db();
+
+        // compressible and larger than min blob size
+        let value = b"block data ".repeat(10_000);
+        check_round_trip(db, &db.block_data_entries, &value)?;
+        check_round_trip(db, &db.archives, &value)?;
+
+        // the latest options file reflects options set in runtime
+        let options = read_latest_options(&tmp_dir.path().join("base"))?;
+
+        for cf in ["block_data_entries", "archives"] {
+            let lines = options_section(&options, &format!("CFOptions \"{cf}\""));
+
+            assert!(lines.contains(&"compression=kZSTD"), "{cf}");
+            assert!(lines.contains(&"blob_compression_type=kZSTD"), "{cf}");
+
+            let compression_opts = (lines.iter())
+                .find_map(|line| line.strip_prefix("compression_opts="))
+                .expect("compression opts must exist");
+            let level = compression_opts
+                .split(['{', ';', '}'])
+                .any(|item| item == "level=5");
+            assert!(level, "{cf}: {compression_opts}");
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn unsupported_db_version_errors() -> anyhow::Result<()> {
+        let tmp_dir = tempfile::tempdir()?;
+
+        // First open writes the current version
+        let storage = Storage::builder()
+            .with_config(StorageConfig::new_potato(tmp_dir.path()))
+            .build()
+            .await?;
+        let provider = StateVersionProvider {
+            db_name: BaseDb::NAME,
+        };
+        let db = storage.base_db();
+        assert_eq!(provider.get_version(db.raw())?, Some(BaseDb::VERSION));
+
+        // Pretend that the storage was written by a version without migrations
+        provider.set_version(db.raw(), [0, 0, 0])?;
+        drop(storage);
+
+        let Err(e) = Storage::builder()
+            .with_config(StorageConfig::new_potato(tmp_dir.path()))
+            .build()
+            .await
+        else {
+            anyhow::bail!("storage with an unsupported version must not be opened");
+        };
+        assert!(e.downcast_ref::<MigrationError>().is_some(), "{e:?}");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn wal_options_are_applied() -> anyhow::Result<()> {
+        let tmp_dir = tempfile::tempdir()?;
+        let storage = Storage::builder()
+            .with_config(StorageConfig::new_potato(tmp_dir.path()))
+            .build()
+            .await?;
+        let options = read_latest_options(&tmp_dir.path().join("base"))?;
+        let lines = options_section(&options, "DBOptions");
+        assert!(lines.contains(&"manual_wal_flush=false"));
+        assert!(lines.contains(&"use_fsync=false"));
+        drop(storage);
+
+        let tmp_dir = tempfile::tempdir()?;
+        let mut config = StorageConfig::new_potato(tmp_dir.path());
+        config.wal = WalConfig {
+            mode: WalMode::Buffered {
+                interval: Duration::from_millis(100),
+            },
+            use_fsync: true,
+        };
+        let storage = Storage::builder().with_config(config).build().await?;
+
+        for subdir in ["base", "int_queue", "mempool"] {
+            let options = read_latest_options(&tmp_dir.path().join(subdir))?;
+            let lines = options_section(&options, "DBOptions");
+            assert!(lines.contains(&"manual_wal_flush=true"), "{subdir}");
+            assert!(lines.contains(&"use_fsync=true"), "{subdir}");
+        }
+
+        // buffered writes are readable and can be flushed explicitly
+        let db = storage.base_db();
+        db.block_handles.insert(b"key", b"value")?;
+        storage.flush_wal(true)?;
+        let stored = db.block_handles.get(b"key")?.expect("value must be stored");
+        assert_eq!(stored.as_ref(), b"value");
+
+        Ok(())
+    }
+
+    fn read_latest_options(db_dir: &Path) -> anyhow::Result<String> {
+        let mut options_files = std::fs::read_dir(db_dir)?
+            .map(|entry| entry.map(|entry| entry.file_name().to_string_lossy().into_owned()))
+            .filter(|name| matches!(name, Ok(name) if name.starts_with("OPTIONS-")))
+            .collect::<Result<Vec<_>, _>>()?;
+        options_files.sort();
+        let options_file = options_files.last().expect("options file must exist");
+        Ok(std::fs::read_to_string(db_dir.join(options_file))?)
+    }
+
+    fn options_section<'a>(options: &'a str, name: &str) -> Vec<&'a str> {
+        let header = format!("[{name}]");
+        let section = (options.split(&header).nth(1))
+            .and_then(|rest| rest.split("\n[").next())
+            .expect("options section must exist");
+        section.lines().map(str::trim).collect()
+    }
+
+    fn check_round_trip<T>(db: &BaseDb, table: &Table<T>, value: &[u8]) -> anyhow::Result<()>
+    where
+        T: ColumnFamily,
+    {
+        table.insert(b"key", value)?;
+        db.rocksdb().flush_cf(&table.cf())?;
+
+        let stored = table.get(b"key")?.expect("value must be stored");
+        assert_eq!(stored.as_ref(), value);
+        Ok(())
+    }
+}
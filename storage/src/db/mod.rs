@@ -1,5 +1,7 @@
+pub use self::compaction::*;
 pub use self::file_db::*;
 pub use self::kv_db::*;
 
+mod compaction;
 mod file_db;
 mod kv_db;
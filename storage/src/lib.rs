@@ -1,3 +1,4 @@
+use std::ops::RangeBounds;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -56,6 +57,7 @@ impl StorageBuilder {
             }
         };
 
+        let wal = self.config.wal;
         let update_options = |opts: &mut rocksdb::Options, threads: usize, fdlimit: u64| {
             opts.set_paranoid_checks(false);
 
@@ -92,6 +94,10 @@ impl StorageBuilder {
 
             opts.set_allow_concurrent_memtable_write(false);
 
+            // wal
+            opts.set_use_fsync(wal.use_fsync);
+            opts.set_manual_wal_flush(matches!(wal.mode, WalMode::Buffered { .. }));
+
             // debug
             // NOTE: could slower everything a bit in some cloud environments.
             //       See: https://github.com/facebook/rocksdb/issues/3889
@@ -132,6 +138,7 @@ impl StorageBuilder {
 
         base_db.normalize_version()?; // TODO: Remove on testnet reset
         base_db.apply_migrations().await?;
+        base_db.set_block_data_compression(self.config.block_data_compression)?;
 
         let temp_file_storage = TempFileStorage::new(&file_db)?;
 
@@ -139,6 +146,7 @@ impl StorageBuilder {
             archive_chunk_size: self.config.archive_chunk_size,
             blocks_cache: self.config.blocks_cache,
             split_block_tasks: self.config.split_block_tasks,
+            archive_writer_tasks: self.config.archive_writer_tasks,
         };
         let block_handle_storage = Arc::new(BlockHandleStorage::new(base_db.clone()));
         let block_connection_storage = Arc::new(BlockConnectionStorage::new(base_db.clone()));
@@ -220,6 +228,10 @@ impl StorageBuilder {
             });
         }
 
+        if let Some((interval, sync)) = inner.config.wal.mode.flush_interval() {
+            spawn_wal_flush_loop(&inner, interval, sync);
+        }
+
         Ok(Storage { inner })
     }
 
@@ -321,6 +333,52 @@ impl Storage {
     pub fn mempool_storage(&self) -> &MempoolStorage {
         &self.inner.mempool_storage
     }
+
+    /// Takes a consistent read view of the base DB.
+    ///
+    /// NOTE: Snapshots pin DB resources, so they should be short-lived.
+    pub fn snapshot(&self) -> StorageSnapshot {
+        StorageSnapshot::new(&self.inner.base_db)
+    }
+
+    /// Removes outdated states relative to the last known masterchain block
+    /// and frees cells which are no longer referenced by any stored state.
+    ///
+    /// Scheduled GC is controlled by [`StorageConfig::states_gc`].
+    pub async fn run_cell_gc(&self) -> Result<StatesGcStats> {
+        let Some(mc_block_id) = self.inner.node_state_storage.load_last_mc_block_id() else {
+            tracing::warn!("no masterchain blocks yet, skipping cell GC");
+            return Ok(StatesGcStats::default());
+        };
+        self.inner
+            .shard_state_storage
+            .remove_outdated_states(mc_block_id.seqno)
+            .await
+    }
+
+    /// Starts a background compaction of the key range of the base DB column family.
+    ///
+    /// Can be used to reclaim space after a large amount of data was removed.
+    pub fn compact_range<'a, R>(&self, cf: &str, range: R) -> Result<CompactionHandle>
+    where
+        R: RangeBounds<&'a [u8]>,
+    {
+        let ranges = vec![CompactionRange::new(cf, range)];
+        spawn_compaction(self.inner.base_db.clone(), ranges)
+    }
+
+    /// Starts a background compaction of all base DB column families one by one.
+    pub fn compact_all(&self) -> Result<CompactionHandle> {
+        let ranges = CompactionRange::all(&self.inner.base_db)?;
+        spawn_compaction(self.inner.base_db.clone(), ranges)
+    }
+
+    /// Writes buffered WAL records of all databases and optionally syncs them to disk.
+    ///
+    /// See [`WalMode`] for details.
+    pub fn flush_wal(&self, sync: bool) -> Result<()> {
+        self.inner.flush_wal(sync)
+    }
 }
 
 struct Inner {
@@ -339,3 +397,36 @@ struct Inner {
     temp_file_storage: TempFileStorage,
     mempool_storage: MempoolStorage,
 }
+
+impl Inner {
+    fn flush_wal(&self, sync: bool) -> Result<()> {
+        self.base_db.rocksdb().flush_wal(sync)?;
+        self.internal_queue_storage.db().rocksdb().flush_wal(sync)?;
+        if let Some(rpc_state) = self.rpc_state.as_ref() {
+            rpc_state.db().rocksdb().flush_wal(sync)?;
+        }
+        self.mempool_storage.db.rocksdb().flush_wal(sync)?;
+        Ok(())
+    }
+}
+
+fn spawn_wal_flush_loop(inner: &Arc<Inner>, interval: Duration, sync: bool) {
+    let inner = Arc::downgrade(inner);
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            let Some(inner) = inner.upgrade() else {
+                break;
+            };
+
+            let res = tokio::task::spawn_blocking(move || inner.flush_wal(sync)).await;
+            match res {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => tracing::error!("failed to flush WAL: {e:?}"),
+                Err(e) => tracing::error!("WAL flush task panicked: {e:?}"),
+            }
+        }
+    });
+}
@@ -0,0 +1,68 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+const METRIC_ACTIVE_ARCHIVE_WRITERS: &str = "tycho_storage_active_archive_writers";
+
+/// Limits the number of archives which are committed concurrently.
+#[derive(Clone)]
+pub(crate) struct ArchiveWritersPool {
+    semaphore: Arc<Semaphore>,
+}
+
+impl ArchiveWritersPool {
+    pub fn new(max_writers: usize) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(max_writers.max(1))),
+        }
+    }
+
+    /// Waits until there is a free slot for a new archive writer.
+    pub async fn acquire(&self) -> Result<ArchiveWriterSlot> {
+        let permit = self.semaphore.clone().acquire_owned().await?;
+        metrics::gauge!(METRIC_ACTIVE_ARCHIVE_WRITERS).increment(1);
+        Ok(ArchiveWriterSlot { _permit: permit })
+    }
+}
+
+/// Releases the slot on drop.
+pub(crate) struct ArchiveWriterSlot {
+    _permit: OwnedSemaphorePermit,
+}
+
+impl Drop for ArchiveWriterSlot {
+    fn drop(&mut self) {
+        metrics::gauge!(METRIC_ACTIVE_ARCHIVE_WRITERS).decrement(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn acquire_waits_for_released_slot() -> Result<()> {
+        let pool = ArchiveWritersPool::new(2);
+
+        let first = pool.acquire().await?;
+        let _second = pool.acquire().await?;
+
+        let third = tokio::time::timeout(Duration::from_millis(100), pool.acquire()).await;
+        assert!(third.is_err(), "must wait while all slots are busy");
+
+        let third = tokio::spawn({
+            let pool = pool.clone();
+            async move { pool.acquire().await }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!third.is_finished());
+
+        drop(first);
+        let _third = tokio::time::timeout(Duration::from_secs(1), third).await???;
+
+        Ok(())
+    }
+}
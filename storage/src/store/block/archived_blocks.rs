@@ -0,0 +1,70 @@
+use std::collections::BTreeMap;
+
+use everscale_types::models::{BlockId, ShardIdent};
+use tycho_util::FastHashMap;
+
+/// Maps block seqno to the id of the archive which contains it.
+///
+/// Filled when an archive is committed or loaded from the storage,
+/// so it is not complete right after the node restart.
+#[derive(Default)]
+pub(crate) struct ArchivedBlocksIndex {
+    shards: FastHashMap<ShardIdent, BTreeMap<u32, u32>>,
+}
+
+impl ArchivedBlocksIndex {
+    pub fn insert<'a, I>(&mut self, archive_id: u32, block_ids: I)
+    where
+        I: IntoIterator<Item = &'a BlockId>,
+    {
+        for block_id in block_ids {
+            let blocks = self.shards.entry(block_id.shard).or_default();
+            blocks.insert(block_id.seqno, archive_id);
+        }
+    }
+
+    pub fn get(&self, block_id: &BlockId) -> Option<u32> {
+        self.shards
+            .get(&block_id.shard)?
+            .get(&block_id.seqno)
+            .copied()
+    }
+
+    /// Removes all blocks from archives with ids less than `until_id`.
+    pub fn remove_until(&mut self, until_id: u32) {
+        self.shards.retain(|_, blocks| {
+            blocks.retain(|_, archive_id| *archive_id >= until_id);
+            !blocks.is_empty()
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn index_is_separate_for_shards() {
+        let mc_block = BlockId {
+            shard: ShardIdent::MASTERCHAIN,
+            seqno: 10,
+            ..Default::default()
+        };
+        let shard_block = BlockId {
+            shard: ShardIdent::BASECHAIN,
+            seqno: 10,
+            ..Default::default()
+        };
+
+        let mut index = ArchivedBlocksIndex::default();
+        index.insert(1, [&mc_block]);
+        index.insert(101, [&shard_block]);
+
+        assert_eq!(index.get(&mc_block), Some(1));
+        assert_eq!(index.get(&shard_block), Some(101));
+
+        index.remove_until(101);
+        assert_eq!(index.get(&mc_block), None);
+        assert_eq!(index.get(&shard_block), Some(101));
+    }
+}
@@ -1,39 +1,44 @@
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, VecDeque};
 use std::num::NonZeroU32;
+use std::ops::{Bound, RangeBounds};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
-use bytes::Buf;
+use bytes::{Buf, Bytes};
 use bytesize::ByteSize;
 use everscale_types::boc::{Boc, BocRepr};
 use everscale_types::cell::HashBytes;
 use everscale_types::models::*;
-use parking_lot::RwLock;
-use tl_proto::TlWrite;
+use parking_lot::{Mutex, RwLock};
 use tokio::sync::{broadcast, OwnedSemaphorePermit, Semaphore};
 use tokio::task::JoinHandle;
 use tycho_block_util::archive::{
-    ArchiveData, ArchiveEntryHeader, ArchiveEntryType, ARCHIVE_ENTRY_HEADER_LEN, ARCHIVE_PREFIX,
+    make_archive_entry, Archive, ArchiveData, ArchiveEntryType, ARCHIVE_PREFIX,
 };
 use tycho_block_util::block::{
     BlockProofStuff, BlockProofStuffAug, BlockStuff, BlockStuffAug, ShardHeights,
 };
 use tycho_block_util::queue::{QueueDiffStuff, QueueDiffStuffAug};
-use tycho_util::compression::ZstdCompressStream;
+use tycho_util::compression::{zstd_compress, ZstdCompressStream, ZstdDecompressStream};
 use tycho_util::metrics::HistogramGuard;
 use tycho_util::sync::{rayon_run, CancellationFlag};
 use tycho_util::{FastHashSet, FastHasherState};
 use weedb::{rocksdb, ColumnFamily, OwnedPinnableSlice};
 
+use self::archive_writers::ArchiveWritersPool;
+use self::archived_blocks::ArchivedBlocksIndex;
 pub use self::package_entry::{BlockDataEntryKey, PackageEntryKey, PartialBlockId};
 use crate::db::*;
+use crate::store::StorageSnapshot;
 use crate::util::*;
 use crate::{
-    BlockConnectionStorage, BlockDataGuard, BlockFlags, BlockHandle, BlockHandleStorage,
+    BlockConnectionStorage, BlockDataGuard, BlockFlags, BlockHandle, BlockHandleStorage, BlockMeta,
     BlocksCacheConfig, HandleCreationStatus, NewBlockMeta,
 };
 
+mod archive_writers;
+mod archived_blocks;
 mod package_entry;
 
 const METRIC_LOAD_BLOCK_TOTAL: &str = "tycho_storage_load_block_total";
@@ -45,12 +50,18 @@ pub struct BlockStorage {
     block_handle_storage: Arc<BlockHandleStorage>,
     block_connection_storage: Arc<BlockConnectionStorage>,
     archive_ids: RwLock<ArchiveIds>,
+    archived_blocks: Arc<RwLock<ArchivedBlocksIndex>>,
+    archived_blocks_cache: ArchivedBlocksCache,
+    loaded_archives: LoadedArchivesCache,
+    load_archive_lock: tokio::sync::Mutex<()>,
     block_subscriptions: SlotSubscriptions<BlockId, BlockStuff>,
     store_block_data: tokio::sync::RwLock<()>,
-    prev_archive_commit: tokio::sync::Mutex<Option<CommitArchiveTask>>,
+    pending_archive_commits: tokio::sync::Mutex<VecDeque<CommitArchiveTask>>,
     archive_ids_tx: ArchiveIdsTx,
     archive_chunk_size: NonZeroU32,
     split_block_semaphore: Arc<Semaphore>,
+    archive_writers: ArchiveWritersPool,
+    pending_writes: Arc<Mutex<Option<PendingWrites>>>,
 }
 
 impl BlockStorage {
@@ -77,12 +88,33 @@ impl BlockStorage {
             .weigher(weigher)
             .build_with_hasher(Default::default());
 
+        fn archived_block_weigher(_key: &BlockId, value: &ArchivedBlock) -> u32 {
+            (std::mem::size_of::<BlockId>()
+                + value.data.len()
+                + value.proof.len()
+                + value.queue_diff.len())
+            .try_into()
+            .unwrap_or(u32::MAX)
+        }
+
+        let archived_blocks_cache = moka::sync::Cache::builder()
+            .time_to_live(config.blocks_cache.ttl)
+            .max_capacity(ARCHIVED_BLOCKS_CACHE_SIZE)
+            .weigher(archived_block_weigher)
+            .build_with_hasher(Default::default());
+
+        let loaded_archives = moka::sync::Cache::builder()
+            .time_to_live(LOADED_ARCHIVES_TTL)
+            .max_capacity(LOADED_ARCHIVES_CAPACITY)
+            .build_with_hasher(Default::default());
+
         let (archive_ids_tx, _) = broadcast::channel(4);
 
         let archive_chunk_size =
             NonZeroU32::new(archive_chunk_size.as_u64().clamp(1, u32::MAX as _) as _).unwrap();
 
         let split_block_semaphore = Arc::new(Semaphore::new(config.split_block_tasks));
+        let archive_writers = ArchiveWritersPool::new(config.archive_writer_tasks);
 
         Self {
             db,
@@ -92,10 +124,16 @@ impl BlockStorage {
             archive_ids_tx,
             archive_chunk_size,
             split_block_semaphore,
+            archive_writers,
             archive_ids: Default::default(),
+            archived_blocks: Default::default(),
+            archived_blocks_cache,
+            loaded_archives,
+            load_archive_lock: Default::default(),
             block_subscriptions: Default::default(),
             store_block_data: Default::default(),
-            prev_archive_commit: Default::default(),
+            pending_archive_commits: Default::default(),
+            pending_writes: Default::default(),
         }
     }
 
@@ -109,6 +147,60 @@ impl BlockStorage {
         NonZeroU32::new(BLOCK_DATA_CHUNK_SIZE).unwrap()
     }
 
+    // === Write batch stuff ===
+
+    /// Enables batched writes of block proofs and queue diffs.
+    ///
+    /// Entries are accumulated in memory and written at once when either
+    /// `max_entries` stores were batched or `max_delay` has passed since
+    /// the first batched store, or on [`flush`].
+    ///
+    /// Batched stores return `updated: false` and a handle without the new
+    /// flags. Flags are set only when the batch is written, and handles
+    /// updated by the write are returned from [`flush`] and [`end_write_batch`].
+    ///
+    /// Crash-safety: entry data and the corresponding handle flags are
+    /// written in the same batch, and flags become visible only after the
+    /// batch is written. So a crash can only lose not yet flushed entries,
+    /// which will be treated as missing on the next start.
+    ///
+    /// NOTE: Block data is always written immediately since it is split
+    /// into chunks in the background.
+    ///
+    /// [`flush`]: Self::flush
+    /// [`end_write_batch`]: Self::end_write_batch
+    pub fn begin_write_batch(&self, config: BlockWriteBatchConfig) {
+        let mut pending = self.pending_writes.lock();
+        match &mut *pending {
+            Some(pending) => pending.config = config,
+            None => *pending = Some(PendingWrites::new(config)),
+        }
+    }
+
+    /// Writes all batched entries.
+    ///
+    /// Returns handles which were updated by this write.
+    pub fn flush(&self) -> Result<Vec<BlockHandle>> {
+        let mut pending = self.pending_writes.lock();
+        match &mut *pending {
+            Some(pending) => Ok(pending.write(&self.db)?),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Writes all batched entries and disables batched writes.
+    ///
+    /// Returns handles which were updated by this write.
+    pub fn end_write_batch(&self) -> Result<Vec<BlockHandle>> {
+        let mut pending = self.pending_writes.lock();
+        let updated = match &mut *pending {
+            Some(pending) => pending.write(&self.db)?,
+            None => Vec::new(),
+        };
+        *pending = None;
+        Ok(updated)
+    }
+
     pub async fn finish_block_data(&self) -> Result<()> {
         let started_at = Instant::now();
 
@@ -163,12 +255,13 @@ impl BlockStorage {
             };
 
             let data = match self.db.package_entries.get(key.to_vec())? {
-                Some(data) => data,
+                // SAFETY: A value was received from the same RocksDB instance.
+                Some(data) => unsafe { OwnedPinnableSlice::new(self.db.rocksdb().clone(), data) },
                 None => return Err(BlockStorageError::BlockDataNotFound.into()),
             };
 
             let permit = self.split_block_semaphore.clone().acquire_owned().await?;
-            self.spawn_split_block_data(&block_id, &data, permit)
+            self.spawn_split_block_data(&block_id, data, permit)
                 .await??;
         }
 
@@ -187,6 +280,7 @@ impl BlockStorage {
         tracing::info!("started preloading archive ids");
 
         let db = self.db.clone();
+        let archived_blocks = self.archived_blocks.clone();
 
         let (archive_ids, override_next_id, to_commit) = tokio::task::spawn_blocking(move || {
             let mut iter = db.archives.raw_iterator();
@@ -208,6 +302,8 @@ impl BlockStorage {
 
                 const _: () = const {
                     // Rely on the specific order of these constants
+                    assert!(ARCHIVE_MAGIC_MIN <= ARCHIVE_BLOCK_IDS_MAGIC);
+                    assert!(ARCHIVE_BLOCK_IDS_MAGIC < ARCHIVE_STARTED_MAGIC);
                     assert!(ARCHIVE_STARTED_MAGIC < ARCHIVE_OVERRIDE_NEXT_MAGIC);
                     assert!(ARCHIVE_OVERRIDE_NEXT_MAGIC < ARCHIVE_TO_COMMIT_MAGIC);
                     assert!(ARCHIVE_TO_COMMIT_MAGIC < ARCHIVE_SIZE_MAGIC);
@@ -224,6 +320,14 @@ impl BlockStorage {
 
                 // Chunk keys are sorted by offset.
                 match chunk_index {
+                    // "Block ids" magic precedes other magics, it is written on archive commit.
+                    ARCHIVE_BLOCK_IDS_MAGIC => {
+                        let block_ids = value
+                            .chunks_exact(BlockId::SIZE_HINT)
+                            .map(BlockId::from_slice)
+                            .collect::<Vec<_>>();
+                        archived_blocks.write().insert(archive_id, &block_ids);
+                    }
                     // "Started" magic comes first, and indicates that the archive exists.
                     ARCHIVE_STARTED_MAGIC => {
                         archive_ids.insert(archive_id);
@@ -246,15 +350,15 @@ impl BlockStorage {
                         let last = archives_to_commit.pop();
                         anyhow::ensure!(last == Some(archive_id), "invalid archive SIZE entry");
 
-                        // Require only contiguous uncommited archives list
-                        anyhow::ensure!(archives_to_commit.is_empty(), "skipped archive commit");
+                        // NOTE: Previous archives might be still uncommitted
+                        // since archives are committed concurrently.
                     }
                     _ => {
                         // Skip all chunks until the magic
-                        if chunk_index < ARCHIVE_STARTED_MAGIC {
+                        if chunk_index < ARCHIVE_BLOCK_IDS_MAGIC {
                             let mut next_key = [0; tables::Archives::KEY_LEN];
                             next_key[..4].copy_from_slice(&archive_id.to_be_bytes());
-                            next_key[4..].copy_from_slice(&ARCHIVE_STARTED_MAGIC.to_be_bytes());
+                            next_key[4..].copy_from_slice(&ARCHIVE_BLOCK_IDS_MAGIC.to_be_bytes());
                             skip = Some(next_key);
                         }
                     }
@@ -289,7 +393,7 @@ impl BlockStorage {
             self.clear_archive(archive_id)?;
 
             tracing::info!(archive_id, "rewrite partially committed archive");
-            let mut task = self.spawn_commit_archive(archive_id);
+            let mut task = self.spawn_commit_archive(archive_id).await?;
             task.finish().await?;
 
             // Notify archive subscribers
@@ -356,12 +460,13 @@ impl BlockStorage {
         let archive_id = PackageEntryKey::block(block_id);
         let mut updated = false;
         if !handle.has_data() {
-            let data = archive_data.as_new_archive_data()?;
+            let data = archive_data.as_new_archive_bytes()?;
             metrics::histogram!("tycho_storage_store_block_data_size").record(data.len() as f64);
 
             let _lock = handle.block_data_lock().write().await;
             if !handle.has_data() {
-                self.add_block_data_and_split(&archive_id, data).await?;
+                self.add_block_data_and_split(&archive_id, data.clone())
+                    .await?;
                 if handle.meta().add_flags(BlockFlags::HAS_DATA) {
                     self.block_handle_storage.store_handle(&handle, false);
                     updated = true;
@@ -398,6 +503,7 @@ impl BlockStorage {
         // Fast path - lookup in cache
         if let Some(block) = self.blocks_cache.get(handle.id()) {
             metrics::counter!(METRIC_BLOCK_CACHE_HIT_TOTAL).increment(1);
+            handle.track_access();
             return Ok(block.clone());
         }
 
@@ -425,6 +531,19 @@ impl BlockStorage {
             .await
     }
 
+    /// Same as [`Self::load_block_data_raw`], but reads the data as it was
+    /// when the snapshot was taken. Use with a handle from the same snapshot.
+    pub fn load_block_data_raw_at(
+        &self,
+        snapshot: &StorageSnapshot,
+        handle: &BlockHandle,
+    ) -> Result<OwnedPinnableSlice> {
+        if !handle.has_data() {
+            return Err(BlockStorageError::BlockDataNotFound.into());
+        }
+        self.get_data_at(snapshot, &PackageEntryKey::block(handle.id()))
+    }
+
     pub async fn list_blocks(
         &self,
         continuation: Option<BlockIdShort>,
@@ -494,6 +613,50 @@ impl BlockStorage {
         Ok((blocks, continuation))
     }
 
+    /// Iterates over ids of the blocks with stored handles for the shard,
+    /// in ascending order of seqno.
+    ///
+    /// NOTE: Uses the full block ids index, which is filled together with new handles.
+    /// Iteration stops after the first error.
+    pub fn iter_block_ids<R>(
+        &self,
+        shard: ShardIdent,
+        range: R,
+    ) -> impl Iterator<Item = Result<BlockId, rocksdb::Error>> + '_
+    where
+        R: RangeBounds<u32>,
+    {
+        let from = match range.start_bound() {
+            Bound::Included(seqno) => Some(*seqno),
+            Bound::Excluded(seqno) => seqno.checked_add(1),
+            Bound::Unbounded => Some(0),
+        };
+        let to = match range.end_bound() {
+            Bound::Included(seqno) => Some(*seqno),
+            Bound::Excluded(seqno) => seqno.checked_sub(1),
+            Bound::Unbounded => Some(u32::MAX),
+        };
+        let range = from.zip(to).filter(|(from, to)| from <= to);
+
+        let mut raw_iterator = self.db.full_block_ids.raw_iterator();
+        if let Some((from, _)) = range {
+            raw_iterator.seek(
+                PartialBlockId {
+                    shard,
+                    seqno: from,
+                    root_hash: HashBytes::ZERO,
+                }
+                .to_vec(),
+            );
+        }
+
+        BlockIdsIterator {
+            raw_iterator,
+            shard,
+            to: range.map(|(_, to)| to),
+        }
+    }
+
     pub fn list_archive_ids(&self) -> Vec<u32> {
         self.archive_ids.read().items.iter().cloned().collect()
     }
@@ -572,11 +735,8 @@ impl BlockStorage {
 
             let _lock = handle.proof_data_lock().write().await;
             if !handle.has_proof() {
-                self.add_data(&archive_id, data)?;
-                if handle.meta().add_flags(BlockFlags::HAS_PROOF) {
-                    self.block_handle_storage.store_handle(&handle, false);
-                    updated = true;
-                }
+                updated =
+                    self.add_data_with_flags(&handle, &archive_id, data, BlockFlags::HAS_PROOF)?;
             }
         }
 
@@ -601,6 +761,19 @@ impl BlockStorage {
             .await
     }
 
+    /// Same as [`Self::load_block_proof_raw`], but reads the proof as it was
+    /// when the snapshot was taken. Use with a handle from the same snapshot.
+    pub fn load_block_proof_raw_at(
+        &self,
+        snapshot: &StorageSnapshot,
+        handle: &BlockHandle,
+    ) -> Result<OwnedPinnableSlice> {
+        if !handle.has_proof() {
+            return Err(BlockStorageError::BlockProofNotFound.into());
+        }
+        self.get_data_at(snapshot, &PackageEntryKey::proof(handle.id()))
+    }
+
     pub async fn load_block_proof_raw_ref<'a>(
         &'a self,
         handle: &'a BlockHandle,
@@ -639,11 +812,12 @@ impl BlockStorage {
 
             let _lock = handle.queue_diff_data_lock().write().await;
             if !handle.has_queue_diff() {
-                self.add_data(&archive_id, data)?;
-                if handle.meta().add_flags(BlockFlags::HAS_QUEUE_DIFF) {
-                    self.block_handle_storage.store_handle(&handle, false);
-                    updated = true;
-                }
+                updated = self.add_data_with_flags(
+                    &handle,
+                    &archive_id,
+                    data,
+                    BlockFlags::HAS_QUEUE_DIFF,
+                )?;
             }
         }
 
@@ -738,17 +912,23 @@ impl BlockStorage {
 
         if let Some(to_commit) = archive_id.to_commit {
             // Commit previous archive
-            let mut prev_archive_commit = self.prev_archive_commit.lock().await;
+            let mut pending_commits = self.pending_archive_commits.lock().await;
 
-            // NOTE: Wait on reference to make sure that the task is cancel safe
-            if let Some(task) = &mut *prev_archive_commit {
-                // Wait commit archive
-                task.finish().await?;
+            // Notify archive subscribers in the order of commits
+            while let Some(task) = pending_commits.front_mut() {
+                if !task.is_finished() {
+                    break;
+                }
 
-                // Notify archive subscribers
+                // NOTE: Wait on reference to make sure that the task is cancel safe
+                task.finish().await?;
                 self.archive_ids_tx.send(task.archive_id).ok();
+                pending_commits.pop_front();
             }
-            *prev_archive_commit = Some(self.spawn_commit_archive(to_commit));
+
+            // NOTE: Waits for a free writer slot when too many archives are committed
+            let task = self.spawn_commit_archive(to_commit).await?;
+            pending_commits.push_back(task);
         }
 
         // Done
@@ -756,10 +936,10 @@ impl BlockStorage {
     }
 
     pub async fn wait_for_archive_commit(&self) -> Result<()> {
-        let mut prev_archive_commit = self.prev_archive_commit.lock().await;
-        if let Some(task) = &mut *prev_archive_commit {
+        let mut pending_commits = self.pending_archive_commits.lock().await;
+        while let Some(task) = pending_commits.front_mut() {
             task.finish().await?;
-            *prev_archive_commit = None;
+            pending_commits.pop_front();
         }
         Ok(())
     }
@@ -859,23 +1039,114 @@ impl BlockStorage {
     }
 
     pub fn archive_chunks_iterator(&self, archive_id: u32) -> rocksdb::DBRawIterator<'_> {
-        let mut from = [0u8; tables::Archives::KEY_LEN];
-        from[..4].copy_from_slice(&archive_id.to_be_bytes());
+        archive_chunks_iterator(&self.db, archive_id)
+    }
+
+    /// Finds an archive which contains the specified block.
+    pub fn find_block_archive_id(&self, block_id: &BlockId) -> Option<u32> {
+        if let Some(id) = self.archived_blocks.read().get(block_id) {
+            return Some(id);
+        }
 
-        let mut to = [0u8; tables::Archives::KEY_LEN];
-        to[..4].copy_from_slice(&archive_id.to_be_bytes());
-        to[4..].copy_from_slice(&ARCHIVE_MAGIC_MIN.to_be_bytes());
+        // Masterchain blocks are grouped into archives by seqno
+        match block_id.is_masterchain() {
+            true => match self.get_archive_id(block_id.seqno) {
+                ArchiveId::Found(id) => Some(id),
+                ArchiveId::TooNew | ArchiveId::NotFound => None,
+            },
+            false => None,
+        }
+    }
 
-        let mut read_opts = self.db.archives.new_read_config();
-        read_opts.set_iterate_upper_bound(to.as_slice());
+    /// Loads block parts from the committed archive.
+    /// Block data is compressed the same way as for the [`get_block_data_chunk`].
+    ///
+    /// Loaded blocks are cached, use [`get_cached_archived_block`] to check
+    /// whether the archive must be decompressed to serve the block.
+    ///
+    /// [`get_block_data_chunk`]: Self::get_block_data_chunk
+    /// [`get_cached_archived_block`]: Self::get_cached_archived_block
+    pub async fn load_archived_block(&self, block_id: &BlockId) -> Result<Option<ArchivedBlock>> {
+        if let Some(archived) = self.archived_blocks_cache.get(block_id) {
+            return Ok(Some(archived));
+        }
 
-        let rocksdb = self.db.rocksdb();
-        let archives_cf = self.db.archives.cf();
+        let Some(archive_id) = self.find_block_archive_id(block_id) else {
+            return Ok(None);
+        };
 
-        let mut raw_iterator = rocksdb.raw_iterator_cf_opt(&archives_cf, read_opts);
-        raw_iterator.seek(from);
+        let archive = self.load_archive(archive_id).await?;
+        let Some(entry) = archive.blocks.get(block_id) else {
+            return Ok(None);
+        };
+        let (Some(data), Some(proof), Some(queue_diff)) =
+            (&entry.block, &entry.proof, &entry.queue_diff)
+        else {
+            return Ok(None);
+        };
 
-        raw_iterator
+        let data = data.clone();
+        let compressed = rayon_run(move || {
+            let mut compressed = Vec::new();
+            zstd_compress(&data, &mut compressed, 3);
+            compressed
+        })
+        .await;
+
+        let archived = ArchivedBlock {
+            data: Bytes::from(compressed),
+            proof: proof.clone(),
+            queue_diff: queue_diff.clone(),
+        };
+        self.archived_blocks_cache
+            .insert(*block_id, archived.clone());
+
+        Ok(Some(archived))
+    }
+
+    /// Returns an archived block if it was recently loaded.
+    pub fn get_cached_archived_block(&self, block_id: &BlockId) -> Option<ArchivedBlock> {
+        self.archived_blocks_cache.get(block_id)
+    }
+
+    async fn load_archive(&self, archive_id: u32) -> Result<Arc<Archive>> {
+        if let Some(archive) = self.loaded_archives.get(&archive_id) {
+            return Ok(archive);
+        }
+
+        // Decompress at most one archive at a time
+        let _guard = self.load_archive_lock.lock().await;
+        if let Some(archive) = self.loaded_archives.get(&archive_id) {
+            return Ok(archive);
+        }
+
+        let _histogram = HistogramGuard::begin("tycho_storage_load_archive_time");
+
+        let db = self.db.clone();
+        let chunk_size = self.archive_chunk_size().get() as usize;
+        let archive = rayon_run(move || {
+            let mut decoder = ZstdDecompressStream::new(chunk_size)?;
+            let mut decompressed = Vec::new();
+
+            let mut iterator = archive_chunks_iterator(&db, archive_id);
+            while let Some(chunk) = iterator.value() {
+                decoder.write(chunk, &mut decompressed)?;
+                iterator.next();
+            }
+            iterator.status()?;
+
+            Archive::new(decompressed)
+        })
+        .await
+        .with_context(|| format!("failed to load archive {archive_id}"))?;
+
+        let archive = Arc::new(archive);
+        self.archived_blocks
+            .write()
+            .insert(archive_id, archive.blocks.keys());
+        self.loaded_archives.insert(archive_id, archive.clone());
+
+        Ok(archive)
     }
 
     // === GC stuff ===
@@ -991,6 +1262,10 @@ impl BlockStorage {
 
         drop(archive_ids);
 
+        self.archived_blocks.write().remove_until(until_id);
+        self.archived_blocks_cache.invalidate_all();
+        self.loaded_archives.invalidate_all();
+
         // Remove all archives in range `[0, until_id)`
         let archives_cf = self.db.archives.cf();
         let write_options = self.db.archives.write_config();
@@ -1017,19 +1292,91 @@ impl BlockStorage {
         self.db.package_entries.insert(id.to_vec(), data)
     }
 
-    async fn add_block_data_and_split(&self, id: &PackageEntryKey, data: &[u8]) -> Result<()> {
-        let mut batch = rocksdb::WriteBatch::default();
+    /// Stores a package entry and adds `flags` to the block handle.
+    ///
+    /// In batched mode always returns `false` since the handle is updated
+    /// only when the batch is written.
+    fn add_data_with_flags(
+        &self,
+        handle: &BlockHandle,
+        id: &PackageEntryKey,
+        data: &[u8],
+        flags: BlockFlags,
+    ) -> Result<bool, rocksdb::Error> {
+        let mut pending = self.pending_writes.lock();
+        if let Some(pending) = &mut *pending {
+            pending
+                .batch
+                .put_cf(&self.db.package_entries.cf(), id.to_vec(), data);
+            pending.batch.merge_cf(
+                &self.db.block_handles.cf(),
+                handle.id().root_hash,
+                BlockMeta::flags_merge_operand(flags),
+            );
+            pending.handles.push((handle.clone(), flags));
+
+            if pending.first_write_at.is_none() {
+                pending.first_write_at = Some(Instant::now());
+                self.spawn_delayed_flush(pending.config.max_delay);
+            }
+
+            if pending.handles.len() >= pending.config.max_entries {
+                pending.write(&self.db)?;
+            }
+            return Ok(false);
+        }
+        drop(pending);
+
+        self.add_data(id, data)?;
+        let updated = handle.meta().add_flags(flags);
+        if updated {
+            self.block_handle_storage.store_handle(handle, false);
+        }
+        Ok(updated)
+    }
+
+    /// Writes the current batch when `max_delay` passes, even if
+    /// there are no more stores.
+    fn spawn_delayed_flush(&self, max_delay: Duration) {
+        let db = self.db.clone();
+        let pending_writes = Arc::downgrade(&self.pending_writes);
+
+        tokio::spawn(async move {
+            tokio::time::sleep(max_delay).await;
+
+            let Some(pending_writes) = pending_writes.upgrade() else {
+                return;
+            };
+            let mut pending = pending_writes.lock();
+            let Some(pending) = &mut *pending else {
+                return;
+            };
+
+            // NOTE: The batch might have been already written and a new one started
+            match pending.first_write_at {
+                Some(first_write_at) if first_write_at.elapsed() >= pending.config.max_delay => {}
+                _ => return,
+            }
+
+            if let Err(e) = pending.write(&db) {
+                tracing::error!("failed to write batched block entries: {e}");
+            }
+        });
+    }
 
-        batch.put_cf(&self.db.package_entries.cf(), id.to_vec(), data);
+    async fn add_block_data_and_split(&self, id: &PackageEntryKey, data: Bytes) -> Result<()> {
+        // NOTE: Data is written directly without a write batch to avoid
+        //       an extra copy of a possibly large block.
+        self.db.package_entries.insert(id.to_vec(), &data)?;
 
-        // Store info that new block was started
+        // Store info that new block was started.
+        // NOTE: Splitting is restarted even if the data was stored before
+        //       since it might not have been finished.
         let key = BlockDataEntryKey {
             block_id: id.block_id,
             chunk_index: BLOCK_DATA_STARTED_MAGIC,
         };
-        batch.put_cf(&self.db.block_data_entries.cf(), key.to_vec(), []);
-
-        self.db.rocksdb().write(batch)?;
+        self.db.block_data_entries.insert(key.to_vec(), [])?;
 
         // Start splitting block data
         let permit = self.split_block_semaphore.clone().acquire_owned().await?;
@@ -1043,6 +1390,8 @@ impl BlockStorage {
         handle: &BlockHandle,
         id: &PackageEntryKey,
     ) -> Result<OwnedPinnableSlice> {
+        handle.track_access();
+
         let _lock = match id.ty {
             ArchiveEntryType::Block => handle.block_data_lock(),
             ArchiveEntryType::Proof => handle.proof_data_lock(),
@@ -1058,11 +1407,26 @@ impl BlockStorage {
         }
     }
 
+    /// NOTE: Data locks are not needed since the snapshot is never modified.
+    fn get_data_at(
+        &self,
+        snapshot: &StorageSnapshot,
+        id: &PackageEntryKey,
+    ) -> Result<OwnedPinnableSlice> {
+        match snapshot.get(&self.db.package_entries, id.to_vec())? {
+            // SAFETY: A value was received from the same RocksDB instance.
+            Some(value) => Ok(unsafe { OwnedPinnableSlice::new(self.db.rocksdb().clone(), value) }),
+            None => Err(BlockStorageError::PackageEntryNotFound.into()),
+        }
+    }
+
     async fn get_data_ref<'a, 'b: 'a>(
         &'a self,
         handle: &'b BlockHandle,
         id: &PackageEntryKey,
     ) -> Result<FullBlockDataGuard<'a>> {
+        handle.track_access();
+
         let lock = match id.ty {
             ArchiveEntryType::Block => handle.block_data_lock(),
             ArchiveEntryType::Proof => handle.proof_data_lock(),
@@ -1146,9 +1510,13 @@ impl BlockStorage {
     }
 
     #[tracing::instrument(skip(self))]
-    fn spawn_commit_archive(&self, archive_id: u32) -> CommitArchiveTask {
+    async fn spawn_commit_archive(&self, archive_id: u32) -> Result<CommitArchiveTask> {
+        // NOTE: The slot is released when the writer task is finished
+        let writer_slot = self.archive_writers.acquire().await?;
+
         let db = self.db.clone();
         let block_handle_storage = self.block_handle_storage.clone();
+        let archived_blocks = self.archived_blocks.clone();
         let chunk_size = self.archive_chunk_size().get() as u64;
 
         let span = tracing::Span::current();
@@ -1159,6 +1527,7 @@ impl BlockStorage {
 
             move || {
                 let _span = span.enter();
+                let _writer_slot = writer_slot;
 
                 let histogram = HistogramGuard::begin("tycho_storage_commit_archive_time");
 
@@ -1174,7 +1543,6 @@ impl BlockStorage {
                 assert_eq!(raw_block_ids.len() % BlockId::SIZE_HINT, 0);
 
                 let mut writer = ArchiveWriter::new(&db, archive_id, chunk_size)?;
-                let mut header_buffer = Vec::with_capacity(ARCHIVE_ENTRY_HEADER_LEN);
 
                 // Write archive prefix
                 writer.write(&ARCHIVE_PREFIX)?;
@@ -1217,28 +1585,25 @@ impl BlockStorage {
                             return Err(BlockStorageError::BlockDataNotFound.into());
                         };
 
-                        // Serialize entry header
-                        header_buffer.clear();
-                        ArchiveEntryHeader {
-                            block_id,
-                            ty,
-                            data_len: data.len() as u32,
-                        }
-                        .write_to(&mut header_buffer);
-
                         // Write entry header and data
-                        writer.write(&header_buffer)?;
-                        writer.write(data.as_ref())?;
+                        writer.write(&make_archive_entry(&block_id, ty, data.as_ref()))?;
                     }
 
                     unique_ids.clear();
                 }
 
+                let block_ids = raw_block_ids
+                    .chunks_exact(BlockId::SIZE_HINT)
+                    .map(BlockId::from_slice)
+                    .collect::<Vec<_>>();
+
                 // Drop ids entry just in case (before removing it)
                 drop(raw_block_ids);
 
                 // Finalize the archive
-                writer.finalize()?;
+                writer.finalize(&block_ids)?;
+
+                archived_blocks.write().insert(archive_id, &block_ids);
 
                 // Done
                 scopeguard::ScopeGuard::into_inner(guard);
@@ -1251,52 +1616,83 @@ impl BlockStorage {
             }
         });
 
-        CommitArchiveTask {
+        Ok(CommitArchiveTask {
             archive_id,
             cancelled,
             handle: Some(handle),
-        }
+        })
     }
 
+    /// Compresses and splits block data into chunks in the background.
+    ///
+    /// Data is compressed as a stream and each chunk is written as soon as
+    /// it is ready, so at most one compressed chunk is kept in memory
+    /// in addition to the shared raw data.
     #[tracing::instrument(skip(self, data))]
-    fn spawn_split_block_data(
+    fn spawn_split_block_data<D>(
         &self,
         block_id: &PartialBlockId,
-        data: &[u8],
+        data: D,
         permit: OwnedSemaphorePermit,
-    ) -> JoinHandle<Result<()>> {
+    ) -> JoinHandle<Result<()>>
+    where
+        D: AsRef<[u8]> + Send + 'static,
+    {
         let db = self.db.clone();
         let chunk_size = self.block_data_chunk_size().get() as usize;
 
         let span = tracing::Span::current();
         let handle = tokio::task::spawn_blocking({
             let block_id = *block_id;
-            let data = data.to_vec();
 
             move || {
                 let _span = span.enter();
 
                 let _histogram = HistogramGuard::begin("tycho_storage_split_block_data_time");
 
-                let mut compressed = Vec::new();
-                tycho_util::compression::zstd_compress(&data, &mut compressed, 3);
+                let mut compressor = ZstdCompressStream::new(3, chunk_size)?;
+                let mut buffer = Vec::with_capacity(chunk_size);
+                let mut chunk_index = 0u32;
+                let mut total_len = 0usize;
+
+                let mut flush = |buffer: &mut Vec<u8>, finalize: bool| -> Result<()> {
+                    let mut offset = 0;
+                    while offset < buffer.len() {
+                        // Only the last chunk can be incomplete
+                        let end = offset + chunk_size;
+                        if end > buffer.len() && !finalize {
+                            break;
+                        }
+                        let end = std::cmp::min(end, buffer.len());
 
-                let chunks = compressed.chunks(chunk_size);
-                for (index, chunk) in chunks.enumerate() {
-                    let key = BlockDataEntryKey {
-                        block_id,
-                        chunk_index: index as u32,
-                    };
+                        let key = BlockDataEntryKey {
+                            block_id,
+                            chunk_index,
+                        };
+                        db.block_data_entries
+                            .insert(key.to_vec(), &buffer[offset..end])?;
+
+                        chunk_index += 1;
+                        total_len += end - offset;
+                        offset = end;
+                    }
+                    buffer.drain(..offset);
+                    Ok(())
+                };
 
-                    db.block_data_entries.insert(key.to_vec(), chunk)?;
+                for part in data.as_ref().chunks(chunk_size) {
+                    compressor.write(part, &mut buffer)?;
+                    flush(&mut buffer, false)?;
                 }
+                compressor.finish(&mut buffer)?;
+                flush(&mut buffer, true)?;
 
                 let key = BlockDataEntryKey {
                     block_id,
                     chunk_index: BLOCK_DATA_SIZE_MAGIC,
                 };
                 db.block_data_entries
-                    .insert(key.to_vec(), (compressed.len() as u32).to_le_bytes())?;
+                    .insert(key.to_vec(), (total_len as u32).to_le_bytes())?;
 
                 drop(permit);
 
@@ -1315,6 +1711,10 @@ struct CommitArchiveTask {
 }
 
 impl CommitArchiveTask {
+    fn is_finished(&self) -> bool {
+        self.handle.as_ref().is_none_or(JoinHandle::is_finished)
+    }
+
     async fn finish(&mut self) -> Result<()> {
         // NOTE: Await on reference to make sure that the task is cancel safe
         if let Some(handle) = &mut self.handle {
@@ -1385,7 +1785,7 @@ impl<'a> ArchiveWriter<'a> {
         self.flush(false)
     }
 
-    fn finalize(mut self) -> Result<()> {
+    fn finalize(mut self, block_ids: &[BlockId]) -> Result<()> {
         self.zstd_compressor.finish(&mut self.chunks_buffer)?;
 
         // Write the last chunk
@@ -1404,6 +1804,14 @@ impl<'a> ArchiveWriter<'a> {
         key[4..].copy_from_slice(&ARCHIVE_SIZE_MAGIC.to_be_bytes());
         batch.put_cf(&archives_cf, key.as_slice(), self.total_len.to_le_bytes());
 
+        // Keep block ids of the committed archive to find archived blocks after restart
+        let mut raw_block_ids = Vec::with_capacity(block_ids.len() * BlockId::SIZE_HINT);
+        for block_id in block_ids {
+            raw_block_ids.extend_from_slice(&block_id.to_vec());
+        }
+        key[4..].copy_from_slice(&ARCHIVE_BLOCK_IDS_MAGIC.to_be_bytes());
+        batch.put_cf(&archives_cf, key.as_slice(), raw_block_ids);
+
         // Remove related block ids
         batch.delete_cf(&block_ids_cf, self.archive_id.to_be_bytes());
 
@@ -1480,6 +1888,15 @@ pub struct StoreBlockResult {
     pub new: bool,
 }
 
+/// Block parts loaded from the committed archive.
+#[derive(Clone)]
+pub struct ArchivedBlock {
+    /// Compressed block data.
+    pub data: Bytes,
+    pub proof: Bytes,
+    pub queue_diff: Bytes,
+}
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum ArchiveId {
     Found(u32),
@@ -1493,6 +1910,59 @@ struct ArchiveIds {
     override_next_id: Option<u32>,
 }
 
+fn archive_chunks_iterator(db: &BaseDb, archive_id: u32) -> rocksdb::DBRawIterator<'_> {
+    let mut from = [0u8; tables::Archives::KEY_LEN];
+    from[..4].copy_from_slice(&archive_id.to_be_bytes());
+
+    let mut to = [0u8; tables::Archives::KEY_LEN];
+    to[..4].copy_from_slice(&archive_id.to_be_bytes());
+    to[4..].copy_from_slice(&ARCHIVE_MAGIC_MIN.to_be_bytes());
+
+    let mut read_opts = db.archives.new_read_config();
+    read_opts.set_iterate_upper_bound(to.as_slice());
+
+    let rocksdb = db.rocksdb();
+    let archives_cf = db.archives.cf();
+
+    let mut raw_iterator = rocksdb.raw_iterator_cf_opt(&archives_cf, read_opts);
+    raw_iterator.seek(from);
+
+    raw_iterator
+}
+
+struct BlockIdsIterator<'a> {
+    raw_iterator: rocksdb::DBRawIterator<'a>,
+    shard: ShardIdent,
+    /// `None` for an empty range
+    to: Option<u32>,
+}
+
+impl Iterator for BlockIdsIterator<'_> {
+    type Item = Result<BlockId, rocksdb::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let to = self.to?;
+        let Some((key, value)) = self.raw_iterator.item() else {
+            // NOTE: Invalid iterator is either the end or an error
+            self.to = None;
+            return match self.raw_iterator.status() {
+                Ok(()) => None,
+                Err(e) => Some(Err(e)),
+            };
+        };
+
+        let id = PartialBlockId::from_slice(key);
+        if id.shard != self.shard || id.seqno > to {
+            self.to = None;
+            return None;
+        }
+        let block_id = id.make_full(HashBytes::from_slice(value));
+
+        self.raw_iterator.next();
+        Some(Ok(block_id))
+    }
+}
+
 fn remove_blocks(
     db: BaseDb,
     max_blocks_per_batch: Option<usize>,
@@ -1655,6 +2125,53 @@ pub struct BlockStorageConfig {
     pub archive_chunk_size: ByteSize,
     pub blocks_cache: BlocksCacheConfig,
     pub split_block_tasks: usize,
+    pub archive_writer_tasks: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct BlockWriteBatchConfig {
+    /// Max number of batched stores before the batch is written.
+    pub max_entries: usize,
+    /// Max time since the first batched store before the batch is written.
+    pub max_delay: Duration,
+}
+
+struct PendingWrites {
+    config: BlockWriteBatchConfig,
+    batch: rocksdb::WriteBatch,
+    handles: Vec<(BlockHandle, BlockFlags)>,
+    first_write_at: Option<Instant>,
+}
+
+impl PendingWrites {
+    fn new(config: BlockWriteBatchConfig) -> Self {
+        Self {
+            config,
+            batch: Default::default(),
+            handles: Vec::new(),
+            first_write_at: None,
+        }
+    }
+
+    /// Writes the batch and returns handles which were updated by it.
+    fn write(&mut self, db: &BaseDb) -> Result<Vec<BlockHandle>, rocksdb::Error> {
+        if self.handles.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let batch = std::mem::take(&mut self.batch);
+        db.rocksdb().write(batch)?;
+        self.first_write_at = None;
+
+        // Flags are set only after the data is written
+        let mut updated = Vec::with_capacity(self.handles.len());
+        for (handle, flags) in self.handles.drain(..) {
+            if handle.meta().add_flags(flags) {
+                updated.push(handle);
+            }
+        }
+        Ok(updated)
+    }
 }
 
 #[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
@@ -1687,9 +2204,15 @@ const ARCHIVE_TO_COMMIT_MAGIC: u64 = u64::MAX - 1;
 const ARCHIVE_OVERRIDE_NEXT_MAGIC: u64 = u64::MAX - 2;
 // Reserved key in which we store the fact that archive was started
 const ARCHIVE_STARTED_MAGIC: u64 = u64::MAX - 3;
+// Reserved key in which we store ids of all blocks of the committed archive
+const ARCHIVE_BLOCK_IDS_MAGIC: u64 = u64::MAX - 4;
 
 const ARCHIVE_MAGIC_MIN: u64 = u64::MAX & !0xff;
 
+const ARCHIVED_BLOCKS_CACHE_SIZE: u64 = 64 << 20; // 64 MB
+const LOADED_ARCHIVES_CAPACITY: u64 = 2;
+const LOADED_ARCHIVES_TTL: Duration = Duration::from_secs(30);
+
 const BLOCK_DATA_CHUNK_SIZE: u32 = 1024 * 1024; // 1MB
 
 // Reserved key in which the compressed block size is stored
@@ -1707,6 +2230,8 @@ struct PreparedArchiveId {
 
 type ArchiveIdsTx = broadcast::Sender<u32>;
 type BlocksCache = moka::sync::Cache<BlockId, BlockStuff, FastHasherState>;
+type ArchivedBlocksCache = moka::sync::Cache<BlockId, ArchivedBlock, FastHasherState>;
+type LoadedArchivesCache = moka::sync::Cache<u32, Arc<Archive>, FastHasherState>;
 
 #[derive(thiserror::Error, Debug)]
 enum BlockStorageError {
@@ -1836,6 +2361,263 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn loading_block_tracks_access() -> Result<()> {
+        let (storage, _tmp_dir) = Storage::new_temp().await?;
+
+        let block = BlockStuff::new_empty(ShardIdent::MASTERCHAIN, 1);
+        let block = {
+            let data = BocRepr::encode_rayon(block.as_ref()).unwrap();
+            WithArchiveData::new(block, data)
+        };
+
+        let block_meta = NewBlockMeta {
+            is_key_block: false,
+            gen_utime: 0,
+            ref_by_mc_seqno: 1,
+        };
+        let res = storage
+            .block_storage()
+            .store_block_data(&block, &block.archive_data, block_meta)
+            .await?;
+        let handle = res.handle;
+
+        assert_eq!(handle.stats().times_loaded, 0);
+        assert_eq!(handle.stats().last_access, 0);
+
+        for i in 1..=3 {
+            let loaded = storage.block_storage().load_block_data(&handle).await?;
+            assert_eq!(loaded.id(), block.id());
+
+            let stats = handle.stats();
+            assert_eq!(stats.times_loaded, i);
+            assert!(stats.last_access > 0);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn large_block_data_round_trip() -> Result<()> {
+        use everscale_types::cell::{CellBuilder, Lazy};
+        use everscale_types::merkle::MerkleUpdate;
+        use rand::RngCore;
+
+        let (storage, _tmp_dir) = Storage::new_temp().await?;
+        let blocks = storage.block_storage();
+        let chunk_size = blocks.block_data_chunk_size().get();
+
+        // Random data is incompressible, so block data is split into several chunks
+        let mut layer = (0..40000)
+            .map(|_| {
+                let mut data = [0u8; 127];
+                rand::thread_rng().fill_bytes(&mut data);
+                let mut b = CellBuilder::new();
+                b.store_raw(&data, 127 * 8)?;
+                b.build()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        while layer.len() > 1 {
+            layer = layer
+                .chunks(4)
+                .map(|refs| {
+                    let mut b = CellBuilder::new();
+                    for cell in refs {
+                        b.store_reference(cell.clone())?;
+                    }
+                    b.build()
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+        }
+
+        let block_info = BlockInfo {
+            shard: ShardIdent::MASTERCHAIN,
+            seqno: 1,
+            ..Default::default()
+        };
+        let block = Block {
+            global_id: 0,
+            info: Lazy::new(&block_info)?,
+            value_flow: Lazy::new(&ValueFlow::default())?,
+            state_update: Lazy::new(&MerkleUpdate::default())?,
+            out_msg_queue_updates: OutMsgQueueUpdates {
+                diff_hash: Default::default(),
+                tail_len: 0,
+            },
+            extra: Lazy::new(&BlockExtra {
+                in_msg_description: Lazy::from_raw(layer.pop().unwrap())?,
+                ..Default::default()
+            })?,
+        };
+
+        let root = CellBuilder::build_from(&block)?;
+        let data = Boc::encode(&root);
+        let block_id = BlockId {
+            shard: block_info.shard,
+            seqno: block_info.seqno,
+            root_hash: *root.repr_hash(),
+            file_hash: Boc::file_hash_blake(&data),
+        };
+        let block = BlockStuff::from_block_and_root(&block_id, block, root, data.len());
+        let block = WithArchiveData::new(block, data.clone());
+
+        let res = blocks
+            .store_block_data(&block, &block.archive_data, NewBlockMeta {
+                is_key_block: false,
+                gen_utime: 0,
+                ref_by_mc_seqno: 1,
+            })
+            .await?;
+
+        // Raw data is stored as is
+        let raw = blocks.load_block_data_raw(&res.handle).await?;
+        assert_eq!(raw.as_ref(), data.as_slice());
+
+        // Wait for the background split to finish
+        let size = tokio::time::timeout(Duration::from_secs(30), async {
+            loop {
+                if let Some(size) = blocks.get_block_data_size(&block_id)? {
+                    break Ok::<_, anyhow::Error>(size);
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await??;
+        assert!(size > chunk_size);
+
+        let mut compressed = Vec::new();
+        for offset in (0..size).step_by(chunk_size as usize) {
+            let chunk = blocks.get_block_data_chunk(&block_id, offset)?.unwrap();
+            compressed.extend_from_slice(chunk.as_ref());
+        }
+        assert_eq!(compressed.len(), size as usize);
+
+        // Chunks are decompressed into an identical block
+        let mut decompressed = Vec::new();
+        tycho_util::compression::zstd_decompress(&compressed, &mut decompressed)?;
+        assert_eq!(decompressed, data);
+
+        let parsed = BlockStuff::deserialize_checked(&block_id, &decompressed)?;
+        assert_eq!(parsed.id(), &block_id);
+        assert_eq!(parsed.root_cell().repr_hash(), &block_id.root_hash);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn batched_writes_visible_after_flush() -> Result<()> {
+        let (storage, _tmp_dir) = Storage::new_temp().await?;
+        let blocks = storage.block_storage();
+
+        blocks.begin_write_batch(BlockWriteBatchConfig {
+            max_entries: 1000,
+            max_delay: Duration::from_secs(3600),
+        });
+
+        let mut handles = Vec::new();
+        for seqno in 1..=10 {
+            let block_id = BlockId {
+                shard: ShardIdent::MASTERCHAIN,
+                seqno,
+                root_hash: HashBytes(rand::random()),
+                file_hash: HashBytes(rand::random()),
+            };
+
+            let proof = BlockProofStuff::new_empty(&block_id);
+            let proof = {
+                let data = BocRepr::encode_rayon(proof.as_ref()).unwrap();
+                WithArchiveData::new(proof, data)
+            };
+
+            let res = blocks
+                .store_block_proof(
+                    &proof,
+                    MaybeExistingHandle::New(NewBlockMeta {
+                        is_key_block: false,
+                        gen_utime: 0,
+                        ref_by_mc_seqno: seqno,
+                    }),
+                )
+                .await?;
+            // Nothing is visible until flush
+            assert!(!res.updated);
+            assert!(!res.handle.has_proof());
+            handles.push(res.handle);
+        }
+
+        let updated = blocks.end_write_batch()?;
+        assert_eq!(updated.len(), handles.len());
+
+        for handle in &handles {
+            assert!(handle.has_proof());
+            let proof = blocks.load_block_proof(handle).await?;
+            assert_eq!(proof.id(), handle.id());
+        }
+
+        // Handle flags must also be persisted
+        let block_ids = handles.iter().map(|h| *h.id()).collect::<Vec<_>>();
+        drop(handles);
+        for block_id in &block_ids {
+            let handle = storage
+                .block_handle_storage()
+                .load_handle(block_id)
+                .unwrap();
+            assert!(handle.has_proof());
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn batched_writes_flushed_after_delay() -> Result<()> {
+        let (storage, _tmp_dir) = Storage::new_temp().await?;
+        let blocks = storage.block_storage();
+
+        blocks.begin_write_batch(BlockWriteBatchConfig {
+            max_entries: 1000,
+            max_delay: Duration::from_millis(50),
+        });
+
+        let block_id = BlockId {
+            shard: ShardIdent::MASTERCHAIN,
+            seqno: 1,
+            root_hash: HashBytes(rand::random()),
+            file_hash: HashBytes(rand::random()),
+        };
+
+        let proof = BlockProofStuff::new_empty(&block_id);
+        let proof = {
+            let data = BocRepr::encode_rayon(proof.as_ref()).unwrap();
+            WithArchiveData::new(proof, data)
+        };
+
+        let res = blocks
+            .store_block_proof(
+                &proof,
+                MaybeExistingHandle::New(NewBlockMeta {
+                    is_key_block: false,
+                    gen_utime: 0,
+                    ref_by_mc_seqno: 1,
+                }),
+            )
+            .await?;
+        assert!(!res.handle.has_proof());
+
+        // A lone store must be written without any other stores or flushes
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while !res.handle.has_proof() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await?;
+
+        let loaded = blocks.load_block_proof(&res.handle).await?;
+        assert_eq!(loaded.id(), &block_id);
+
+        assert!(blocks.end_write_batch()?.is_empty());
+        Ok(())
+    }
+
     #[tokio::test]
     async fn blocks_gc() -> Result<()> {
         const GARBAGE: &[u8] = b"garbage";
@@ -1963,4 +2745,56 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn iter_block_ids_in_range() -> Result<()> {
+        let (storage, _tmp_dir) = Storage::new_temp().await?;
+
+        let blocks = storage.block_storage();
+        let block_handles = storage.block_handle_storage();
+
+        let mut mc_block_ids = Vec::new();
+        for shard in [ShardIdent::MASTERCHAIN, ShardIdent::BASECHAIN] {
+            for seqno in 0..10 {
+                let block_id = BlockId {
+                    shard,
+                    seqno,
+                    root_hash: HashBytes(rand::random()),
+                    file_hash: HashBytes(rand::random()),
+                };
+                if shard.is_masterchain() {
+                    mc_block_ids.push(block_id);
+                }
+
+                block_handles.create_or_load_handle(&block_id, NewBlockMeta {
+                    is_key_block: false,
+                    gen_utime: 0,
+                    ref_by_mc_seqno: seqno,
+                });
+            }
+        }
+
+        let ids = blocks
+            .iter_block_ids(ShardIdent::MASTERCHAIN, 3..=6)
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(ids, mc_block_ids[3..=6]);
+
+        let ids = blocks
+            .iter_block_ids(ShardIdent::MASTERCHAIN, 8..)
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(ids, mc_block_ids[8..]);
+
+        let ids = blocks
+            .iter_block_ids(ShardIdent::BASECHAIN, ..)
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(ids.len(), 10);
+        assert!(ids.iter().all(|id| id.shard == ShardIdent::BASECHAIN));
+
+        for range in [5..5, 20..u32::MAX] {
+            let mut ids = blocks.iter_block_ids(ShardIdent::MASTERCHAIN, range);
+            assert!(ids.next().is_none());
+        }
+
+        Ok(())
+    }
 }
@@ -1,3 +1,4 @@
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
 use std::sync::{Arc, Weak};
 
 use everscale_types::models::*;
@@ -36,6 +37,7 @@ impl BlockHandle {
                 block_data_lock: Default::default(),
                 proof_data_block: Default::default(),
                 queue_diff_data_lock: Default::default(),
+                access_stats: Default::default(),
                 cache,
             }),
         }
@@ -130,6 +132,26 @@ impl BlockHandle {
         }
     }
 
+    /// Returns in-memory access statistics of this handle.
+    ///
+    /// NOTE: Stats are not persisted and are reset when the handle
+    /// is evicted from the cache.
+    pub fn stats(&self) -> BlockHandleStats {
+        let stats = &self.inner.access_stats;
+        BlockHandleStats {
+            times_loaded: stats.times_loaded.load(Ordering::Relaxed),
+            last_access: stats.last_access.load(Ordering::Relaxed),
+        }
+    }
+
+    pub(crate) fn track_access(&self) {
+        let stats = &self.inner.access_stats;
+        stats.times_loaded.fetch_add(1, Ordering::Relaxed);
+        stats
+            .last_access
+            .store(tycho_util::time::now_sec(), Ordering::Relaxed);
+    }
+
     pub(crate) fn block_data_lock(&self) -> &BlockDataLock {
         &self.inner.block_data_lock
     }
@@ -168,9 +190,24 @@ pub struct Inner {
     block_data_lock: BlockDataLock,
     proof_data_block: BlockDataLock,
     queue_diff_data_lock: BlockDataLock,
+    access_stats: AccessStats,
     cache: Arc<BlockHandleCache>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockHandleStats {
+    /// How many times block data, proof or queue diff was loaded.
+    pub times_loaded: u64,
+    /// Unix timestamp of the last load (in seconds), zero if never loaded.
+    pub last_access: u32,
+}
+
+#[derive(Default)]
+struct AccessStats {
+    times_loaded: AtomicU64,
+    last_access: AtomicU32,
+}
+
 impl Drop for Inner {
     fn drop(&mut self) {
         self.cache
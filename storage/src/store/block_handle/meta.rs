@@ -73,6 +73,14 @@ impl BlockMeta {
         self.gen_utime
     }
 
+    /// Merge operand which only adds the specified flags to the stored meta.
+    pub(crate) fn flags_merge_operand(flags: BlockFlags) -> [u8; Self::SIZE_HINT] {
+        let mut operand = [0u8; Self::SIZE_HINT];
+        let flags = (flags.bits() as u64) << BLOCK_FLAGS_OFFSET;
+        operand[..8].copy_from_slice(&flags.to_le_bytes());
+        operand
+    }
+
     pub(crate) fn add_flags(&self, flags: BlockFlags) -> bool {
         let flags = (flags.bits() as u64) << BLOCK_FLAGS_OFFSET;
         self.flags.fetch_or(flags, Ordering::Release) & flags != flags
@@ -1,3 +1,4 @@
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
 use everscale_types::models::BlockId;
@@ -5,10 +6,10 @@ use tycho_block_util::block::{BlockStuff, ShardHeights};
 use tycho_util::FastDashMap;
 
 pub(crate) use self::handle::BlockDataGuard;
-pub use self::handle::{BlockHandle, WeakBlockHandle};
+pub use self::handle::{BlockHandle, BlockHandleStats, WeakBlockHandle};
 pub use self::meta::{BlockFlags, BlockMeta, LoadedBlockMeta, NewBlockMeta};
 use crate::db::*;
-use crate::store::PartialBlockId;
+use crate::store::{PartialBlockId, StorageSnapshot};
 use crate::util::*;
 
 mod handle;
@@ -19,6 +20,8 @@ type BlockHandleCache = FastDashMap<BlockId, WeakBlockHandle>;
 pub struct BlockHandleStorage {
     db: BaseDb,
     cache: Arc<BlockHandleCache>,
+    cache_hits: AtomicU64,
+    cache_misses: AtomicU64,
 }
 
 impl BlockHandleStorage {
@@ -26,6 +29,16 @@ impl BlockHandleStorage {
         Self {
             db,
             cache: Arc::new(Default::default()),
+            cache_hits: AtomicU64::new(0),
+            cache_misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Lookups of handles since start. A low hit ratio during sync means the cache is too small.
+    pub fn cache_stats(&self) -> BlockHandleCacheStats {
+        BlockHandleCacheStats {
+            hits: self.cache_hits.load(Ordering::Relaxed),
+            misses: self.cache_misses.load(Ordering::Relaxed),
         }
     }
 
@@ -77,9 +90,11 @@ impl BlockHandleStorage {
         // Fast path - lookup in cache
         if let Some(handle) = self.cache.get(block_id) {
             if let Some(handle) = handle.upgrade() {
+                self.track_cache_access(true);
                 return (handle, HandleCreationStatus::Fetched);
             }
         }
+        self.track_cache_access(false);
 
         match block_handles.get(block_id.root_hash.as_slice()).unwrap() {
             // Try to load block handle from an existing data
@@ -131,9 +146,11 @@ impl BlockHandleStorage {
         // Fast path - lookup in cache
         if let Some(handle) = self.cache.get(block_id) {
             if let Some(handle) = handle.upgrade() {
+                self.track_cache_access(true);
                 return Some(handle);
             }
         }
+        self.track_cache_access(false);
 
         // Load meta from storage
         let meta = match block_handles.get(block_id.root_hash.as_slice()).unwrap() {
@@ -198,6 +215,50 @@ impl BlockHandleStorage {
         self.load_handle(&key_block_id)
     }
 
+    /// Same as [`Self::load_key_block_handle`], but reads the key block id
+    /// and its meta as they were when the snapshot was taken.
+    ///
+    /// See [`Self::load_handle_at`].
+    pub fn load_key_block_handle_at(
+        &self,
+        snapshot: &StorageSnapshot,
+        seqno: u32,
+    ) -> Option<BlockHandle> {
+        let key_block_id = match snapshot
+            .get(&self.db.key_blocks, seqno.to_be_bytes())
+            .unwrap()
+        {
+            Some(data) => BlockId::from_slice(data.as_ref()),
+            None => return None,
+        };
+        self.load_handle_at(snapshot, &key_block_id)
+    }
+
+    /// Loads the block handle as it was when the snapshot was taken.
+    ///
+    /// NOTE: The returned handle is not added to the shared cache since its flags
+    /// might be outdated, so it must be used only to read data from the same snapshot.
+    pub fn load_handle_at(
+        &self,
+        snapshot: &StorageSnapshot,
+        block_id: &BlockId,
+    ) -> Option<BlockHandle> {
+        let meta = self.load_meta_at(snapshot, block_id)?;
+        Some(BlockHandle::new(block_id, meta, self.cache.clone()))
+    }
+
+    /// Loads the block meta as it was when the snapshot was taken, bypassing the cache.
+    pub fn load_meta_at(
+        &self,
+        snapshot: &StorageSnapshot,
+        block_id: &BlockId,
+    ) -> Option<BlockMeta> {
+        let data = snapshot
+            .get(&self.db.block_handles, block_id.root_hash.as_slice())
+            .unwrap()?;
+        Some(BlockMeta::from_slice(data.as_ref()))
+    }
+
     pub fn find_last_key_block(&self) -> Option<BlockHandle> {
         let mut iter = self.db.key_blocks.raw_iterator();
         iter.seek_to_last();
@@ -316,6 +377,20 @@ impl BlockHandleStorage {
         total_removed
     }
 
+    fn track_cache_access(&self, hit: bool) {
+        if hit {
+            self.cache_hits.fetch_add(1, Ordering::Relaxed);
+            metrics::counter!("tycho_storage_block_handle_cache_hits").increment(1);
+        } else {
+            self.cache_misses.fetch_add(1, Ordering::Relaxed);
+            metrics::counter!("tycho_storage_block_handle_cache_misses").increment(1);
+        }
+
+        if let Some(ratio) = self.cache_stats().hit_ratio() {
+            metrics::gauge!("tycho_storage_block_handle_cache_hit_ratio").set(ratio);
+        }
+    }
+
     fn fill_cache(&self, block_id: &BlockId, meta: BlockMeta) -> BlockHandle {
         use dashmap::mapref::entry::Entry;
 
@@ -337,6 +412,20 @@ impl BlockHandleStorage {
     }
 }
 
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub struct BlockHandleCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl BlockHandleCacheStats {
+    /// Returns `None` if there were no lookups yet.
+    pub fn hit_ratio(&self) -> Option<f64> {
+        let total = self.hits + self.misses;
+        (total > 0).then(|| self.hits as f64 / total as f64)
+    }
+}
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum HandleCreationStatus {
     Created,
@@ -433,4 +522,100 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn key_block_handle_from_snapshot() -> anyhow::Result<()> {
+        let (storage, _tmp_dir) = Storage::new_temp().await?;
+
+        let block_handles = storage.block_handle_storage();
+
+        let block_id = BlockId {
+            shard: ShardIdent::MASTERCHAIN,
+            seqno: 10,
+            ..Default::default()
+        };
+        let meta = NewBlockMeta {
+            is_key_block: true,
+            gen_utime: 123,
+            ref_by_mc_seqno: 10,
+        };
+
+        let (handle, _) = block_handles.create_or_load_handle(&block_id, meta);
+        let snapshot = storage.snapshot();
+        assert!(block_handles.set_block_committed(&handle));
+
+        // Snapshot handle has flags as they were when the snapshot was taken
+        let snapshot_handle = block_handles
+            .load_key_block_handle_at(&snapshot, 10)
+            .unwrap();
+        assert_eq!(snapshot_handle.id(), &block_id);
+        assert!(snapshot_handle.is_key_block());
+        assert!(!snapshot_handle.is_committed());
+
+        // And it does not replace the cached one
+        let live_handle = block_handles.load_key_block_handle(10).unwrap();
+        assert!(live_handle.is_committed());
+        assert_eq!(
+            arc_swap::RefCnt::as_ptr(&handle),
+            arc_swap::RefCnt::as_ptr(&live_handle),
+        );
+
+        // Key blocks added after the snapshot are not visible
+        let new_block_id = BlockId {
+            seqno: 20,
+            ..block_id
+        };
+        block_handles.create_or_load_handle(&new_block_id, NewBlockMeta {
+            ref_by_mc_seqno: 20,
+            ..meta
+        });
+        assert!(block_handles
+            .load_key_block_handle_at(&snapshot, 20)
+            .is_none());
+        assert!(block_handles.load_key_block_handle(20).is_some());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn cache_stats_count_hits_and_misses() -> anyhow::Result<()> {
+        let (storage, _tmp_dir) = Storage::new_temp().await?;
+
+        let block_handles = storage.block_handle_storage();
+        let initial = block_handles.cache_stats();
+
+        let block_id = BlockId {
+            shard: ShardIdent::BASECHAIN,
+            seqno: 100,
+            ..Default::default()
+        };
+        let meta = NewBlockMeta {
+            is_key_block: false,
+            gen_utime: 123,
+            ref_by_mc_seqno: 456,
+        };
+
+        // Novel handle is not cached
+        let (handle, _) = block_handles.create_or_load_handle(&block_id, meta);
+        let stats = block_handles.cache_stats();
+        assert_eq!(stats.hits, initial.hits);
+        assert_eq!(stats.misses, initial.misses + 1);
+
+        // Repeated loads are served from cache while the handle is alive
+        block_handles.load_handle(&block_id).unwrap();
+        block_handles.create_or_load_handle(&block_id, meta);
+        let stats = block_handles.cache_stats();
+        assert_eq!(stats.hits, initial.hits + 2);
+        assert_eq!(stats.misses, initial.misses + 1);
+
+        // Dropped handle is loaded from storage
+        drop(handle);
+        block_handles.load_handle(&block_id).unwrap();
+        let stats = block_handles.cache_stats();
+        assert_eq!(stats.hits, initial.hits + 2);
+        assert_eq!(stats.misses, initial.misses + 2);
+        assert!(stats.hit_ratio().is_some());
+
+        Ok(())
+    }
 }
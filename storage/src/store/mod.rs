@@ -7,6 +7,7 @@ pub use self::node_state::*;
 pub use self::persistent_state::*;
 pub use self::rpc::*;
 pub use self::shard_state::*;
+pub use self::snapshot::*;
 pub use self::temp_file::*;
 
 mod block;
@@ -18,4 +19,5 @@ mod node_state;
 mod persistent_state;
 mod rpc;
 mod shard_state;
+mod snapshot;
 mod temp_file;
@@ -4,6 +4,7 @@ use everscale_types::models::*;
 use parking_lot::Mutex;
 
 use crate::db::*;
+use crate::store::{BlockFlags, BlockHandle, BlockMeta};
 use crate::util::*;
 
 pub struct NodeStateStorage {
@@ -50,6 +51,28 @@ impl NodeStateStorage {
         self.store_block_id(&self.last_mc_block_id, id);
     }
 
+    /// Marks the masterchain block as committed and makes it the last one
+    /// in a single write batch, so the position never outruns applied blocks.
+    pub fn commit_last_mc_block(&self, handle: &BlockHandle) {
+        let id = handle.id();
+        debug_assert!(id.is_masterchain());
+
+        let mut batch = weedb::rocksdb::WriteBatch::default();
+        batch.merge_cf(
+            &self.db.block_handles.cf(),
+            id.root_hash,
+            BlockMeta::flags_merge_operand(BlockFlags::IS_COMMITTED),
+        );
+        batch.put_cf(&self.db.state.cf(), LAST_MC_BLOCK_ID, write_block_id_le(id));
+        self.db
+            .rocksdb()
+            .write_opt(batch, self.db.state.write_config())
+            .unwrap();
+
+        handle.meta().add_flags(BlockFlags::IS_COMMITTED);
+        *self.last_mc_block_id.0.lock() = Some(*id);
+    }
+
     pub fn load_last_mc_block_id(&self) -> Option<BlockId> {
         self.load_block_id(&self.last_mc_block_id)
     }
@@ -90,6 +113,6 @@ impl NodeStateStorage {
 
 type BlockIdCache = (Mutex<Option<BlockId>>, &'static [u8]);
 
-const LAST_MC_BLOCK_ID: &[u8] = b"last_mc_block";
+pub(crate) const LAST_MC_BLOCK_ID: &[u8] = b"last_mc_block";
 const INIT_MC_BLOCK_ID: &[u8] = b"init_mc_block";
 const INSTANCE_ID: &[u8] = b"instance_id";
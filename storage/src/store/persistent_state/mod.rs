@@ -243,6 +243,17 @@ impl PersistentStateStorage {
         })
     }
 
+    /// Returns ids of all blocks with a stored persistent shard state,
+    /// sorted by seqno in descending order.
+    pub fn persistent_states(&self) -> Vec<BlockId> {
+        let mut block_ids = (self.inner.descriptor_cache.iter())
+            .filter(|item| item.key().kind == PersistentStateKind::Shard)
+            .map(|item| item.key().block_id)
+            .collect::<Vec<_>>();
+        block_ids.sort_unstable_by(|a, b| b.seqno.cmp(&a.seqno));
+        block_ids
+    }
+
     pub fn get_state_info(
         &self,
         block_id: &BlockId,
@@ -66,6 +66,7 @@ async fn persistent_shard_state() -> Result<()> {
 
     // Write persistent state to file
     assert!(persistent_states.load_oldest_known_handle().is_none());
+    assert!(persistent_states.persistent_states().is_empty());
 
     persistent_states
         .store_shard_state(0, &handle, zerostate.ref_mc_state_handle().clone())
@@ -74,6 +75,7 @@ async fn persistent_shard_state() -> Result<()> {
     // Check if state exists
     let exist = persistent_states.state_exists(zerostate.block_id(), PersistentStateKind::Shard);
     assert!(exist);
+    assert_eq!(persistent_states.persistent_states(), [zerostate_id]);
 
     let read_verify_state = || async {
         let persistent_state_data = persistent_states
@@ -163,6 +165,7 @@ async fn persistent_shard_state() -> Result<()> {
         .await?;
 
     let new_persistent_states = new_storage.persistent_state_storage();
+    assert_eq!(new_persistent_states.persistent_states(), [zerostate_id]);
 
     {
         let index = new_persistent_states.inner.mc_seqno_to_block_ids.lock();
@@ -408,11 +408,12 @@ impl CellStorage {
         &self,
         alloc: &Bump,
         hash: &HashBytes,
-    ) -> Result<(usize, WriteBatch), CellStorageError> {
+    ) -> Result<(RemovedCellsStats, WriteBatch), CellStorageError> {
         #[derive(Clone, Copy)]
         struct RemovedCell<'a> {
             old_rc: i64,
             removes: u32,
+            size: usize,
             refs: &'a [HashBytes],
         }
 
@@ -456,7 +457,7 @@ impl CellStorage {
                 let refs = match transaction.entry(cell_id) {
                     hash_map::Entry::Occupied(mut v) => v.get_mut().remove()?,
                     hash_map::Entry::Vacant(v) => {
-                        let old_rc = self.raw_cells_cache.get_rc_for_delete(
+                        let (old_rc, size) = self.raw_cells_cache.get_rc_for_delete(
                             &self.db,
                             cell_id,
                             &mut buffer,
@@ -466,6 +467,7 @@ impl CellStorage {
                         v.insert(RemovedCell {
                             old_rc,
                             removes: 1,
+                            size,
                             refs: alloc.alloc_slice_copy(buffer.as_slice()),
                         })
                         .next_refs()
@@ -489,6 +491,10 @@ impl CellStorage {
         // Write transaction to the `WriteBatch`
         let _hist = HistogramGuard::begin("tycho_storage_batch_write_time");
         let total = transaction.len();
+        let mut stats = RemovedCellsStats {
+            scanned: total,
+            ..Default::default()
+        };
 
         // NOTE: For each cell we have 32 bytes for key and 8 bytes for RC,
         //       and a bit more just in case.
@@ -503,9 +509,14 @@ impl CellStorage {
 
             let new_rc = item.old_rc - item.removes as i64;
             self.raw_cells_cache.on_remove_cell(key, new_rc);
+
+            if new_rc <= 0 {
+                stats.freed += 1;
+                stats.freed_bytes += (key.as_slice().len() + item.size) as u64;
+            }
         }
 
-        Ok((total, batch))
+        Ok((stats, batch))
     }
 
     pub fn drop_cell(&self, hash: &HashBytes) {
@@ -515,6 +526,17 @@ impl CellStorage {
     }
 }
 
+/// Stats of a single [`CellStorage::remove_cell`] call.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RemovedCellsStats {
+    /// Number of unique cells with decremented reference counters.
+    pub scanned: usize,
+    /// Number of cells that are no longer referenced.
+    pub freed: usize,
+    /// Approximate size of freed cells (keys and values).
+    pub freed_bytes: u64,
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum CellStorageError {
     #[error("Cell not found in cell db")]
@@ -1026,7 +1048,7 @@ impl RawCellsCache {
         db: &BaseDb,
         key: &HashBytes,
         refs_buffer: &mut Vec<HashBytes>,
-    ) -> Result<i64, CellStorageError> {
+    ) -> Result<(i64, usize), CellStorageError> {
         refs_buffer.clear();
 
         // NOTE: `peek` here is used to avoid affecting a "hotness" of the value
@@ -1036,7 +1058,7 @@ impl RawCellsCache {
                 return Err(CellStorageError::CellNotFound);
             } else if rc != i64::MAX {
                 return StorageCell::deserialize_references(&value.slice, refs_buffer)
-                    .then_some(rc)
+                    .then_some((rc, value.slice.len()))
                     .ok_or(CellStorageError::InvalidCell);
             }
         }
@@ -1046,7 +1068,7 @@ impl RawCellsCache {
                 if let Some(value) = value {
                     if let (rc, Some(value)) = refcount::decode_value_with_rc(&value) {
                         return StorageCell::deserialize_references(value, refs_buffer)
-                            .then_some(rc)
+                            .then_some((rc, value.len()))
                             .ok_or(CellStorageError::InvalidCell);
                     }
                 }
@@ -1095,3 +1117,64 @@ impl RawCellsCache {
         metrics::gauge!("tycho_storage_raw_cells_cache_size").set(self.inner.weight() as f64);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use weedb::rocksdb::IteratorMode;
+
+    use super::*;
+    use crate::Storage;
+
+    #[tokio::test]
+    async fn removed_state_cells_are_reclaimed() -> Result<()> {
+        let (storage, _tmp_dir) = Storage::new_temp().await?;
+        let base_db = storage.base_db();
+        let cell_storage = &storage.shard_state_storage().cell_storage;
+
+        let leaf = |value: u32| CellBuilder::build_from(value).unwrap();
+        let root = |children: &[Cell]| {
+            let mut builder = CellBuilder::new();
+            for child in children {
+                builder.store_reference(child.clone()).unwrap();
+            }
+            builder.build().unwrap()
+        };
+
+        // Both states share the same cell
+        let shared = leaf(0);
+        let old_state = root(&[shared.clone(), leaf(1)]);
+        let new_state = root(&[shared, leaf(2)]);
+
+        for state in [&old_state, &new_state] {
+            let mut batch = WriteBatch::new();
+            cell_storage.store_cell(&mut batch, state.as_ref(), 3)?;
+            base_db
+                .rocksdb()
+                .write_opt(batch, base_db.cells.write_config())?;
+        }
+
+        let bump = Bump::new();
+        let (stats, batch) = cell_storage.remove_cell(&bump, old_state.repr_hash())?;
+        base_db
+            .rocksdb()
+            .write_opt(batch, base_db.cells.write_config())?;
+
+        assert_eq!(stats.scanned, 3);
+        assert_eq!(stats.freed, 2);
+        assert!(stats.freed_bytes > 0);
+
+        // two compactions in row. First one run merge operators, second one will remove all tombstones
+        base_db.trigger_compaction().await;
+        base_db.trigger_compaction().await;
+
+        // Only cells of the live state are left
+        let cells_left = base_db.cells.iterator(IteratorMode::Start).count();
+        assert_eq!(cells_left, 3);
+
+        let loaded = cell_storage.load_cell(*new_state.repr_hash())?;
+        assert_eq!(loaded.repr_hash(), new_state.repr_hash());
+        assert_eq!(loaded.reference_count(), 2);
+
+        Ok(())
+    }
+}
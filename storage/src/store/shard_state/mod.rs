@@ -15,7 +15,9 @@ use weedb::rocksdb;
 use self::cell_storage::*;
 use self::store_state_raw::StoreStateContext;
 use crate::db::*;
-use crate::store::{BlockFlags, BlockHandle, BlockHandleStorage, BlockStorage, TempFileStorage};
+use crate::store::{
+    BlockFlags, BlockHandle, BlockHandleStorage, BlockStorage, StorageSnapshot, TempFileStorage,
+};
 use crate::util::*;
 
 mod cell_storage;
@@ -31,6 +33,8 @@ pub struct ShardStateStorage {
     temp_file_storage: TempFileStorage,
 
     gc_lock: tokio::sync::Mutex<()>,
+    /// Serializes whole GC runs: scheduled and manual ones
+    gc_run_lock: tokio::sync::Mutex<()>,
     min_ref_mc_state: MinRefMcStateTracker,
     max_new_mc_cell_count: AtomicUsize,
     max_new_sc_cell_count: AtomicUsize,
@@ -53,6 +57,7 @@ impl ShardStateStorage {
             temp_file_storage,
             cell_storage,
             gc_lock: Default::default(),
+            gc_run_lock: Default::default(),
             min_ref_mc_state: MinRefMcStateTracker::new(),
             max_new_mc_cell_count: AtomicUsize::new(0),
             max_new_sc_cell_count: AtomicUsize::new(0),
@@ -187,12 +192,19 @@ impl ShardStateStorage {
         ShardStateStuff::from_root(block_id, Cell::from(cell as Arc<_>), &self.min_ref_mc_state)
     }
 
+    /// Removes states (and cells which are no longer referenced)
+    /// older than the edge computed for the specified masterchain seqno.
+    ///
+    /// States referenced by [`MinRefMcStateTracker`] are always kept.
+    /// Concurrent runs are executed one after another.
     #[tracing::instrument(skip(self))]
-    pub async fn remove_outdated_states(&self, mc_seqno: u32) -> Result<()> {
+    pub async fn remove_outdated_states(&self, mc_seqno: u32) -> Result<StatesGcStats> {
+        let _gc_run_lock = self.gc_run_lock.lock().await;
+
         // Compute recent block ids for the specified masterchain seqno
         let Some(top_blocks) = self.compute_recent_blocks(mc_seqno).await? else {
             tracing::warn!("recent blocks edge not found");
-            return Ok(());
+            return Ok(StatesGcStats::default());
         };
 
         tracing::info!(
@@ -216,8 +228,7 @@ impl ShardStateStorage {
         iter.seek_to_first();
 
         // Iterate all states and remove outdated
-        let mut removed_states = 0usize;
-        let mut removed_cells = 0usize;
+        let mut stats = StatesGcStats::default();
         loop {
             let _hist = HistogramGuard::begin("tycho_storage_state_gc_time");
             let (key, value) = match iter.item() {
@@ -260,13 +271,13 @@ impl ShardStateStorage {
                 })
                 .await??;
 
-                removed_cells += total;
+                stats.add_cells(&total);
                 alloc = inner_alloc; // Reuse allocation without passing alloc by ref
 
-                tracing::debug!(removed_cells = total, %block_id);
+                tracing::debug!(removed_cells = total.scanned, %block_id);
             }
 
-            removed_states += 1;
+            stats.removed_states += 1;
             iter.next();
 
             metrics::counter!("tycho_storage_state_gc_count").increment(1);
@@ -274,18 +285,25 @@ impl ShardStateStorage {
             if block_id.is_masterchain() {
                 metrics::gauge!("tycho_gc_states_seqno").set(block_id.seqno as f64);
             }
-            tracing::debug!(removed_states, removed_cells, %block_id, "removed state");
+            tracing::debug!(
+                removed_states = stats.removed_states,
+                removed_cells = stats.scanned_cells,
+                %block_id,
+                "removed state"
+            );
         }
 
         // Done
         tracing::info!(
-            removed_states,
-            removed_cells,
+            removed_states = stats.removed_states,
+            scanned_cells = stats.scanned_cells,
+            freed_cells = stats.freed_cells,
+            freed_bytes = stats.freed_bytes,
             block_id = %top_blocks.mc_block,
             elapsed_sec = started_at.elapsed().as_secs_f64(),
             "finished states GC",
         );
-        Ok(())
+        Ok(stats)
     }
 
     /// Searches for an edge with the least referenced masterchain block
@@ -357,6 +375,20 @@ impl ShardStateStorage {
         }
     }
 
+    /// Same as [`Self::load_state_root`], but reads the root as it was
+    /// when the snapshot was taken.
+    pub fn load_state_root_at(
+        &self,
+        snapshot: &StorageSnapshot,
+        block_id: &BlockId,
+    ) -> Result<HashBytes> {
+        let shard_state = snapshot.get(&self.db.shard_states, block_id.to_vec())?;
+        match shard_state {
+            Some(root) => Ok(HashBytes::from_slice(&root[..32])),
+            None => Err(ShardStateStorageError::NotFound.into()),
+        }
+    }
+
     fn find_mc_block_id(
         &self,
         mc_seqno: u32,
@@ -387,6 +419,26 @@ impl ShardStateStorage {
     }
 }
 
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatesGcStats {
+    pub removed_states: usize,
+    pub scanned_cells: usize,
+    pub freed_cells: usize,
+    pub freed_bytes: u64,
+}
+
+impl StatesGcStats {
+    fn add_cells(&mut self, cells: &RemovedCellsStats) {
+        self.scanned_cells += cells.scanned;
+        self.freed_cells += cells.freed;
+        self.freed_bytes += cells.freed_bytes;
+
+        metrics::counter!("tycho_storage_cell_gc_scanned_count").increment(cells.scanned as _);
+        metrics::counter!("tycho_storage_cell_gc_freed_count").increment(cells.freed as _);
+        metrics::counter!("tycho_storage_cell_gc_freed_bytes").increment(cells.freed_bytes);
+    }
+}
+
 #[derive(Default, Debug, Clone, Copy)]
 pub struct StoreStateHint {
     pub block_data_size: Option<usize>,
@@ -417,3 +469,42 @@ pub enum ShardStateStorageError {
     #[error("Block handle id mismatch")]
     BlockHandleIdMismatch,
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::Storage;
+
+    #[tokio::test]
+    async fn concurrent_gc_runs_are_serialized() -> Result<()> {
+        let (storage, _tmp_dir) = Storage::new_temp().await?;
+        let shard_states = storage.shard_state_storage();
+
+        // a run in progress blocks the next one until it finishes
+        let running = shard_states.gc_run_lock.lock().await;
+        let next_run = tokio::spawn({
+            let storage = storage.clone();
+            async move {
+                let shard_states = storage.shard_state_storage();
+                shard_states.remove_outdated_states(10).await
+            }
+        });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!next_run.is_finished());
+
+        drop(running);
+        tokio::time::timeout(Duration::from_secs(5), next_run).await???;
+
+        // manual and scheduled runs at the same time
+        let (manual, scheduled) = tokio::join!(
+            storage.run_cell_gc(),
+            shard_states.remove_outdated_states(10),
+        );
+        manual?;
+        scheduled?;
+
+        Ok(())
+    }
+}
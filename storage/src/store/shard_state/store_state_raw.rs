@@ -739,7 +739,7 @@ mod test {
             base_db
                 .rocksdb()
                 .write_opt(batch, base_db.cells.write_config())?;
-            tracing::info!("Gc {id} of {total} done. Traversed: {res}",);
+            tracing::info!("Gc {id} of {total} done. Traversed: {}", res.scanned);
             bump.reset();
         }
 
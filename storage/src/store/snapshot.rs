@@ -0,0 +1,145 @@
+use std::sync::Arc;
+
+use weedb::{rocksdb, ColumnFamily, OwnedRawIterator, OwnedSnapshot, Table};
+
+use crate::db::BaseDb;
+
+/// A consistent read view of the base DB.
+///
+/// All reads through the snapshot observe the DB as it was at the moment
+/// the snapshot was taken, so multi-key reads are not torn by concurrent writes.
+///
+/// NOTE: A snapshot pins all data versions visible to it, preventing compaction
+/// from dropping them. Keep snapshots short-lived.
+pub struct StorageSnapshot {
+    db: BaseDb,
+    snapshot: Arc<OwnedSnapshot>,
+}
+
+impl StorageSnapshot {
+    pub(crate) fn new(db: &BaseDb) -> Self {
+        Self {
+            db: db.clone(),
+            snapshot: Arc::new(db.owned_snapshot()),
+        }
+    }
+
+    pub fn db(&self) -> &BaseDb {
+        &self.db
+    }
+
+    /// Returns a raw iterator over the table as it was when the snapshot was taken.
+    ///
+    /// The iterator keeps the snapshot alive, so it can outlive this object.
+    pub fn raw_iterator<T: ColumnFamily>(&self, table: &Table<T>) -> SnapshotRawIterator {
+        let mut readopts = table.new_read_config();
+        readopts.set_snapshot(&self.snapshot);
+
+        let db = self.db.rocksdb();
+        let iter = db.raw_iterator_cf_opt(&table.cf(), readopts);
+        SnapshotRawIterator {
+            // SAFETY: Iterator was created from the same DB instance.
+            inner: unsafe { OwnedRawIterator::new(db.clone(), iter) },
+            _snapshot: self.snapshot.clone(),
+        }
+    }
+
+    /// Reads a value as it was when the snapshot was taken.
+    pub fn get<T, K>(
+        &self,
+        table: &Table<T>,
+        key: K,
+    ) -> Result<Option<rocksdb::DBPinnableSlice<'_>>, rocksdb::Error>
+    where
+        T: ColumnFamily,
+        K: AsRef<[u8]>,
+    {
+        let mut readopts = table.new_read_config();
+        readopts.set_snapshot(&self.snapshot);
+        self.db
+            .rocksdb()
+            .get_pinned_cf_opt(&table.cf(), key, &readopts)
+    }
+
+    /// Checks whether a key existed when the snapshot was taken.
+    pub fn contains_key<T, K>(&self, table: &Table<T>, key: K) -> Result<bool, rocksdb::Error>
+    where
+        T: ColumnFamily,
+        K: AsRef<[u8]>,
+    {
+        self.get(table, key).map(|value| value.is_some())
+    }
+}
+
+pub struct SnapshotRawIterator {
+    inner: OwnedRawIterator,
+    // NOTE: We must store the snapshot for as long as iterator is alive.
+    _snapshot: Arc<OwnedSnapshot>,
+}
+
+impl std::ops::Deref for SnapshotRawIterator {
+    type Target = OwnedRawIterator;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl std::ops::DerefMut for SnapshotRawIterator {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::Storage;
+
+    #[tokio::test]
+    async fn snapshot_ignores_later_writes() -> Result<()> {
+        let (storage, _tmp_dir) = Storage::new_temp().await?;
+        let state = &storage.base_db().state;
+
+        state.insert(b"first", b"old")?;
+
+        let snapshot = storage.snapshot();
+
+        state.insert(b"first", b"new")?;
+        state.insert(b"second", b"new")?;
+        state.remove(b"first")?;
+
+        let first = snapshot.get(state, b"first")?;
+        assert_eq!(first.as_deref(), Some(b"old".as_slice()));
+        assert!(!snapshot.contains_key(state, b"second")?);
+
+        // Live reads observe all writes
+        assert!(state.get(b"first")?.is_none());
+        assert!(state.get(b"second")?.is_some());
+
+        // Iterator keeps the snapshot alive after it is dropped
+        let mut iter = snapshot.raw_iterator(state);
+        drop(snapshot);
+        state.insert(b"third", b"new")?;
+        iter.seek_to_first();
+        let mut keys = Vec::new();
+        while let Some(key) = iter.key() {
+            keys.push(key.to_vec());
+            iter.next();
+        }
+        assert!(keys.contains(&b"first".to_vec()));
+        assert!(!keys.contains(&b"second".to_vec()));
+        assert!(!keys.contains(&b"third".to_vec()));
+        drop(iter);
+
+        // A new snapshot observes writes made before it
+        let snapshot = storage.snapshot();
+        assert!(!snapshot.contains_key(state, b"first")?);
+        assert!(snapshot.contains_key(state, b"second")?);
+
+        Ok(())
+    }
+}
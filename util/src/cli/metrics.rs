@@ -51,6 +51,11 @@ pub fn init_metrics(config: &MetricsConfig) -> anyhow::Result<()> {
 
     const EXPONENTIAL_THREADS: &[f64] = &[1.0, 2.0, 4.0, 8.0, 16.0, 32.0, 64.0];
 
+    const PEERS: &[f64] = &[
+        0.0, 1.0, 2.0, 3.0, 5.0, 7.0, 10.0, 15.0, 20.0, 30.0, 50.0, 70.0, 100.0, 150.0, 200.0,
+        300.0, 500.0,
+    ];
+
     metrics_exporter_prometheus::PrometheusBuilder::new()
         .set_buckets_for_metric(Matcher::Suffix("_time".to_string()), EXPONENTIAL_SECONDS)?
         .set_buckets_for_metric(
@@ -58,6 +63,7 @@ pub fn init_metrics(config: &MetricsConfig) -> anyhow::Result<()> {
             EXPONENTIAL_SECONDS_HIGH,
         )?
         .set_buckets_for_metric(Matcher::Suffix("_threads".to_string()), EXPONENTIAL_THREADS)?
+        .set_buckets_for_metric(Matcher::Suffix("_peers".to_string()), PEERS)?
         .set_buckets_for_metric(
             Matcher::Suffix("_time_long".to_string()),
             EXPONENTIAL_LONG_SECONDS,
@@ -35,8 +35,10 @@ pub mod sync {
 #[cfg(any(test, feature = "test"))]
 pub mod test {
     pub use self::logger::init_logger;
+    pub use self::metrics::TestRecorder;
 
     mod logger;
+    mod metrics;
 }
 
 pub mod metrics {
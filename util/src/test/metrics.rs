@@ -0,0 +1,142 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use metrics::{
+    Counter, Gauge, Histogram, HistogramFn, Key, KeyName, Metadata, Recorder, SharedString, Unit,
+};
+
+/// Recorder which keeps all registered metrics in memory.
+///
+/// Use it with [`metrics::with_local_recorder`] to check metrics in tests.
+#[derive(Default)]
+pub struct TestRecorder {
+    counters: Mutex<Vec<(Key, Arc<AtomicU64>)>>,
+    gauges: Mutex<Vec<(Key, Arc<AtomicU64>)>>,
+    histograms: Mutex<Vec<(Key, Arc<HistogramValues>)>>,
+}
+
+impl TestRecorder {
+    /// Sum of all counters with the specified name.
+    pub fn counter(&self, name: &str) -> u64 {
+        sum(&self.counters, name, |_| true)
+    }
+
+    /// Sum of all counters with the specified name and label value.
+    pub fn counter_with_label(&self, name: &str, label: &str, value: &str) -> u64 {
+        sum(&self.counters, name, |key| has_label(key, label, value))
+    }
+
+    /// Current value of the gauge with the specified name.
+    pub fn gauge(&self, name: &str) -> Option<f64> {
+        let gauges = self.gauges.lock().unwrap();
+        gauges
+            .iter()
+            .find(|(key, _)| key.name() == name)
+            .map(|(_, value)| f64::from_bits(value.load(Ordering::Relaxed)))
+    }
+
+    /// All values recorded to histograms with the specified name.
+    pub fn histogram(&self, name: &str) -> Vec<f64> {
+        let histograms = self.histograms.lock().unwrap();
+        histograms
+            .iter()
+            .filter(|(key, _)| key.name() == name)
+            .flat_map(|(_, values)| values.0.lock().unwrap().clone())
+            .collect()
+    }
+
+    /// Values of the label for all metrics with the specified name,
+    /// in the order of registration.
+    pub fn label_values(&self, name: &str, label: &str) -> Vec<String> {
+        let counters = self.counters.lock().unwrap();
+        let gauges = self.gauges.lock().unwrap();
+        let histograms = self.histograms.lock().unwrap();
+
+        let keys = (counters.iter().map(|(key, _)| key))
+            .chain(gauges.iter().map(|(key, _)| key))
+            .chain(histograms.iter().map(|(key, _)| key));
+
+        keys.filter(|key| key.name() == name)
+            .flat_map(|key| key.labels())
+            .filter(|item| item.key() == label)
+            .map(|item| item.value().to_owned())
+            .collect()
+    }
+}
+
+impl Recorder for TestRecorder {
+    fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+    fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+    fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+        Counter::from_arc(get_or_insert(&self.counters, key))
+    }
+
+    fn register_gauge(&self, key: &Key, _: &Metadata<'_>) -> Gauge {
+        Gauge::from_arc(get_or_insert(&self.gauges, key))
+    }
+
+    fn register_histogram(&self, key: &Key, _: &Metadata<'_>) -> Histogram {
+        Histogram::from_arc(get_or_insert(&self.histograms, key))
+    }
+}
+
+#[derive(Default)]
+struct HistogramValues(Mutex<Vec<f64>>);
+
+impl HistogramFn for HistogramValues {
+    fn record(&self, value: f64) {
+        self.0.lock().unwrap().push(value);
+    }
+}
+
+fn get_or_insert<T: Default>(items: &Mutex<Vec<(Key, Arc<T>)>>, key: &Key) -> Arc<T> {
+    let mut items = items.lock().unwrap();
+    if let Some((_, value)) = items.iter().find(|(k, _)| k == key) {
+        return value.clone();
+    }
+    let value = Arc::<T>::default();
+    items.push((key.clone(), value.clone()));
+    value
+}
+
+fn sum(items: &Mutex<Vec<(Key, Arc<AtomicU64>)>>, name: &str, f: impl Fn(&Key) -> bool) -> u64 {
+    let items = items.lock().unwrap();
+    items
+        .iter()
+        .filter(|(key, _)| key.name() == name && f(key))
+        .map(|(_, value)| value.load(Ordering::Relaxed))
+        .sum()
+}
+
+fn has_label(key: &Key, label: &str, value: &str) -> bool {
+    key.labels()
+        .any(|item| item.key() == label && item.value() == value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_metrics() {
+        let recorder = TestRecorder::default();
+        metrics::with_local_recorder(&recorder, || {
+            metrics::counter!("counter", "kind" => "a").increment(1);
+            metrics::counter!("counter", "kind" => "b").increment(2);
+            metrics::counter!("counter", "kind" => "a").increment(3);
+            metrics::gauge!("gauge").set(1.5);
+            metrics::histogram!("histogram", "kind" => "c").record(1.0);
+            metrics::histogram!("histogram", "kind" => "d").record(2.0);
+        });
+
+        assert_eq!(recorder.counter("counter"), 6);
+        assert_eq!(recorder.counter_with_label("counter", "kind", "a"), 4);
+        assert_eq!(recorder.counter("unknown"), 0);
+        assert_eq!(recorder.gauge("gauge"), Some(1.5));
+        assert_eq!(recorder.histogram("histogram"), [1.0, 2.0]);
+        assert_eq!(recorder.label_values("counter", "kind"), ["a", "b"]);
+        assert_eq!(recorder.label_values("histogram", "kind"), ["c", "d"]);
+    }
+}